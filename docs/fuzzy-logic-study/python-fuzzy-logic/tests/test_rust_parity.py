@@ -0,0 +1,126 @@
+"""
+Parity tests between fuzzy_utils.py and the Rust engine's Python bindings.
+
+Build the bindings first:
+
+    cd ../rust-fuzzy-logic && maturin develop --release
+
+Run with `python -m unittest discover tests` from python-fuzzy-logic.
+Skipped when the bindings or NumPy are not installed.
+"""
+
+import os
+import sys
+import types
+import unittest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+try:
+    import numpy as np
+    import fuzzy_logic as rust
+except ImportError as err:  # pragma: no cover - depends on the environment
+    raise unittest.SkipTest(f"bindings unavailable: {err}")
+
+# fuzzy_utils imports plotting libraries at module level; the parity tests
+# only need its numerical functions
+for name in ("matplotlib", "matplotlib.pyplot", "skfuzzy"):
+    try:
+        __import__(name)
+    except ImportError:
+        sys.modules[name] = types.ModuleType(name)
+
+import fuzzy_utils as py  # noqa: E402
+
+SYSTEMS = os.path.join(os.path.dirname(__file__), "..", "..", "rust-fuzzy-logic", "systems")
+
+
+class MembershipParityTest(unittest.TestCase):
+    xs = np.linspace(-10, 110, 241)
+
+    def assert_same_curve(self, py_fn, rust_fn):
+        expected = np.array([py_fn(x) for x in self.xs])
+        np.testing.assert_allclose(rust_fn(self.xs), expected, atol=1e-12)
+
+    def test_triangular(self):
+        self.assert_same_curve(py.triangular_mf(25, 50, 75), rust.triangular_mf(25, 50, 75))
+
+    def test_trapezoidal(self):
+        self.assert_same_curve(py.trapezoidal_mf(0, 20, 40, 60), rust.trapezoidal_mf(0, 20, 40, 60))
+
+    def test_gaussian(self):
+        self.assert_same_curve(py.gaussian_mf(50, 12), rust.gaussian_mf(50, 12))
+
+    def test_sigmoid(self):
+        self.assert_same_curve(py.sigmoid_mf(0.2, 50), rust.sigmoid_mf(0.2, 50))
+
+    def test_shoulder_difference(self):
+        # Documented difference: Rust gives a degenerate shoulder (a == b)
+        # full membership at its edge
+        self.assertEqual(py.triangular_mf(0, 0, 50)(0), 0.0)
+        self.assertEqual(rust.triangular_mf(0, 0, 50)(0), 1.0)
+
+    def test_scalar_and_list_inputs(self):
+        mf = rust.triangular_mf(0, 5, 10)
+        self.assertEqual(mf(2.5), 0.5)
+        self.assertEqual(mf([2.5, 5]), [0.5, 1.0])
+        self.assertIsInstance(mf(np.array([2.5])), np.ndarray)
+
+    def test_fuzzy_set_with_python_callable(self):
+        universe = np.linspace(0, 100, 11)
+        expected = py.FuzzySet("Warm", py.triangular_mf(25, 50, 75), universe)
+        actual = rust.FuzzySet("Warm", py.triangular_mf(25, 50, 75), universe)
+        self.assertIs(actual.universe, universe)
+        for x in (0, 30, 50, 60, 80):
+            self.assertAlmostEqual(actual(x), expected(x))
+
+
+class OperationParityTest(unittest.TestCase):
+    pairs = [(0.7, 0.5), (0.0, 1.0), (0.25, 0.25)]
+
+    def test_operators(self):
+        for name in ("fuzzy_union", "fuzzy_intersection", "algebraic_product", "algebraic_sum"):
+            for a, b in self.pairs:
+                self.assertAlmostEqual(getattr(rust, name)(a, b), getattr(py, name)(a, b), msg=name)
+        for a, _ in self.pairs:
+            self.assertAlmostEqual(rust.fuzzy_complement(a), py.fuzzy_complement(a))
+
+    def test_arrays_match_numpy(self):
+        a, b = np.random.default_rng(1).random((2, 50))
+        np.testing.assert_array_equal(rust.fuzzy_union(a, b), np.fmax(a, b))
+        np.testing.assert_array_equal(rust.fuzzy_intersection(a, b), np.fmin(a, b))
+
+
+class DefuzzificationParityTest(unittest.TestCase):
+    x = np.arange(0, 101, 1.0)
+
+    def test_methods(self):
+        warm = np.array([py.triangular_mf(25, 50, 75)(xi) for xi in self.x])
+        skewed = np.array([py.trapezoidal_mf(10, 20, 30, 90)(xi) for xi in self.x])
+        for membership in (warm, skewed):
+            self.assertAlmostEqual(rust.defuzzify_centroid(self.x, membership),
+                                   py.defuzzify_centroid(self.x, membership))
+            self.assertAlmostEqual(rust.defuzzify_mean_of_maximum(self.x, membership),
+                                   py.defuzzify_mean_of_maximum(self.x, membership))
+            self.assertAlmostEqual(rust.defuzzify_bisector(self.x, membership),
+                                   py.defuzzify_bisector(self.x, membership))
+
+    def test_empty_set_difference(self):
+        # Documented difference: Rust returns the middle of the universe
+        zeros = np.zeros_like(self.x)
+        self.assertEqual(py.defuzzify_centroid(self.x, zeros), 0.0)
+        self.assertEqual(rust.defuzzify_centroid(self.x, zeros), 50.0)
+
+
+class SystemTest(unittest.TestCase):
+    def test_batch_matches_rows(self):
+        system = rust.FuzzySystem.load(os.path.join(SYSTEMS, "tipping.fuzzy"))
+        grid = np.array([[s, f] for s in range(0, 11, 2) for f in range(0, 11, 2)], dtype=float)
+        batch = system.evaluate_batch(grid)
+        self.assertEqual(batch.shape, (len(grid), 1))
+        for row, (tip,) in zip(grid, batch):
+            self.assertAlmostEqual(system.evaluate(list(row))[0], tip)
+
+
+if __name__ == "__main__":
+    unittest.main()
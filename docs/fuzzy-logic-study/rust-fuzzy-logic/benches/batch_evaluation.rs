@@ -0,0 +1,171 @@
+//! Batch Evaluation Benchmark
+//!
+//! Compares per-call controller evaluation, as it worked before the batch
+//! API (rebuilding the output universe and re-evaluating every conclusion on
+//! each call), against `evaluate_batch` and the allocation-free
+//! `evaluate_into`.
+//!
+//! Run with: cargo bench --bench batch_evaluation
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fuzzy_logic::defuzzification::centroid;
+use fuzzy_logic::inference::{FuzzyController, FuzzyRule};
+use fuzzy_logic::membership::MembershipFunction;
+use fuzzy_logic::operations::fuzzy_union;
+use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+
+const SAMPLES: usize = 20_000;
+
+fn temperature_terms() -> Vec<MembershipFunction> {
+    vec![
+        MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 },
+        MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 20.0 },
+        MembershipFunction::Triangular { a: 10.0, b: 20.0, c: 30.0 },
+        MembershipFunction::Triangular { a: 20.0, b: 30.0, c: 40.0 },
+        MembershipFunction::Triangular { a: 30.0, b: 40.0, c: 40.0 },
+    ]
+}
+
+fn fan_terms() -> Vec<MembershipFunction> {
+    vec![
+        MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 25.0 },
+        MembershipFunction::Triangular { a: 0.0, b: 25.0, c: 50.0 },
+        MembershipFunction::Triangular { a: 25.0, b: 50.0, c: 75.0 },
+        MembershipFunction::Triangular { a: 50.0, b: 75.0, c: 100.0 },
+        MembershipFunction::Triangular { a: 75.0, b: 100.0, c: 100.0 },
+    ]
+}
+
+fn rules() -> Vec<FuzzyRule> {
+    temperature_terms()
+        .into_iter()
+        .zip(fan_terms())
+        .map(|(condition, conclusion)| {
+            FuzzyRule::new(
+                Box::new(move |x| condition.evaluate(x)),
+                Box::new(move |y| conclusion.evaluate(y)),
+            )
+        })
+        .collect()
+}
+
+/// Per-call evaluation as `FuzzyController::evaluate` used to do it
+fn evaluate_naive(rules: &[FuzzyRule], input: f64) -> f64 {
+    let (min, max, steps) = (0.0, 100.0, 100);
+    let step_size = (max - min) / (steps as f64);
+    let output_values: Vec<f64> = (0..steps).map(|i| min + i as f64 * step_size).collect();
+    let mut aggregated: Vec<f64> = vec![0.0; steps];
+
+    for rule in rules {
+        for (i, &output_val) in output_values.iter().enumerate() {
+            aggregated[i] = fuzzy_union(aggregated[i], rule.apply(input, output_val));
+        }
+    }
+
+    centroid(&output_values, &aggregated)
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    // Warm up once, then report the best of three runs
+    f();
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration, baseline: Duration) {
+    println!(
+        "  {:<28} {:>10.3} ms  {:>7.1} ns/sample  {:>6.1}x",
+        name,
+        elapsed.as_secs_f64() * 1e3,
+        elapsed.as_secs_f64() * 1e9 / SAMPLES as f64,
+        baseline.as_secs_f64() / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let inputs: Vec<f64> = (0..SAMPLES)
+        .map(|i| 40.0 * i as f64 / SAMPLES as f64)
+        .collect();
+
+    let naive_rules = rules();
+    let mut controller = FuzzyController::new(0.0, 100.0, 100);
+    for rule in rules() {
+        controller.add_rule(rule);
+    }
+
+    println!("Single-input controller, {} samples, 5 rules", SAMPLES);
+
+    let naive = time(|| {
+        for &x in &inputs {
+            black_box(evaluate_naive(&naive_rules, black_box(x)));
+        }
+    });
+    report("per-call (rebuild)", naive, naive);
+
+    let per_call = time(|| {
+        for &x in &inputs {
+            black_box(controller.evaluate(black_box(x)));
+        }
+    });
+    report("evaluate", per_call, naive);
+
+    let batch = time(|| {
+        black_box(controller.evaluate_batch(black_box(&inputs)));
+    });
+    report("evaluate_batch", batch, naive);
+
+    let mut outputs = vec![0.0; SAMPLES];
+    let mut scratch = controller.scratch();
+    let into = time(|| {
+        controller.evaluate_into(black_box(&inputs), &mut outputs, &mut scratch);
+        black_box(&outputs);
+    });
+    report("evaluate_into", into, naive);
+
+    let mut system = FuzzySystem::new();
+    let mut temperature = LinguisticVariable::new("temperature", 0.0, 40.0);
+    for (i, mf) in temperature_terms().into_iter().enumerate() {
+        temperature.add_term(format!("t{}", i), mf);
+    }
+    let mut humidity = LinguisticVariable::new("humidity", 0.0, 100.0);
+    let mut fan = LinguisticVariable::new("fan", 0.0, 100.0);
+    for (i, mf) in fan_terms().into_iter().enumerate() {
+        humidity.add_term(format!("h{}", i), mf.clone());
+        fan.add_term(format!("f{}", i), mf);
+    }
+    system.add_input(temperature);
+    system.add_input(humidity);
+    system.add_output(fan);
+    for t in 0..5 {
+        for h in 0..5 {
+            system.add_rule(Rule::new(
+                vec![Antecedent::new(0, t), Antecedent::new(1, h)],
+                vec![Consequent::new(0, (t + h) / 2)],
+            ));
+        }
+    }
+    let humidity_column: Vec<f64> = inputs.iter().map(|&t| 100.0 - 2.5 * t).collect();
+
+    println!();
+    println!("Two-input FuzzySystem, {} samples, 25 rules", SAMPLES);
+
+    let rows = time(|| {
+        for (&t, &h) in inputs.iter().zip(&humidity_column) {
+            black_box(system.evaluate(black_box(&[t, h])));
+        }
+    });
+    report("evaluate per row", rows, rows);
+
+    let columns = time(|| {
+        black_box(system.evaluate_batch(black_box(&[&inputs, &humidity_column])));
+    });
+    report("evaluate_batch", columns, rows);
+}
@@ -0,0 +1,7 @@
+//! C API Shared Library
+//!
+//! Builds the functions of [`fuzzy_logic::ffi`] into a `cdylib`, declared
+//! in `include/fuzzy_logic.h`. The main crate stays an `rlib` so that
+//! `no_std` dependents can link it.
+
+pub use fuzzy_logic::ffi::*;
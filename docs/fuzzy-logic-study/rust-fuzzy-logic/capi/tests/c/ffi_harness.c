@@ -0,0 +1,97 @@
+/*
+ * Exercises the C API through the shared library.
+ *
+ * Usage: ffi_harness <definition file>
+ * Prints "ok" and exits 0 when every check passes.
+ */
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "fuzzy_logic.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                   \
+    do {                                                              \
+        if (!(cond)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,    \
+                    __LINE__, #cond);                                 \
+            failures++;                                               \
+        }                                                             \
+    } while (0)
+
+static char *read_file(const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    char *text = malloc((size_t)size + 1);
+    size_t read = fread(text, 1, (size_t)size, file);
+    text[read] = '\0';
+    fclose(file);
+    return text;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <definition file>\n", argv[0]);
+        return 2;
+    }
+    char *definition = read_file(argv[1]);
+    if (!definition) {
+        fprintf(stderr, "cannot read %s\n", argv[1]);
+        return 2;
+    }
+
+    CHECK(strlen(fuzzy_version()) > 0);
+
+    /* Creation and introspection */
+    FuzzyHandle *system = fuzzy_system_from_definition(definition);
+    CHECK(system != NULL);
+    CHECK(fuzzy_last_error() == NULL);
+    CHECK(fuzzy_system_input_count(system) == 2);
+    CHECK(fuzzy_system_output_count(system) == 1);
+    CHECK(strcmp(fuzzy_system_input_name(system, 0), "service") == 0);
+    CHECK(strcmp(fuzzy_system_output_name(system, 0), "tip") == 0);
+    CHECK(fuzzy_system_input_name(system, 2) == NULL);
+
+    /* Single evaluation */
+    double inputs[2] = {3.0, 8.0};
+    double tip = 0.0;
+    CHECK(fuzzy_system_evaluate(system, inputs, 2, &tip, 1) == FUZZY_OK);
+    CHECK(tip > 10.0 && tip < 20.0);
+
+    /* Batch evaluation matches single evaluations */
+    double rows[6] = {3.0, 8.0, 1.0, 1.0, 9.0, 9.0};
+    double tips[3] = {0.0, 0.0, 0.0};
+    CHECK(fuzzy_system_evaluate_batch(system, rows, tips, 3) == FUZZY_OK);
+    CHECK(fabs(tips[0] - tip) < 1e-12);
+    CHECK(tips[1] < tips[0] && tips[0] < tips[2]);
+
+    /* Errors are reported, not crashed on */
+    CHECK(fuzzy_system_evaluate(system, inputs, 1, &tip, 1) == FUZZY_ERROR_INVALID_ARGUMENT);
+    CHECK(fuzzy_last_error() != NULL && strstr(fuzzy_last_error(), "expected 2 inputs") != NULL);
+    CHECK(fuzzy_system_evaluate(NULL, inputs, 2, &tip, 1) == FUZZY_ERROR_NULL_POINTER);
+    CHECK(fuzzy_system_evaluate(system, NULL, 2, &tip, 1) == FUZZY_ERROR_NULL_POINTER);
+    CHECK(fuzzy_system_from_definition("input x 0 10\n  term low triangular 0 0\n") == NULL);
+    CHECK(fuzzy_last_error() != NULL && strncmp(fuzzy_last_error(), "line 2", 6) == 0);
+    CHECK(fuzzy_system_from_definition(NULL) == NULL);
+    CHECK(fuzzy_system_input_count(NULL) == 0);
+
+    fuzzy_system_free(system);
+    fuzzy_system_free(NULL);
+    free(definition);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,59 @@
+//! C API tests
+//!
+//! Compiles `tests/c/ffi_harness.c` against `include/fuzzy_logic.h` and
+//! the shared library built from this crate, then runs the harness.
+//! Skipped when no C compiler is available.
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory holding the shared library built for the tests
+///
+/// `cargo test` builds this crate's `cdylib` as a dependency of the test
+/// targets, next to the test executable in `deps`.
+fn library_dir() -> PathBuf {
+    std::env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn test_c_harness() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = crate_dir.parent().unwrap();
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    if Command::new(&compiler).arg("--version").output().is_err() {
+        eprintln!("skipping C harness: cannot run {}", compiler);
+        return;
+    }
+    let target = library_dir();
+    let harness = target.join(format!("ffi_harness_{}", std::process::id()));
+
+    let compiled = Command::new(&compiler)
+        .arg(crate_dir.join("tests/c/ffi_harness.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(&target)
+        .arg(format!("-Wl,-rpath,{}", target.display()))
+        .args(["-lfuzzy_logic_capi", "-lm", "-Wall", "-Werror", "-o"])
+        .arg(&harness)
+        .output()
+        .unwrap();
+    assert!(
+        compiled.status.success(),
+        "compiling the harness failed:\n{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+
+    let output = Command::new(&harness)
+        .arg(root.join("systems/tipping.fuzzy"))
+        .output()
+        .unwrap();
+    std::fs::remove_file(&harness).unwrap();
+    assert!(
+        output.status.success(),
+        "harness failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
@@ -0,0 +1,32 @@
+//! C header tests
+//!
+//! Generates the header from `src/ffi.rs` with cbindgen and compares it
+//! with `include/fuzzy_logic.h`. Set `UPDATE_HEADER=1` to rewrite the
+//! header instead.
+
+use std::path::Path;
+
+#[test]
+fn test_header_is_generated() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = crate_dir.parent().unwrap();
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src/ffi.rs"))
+        .generate()
+        .expect("cbindgen failed");
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = root.join("include/fuzzy_logic.h");
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let header = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        header == generated,
+        "include/fuzzy_logic.h is out of date; rerun with UPDATE_HEADER=1"
+    );
+}
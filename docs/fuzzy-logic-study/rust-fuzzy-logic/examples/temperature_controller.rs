@@ -78,10 +78,13 @@ fn main() {
 
     // Rule 1: Cold + Low Humidity = Slow
     println!("  1. IF temp is cold AND humidity is low THEN fan is slow");
+    let temp_cold1 = temp_cold.clone();
+    let humidity_low1 = humidity_low.clone();
+    let fan_slow1 = fan_slow.clone();
     controller.add_rule(TwoInputFuzzyRule::new(
-        Box::new(move |x| temp_cold.evaluate(x)),
-        Box::new(move |x| humidity_low.evaluate(x)),
-        Box::new(move |x| fan_slow.evaluate(x)),
+        Box::new(move |x| temp_cold1.evaluate(x)),
+        Box::new(move |x| humidity_low1.evaluate(x)),
+        Box::new(move |x| fan_slow1.evaluate(x)),
     ));
 
     // Rule 2: Cold + Medium Humidity = Slow
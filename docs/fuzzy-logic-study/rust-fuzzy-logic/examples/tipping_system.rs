@@ -84,10 +84,13 @@ fn main() {
 
     // Rule 1: Poor Service + Poor Food = Low Tip
     println!("  1. IF service is poor AND food is poor THEN tip is low");
+    let service_poor1 = service_poor.clone();
+    let food_poor1 = food_poor.clone();
+    let tip_low1 = tip_low.clone();
     controller.add_rule(TwoInputFuzzyRule::new(
-        Box::new(move |x| service_poor.evaluate(x)),
-        Box::new(move |x| food_poor.evaluate(x)),
-        Box::new(move |x| tip_low.evaluate(x)),
+        Box::new(move |x| service_poor1.evaluate(x)),
+        Box::new(move |x| food_poor1.evaluate(x)),
+        Box::new(move |x| tip_low1.evaluate(x)),
     ));
 
     // Rule 2: Poor Service + Good Food = Low Tip
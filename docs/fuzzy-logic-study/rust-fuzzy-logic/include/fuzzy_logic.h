@@ -0,0 +1,160 @@
+/*
+ * fuzzy_logic C API
+ *
+ * Build the shared library with `cargo build --release -p fuzzy_logic_capi`
+ * (libfuzzy_logic_capi.so, libfuzzy_logic_capi.dylib or
+ * fuzzy_logic_capi.dll) and link against it.
+ *
+ * Systems are opaque handles created from definition text in the format
+ * of the `systems/` directory and released with fuzzy_system_free().
+ * Functions that can fail return a status code (or NULL) and leave a
+ * message for fuzzy_last_error(). A handle may be shared between threads;
+ * evaluations on the same handle are serialized.
+ *
+ * Generated by cbindgen from src/ffi.rs; do not edit.
+ */
+
+#ifndef FUZZY_LOGIC_H
+#define FUZZY_LOGIC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The call succeeded
+ */
+#define FUZZY_OK 0
+
+/**
+ * A required pointer was `NULL`
+ */
+#define FUZZY_ERROR_NULL_POINTER -1
+
+/**
+ * An argument was invalid (wrong array length, bad UTF-8, bad definition)
+ */
+#define FUZZY_ERROR_INVALID_ARGUMENT -2
+
+/**
+ * The library panicked; the handle should not be used again
+ */
+#define FUZZY_ERROR_PANIC -3
+
+/**
+ * A system and the buffers used to evaluate it
+ */
+typedef struct FuzzyHandle FuzzyHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a system from definition text
+ *
+ * Returns `NULL` on failure; see [`fuzzy_last_error`].
+ *
+ * # Safety
+ *
+ * `definition` must be `NULL` or a NUL-terminated string.
+ */
+FuzzyHandle *fuzzy_system_from_definition(const char *definition);
+
+/**
+ * Release a system; `NULL` is ignored
+ *
+ * # Safety
+ *
+ * `system` must be `NULL` or a handle that has not been freed yet.
+ */
+void fuzzy_system_free(FuzzyHandle *system);
+
+/**
+ * Number of input variables (0 for `NULL`)
+ *
+ * # Safety
+ *
+ * `system` must be `NULL` or a live handle.
+ */
+size_t fuzzy_system_input_count(const FuzzyHandle *system);
+
+/**
+ * Number of output variables (0 for `NULL`)
+ *
+ * # Safety
+ *
+ * `system` must be `NULL` or a live handle.
+ */
+size_t fuzzy_system_output_count(const FuzzyHandle *system);
+
+/**
+ * Name of an input variable, owned by the handle (`NULL` if out of range)
+ *
+ * # Safety
+ *
+ * `system` must be `NULL` or a live handle.
+ */
+const char *fuzzy_system_input_name(const FuzzyHandle *system, size_t index);
+
+/**
+ * Name of an output variable, owned by the handle (`NULL` if out of range)
+ *
+ * # Safety
+ *
+ * `system` must be `NULL` or a live handle.
+ */
+const char *fuzzy_system_output_name(const FuzzyHandle *system, size_t index);
+
+/**
+ * Evaluate one set of inputs
+ *
+ * `inputs` holds one value per input variable and `outputs` receives one
+ * value per output variable, both in definition order. Returns
+ * [`FUZZY_OK`] or a negative error code.
+ *
+ * # Safety
+ *
+ * `system` must be a live handle; `inputs` and `outputs` must point to
+ * `input_count` and `output_count` values.
+ */
+int32_t fuzzy_system_evaluate(const FuzzyHandle *system,
+                              const double *inputs,
+                              size_t input_count,
+                              double *outputs,
+                              size_t output_count);
+
+/**
+ * Evaluate `rows` sets of inputs
+ *
+ * `inputs` is row-major with one row per sample (`rows * input_count`
+ * values); `outputs` receives `rows * output_count` values, also
+ * row-major.
+ *
+ * # Safety
+ *
+ * `system` must be a live handle; `inputs` and `outputs` must point to
+ * `rows` times as many values as the system has inputs and outputs.
+ */
+int32_t fuzzy_system_evaluate_batch(const FuzzyHandle *system,
+                                    const double *inputs,
+                                    double *outputs,
+                                    size_t rows);
+
+/**
+ * Message describing the most recent failed call on this thread
+ *
+ * Returns `NULL` if the last call succeeded. The string is owned by the
+ * library and stays valid until the next call on this thread.
+ */
+const char *fuzzy_last_error(void);
+
+/**
+ * Library version as a NUL-terminated string
+ */
+const char *fuzzy_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FUZZY_LOGIC_H */
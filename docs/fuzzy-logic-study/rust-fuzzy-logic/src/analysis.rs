@@ -0,0 +1,519 @@
+//! Rule Base Analysis
+//!
+//! This module checks a [`FuzzySystem`] for common design mistakes before it
+//! is deployed:
+//! - Completeness: input regions where no rule fires above a threshold
+//! - Consistency: rules with identical antecedents but different conclusions
+//! - Redundancy: rules whose contribution is always covered by another rule
+//! - Unused terms: terms that no rule refers to
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::system::{Connective, FuzzySystem, Rule};
+
+/// Settings for [`analyze`]
+#[derive(Debug, Clone)]
+pub struct AnalysisConfig {
+    /// Minimum firing strength for a point to count as covered
+    pub threshold: f64,
+    /// Grid points per input variable (the grid has `samples^inputs` points)
+    pub samples_per_input: usize,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            threshold: 0.2,
+            samples_per_input: 11,
+        }
+    }
+}
+
+/// A grid cell in which no rule fires above the threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncoveredRegion {
+    /// Sample point at the center of the cell
+    pub center: Vec<f64>,
+    /// Lower corner of the cell
+    pub lower: Vec<f64>,
+    /// Upper corner of the cell
+    pub upper: Vec<f64>,
+    /// Strongest firing strength observed at the center
+    pub max_strength: f64,
+}
+
+/// Two rules with identical antecedents that conclude different terms
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub first_rule: usize,
+    pub second_rule: usize,
+    pub output: String,
+    pub first_term: String,
+    pub second_term: String,
+}
+
+/// A rule whose contribution is always covered by another rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redundancy {
+    pub rule: usize,
+    pub subsumed_by: usize,
+}
+
+/// A term that no rule refers to
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnusedTerm {
+    pub variable: String,
+    pub term: String,
+}
+
+/// Result of [`analyze`]
+#[derive(Debug, Clone)]
+pub struct AnalysisReport {
+    pub input_names: Vec<String>,
+    pub threshold: f64,
+    /// Number of grid points checked for completeness
+    pub samples: usize,
+    pub uncovered: Vec<UncoveredRegion>,
+    pub conflicts: Vec<Conflict>,
+    pub redundancies: Vec<Redundancy>,
+    pub unused_terms: Vec<UnusedTerm>,
+}
+
+impl AnalysisReport {
+    /// Fraction of grid points where at least one rule fires above the threshold
+    pub fn coverage(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        1.0 - self.uncovered.len() as f64 / self.samples as f64
+    }
+
+    /// True when no problems were found
+    pub fn is_clean(&self) -> bool {
+        self.uncovered.is_empty()
+            && self.conflicts.is_empty()
+            && self.redundancies.is_empty()
+            && self.unused_terms.is_empty()
+    }
+}
+
+/// Maximum number of uncovered regions listed by the `Display` implementation
+const MAX_LISTED_REGIONS: usize = 10;
+
+impl fmt::Display for AnalysisReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Rule base analysis")?;
+        writeln!(
+            f,
+            "  Completeness: {:.1}% of {} samples fire a rule above {:.2}",
+            self.coverage() * 100.0,
+            self.samples,
+            self.threshold
+        )?;
+        for region in self.uncovered.iter().take(MAX_LISTED_REGIONS) {
+            let point: Vec<String> = self
+                .input_names
+                .iter()
+                .zip(&region.center)
+                .map(|(name, x)| format!("{}={:.2}", name, x))
+                .collect();
+            writeln!(
+                f,
+                "    uncovered near {} (max strength {:.2})",
+                point.join(", "),
+                region.max_strength
+            )?;
+        }
+        if self.uncovered.len() > MAX_LISTED_REGIONS {
+            writeln!(
+                f,
+                "    ... and {} more",
+                self.uncovered.len() - MAX_LISTED_REGIONS
+            )?;
+        }
+
+        writeln!(f, "  Inconsistencies: {}", self.conflicts.len())?;
+        for c in &self.conflicts {
+            writeln!(
+                f,
+                "    rules {} and {} share antecedents but conclude {} is {} / {}",
+                c.first_rule, c.second_rule, c.output, c.first_term, c.second_term
+            )?;
+        }
+
+        writeln!(f, "  Redundancies: {}", self.redundancies.len())?;
+        for r in &self.redundancies {
+            writeln!(f, "    rule {} is subsumed by rule {}", r.rule, r.subsumed_by)?;
+        }
+
+        writeln!(f, "  Unused terms: {}", self.unused_terms.len())?;
+        for u in &self.unused_terms {
+            writeln!(f, "    {} is {}", u.variable, u.term)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run every check on a system
+pub fn analyze(system: &FuzzySystem, config: &AnalysisConfig) -> AnalysisReport {
+    let (samples, uncovered) = check_completeness(system, config);
+
+    AnalysisReport {
+        input_names: system.inputs().iter().map(|v| v.name.clone()).collect(),
+        threshold: config.threshold,
+        samples,
+        uncovered,
+        conflicts: find_conflicts(system),
+        redundancies: find_redundancies(system),
+        unused_terms: find_unused_terms(system),
+    }
+}
+
+/// Sample the input space on a regular grid and report poorly covered cells
+///
+/// Returns the number of grid points checked and the uncovered cells.
+pub fn check_completeness(
+    system: &FuzzySystem,
+    config: &AnalysisConfig,
+) -> (usize, Vec<UncoveredRegion>) {
+    let inputs = system.inputs();
+    if inputs.is_empty() {
+        return (0, Vec::new());
+    }
+
+    let steps = config.samples_per_input.max(2);
+    let axes: Vec<Vec<f64>> = inputs.iter().map(|v| v.universe(steps)).collect();
+    let half_widths: Vec<f64> = inputs
+        .iter()
+        .map(|v| (v.max - v.min) / (steps - 1) as f64 / 2.0)
+        .collect();
+
+    let mut uncovered = Vec::new();
+    let mut index = vec![0usize; inputs.len()];
+    let mut samples = 0;
+
+    loop {
+        let point: Vec<f64> = index.iter().zip(&axes).map(|(&i, axis)| axis[i]).collect();
+        let max_strength = system
+            .rules()
+            .iter()
+            .map(|rule| system.firing_strength(rule, &point))
+            .fold(0.0, f64::max);
+        samples += 1;
+
+        if max_strength < config.threshold {
+            let lower = point
+                .iter()
+                .zip(&half_widths)
+                .zip(inputs)
+                .map(|((x, h), v)| (x - h).max(v.min))
+                .collect();
+            let upper = point
+                .iter()
+                .zip(&half_widths)
+                .zip(inputs)
+                .map(|((x, h), v)| (x + h).min(v.max))
+                .collect();
+            uncovered.push(UncoveredRegion {
+                center: point,
+                lower,
+                upper,
+                max_strength,
+            });
+        }
+
+        // Advance the grid index like an odometer
+        let mut dim = 0;
+        loop {
+            index[dim] += 1;
+            if index[dim] < steps {
+                break;
+            }
+            index[dim] = 0;
+            dim += 1;
+            if dim == index.len() {
+                return (samples, uncovered);
+            }
+        }
+    }
+}
+
+/// Find pairs of rules with identical antecedents but different conclusions
+pub fn find_conflicts(system: &FuzzySystem) -> Vec<Conflict> {
+    let rules = system.rules();
+    let mut conflicts = Vec::new();
+
+    for i in 0..rules.len() {
+        for j in (i + 1)..rules.len() {
+            if !same_antecedents(&rules[i], &rules[j]) {
+                continue;
+            }
+            for ci in &rules[i].consequents {
+                for cj in &rules[j].consequents {
+                    if ci.variable == cj.variable && ci.term != cj.term {
+                        let output = &system.outputs()[ci.variable];
+                        conflicts.push(Conflict {
+                            first_rule: i,
+                            second_rule: j,
+                            output: output.name.clone(),
+                            first_term: output.terms[ci.term].name.clone(),
+                            second_term: output.terms[cj.term].name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Find rules that are subsumed by another rule
+///
+/// Rule `r` is subsumed by rule `s` when `s` always fires at least as
+/// strongly as `r` and concludes everything `r` concludes. For AND rules this
+/// holds when the antecedents of `s` are a subset of those of `r`; for OR
+/// rules, when they are a superset. With max aggregation such a rule never
+/// changes the output. When two rules are identical, the later one is
+/// reported.
+pub fn find_redundancies(system: &FuzzySystem) -> Vec<Redundancy> {
+    let rules = system.rules();
+    let mut redundancies = Vec::new();
+
+    for (r, rule) in rules.iter().enumerate() {
+        let subsumer = (0..rules.len()).find(|&s| {
+            if s == r || !subsumes(&rules[s], rule) {
+                return false;
+            }
+            // Identical rules subsume each other; keep the first one
+            !(subsumes(rule, &rules[s]) && s > r)
+        });
+        if let Some(s) = subsumer {
+            redundancies.push(Redundancy {
+                rule: r,
+                subsumed_by: s,
+            });
+        }
+    }
+
+    redundancies
+}
+
+/// Find input and output terms that no rule refers to
+pub fn find_unused_terms(system: &FuzzySystem) -> Vec<UnusedTerm> {
+    let used_inputs: BTreeSet<(usize, usize)> = system
+        .rules()
+        .iter()
+        .flat_map(|r| r.antecedents.iter().map(|a| (a.variable, a.term)))
+        .collect();
+    let used_outputs: BTreeSet<(usize, usize)> = system
+        .rules()
+        .iter()
+        .flat_map(|r| r.consequents.iter().map(|c| (c.variable, c.term)))
+        .collect();
+
+    let mut unused = Vec::new();
+    for (variables, used) in [(system.inputs(), &used_inputs), (system.outputs(), &used_outputs)] {
+        for (v, variable) in variables.iter().enumerate() {
+            for (t, term) in variable.terms.iter().enumerate() {
+                if !used.contains(&(v, t)) {
+                    unused.push(UnusedTerm {
+                        variable: variable.name.clone(),
+                        term: term.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+    unused
+}
+
+/// True when both rules combine the same set of antecedent clauses
+fn same_antecedents(a: &Rule, b: &Rule) -> bool {
+    let set_a: BTreeSet<_> = a.antecedents.iter().collect();
+    let set_b: BTreeSet<_> = b.antecedents.iter().collect();
+    set_a == set_b && (a.connective == b.connective || set_a.len() <= 1)
+}
+
+/// True when `general` always fires at least as strongly as `specific`
+/// and concludes everything `specific` concludes
+fn subsumes(general: &Rule, specific: &Rule) -> bool {
+    if general.weight < specific.weight {
+        return false;
+    }
+    let general_then: BTreeSet<_> = general.consequents.iter().collect();
+    if !specific.consequents.iter().all(|c| general_then.contains(c)) {
+        return false;
+    }
+
+    let general_if: BTreeSet<_> = general.antecedents.iter().collect();
+    let specific_if: BTreeSet<_> = specific.antecedents.iter().collect();
+    if general_if.is_empty() || specific_if.is_empty() {
+        return false;
+    }
+
+    // A single clause reads the same under either connective
+    let general_and = general.connective == Connective::And || general_if.len() == 1;
+    let general_or = general.connective == Connective::Or || general_if.len() == 1;
+    let specific_and = specific.connective == Connective::And || specific_if.len() == 1;
+    let specific_or = specific.connective == Connective::Or || specific_if.len() == 1;
+
+    (general_and && specific_and && general_if.is_subset(&specific_if))
+        || (general_or && specific_or && specific_if.is_subset(&general_if))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::membership::MembershipFunction;
+    use crate::system::{Antecedent, Consequent, LinguisticVariable};
+
+    fn tipping_system() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        for name in ["service", "food"] {
+            system.add_input(
+                LinguisticVariable::new(name, 0.0, 10.0)
+                    .with_term("poor", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 5.0 })
+                    .with_term("good", MembershipFunction::Triangular { a: 0.0, b: 5.0, c: 10.0 })
+                    .with_term("excellent", MembershipFunction::Triangular { a: 5.0, b: 10.0, c: 10.0 }),
+            );
+        }
+        system.add_output(
+            LinguisticVariable::new("tip", 0.0, 30.0)
+                .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 13.0 })
+                .with_term("medium", MembershipFunction::Triangular { a: 0.0, b: 15.0, c: 25.0 })
+                .with_term("high", MembershipFunction::Triangular { a: 15.0, b: 30.0, c: 30.0 }),
+        );
+        system
+    }
+
+    fn and_rule(service: usize, food: usize, tip: usize) -> Rule {
+        Rule::new(
+            vec![Antecedent::new(0, service), Antecedent::new(1, food)],
+            vec![Consequent::new(0, tip)],
+        )
+    }
+
+    #[test]
+    fn test_complete_rule_base_is_clean() {
+        let mut system = tipping_system();
+        let matrix = [[0, 0, 1], [0, 1, 2], [1, 2, 2]];
+        for (s, row) in matrix.iter().enumerate() {
+            for (f, &tip) in row.iter().enumerate() {
+                system.add_rule(and_rule(s, f, tip));
+            }
+        }
+
+        let report = analyze(&system, &AnalysisConfig::default());
+        assert!(report.is_clean(), "{}", report);
+        assert_eq!(report.samples, 121);
+        assert_eq!(report.coverage(), 1.0);
+    }
+
+    #[test]
+    fn test_incomplete_rule_base() {
+        let mut system = tipping_system();
+        system.add_rule(and_rule(0, 0, 0));
+
+        let (samples, uncovered) = check_completeness(&system, &AnalysisConfig::default());
+        assert_eq!(samples, 121);
+        assert!(!uncovered.is_empty());
+
+        let far_corner = uncovered
+            .iter()
+            .find(|r| r.center == vec![10.0, 10.0])
+            .expect("excellent/excellent corner is not covered");
+        assert_eq!(far_corner.max_strength, 0.0);
+        assert_eq!(far_corner.lower, vec![9.5, 9.5]);
+        assert_eq!(far_corner.upper, vec![10.0, 10.0]);
+    }
+
+    #[test]
+    fn test_conflicting_rules() {
+        let mut system = tipping_system();
+        system.add_rule(and_rule(0, 0, 0));
+        // Same antecedents written in the other order
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(1, 0), Antecedent::new(0, 0)],
+            vec![Consequent::new(0, 2)],
+        ));
+
+        let conflicts = find_conflicts(&system);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].first_rule, 0);
+        assert_eq!(conflicts[0].second_rule, 1);
+        assert_eq!(conflicts[0].first_term, "low");
+        assert_eq!(conflicts[0].second_term, "high");
+    }
+
+    #[test]
+    fn test_redundant_rules() {
+        let mut system = tipping_system();
+        // IF service is poor THEN tip is low
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 0)],
+            vec![Consequent::new(0, 0)],
+        ));
+        // IF service is poor AND food is good THEN tip is low (subsumed)
+        system.add_rule(and_rule(0, 1, 0));
+        // IF service is poor AND food is excellent THEN tip is medium (not subsumed)
+        system.add_rule(and_rule(0, 2, 1));
+        // Duplicate of rule 0
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 0)],
+            vec![Consequent::new(0, 0)],
+        ));
+
+        let redundancies = find_redundancies(&system);
+        assert_eq!(
+            redundancies,
+            vec![
+                Redundancy { rule: 1, subsumed_by: 0 },
+                Redundancy { rule: 3, subsumed_by: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_or_rule_subsumption() {
+        let mut system = tipping_system();
+        let or_rule = |clauses: Vec<Antecedent>| {
+            Rule::new(clauses, vec![Consequent::new(0, 2)]).with_connective(Connective::Or)
+        };
+        system.add_rule(or_rule(vec![Antecedent::new(0, 2), Antecedent::new(1, 2)]));
+        system.add_rule(or_rule(vec![Antecedent::new(0, 2)]));
+
+        assert_eq!(
+            find_redundancies(&system),
+            vec![Redundancy { rule: 1, subsumed_by: 0 }]
+        );
+    }
+
+    #[test]
+    fn test_unused_terms() {
+        let mut system = tipping_system();
+        system.add_rule(and_rule(0, 0, 0));
+        system.add_rule(and_rule(2, 2, 2));
+
+        let unused = find_unused_terms(&system);
+        let names: Vec<String> = unused
+            .iter()
+            .map(|u| format!("{}.{}", u.variable, u.term))
+            .collect();
+        assert_eq!(names, vec!["service.good", "food.good", "tip.medium"]);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let mut system = tipping_system();
+        system.add_rule(and_rule(0, 0, 0));
+        system.add_rule(and_rule(0, 0, 1));
+
+        let text = analyze(&system, &AnalysisConfig::default()).to_string();
+        assert!(text.contains("Inconsistencies: 1"));
+        assert!(text.contains("rules 0 and 1 share antecedents but conclude tip is low / medium"));
+        assert!(text.contains("... and"));
+        assert!(text.contains("service is excellent"));
+    }
+}
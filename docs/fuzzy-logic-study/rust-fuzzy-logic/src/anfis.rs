@@ -0,0 +1,751 @@
+//! ANFIS Training
+//!
+//! Learns a first-order Sugeno system from labelled samples with the
+//! adaptive neuro-fuzzy inference system (ANFIS) of Jang (1993). Each input
+//! is covered by evenly spaced terms, the rule base holds every combination
+//! of terms, and antecedents combine with the product t-norm. Every epoch
+//! uses hybrid learning:
+//! - Forward pass: with the premise terms fixed the output is linear in the
+//!   consequent parameters, which are solved by least squares
+//! - Backward pass: with the consequents fixed, the premise parameters take
+//!   a gradient descent step on the squared error
+//!
+//! A fraction of the samples is held out for validation. Training stops
+//! once the validation error has not improved for `patience` epochs, and
+//! the parameters from the epoch with the lowest validation error are kept.
+//! The result is an ordinary [`OwnedSugenoSystem`].
+//!
+//! ```
+//! use fuzzy_logic::anfis::{train, AnfisConfig};
+//!
+//! let inputs: Vec<[f64; 1]> = (0..=60).map(|i| [i as f64 / 10.0]).collect();
+//! let targets: Vec<f64> = inputs.iter().map(|x| x[0].sin()).collect();
+//! let model = train(&inputs, &targets, &AnfisConfig::default()).unwrap();
+//!
+//! let y = model.system.evaluate(&[2.0]).unwrap();
+//! assert!((y - 2.0_f64.sin()).abs() < 0.05);
+//! println!("{}", model.report);
+//! ```
+
+use std::fmt;
+
+use crate::linalg::NormalEquations;
+use crate::membership::MembershipFunction;
+use crate::operations::TNorm;
+use crate::rng::Rng;
+use crate::sugeno::{OwnedSugenoRule, OwnedSugenoSystem};
+
+/// Most consequent parameters [`train`] accepts
+///
+/// The consequent least squares solves for `rules × (inputs + 1)`
+/// parameters with a dense system quadratic in that count, and the rule
+/// base grows as `terms_per_input^inputs`: 3 terms on 4 inputs need 405
+/// parameters, on 6 inputs already 5103.
+pub const MAX_PARAMETERS: usize = 1024;
+
+/// Shape of the premise terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PremiseShape {
+    Gaussian,
+    /// Generalized bell
+    Bell,
+    Triangular,
+}
+
+/// Settings for [`train`]
+#[derive(Debug, Clone)]
+pub struct AnfisConfig {
+    /// Terms per input (the rule base has `terms_per_input^inputs` rules,
+    /// each with `inputs + 1` consequent parameters, at most
+    /// [`MAX_PARAMETERS`] in total)
+    pub terms_per_input: usize,
+    pub shape: PremiseShape,
+    /// Maximum number of epochs
+    pub epochs: usize,
+    /// Initial length of a premise step, as a fraction of the input ranges
+    ///
+    /// It grows by 10% after four consecutive falls in training error and
+    /// shrinks by 10% when the error alternates.
+    pub step_size: f64,
+    /// Fraction of samples held out for validation, in `[0, 1)`; with zero,
+    /// early stopping watches the training error instead
+    pub validation_fraction: f64,
+    /// Epochs without improvement before training stops
+    pub patience: usize,
+    /// Seed for the training/validation split
+    pub seed: u64,
+}
+
+impl Default for AnfisConfig {
+    fn default() -> Self {
+        AnfisConfig {
+            terms_per_input: 3,
+            shape: PremiseShape::Gaussian,
+            epochs: 200,
+            step_size: 0.01,
+            validation_fraction: 0.2,
+            patience: 20,
+            seed: 0,
+        }
+    }
+}
+
+/// Errors from [`train`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnfisError {
+    NoSamples,
+    /// Samples have no input values
+    NoInputs,
+    /// The number of targets differs from the number of samples
+    LengthMismatch { samples: usize, targets: usize },
+    /// A sample has a different number of inputs from the first
+    InconsistentSample { sample: usize, expected: usize, actual: usize },
+    /// A sample or its target is NaN or infinite
+    NonFinite(usize),
+    /// The validation split leaves no samples to train on
+    TooFewSamples(usize),
+    InvalidConfig(&'static str),
+}
+
+impl fmt::Display for AnfisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnfisError::NoSamples => write!(f, "no training samples"),
+            AnfisError::NoInputs => write!(f, "samples have no inputs"),
+            AnfisError::LengthMismatch { samples, targets } => {
+                write!(f, "{} samples but {} targets", samples, targets)
+            }
+            AnfisError::InconsistentSample { sample, expected, actual } => write!(
+                f,
+                "sample {} has {} inputs, expected {}",
+                sample, actual, expected
+            ),
+            AnfisError::NonFinite(sample) => write!(f, "sample {} is not finite", sample),
+            AnfisError::TooFewSamples(n) => {
+                write!(f, "{} samples are too few for the validation split", n)
+            }
+            AnfisError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AnfisError {}
+
+/// Errors after one epoch
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochRecord {
+    /// Epoch number, from 1
+    pub epoch: usize,
+    pub training_rmse: f64,
+    /// `None` without a validation split
+    pub validation_rmse: Option<f64>,
+    /// Premise step size used after this epoch
+    pub step_size: f64,
+}
+
+/// Learning curve of a training run
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingReport {
+    pub rules: usize,
+    pub training_samples: usize,
+    pub validation_samples: usize,
+    pub epochs: Vec<EpochRecord>,
+    /// Epoch whose parameters were kept
+    pub best_epoch: usize,
+    /// True when training stopped before the epoch limit
+    pub stopped_early: bool,
+}
+
+impl TrainingReport {
+    /// Record of the epoch whose parameters were kept
+    pub fn best(&self) -> &EpochRecord {
+        &self.epochs[self.best_epoch - 1]
+    }
+
+    /// The learning curve as `epoch,training_rmse,validation_rmse` rows
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("epoch,training_rmse,validation_rmse\n");
+        for record in &self.epochs {
+            let validation = record.validation_rmse.map(|v| v.to_string()).unwrap_or_default();
+            csv.push_str(&format!("{},{},{}\n", record.epoch, record.training_rmse, validation));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for TrainingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "ANFIS training: {} rules, {} training / {} validation samples",
+            self.rules, self.training_samples, self.validation_samples
+        )?;
+        writeln!(f, "  epoch  training RMSE  validation RMSE  step size")?;
+        for record in &self.epochs {
+            let validation = record
+                .validation_rmse
+                .map(|v| format!("{:15.6}", v))
+                .unwrap_or_else(|| format!("{:>15}", "-"));
+            writeln!(
+                f,
+                "  {:5}  {:13.6}  {}  {:9.5}",
+                record.epoch, record.training_rmse, validation, record.step_size
+            )?;
+        }
+        let best = self.best();
+        write!(f, "  Kept epoch {} (training RMSE {:.6}", best.epoch, best.training_rmse)?;
+        if let Some(v) = best.validation_rmse {
+            write!(f, ", validation RMSE {:.6}", v)?;
+        }
+        write!(f, ")")?;
+        if self.stopped_early {
+            write!(f, "; stopped early after {} epochs", self.epochs.len())?;
+        }
+        writeln!(f)
+    }
+}
+
+/// A trained system and its learning curve
+#[derive(Debug, Clone)]
+pub struct AnfisModel {
+    pub system: OwnedSugenoSystem,
+    pub report: TrainingReport,
+}
+
+/// Premise terms, grid rule base and consequent parameters
+#[derive(Debug, Clone)]
+struct Network {
+    /// Terms of each input
+    terms: Vec<Vec<MembershipFunction>>,
+    /// Term index per input for each rule
+    rules: Vec<Vec<usize>>,
+    /// Per rule: one coefficient per input, then the constant
+    consequents: Vec<f64>,
+}
+
+/// Per-sample quantities of a forward pass
+struct Forward {
+    /// Membership degree of each term of each input
+    memberships: Vec<Vec<f64>>,
+    strengths: Vec<f64>,
+    total: f64,
+}
+
+impl Network {
+    /// Evenly spaced terms over each input range and every combination of them
+    fn new(shape: PremiseShape, terms_per_input: usize, ranges: &[(f64, f64)]) -> Self {
+        let terms: Vec<Vec<MembershipFunction>> = ranges
+            .iter()
+            .map(|&(min, max)| {
+                let span = max - min;
+                let (spacing, start) = if terms_per_input > 1 {
+                    (span / (terms_per_input - 1) as f64, min)
+                } else {
+                    (span, min + span / 2.0)
+                };
+                (0..terms_per_input)
+                    .map(|k| initial_term(shape, start + k as f64 * spacing, spacing))
+                    .collect()
+            })
+            .collect();
+
+        let mut rules = vec![Vec::new()];
+        for _ in ranges {
+            rules = rules
+                .into_iter()
+                .flat_map(|rule| {
+                    (0..terms_per_input).map(move |k| {
+                        let mut rule = rule.clone();
+                        rule.push(k);
+                        rule
+                    })
+                })
+                .collect();
+        }
+
+        let consequents = vec![0.0; rules.len() * (ranges.len() + 1)];
+        Network { terms, rules, consequents }
+    }
+
+    fn forward(&self, x: &[f64]) -> Forward {
+        let memberships: Vec<Vec<f64>> = self
+            .terms
+            .iter()
+            .zip(x)
+            .map(|(terms, &xi)| terms.iter().map(|mf| mf.evaluate(xi)).collect())
+            .collect();
+        let strengths: Vec<f64> = self
+            .rules
+            .iter()
+            .map(|rule| rule.iter().enumerate().map(|(i, &k)| memberships[i][k]).product())
+            .collect();
+        let total = strengths.iter().sum();
+        Forward { memberships, strengths, total }
+    }
+
+    fn rule_output(&self, rule: usize, x: &[f64]) -> f64 {
+        let width = x.len() + 1;
+        let params = &self.consequents[rule * width..(rule + 1) * width];
+        params[..x.len()].iter().zip(x).map(|(c, xi)| c * xi).sum::<f64>() + params[x.len()]
+    }
+
+    /// Output for `x`, or zero where no rule fires
+    fn predict(&self, x: &[f64]) -> f64 {
+        let forward = self.forward(x);
+        if forward.total <= 0.0 {
+            return 0.0;
+        }
+        forward
+            .strengths
+            .iter()
+            .enumerate()
+            .map(|(r, w)| w * self.rule_output(r, x))
+            .sum::<f64>()
+            / forward.total
+    }
+
+    fn rmse(&self, samples: &[(&[f64], f64)]) -> f64 {
+        let sum: f64 = samples.iter().map(|(x, y)| (y - self.predict(x)).powi(2)).sum();
+        (sum / samples.len() as f64).sqrt()
+    }
+
+    /// Least-squares consequents for the current premise terms
+    fn fit_consequents(&mut self, samples: &[(&[f64], f64)]) {
+        let width = self.terms.len() + 1;
+        let mut equations = NormalEquations::new(self.consequents.len());
+        let mut row = vec![0.0; self.consequents.len()];
+
+        for (x, y) in samples {
+            let forward = self.forward(x);
+            if forward.total <= 0.0 {
+                continue;
+            }
+            for (r, w) in forward.strengths.iter().enumerate() {
+                let normalised = w / forward.total;
+                for (i, xi) in x.iter().enumerate() {
+                    row[r * width + i] = normalised * xi;
+                }
+                row[r * width + width - 1] = normalised;
+            }
+            equations.add(&row, *y);
+        }
+        if let Some(solution) = equations.solve() {
+            self.consequents = solution;
+        }
+    }
+
+    /// Gradient of half the summed squared error with respect to each
+    /// premise parameter, indexed like `terms`
+    fn premise_gradient(&self, samples: &[(&[f64], f64)]) -> Vec<Vec<Vec<f64>>> {
+        let mut gradient: Vec<Vec<Vec<f64>>> = self
+            .terms
+            .iter()
+            .map(|terms| terms.iter().map(|mf| vec![0.0; parameters(mf).len()]).collect())
+            .collect();
+
+        for (x, y) in samples {
+            let forward = self.forward(x);
+            if forward.total <= 0.0 {
+                continue;
+            }
+            let outputs: Vec<f64> = (0..self.rules.len()).map(|r| self.rule_output(r, x)).collect();
+            let predicted = forward
+                .strengths
+                .iter()
+                .zip(&outputs)
+                .map(|(w, f)| w * f)
+                .sum::<f64>()
+                / forward.total;
+            let error = y - predicted;
+
+            // dE/dμ for every term, through every rule that uses it
+            let mut by_membership: Vec<Vec<f64>> =
+                self.terms.iter().map(|terms| vec![0.0; terms.len()]).collect();
+            for (r, rule) in self.rules.iter().enumerate() {
+                let by_strength = -error * (outputs[r] - predicted) / forward.total;
+                for (i, &k) in rule.iter().enumerate() {
+                    let others: f64 = rule
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(j, &kj)| forward.memberships[j][kj])
+                        .product();
+                    by_membership[i][k] += by_strength * others;
+                }
+            }
+
+            for (i, terms) in self.terms.iter().enumerate() {
+                for (k, mf) in terms.iter().enumerate() {
+                    if by_membership[i][k] == 0.0 {
+                        continue;
+                    }
+                    for (g, p) in gradient[i][k].iter_mut().zip(parameters(&mf.gradient(x[i]))) {
+                        *g += by_membership[i][k] * p;
+                    }
+                }
+            }
+        }
+        gradient
+    }
+
+    /// Move the premise parameters `step` (a fraction of each input range)
+    /// against the gradient
+    fn descend(&mut self, gradient: &[Vec<Vec<f64>>], step: f64, ranges: &[(f64, f64)]) {
+        // Positions and widths scale with the input range, bell slopes do not
+        let scales: Vec<Vec<Vec<f64>>> = self
+            .terms
+            .iter()
+            .zip(ranges)
+            .map(|(terms, &(min, max))| {
+                let span = max - min;
+                terms
+                    .iter()
+                    .map(|mf| match mf {
+                        MembershipFunction::Bell { .. } => vec![span, 1.0, span],
+                        mf => vec![span; parameters(mf).len()],
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let norm = gradient
+            .iter()
+            .flatten()
+            .flatten()
+            .zip(scales.iter().flatten().flatten())
+            .map(|(g, s)| (g * s).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if norm == 0.0 || !norm.is_finite() {
+            return;
+        }
+
+        for (i, terms) in self.terms.iter_mut().enumerate() {
+            let span = ranges[i].1 - ranges[i].0;
+            for (k, mf) in terms.iter_mut().enumerate() {
+                let mut params = parameters(mf);
+                for (p, param) in params.iter_mut().enumerate() {
+                    let scale = scales[i][k][p];
+                    *param -= step * scale * scale * gradient[i][k][p] / norm;
+                }
+                *mf = constrained(mf, &params, span);
+            }
+        }
+    }
+}
+
+fn initial_term(shape: PremiseShape, center: f64, spacing: f64) -> MembershipFunction {
+    match shape {
+        // Neighbouring terms cross at 0.5, halfway between their centers
+        PremiseShape::Gaussian => MembershipFunction::Gaussian {
+            mean: center,
+            sigma: spacing / (2.0 * (2.0 * std::f64::consts::LN_2).sqrt()),
+        },
+        PremiseShape::Bell => MembershipFunction::Bell {
+            a: spacing / 2.0,
+            b: 2.0,
+            c: center,
+        },
+        PremiseShape::Triangular => MembershipFunction::Triangular {
+            a: center - spacing,
+            b: center,
+            c: center + spacing,
+        },
+    }
+}
+
+/// Trainable parameters of a premise term in declaration order, or the
+/// matching partials when given its [`MembershipFunction::gradient`]
+fn parameters(mf: &MembershipFunction) -> Vec<f64> {
+    match *mf {
+        MembershipFunction::Gaussian { mean, sigma } => vec![mean, sigma],
+        MembershipFunction::Bell { a, b, c } | MembershipFunction::Triangular { a, b, c } => {
+            vec![a, b, c]
+        }
+        _ => unreachable!("ANFIS premise terms are Gaussian, bell or triangular"),
+    }
+}
+
+/// `mf` with new parameters, kept valid: widths stay above a thousandth of
+/// the input range and triangles stay ordered around their peak
+fn constrained(mf: &MembershipFunction, params: &[f64], span: f64) -> MembershipFunction {
+    let min_width = 1e-3 * span;
+    match mf {
+        MembershipFunction::Gaussian { .. } => MembershipFunction::Gaussian {
+            mean: params[0],
+            sigma: params[1].max(min_width),
+        },
+        MembershipFunction::Bell { .. } => MembershipFunction::Bell {
+            a: params[0].max(min_width),
+            b: params[1].max(0.1),
+            c: params[2],
+        },
+        MembershipFunction::Triangular { .. } => MembershipFunction::Triangular {
+            a: params[0].min(params[1] - min_width),
+            b: params[1],
+            c: params[2].max(params[1] + min_width),
+        },
+        _ => unreachable!("ANFIS premise terms are Gaussian, bell or triangular"),
+    }
+}
+
+/// Jang's step size rules over the training errors since the last change
+fn adapt_step(step: f64, errors: &[f64]) -> Option<f64> {
+    if errors.len() < 5 {
+        return None;
+    }
+    let falls: Vec<bool> = errors.windows(2).map(|w| w[1] < w[0]).collect();
+    let recent = &falls[falls.len() - 4..];
+    if recent.iter().all(|&fall| fall) {
+        Some(step * 1.1)
+    } else if recent.windows(2).all(|w| w[0] != w[1]) {
+        Some(step * 0.9)
+    } else {
+        None
+    }
+}
+
+fn validate<S: AsRef<[f64]>>(inputs: &[S], targets: &[f64], config: &AnfisConfig) -> Result<usize, AnfisError> {
+    if config.terms_per_input == 0 {
+        return Err(AnfisError::InvalidConfig("terms_per_input must be at least 1"));
+    }
+    if config.epochs == 0 {
+        return Err(AnfisError::InvalidConfig("epochs must be at least 1"));
+    }
+    if !(config.step_size > 0.0 && config.step_size.is_finite()) {
+        return Err(AnfisError::InvalidConfig("step_size must be positive"));
+    }
+    if !(0.0..1.0).contains(&config.validation_fraction) {
+        return Err(AnfisError::InvalidConfig("validation_fraction must be in [0, 1)"));
+    }
+    if inputs.len() != targets.len() {
+        return Err(AnfisError::LengthMismatch {
+            samples: inputs.len(),
+            targets: targets.len(),
+        });
+    }
+    let Some(first) = inputs.first() else {
+        return Err(AnfisError::NoSamples);
+    };
+    let dimensions = first.as_ref().len();
+    if dimensions == 0 {
+        return Err(AnfisError::NoInputs);
+    }
+    let parameters = u32::try_from(dimensions)
+        .ok()
+        .and_then(|d| config.terms_per_input.checked_pow(d))
+        .and_then(|rules| rules.checked_mul(dimensions + 1));
+    if parameters.is_none_or(|p| p > MAX_PARAMETERS) {
+        return Err(AnfisError::InvalidConfig("rules × (inputs + 1) exceeds MAX_PARAMETERS"));
+    }
+    for (sample, (x, y)) in inputs.iter().zip(targets).enumerate() {
+        let x = x.as_ref();
+        if x.len() != dimensions {
+            return Err(AnfisError::InconsistentSample {
+                sample,
+                expected: dimensions,
+                actual: x.len(),
+            });
+        }
+        if !y.is_finite() || x.iter().any(|v| !v.is_finite()) {
+            return Err(AnfisError::NonFinite(sample));
+        }
+    }
+    Ok(dimensions)
+}
+
+/// Train a first-order Sugeno system on `inputs` (one slice of input values
+/// per sample) and `targets`
+pub fn train<S: AsRef<[f64]>>(
+    inputs: &[S],
+    targets: &[f64],
+    config: &AnfisConfig,
+) -> Result<AnfisModel, AnfisError> {
+    let dimensions = validate(inputs, targets, config)?;
+
+    let ranges: Vec<(f64, f64)> = (0..dimensions)
+        .map(|i| {
+            let (min, max) = inputs
+                .iter()
+                .map(|x| x.as_ref()[i])
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+            // A constant input still needs terms of some width
+            if max > min {
+                (min, max)
+            } else {
+                (min - 0.5, max + 0.5)
+            }
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..inputs.len()).collect();
+    Rng::new(config.seed).shuffle(&mut order);
+    let mut held_out = (inputs.len() as f64 * config.validation_fraction).round() as usize;
+    if config.validation_fraction > 0.0 {
+        held_out = held_out.max(1);
+    }
+    if held_out >= inputs.len() {
+        return Err(AnfisError::TooFewSamples(inputs.len()));
+    }
+    let sample = |&i: &usize| (inputs[i].as_ref(), targets[i]);
+    let validation: Vec<(&[f64], f64)> = order[..held_out].iter().map(sample).collect();
+    let training: Vec<(&[f64], f64)> = order[held_out..].iter().map(sample).collect();
+
+    let mut network = Network::new(config.shape, config.terms_per_input, &ranges);
+    let mut best = network.clone();
+    let mut best_error = f64::INFINITY;
+    let mut best_epoch = 1;
+    let mut stopped_early = false;
+    let mut step = config.step_size;
+    let mut errors_since_change = Vec::new();
+    let mut records = Vec::new();
+
+    for epoch in 1..=config.epochs {
+        network.fit_consequents(&training);
+        let training_rmse = network.rmse(&training);
+        let validation_rmse = (!validation.is_empty()).then(|| network.rmse(&validation));
+
+        errors_since_change.push(training_rmse);
+        if let Some(changed) = adapt_step(step, &errors_since_change) {
+            step = changed;
+            errors_since_change = vec![training_rmse];
+        }
+        records.push(EpochRecord {
+            epoch,
+            training_rmse,
+            validation_rmse,
+            step_size: step,
+        });
+
+        let monitored = validation_rmse.unwrap_or(training_rmse);
+        if monitored < best_error {
+            best_error = monitored;
+            best_epoch = epoch;
+            best = network.clone();
+        } else if epoch - best_epoch >= config.patience {
+            stopped_early = true;
+            break;
+        }
+
+        if epoch < config.epochs {
+            let gradient = network.premise_gradient(&training);
+            network.descend(&gradient, step, &ranges);
+        }
+    }
+
+    let report = TrainingReport {
+        rules: best.rules.len(),
+        training_samples: training.len(),
+        validation_samples: validation.len(),
+        epochs: records,
+        best_epoch,
+        stopped_early,
+    };
+    Ok(AnfisModel {
+        system: best.into_system(),
+        report,
+    })
+}
+
+impl Network {
+    fn into_system(self) -> OwnedSugenoSystem {
+        let width = self.terms.len() + 1;
+        let mut system = OwnedSugenoSystem::new(self.terms).with_and_method(TNorm::AlgebraicProduct);
+        for (r, rule) in self.rules.iter().enumerate() {
+            let params = &self.consequents[r * width..(r + 1) * width];
+            system.add_rule(OwnedSugenoRule::linear(
+                rule.iter().map(|&k| Some(k)).collect(),
+                params[..width - 1].to_vec(),
+                params[width - 1],
+            ));
+        }
+        system
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples<'a>(inputs: &'a [[f64; 2]], targets: &[f64]) -> Vec<(&'a [f64], f64)> {
+        inputs.iter().map(|x| &x[..]).zip(targets.iter().copied()).collect()
+    }
+
+    #[test]
+    fn test_least_squares_fits_linear_data_exactly() {
+        let inputs: Vec<[f64; 2]> = (0..25).map(|i| [(i % 5) as f64, (i / 5) as f64]).collect();
+        let targets: Vec<f64> = inputs.iter().map(|x| 3.0 * x[0] - 2.0 * x[1] + 1.0).collect();
+        let training = samples(&inputs, &targets);
+
+        let mut network = Network::new(PremiseShape::Gaussian, 2, &[(0.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(network.rules.len(), 4);
+        network.fit_consequents(&training);
+        assert!(network.rmse(&training) < 1e-6);
+    }
+
+    #[test]
+    fn test_gradient_step_reduces_error() {
+        let inputs: Vec<[f64; 2]> = (0..49).map(|i| [(i % 7) as f64, (i / 7) as f64]).collect();
+        let targets: Vec<f64> = inputs.iter().map(|x| x[0].sin() * x[1]).collect();
+        let training = samples(&inputs, &targets);
+        let ranges = [(0.0, 6.0), (0.0, 6.0)];
+
+        for shape in [PremiseShape::Gaussian, PremiseShape::Bell, PremiseShape::Triangular] {
+            let mut network = Network::new(shape, 3, &ranges);
+            network.fit_consequents(&training);
+            let before = network.rmse(&training);
+            let gradient = network.premise_gradient(&training);
+            network.descend(&gradient, 1e-3, &ranges);
+            assert!(network.rmse(&training) < before, "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn test_step_size_rules() {
+        assert_eq!(adapt_step(1.0, &[5.0, 4.0, 3.0, 2.0]), None);
+        assert_eq!(adapt_step(1.0, &[5.0, 4.0, 3.0, 2.0, 1.0]), Some(1.1));
+        assert_eq!(adapt_step(1.0, &[5.0, 4.0, 4.5, 4.2, 4.4]), Some(0.9));
+        assert_eq!(adapt_step(1.0, &[5.0, 4.0, 4.5, 4.2, 4.1]), None);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let config = AnfisConfig::default();
+        let empty: [[f64; 1]; 0] = [];
+        assert_eq!(train(&empty, &[], &config).unwrap_err(), AnfisError::NoSamples);
+        assert_eq!(
+            train(&[[1.0], [2.0]], &[1.0], &config).unwrap_err(),
+            AnfisError::LengthMismatch { samples: 2, targets: 1 }
+        );
+        assert_eq!(
+            train(&[vec![1.0], vec![2.0, 3.0]], &[1.0, 2.0], &config).unwrap_err(),
+            AnfisError::InconsistentSample { sample: 1, expected: 1, actual: 2 }
+        );
+        assert_eq!(
+            train(&[[1.0], [f64::NAN]], &[1.0, 2.0], &config).unwrap_err(),
+            AnfisError::NonFinite(1)
+        );
+        assert_eq!(train(&[[1.0]], &[1.0], &config).unwrap_err(), AnfisError::TooFewSamples(1));
+        let bad = AnfisConfig { validation_fraction: 1.0, ..AnfisConfig::default() };
+        assert!(matches!(train(&[[1.0]], &[1.0], &bad), Err(AnfisError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_rule_base_is_bounded() {
+        // 3^6 rules × 7 = 5103 parameters is over the limit, 3^4 × 5 = 405
+        // is not, and 2^7 × 8 = 1024 is the limit itself
+        let config = AnfisConfig::default();
+        let x = [[0.0; 6], [1.0; 6]];
+        assert_eq!(
+            validate(&x, &[0.0, 1.0], &config),
+            Err(AnfisError::InvalidConfig("rules × (inputs + 1) exceeds MAX_PARAMETERS"))
+        );
+        assert_eq!(validate(&[[0.0; 4], [1.0; 4]], &[0.0, 1.0], &config), Ok(4));
+        let two = AnfisConfig { terms_per_input: 2, ..config.clone() };
+        assert_eq!(validate(&[[0.0; 7], [1.0; 7]], &[0.0, 1.0], &two), Ok(7));
+        assert!(validate(&[[0.0; 8], [1.0; 8]], &[0.0, 1.0], &two).is_err());
+        // A power that overflows usize is rejected, not wrapped
+        let wide = AnfisConfig { terms_per_input: usize::MAX, ..config };
+        assert!(matches!(validate(&x, &[0.0, 1.0], &wide), Err(AnfisError::InvalidConfig(_))));
+    }
+}
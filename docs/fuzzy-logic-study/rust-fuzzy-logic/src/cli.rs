@@ -0,0 +1,670 @@
+//! Command-Line Subcommands
+//!
+//! Non-interactive commands for working with system definition files from
+//! shell scripts and CI. Every command returns a process exit code:
+//!
+//! - `0` success
+//! - `1` a check failed (`validate --strict` found problems)
+//! - `2` invalid command-line arguments
+//! - `3` the system definition could not be read or is invalid
+//! - `4` invalid input data, or output could not be written
+
+mod json;
+mod repl;
+#[cfg(feature = "server")]
+mod server;
+mod stream;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use fuzzy_logic::analysis::{analyze, AnalysisConfig};
+use fuzzy_logic::definition::{
+    defuzzification_name, format_membership_function, format_rule, format_system, load_system,
+    parse_draft, snorm_name, tnorm_name,
+};
+use fuzzy_logic::plot::{plot_variable, stack_svg};
+use fuzzy_logic::simulation::Scenario;
+use fuzzy_logic::surface::{ResponseCurve, ResponseSurface};
+use fuzzy_logic::wang_mendel::extract_rules;
+use fuzzy_logic::FuzzySystem;
+
+use stream::{evaluate_stream, split_csv, Format, OnError, StreamOptions};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_CHECK_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_DEFINITION: i32 = 3;
+pub const EXIT_DATA: i32 = 4;
+
+const USAGE: &str = "\
+Usage: fuzzy_logic [COMMAND] [ARGS]
+
+Without a command, starts the interactive lessons.
+
+Commands:
+  eval <system> [VALUES...]      Evaluate inputs given as `name=value` or in
+                                 input order
+  eval <system> [--input FILE] [--format csv|jsonl] [--map COLUMN=INPUT]...
+       [--passthrough] [--trace] [--on-error fail|skip|emit]
+                                 Without values, evaluate every CSV row (with
+                                 a header) or JSON-lines record from FILE or
+                                 stdin and append the outputs. Columns feed
+                                 the input of the same name unless mapped;
+                                 --passthrough keeps unmapped columns, --trace
+                                 adds term memberships and rule strengths
+  validate <system> [--strict] [--threshold T] [--samples N]
+                                 Check a definition and analyse its rule base;
+                                 with --strict, analysis findings fail the check
+  describe <system>              Print variables, terms, settings and rules
+  surface <system> [--x INPUT] [--y INPUT] [--output NAME] [--steps N]
+          [--format csv|gnuplot|svg] [--out FILE]
+                                 Export a response surface (a response curve
+                                 for single-input systems)
+  plot <system> [--variable NAME] [--format ascii|braille|svg]
+       [--width W] [--height H] [--out FILE]
+                                 Plot the terms of every (or one) variable
+  extract <template> [--data FILE] [--out FILE]
+                                 Generate the rules of a template system from
+                                 CSV samples (from FILE or stdin, with a header
+                                 naming every input and output) by the
+                                 Wang-Mendel method and write the definition;
+                                 the extraction report goes to stderr
+  simulate <scenario> [--out FILE]
+                                 Run a closed-loop scenario and write the
+                                 trace as CSV; the response metrics go to
+                                 stderr
+  repl [system]                  Build and query a system interactively,
+                                 optionally starting from a definition file
+  serve <systems...> [--addr ADDR] [--watch-interval MS]
+                                 Serve definition files (or directories of
+                                 .fuzzy files) over HTTP/JSON, reloading them
+                                 when they change; 0 disables watching
+                                 (needs the server feature)
+  help                           Show this message
+
+Exit codes: 0 success, 1 check failed, 2 usage error,
+            3 invalid system definition, 4 invalid input data or I/O error
+";
+
+/// A failed command, carrying its exit code
+#[derive(Debug)]
+pub enum CliError {
+    CheckFailed(String),
+    Usage(String),
+    Definition(String),
+    Data(String),
+    /// The reader of our output went away (e.g. `| head`); not an error
+    Closed,
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::CheckFailed(_) => EXIT_CHECK_FAILED,
+            CliError::Usage(_) => EXIT_USAGE,
+            CliError::Definition(_) => EXIT_DEFINITION,
+            CliError::Data(_) => EXIT_DATA,
+            CliError::Closed => EXIT_OK,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::CheckFailed(m)
+            | CliError::Usage(m)
+            | CliError::Definition(m)
+            | CliError::Data(m) => write!(f, "{}", m),
+            CliError::Closed => write!(f, "output closed"),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::BrokenPipe {
+            return CliError::Closed;
+        }
+        CliError::Data(err.to_string())
+    }
+}
+
+/// Run a command and return the process exit code
+///
+/// `args` excludes the program name.
+pub fn run(args: &[String]) -> i32 {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let result = dispatch(args, &mut stdin.lock(), &mut stdout.lock());
+    match result {
+        Ok(()) | Err(CliError::Closed) => EXIT_OK,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            if let CliError::Usage(_) = err {
+                eprintln!("Run `fuzzy_logic help` for usage.");
+            }
+            err.exit_code()
+        }
+    }
+}
+
+fn dispatch(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), CliError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(CliError::Usage("missing command".to_string()));
+    };
+    match command.as_str() {
+        "eval" => eval(
+            &Options::parse(
+                rest,
+                &["input", "format", "map", "on-error"],
+                &["passthrough", "trace"],
+            )?,
+            input,
+            out,
+        ),
+        "validate" => validate(&Options::parse(rest, &["threshold", "samples"], &["strict"])?, out),
+        "describe" => describe(&Options::parse(rest, &[], &[])?, out),
+        "surface" => surface(
+            &Options::parse(rest, &["x", "y", "output", "steps", "format", "out"], &[])?,
+            out,
+        ),
+        "plot" => plot(
+            &Options::parse(rest, &["variable", "format", "width", "height", "out"], &[])?,
+            out,
+        ),
+        "extract" => extract(&Options::parse(rest, &["data", "out"], &[])?, input, out),
+        "simulate" => simulate(&Options::parse(rest, &["out"], &[])?, out),
+        "repl" => start_repl(&Options::parse(rest, &[], &[])?, input, out),
+        "serve" => serve(&Options::parse(rest, &["addr", "watch-interval"], &[])?, out),
+        "help" | "--help" | "-h" => {
+            write!(out, "{}", USAGE)?;
+            Ok(())
+        }
+        other => Err(CliError::Usage(format!("unknown command '{}'", other))),
+    }
+}
+
+/// Start the REPL on the terminal with an empty system
+pub fn run_repl() -> io::Result<()> {
+    let mut session = repl::Session::new(FuzzySystem::new());
+    repl::run(&mut session, &mut io::stdin().lock(), &mut io::stdout().lock())
+}
+
+fn start_repl(options: &Options, input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), CliError> {
+    let system = match options.positional.as_slice() {
+        [] => FuzzySystem::new(),
+        [path] => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| CliError::Definition(format!("{}: {}", path, e)))?;
+            parse_draft(&text).map_err(|e| CliError::Definition(format!("{}: {}", path, e)))?
+        }
+        [_, extra, ..] => return Err(CliError::Usage(format!("unexpected argument '{}'", extra))),
+    };
+    repl::run(&mut repl::Session::new(system), input, out)?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+fn serve(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    if options.positional.is_empty() {
+        return Err(CliError::Usage("missing system definition file or directory".to_string()));
+    }
+    let addr = options.value("addr").unwrap_or("127.0.0.1:8080");
+    let interval: u64 = options.number("watch-interval", 1000)?;
+
+    let mut registry = server::Registry::new(options.positional.iter().map(Into::into).collect());
+    for change in registry.refresh() {
+        eprintln!("{}", change);
+    }
+    if let Some((name, error)) = registry.errors().into_iter().next() {
+        return Err(CliError::Definition(format!("{}: {}", name, error)));
+    }
+    if registry.is_empty() {
+        return Err(CliError::Definition("no system definitions found".to_string()));
+    }
+
+    let listener = std::net::TcpListener::bind(addr)
+        .map_err(|e| CliError::Data(format!("cannot listen on {}: {}", addr, e)))?;
+    writeln!(out, "listening on http://{}", listener.local_addr()?)?;
+    out.flush()?;
+    let interval = (interval > 0).then(|| std::time::Duration::from_millis(interval));
+    server::serve(listener, registry, interval)
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_options: &Options, _out: &mut dyn Write) -> Result<(), CliError> {
+    Err(CliError::Usage("built without the server feature".to_string()))
+}
+
+/// Positional arguments plus `--name value` options and `--flag` switches
+///
+/// Options may be repeated; [`Options::value`] returns the last occurrence.
+struct Options {
+    positional: Vec<String>,
+    values: HashMap<String, Vec<String>>,
+    flags: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String], value_names: &[&str], flag_names: &[&str]) -> Result<Self, CliError> {
+        let mut options = Options {
+            positional: Vec::new(),
+            values: HashMap::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                options.positional.push(arg.clone());
+                continue;
+            };
+            let (name, inline) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if flag_names.contains(&name) && inline.is_none() {
+                options.flags.push(name.to_string());
+            } else if value_names.contains(&name) {
+                let value = match inline {
+                    Some(value) => value,
+                    None => iter
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?,
+                };
+                options.values.entry(name.to_string()).or_default().push(value);
+            } else {
+                return Err(CliError::Usage(format!("unknown option '--{}'", name)));
+            }
+        }
+        Ok(options)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|v| v.last()).map(String::as_str)
+    }
+
+    /// Every occurrence of a repeatable option
+    fn all(&self, name: &str) -> &[String] {
+        self.values.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Parse an option value, falling back to `default` when absent
+    fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.value(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| CliError::Usage(format!("invalid value '{}' for --{}", v, name))),
+            None => Ok(default),
+        }
+    }
+
+    /// Load the system named by the first positional argument
+    fn system(&self) -> Result<(FuzzySystem, &[String]), CliError> {
+        let Some((path, rest)) = self.positional.split_first() else {
+            return Err(CliError::Usage("missing system definition file".to_string()));
+        };
+        let system = load_system(path).map_err(|e| CliError::Definition(format!("{}: {}", path, e)))?;
+        Ok((system, rest))
+    }
+
+    /// Write to `--out`, or to stdout when absent
+    fn emit(&self, content: &str, out: &mut dyn Write) -> Result<(), CliError> {
+        match self.value("out") {
+            Some(path) => std::fs::write(path, content)
+                .map_err(|e| CliError::Data(format!("cannot write {}: {}", path, e))),
+            None => {
+                out.write_all(content.as_bytes())?;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn input_names(system: &FuzzySystem) -> Vec<&str> {
+    system.inputs().iter().map(|v| v.name.as_str()).collect()
+}
+
+fn output_names(system: &FuzzySystem) -> Vec<&str> {
+    system.outputs().iter().map(|v| v.name.as_str()).collect()
+}
+
+fn parse_value(text: &str, context: &str) -> Result<f64, CliError> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| CliError::Data(format!("{}: '{}' is not a number", context, text.trim())))
+}
+
+fn eval(options: &Options, input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), CliError> {
+    let (system, values) = options.system()?;
+    if values.is_empty() {
+        return eval_stream(&system, options, input, out);
+    }
+    let streaming = ["input", "format", "map", "on-error"]
+        .iter()
+        .any(|name| options.value(name).is_some())
+        || options.flag("trace")
+        || options.flag("passthrough");
+    if streaming {
+        return Err(CliError::Usage(
+            "record options cannot be combined with input values".to_string(),
+        ));
+    }
+
+    let point = parse_point(&system, values)?;
+    for (name, value) in output_names(&system).iter().zip(system.evaluate(&point)) {
+        writeln!(out, "{}={}", name, value)?;
+    }
+    Ok(())
+}
+
+/// Input values given as `name=value` or in input order
+fn parse_point(system: &FuzzySystem, values: &[String]) -> Result<Vec<f64>, CliError> {
+    let names = input_names(system);
+    let mut point: Vec<Option<f64>> = vec![None; names.len()];
+    for (i, arg) in values.iter().enumerate() {
+        let (index, text) = match arg.split_once('=') {
+            Some((name, text)) => {
+                let index = system
+                    .input_index(name)
+                    .ok_or_else(|| CliError::Usage(format!("unknown input '{}'", name)))?;
+                (index, text)
+            }
+            None if i < names.len() => (i, arg.as_str()),
+            None => return Err(CliError::Usage(format!("too many input values ({} inputs)", names.len()))),
+        };
+        point[index] = Some(parse_value(text, names[index])?);
+    }
+    point
+        .iter()
+        .zip(&names)
+        .map(|(v, name)| v.ok_or_else(|| CliError::Usage(format!("missing value for input '{}'", name))))
+        .collect()
+}
+
+/// Evaluate a stream of CSV or JSON-lines records from `--input` or stdin
+fn eval_stream(
+    system: &FuzzySystem,
+    options: &Options,
+    stdin: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<(), CliError> {
+    let path = options.value("input");
+    let format = match options.value("format") {
+        Some(name) => Format::parse(name)
+            .ok_or_else(|| CliError::Usage(format!("unknown record format '{}'", name)))?,
+        None => path.map_or(Format::Csv, Format::from_path),
+    };
+    let on_error = match options.value("on-error") {
+        Some(name) => OnError::parse(name)
+            .ok_or_else(|| CliError::Usage(format!("unknown --on-error mode '{}'", name)))?,
+        None => OnError::Fail,
+    };
+    let mut mapping = Vec::new();
+    for pair in options.all("map").iter().flat_map(|m| m.split(',')) {
+        let (column, input) = pair
+            .split_once('=')
+            .ok_or_else(|| CliError::Usage(format!("--map expects COLUMN=INPUT, got '{}'", pair)))?;
+        mapping.push((column.trim().to_string(), input.trim().to_string()));
+    }
+    let stream_options = StreamOptions {
+        format,
+        mapping,
+        trace: options.flag("trace"),
+        on_error,
+        passthrough: options.flag("passthrough"),
+    };
+
+    let mut file;
+    let input: &mut dyn BufRead = match path {
+        Some(path) => {
+            let opened = File::open(path).map_err(|e| CliError::Data(format!("cannot read {}: {}", path, e)))?;
+            file = BufReader::new(opened);
+            &mut file
+        }
+        None => stdin,
+    };
+    let mut writer = BufWriter::new(out);
+    let summary = evaluate_stream(system, &stream_options, input, &mut writer)?;
+    writer.flush()?;
+    if summary.failed > 0 {
+        eprintln!("warning: {} of {} records failed", summary.failed, summary.records);
+    }
+    Ok(())
+}
+
+fn validate(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    let (system, extra) = options.system()?;
+    if !extra.is_empty() {
+        return Err(CliError::Usage(format!("unexpected argument '{}'", extra[0])));
+    }
+    let defaults = AnalysisConfig::default();
+    let config = AnalysisConfig {
+        threshold: options.number("threshold", defaults.threshold)?,
+        samples_per_input: options.number("samples", defaults.samples_per_input)?,
+    };
+    if config.samples_per_input < 2 {
+        return Err(CliError::Usage("--samples must be at least 2".to_string()));
+    }
+
+    writeln!(
+        out,
+        "{}: inputs {}, outputs {}, rules {}",
+        options.positional[0],
+        system.inputs().len(),
+        system.outputs().len(),
+        system.rules().len()
+    )?;
+    let report = analyze(&system, &config);
+    write!(out, "{}", report)?;
+
+    if options.flag("strict") && !report.is_clean() {
+        return Err(CliError::CheckFailed("rule base analysis found problems".to_string()));
+    }
+    Ok(())
+}
+
+fn describe(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    let (system, extra) = options.system()?;
+    if let Some(arg) = extra.first() {
+        return Err(CliError::Usage(format!("unexpected argument '{}'", arg)));
+    }
+    write_description(&system, out)?;
+    Ok(())
+}
+
+/// Variables, terms, settings and numbered rules of a system
+fn write_description(system: &FuzzySystem, out: &mut dyn Write) -> io::Result<()> {
+    let sections = [("Inputs", system.inputs()), ("Outputs", system.outputs())];
+    for (title, variables) in sections {
+        writeln!(out, "{}:", title)?;
+        for variable in variables {
+            writeln!(out, "  {} [{}, {}]", variable.name, variable.min, variable.max)?;
+            for term in &variable.terms {
+                writeln!(
+                    out,
+                    "    {:<12} {}",
+                    term.name,
+                    format_membership_function(&term.membership_function)
+                )?;
+            }
+        }
+    }
+
+    writeln!(out, "Settings:")?;
+    writeln!(out, "  and:             {}", tnorm_name(system.and_method()))?;
+    writeln!(out, "  or:              {}", snorm_name(system.or_method()))?;
+    writeln!(out, "  implication:     {}", tnorm_name(system.implication()))?;
+    writeln!(out, "  aggregation:     {}", snorm_name(system.aggregation()))?;
+    writeln!(out, "  defuzzification: {}", defuzzification_name(system.defuzzification()))?;
+    writeln!(out, "  resolution:      {}", system.resolution())?;
+
+    writeln!(out, "Rules:")?;
+    for (i, rule) in system.rules().iter().enumerate() {
+        writeln!(out, "  {}. {}", i + 1, format_rule(system, rule))?;
+    }
+    Ok(())
+}
+
+fn find(
+    name: Option<&str>,
+    index: impl Fn(&str) -> Option<usize>,
+    default: usize,
+    kind: &str,
+) -> Result<usize, CliError> {
+    match name {
+        Some(name) => index(name).ok_or_else(|| CliError::Usage(format!("unknown {} '{}'", kind, name))),
+        None => Ok(default),
+    }
+}
+
+fn surface(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    let (system, extra) = options.system()?;
+    if let Some(arg) = extra.first() {
+        return Err(CliError::Usage(format!("unexpected argument '{}'", arg)));
+    }
+    let x = find(options.value("x"), |n| system.input_index(n), 0, "input")?;
+    let output = find(options.value("output"), |n| system.output_index(n), 0, "output")?;
+    let steps: usize = options.number("steps", 41)?;
+    if steps < 2 {
+        return Err(CliError::Usage("--steps must be at least 2".to_string()));
+    }
+    let format = options.value("format").unwrap_or("csv");
+
+    let content = if system.inputs().len() == 1 {
+        let curve = ResponseCurve::sweep(&system, x, output, &[], steps);
+        match format {
+            "csv" => curve.to_csv(),
+            "gnuplot" => curve.to_gnuplot(),
+            "svg" => curve.to_svg(),
+            other => return Err(CliError::Usage(format!("unknown format '{}'", other))),
+        }
+    } else {
+        let other_input = if x == 0 { 1 } else { 0 };
+        let y = find(options.value("y"), |n| system.input_index(n), other_input, "input")?;
+        if x == y {
+            return Err(CliError::Usage("--x and --y must be different inputs".to_string()));
+        }
+        let surface = ResponseSurface::sweep(&system, x, y, output, &[], steps);
+        match format {
+            "csv" => surface.to_csv(),
+            "gnuplot" => surface.to_gnuplot(),
+            "svg" => surface.to_svg(),
+            other => return Err(CliError::Usage(format!("unknown format '{}'", other))),
+        }
+    };
+    options.emit(&content, out)
+}
+
+fn plot(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    let (system, extra) = options.system()?;
+    if let Some(arg) = extra.first() {
+        return Err(CliError::Usage(format!("unexpected argument '{}'", arg)));
+    }
+    let variables: Vec<_> = system.inputs().iter().chain(system.outputs()).collect();
+    let selected: Vec<_> = match options.value("variable") {
+        Some(name) => {
+            let variable = variables
+                .iter()
+                .find(|v| v.name == name)
+                .ok_or_else(|| CliError::Usage(format!("unknown variable '{}'", name)))?;
+            vec![*variable]
+        }
+        None => variables,
+    };
+    let width: usize = options.number("width", 60)?;
+    let height: usize = options.number("height", 12)?;
+    let plots: Vec<_> = selected.iter().map(|v| plot_variable(v, 201)).collect();
+
+    let content = match options.value("format").unwrap_or("ascii") {
+        "ascii" => plots.iter().map(|p| p.to_ascii(width, height)).collect::<Vec<_>>().join("\n"),
+        "braille" => plots.iter().map(|p| p.to_braille(width, height)).collect::<Vec<_>>().join("\n"),
+        "svg" => stack_svg(&plots),
+        other => return Err(CliError::Usage(format!("unknown format '{}'", other))),
+    };
+    options.emit(&content, out)
+}
+
+fn extract(options: &Options, stdin: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), CliError> {
+    let (template, extra) = options.system()?;
+    if let Some(arg) = extra.first() {
+        return Err(CliError::Usage(format!("unexpected argument '{}'", arg)));
+    }
+
+    let mut file;
+    let input: &mut dyn BufRead = match options.value("data") {
+        Some(path) => {
+            let opened = File::open(path).map_err(|e| CliError::Data(format!("cannot read {}: {}", path, e)))?;
+            file = BufReader::new(opened);
+            &mut file
+        }
+        None => stdin,
+    };
+    let mut lines = input.lines().enumerate();
+    let header = match lines.next() {
+        Some((_, line)) => split_csv(&line?).map_err(|e| CliError::Data(format!("line 1: {}", e)))?,
+        None => return Err(CliError::Data("the CSV input is empty".to_string())),
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| CliError::Data(format!("CSV header has no column '{}'", name)))
+    };
+    let input_columns = input_names(&template).into_iter().map(column).collect::<Result<Vec<_>, _>>()?;
+    let output_columns = output_names(&template).into_iter().map(column).collect::<Result<Vec<_>, _>>()?;
+
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    for (i, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let context = format!("line {}", i + 1);
+        let fields = split_csv(&line).map_err(|e| CliError::Data(format!("{}: {}", context, e)))?;
+        if fields.len() != header.len() {
+            return Err(CliError::Data(format!(
+                "{}: expected {} fields, got {}",
+                context,
+                header.len(),
+                fields.len()
+            )));
+        }
+        let values = |columns: &[usize]| {
+            columns
+                .iter()
+                .map(|&c| parse_value(&fields[c], &context))
+                .collect::<Result<Vec<f64>, _>>()
+        };
+        inputs.push(values(&input_columns)?);
+        outputs.push(values(&output_columns)?);
+    }
+
+    let extraction =
+        extract_rules(&template, &inputs, &outputs).map_err(|e| CliError::Data(e.to_string()))?;
+    eprint!("{}", extraction.report);
+    options.emit(&format_system(&extraction.system), out)
+}
+
+fn simulate(options: &Options, out: &mut dyn Write) -> Result<(), CliError> {
+    let path = match options.positional.as_slice() {
+        [path] => path,
+        [] => return Err(CliError::Usage("missing scenario file".to_string())),
+        [_, extra, ..] => return Err(CliError::Usage(format!("unexpected argument '{}'", extra))),
+    };
+    let mut scenario = Scenario::load(path).map_err(|e| CliError::Definition(format!("{}: {}", path, e)))?;
+    let trace = scenario.run().map_err(|e| CliError::Definition(format!("{}: {}", path, e)))?;
+    eprint!("{}", trace.metrics());
+    options.emit(&trace.to_csv(), out)
+}
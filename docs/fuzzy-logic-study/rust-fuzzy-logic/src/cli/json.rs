@@ -0,0 +1,210 @@
+//! Minimal JSON Support
+//!
+//! Just enough JSON for the command-line tool: objects are split into
+//! `(key, raw value)` pairs without building a document tree, and output
+//! is written as text.
+
+use std::fmt::Write as _;
+
+/// Append `"key":value` to an unclosed JSON object
+pub fn push_field(record: &mut String, key: &str, value: &str) {
+    if !record.ends_with('{') {
+        record.push(',');
+    }
+    let _ = write!(record, "{}:{}", json_string(key), value);
+}
+
+/// A number as JSON (`null` for NaN and infinities)
+pub fn json_value(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// A string as a quoted JSON string
+pub fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A field value as a number; numeric strings are accepted
+pub fn json_number(raw: &str, source: &str) -> Result<f64, String> {
+    if raw == "null" {
+        return Err(format!("{}: value is null", source));
+    }
+    let text = if raw.starts_with('"') { unescape(raw)? } else { raw.to_string() };
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("{}: '{}' is not a number", source, text.trim()))
+}
+
+/// Parse a flat JSON object into `(key, raw value)` pairs
+///
+/// Nested values are kept as raw text and never interpreted.
+pub fn parse_object(text: &str) -> Result<Vec<(String, &str)>, String> {
+    let invalid = |what: &str| format!("invalid JSON: {}", what);
+    let bytes = text.as_bytes();
+    let mut pos = skip_space(bytes, 0);
+    if bytes.get(pos) != Some(&b'{') {
+        return Err(invalid("expected an object"));
+    }
+    pos = skip_space(bytes, pos + 1);
+    let mut fields = Vec::new();
+    if bytes.get(pos) == Some(&b'}') {
+        pos += 1;
+    } else {
+        loop {
+            if bytes.get(pos) != Some(&b'"') {
+                return Err(invalid("expected a field name"));
+            }
+            let end = skip_value(bytes, pos).ok_or_else(|| invalid("unterminated string"))?;
+            let key = unescape(&text[pos..end])?;
+            pos = skip_space(bytes, end);
+            if bytes.get(pos) != Some(&b':') {
+                return Err(invalid("expected ':'"));
+            }
+            let start = skip_space(bytes, pos + 1);
+            let end = skip_value(bytes, start).ok_or_else(|| invalid("malformed value"))?;
+            fields.push((key, &text[start..end]));
+            pos = skip_space(bytes, end);
+            match bytes.get(pos) {
+                Some(b',') => pos = skip_space(bytes, pos + 1),
+                Some(b'}') => {
+                    pos += 1;
+                    break;
+                }
+                _ => return Err(invalid("expected ',' or '}'")),
+            }
+        }
+    }
+    if skip_space(bytes, pos) != bytes.len() {
+        return Err(invalid("trailing characters after the object"));
+    }
+    Ok(fields)
+}
+
+fn skip_space(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+        pos += 1;
+    }
+    pos
+}
+
+/// End position of the JSON value starting at `pos`
+fn skip_value(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes.get(pos)? {
+        b'"' => {
+            let mut i = pos + 1;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b'"' => return Some(i + 1),
+                    _ => i += 1,
+                }
+            }
+            None
+        }
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            let mut i = pos;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'"' => {
+                        i = skip_value(bytes, i)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            None
+        }
+        _ => {
+            let end = bytes[pos..]
+                .iter()
+                .position(|&b| b == b',' || b == b'}' || b == b']' || b.is_ascii_whitespace())
+                .map_or(bytes.len(), |n| pos + n);
+            (end > pos).then_some(end)
+        }
+    }
+}
+
+/// Decode a quoted JSON string
+pub fn unescape(raw: &str) -> Result<String, String> {
+    let inner = &raw[1..raw.len() - 1];
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('r') => text.push('\r'),
+            Some('b') => text.push('\u{8}'),
+            Some('f') => text.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16)
+                    .map_err(|_| "invalid JSON: bad \\u escape".to_string())?;
+                text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            Some(c) => text.push(c),
+            None => return Err("invalid JSON: bad escape".to_string()),
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object() {
+        let fields = parse_object(r#"{"a": 1.5, "b": "x\"y", "c": {"d": [1, 2]}, "e": null}"#).unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0], ("a".to_string(), "1.5"));
+        assert_eq!(unescape(fields[1].1).unwrap(), "x\"y");
+        assert_eq!(fields[2].1, r#"{"d": [1, 2]}"#);
+        assert!(parse_object("{\"a\": 1").is_err());
+        assert!(parse_object("[1]").is_err());
+    }
+
+    #[test]
+    fn test_json_number_and_string() {
+        assert_eq!(json_number("2.5", "x"), Ok(2.5));
+        assert_eq!(json_number("\"7\"", "x"), Ok(7.0));
+        assert!(json_number("true", "x").is_err());
+        assert_eq!(json_string("a\"b\n"), "\"a\\\"b\\n\"");
+        assert_eq!(json_value(f64::NAN), "null");
+    }
+}
@@ -0,0 +1,407 @@
+//! Interactive REPL
+//!
+//! Builds a [`FuzzySystem`] one command at a time: define variables and
+//! terms, add rules in the text form of definition files, change operators,
+//! then evaluate, trace and plot. Sessions are saved and loaded as
+//! definition files.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+use fuzzy_logic::analysis::{analyze, AnalysisConfig};
+use fuzzy_logic::definition::{
+    format_rule, format_system, parse_draft, parse_membership_function, parse_rule, set_option,
+    validate_name,
+};
+use fuzzy_logic::plot::{plot_variable, Plot};
+use fuzzy_logic::{FuzzySystem, LinguisticVariable};
+
+use super::{parse_point, write_description};
+
+const HELP: &str = "\
+Variables and rules:
+  input <name> <min> <max>                 Define an input variable
+  output <name> <min> <max>                Define an output variable
+  term <variable> <name> <function> <parameters...>
+                                           Add a term, e.g.
+                                           term service poor triangular 0 0 5
+                                           (triangular, trapezoidal, gaussian, sigmoid, bell)
+  rule if <clauses> then <conclusions> [with <weight>]
+                                           Add a rule, e.g.
+                                           rule if service is poor then tip is low
+  rules                                    List the rules
+  delete rule <n>                          Remove rule n
+Settings:
+  and min|prod|bounded|drastic             AND operator
+  or max|probor|bounded|drastic            OR operator
+  implication <t-norm>  aggregation <s-norm>
+  defuzzification centroid|mom|som|lom|bisector
+  resolution <n>                           Output samples
+Queries:
+  show                                     Describe the system
+  eval <values...>                         Evaluate (values in input order or name=value)
+  trace <values...>                        Show memberships, rule strengths and outputs
+  plot <variable>                          Plot the terms of a variable
+  plot <output> <values...>                Plot an output's aggregated set
+  analyze                                  Check coverage, conflicts and redundancy
+Session:
+  save <file>   load <file>   new   help   quit
+";
+
+/// Result of one REPL command
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// Text to print before the next prompt
+    Continue(String),
+    Quit,
+}
+
+/// A system under construction
+pub struct Session {
+    system: FuzzySystem,
+}
+
+impl Session {
+    pub fn new(system: FuzzySystem) -> Self {
+        Session { system }
+    }
+
+    /// Run one command line
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, String> {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return Ok(Outcome::Continue(String::new()));
+        };
+
+        let text = match command {
+            "help" | "?" => HELP.to_string(),
+            "quit" | "exit" => return Ok(Outcome::Quit),
+            "new" => {
+                self.system = FuzzySystem::new();
+                "started a new system\n".to_string()
+            }
+            "input" | "output" => self.define_variable(command, args)?,
+            "term" => self.define_term(args)?,
+            "rule" => {
+                let rule = parse_rule(&self.system, &line["rule".len()..]).map_err(message)?;
+                let index = self.system.add_rule(rule);
+                format!("added rule {}\n", index + 1)
+            }
+            "rules" => self.list_rules(),
+            "delete" => match args {
+                ["rule", n] => {
+                    let n: usize = n.parse().map_err(|_| format!("'{}' is not a rule number", n))?;
+                    if n == 0 || n > self.system.rules().len() {
+                        return Err(format!("there is no rule {}", n));
+                    }
+                    self.system.remove_rule(n - 1);
+                    format!("deleted rule {}\n", n)
+                }
+                _ => return Err("usage: delete rule <n>".to_string()),
+            },
+            "and" | "or" | "implication" | "aggregation" | "defuzzification" | "resolution" => {
+                let [value] = args else {
+                    return Err(format!("usage: {} <value>", command));
+                };
+                set_option(&mut self.system, command, value).map_err(message)?;
+                format!("{} set to {}\n", command, value)
+            }
+            "show" => {
+                let mut buffer = Vec::new();
+                write_description(&self.system, &mut buffer).map_err(|e| e.to_string())?;
+                String::from_utf8_lossy(&buffer).into_owned()
+            }
+            "eval" => {
+                let point = self.point(args)?;
+                let mut text = String::new();
+                for (variable, value) in self.system.outputs().iter().zip(self.system.evaluate(&point)) {
+                    let _ = writeln!(text, "{} = {:.3}", variable.name, value);
+                }
+                text
+            }
+            "trace" => self.trace(args)?,
+            "plot" => self.plot(args)?,
+            "analyze" => analyze(&self.system, &AnalysisConfig::default()).to_string(),
+            "save" => {
+                let [path] = args else {
+                    return Err("usage: save <file>".to_string());
+                };
+                std::fs::write(path, format_system(&self.system))
+                    .map_err(|e| format!("cannot write {}: {}", path, e))?;
+                format!("saved to {}\n", path)
+            }
+            "load" => {
+                let [path] = args else {
+                    return Err("usage: load <file>".to_string());
+                };
+                let text =
+                    std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+                self.system = parse_draft(&text).map_err(|e| format!("{}: {}", path, e))?;
+                format!(
+                    "loaded {} inputs, {} outputs and {} rules from {}\n",
+                    self.system.inputs().len(),
+                    self.system.outputs().len(),
+                    self.system.rules().len(),
+                    path
+                )
+            }
+            other => return Err(format!("unknown command '{}' (type `help`)", other)),
+        };
+        Ok(Outcome::Continue(text))
+    }
+
+    fn define_variable(&mut self, kind: &str, args: &[&str]) -> Result<String, String> {
+        let [name, min, max] = args else {
+            return Err(format!("usage: {} <name> <min> <max>", kind));
+        };
+        validate_name(name)?;
+        if self.system.input_index(name).is_some() || self.system.output_index(name).is_some() {
+            return Err(format!("variable '{}' is already defined", name));
+        }
+        let min: f64 = min.parse().map_err(|_| format!("'{}' is not a number", min))?;
+        let max: f64 = max.parse().map_err(|_| format!("'{}' is not a number", max))?;
+        if !(min.is_finite() && max.is_finite() && max > min) {
+            return Err("the universe maximum must be greater than the minimum".to_string());
+        }
+        let variable = LinguisticVariable::new(*name, min, max);
+        if kind == "input" {
+            self.system.add_input(variable);
+        } else {
+            self.system.add_output(variable);
+        }
+        Ok(format!("defined {} {} on [{}, {}]\n", kind, name, min, max))
+    }
+
+    fn define_term(&mut self, args: &[&str]) -> Result<String, String> {
+        let [variable, name, function @ ..] = args else {
+            return Err("usage: term <variable> <name> <function> <parameters...>".to_string());
+        };
+        validate_name(name)?;
+        let mf = parse_membership_function(function)?;
+        let existing = self
+            .variable(variable)
+            .ok_or_else(|| format!("unknown variable '{}'", variable))?;
+        if existing.term_index(name).is_some() {
+            return Err(format!("term '{}' is already defined for '{}'", name, variable));
+        }
+        if let Some(i) = self.system.input_index(variable) {
+            self.system.add_input_term(i, *name, mf);
+        } else if let Some(o) = self.system.output_index(variable) {
+            self.system.add_output_term(o, *name, mf);
+        } else {
+            return Err(format!("unknown variable '{}'", variable));
+        }
+        Ok(format!("added term {} to {}\n", name, variable))
+    }
+
+    fn variable(&self, name: &str) -> Option<&LinguisticVariable> {
+        self.system
+            .inputs()
+            .iter()
+            .chain(self.system.outputs())
+            .find(|v| v.name == name)
+    }
+
+    fn list_rules(&self) -> String {
+        if self.system.rules().is_empty() {
+            return "no rules yet\n".to_string();
+        }
+        let mut text = String::new();
+        for (i, rule) in self.system.rules().iter().enumerate() {
+            let _ = writeln!(text, "  {}. {}", i + 1, format_rule(&self.system, rule));
+        }
+        text
+    }
+
+    fn point(&self, args: &[&str]) -> Result<Vec<f64>, String> {
+        if self.system.inputs().is_empty() {
+            return Err("define an input first".to_string());
+        }
+        let values: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_point(&self.system, &values).map_err(|e| e.to_string())
+    }
+
+    fn trace(&self, args: &[&str]) -> Result<String, String> {
+        let point = self.point(args)?;
+        let mut text = String::from("Fuzzification:\n");
+        for (variable, &x) in self.system.inputs().iter().zip(&point) {
+            let _ = writeln!(text, "  {} = {}", variable.name, x);
+            for (term, mu) in variable.terms.iter().zip(variable.fuzzify(x)) {
+                let _ = writeln!(text, "    {:<12} {:.3}", term.name, mu);
+            }
+        }
+        text.push_str("Rules:\n");
+        let strengths = self.system.firing_strengths(&point);
+        for (i, (rule, strength)) in self.system.rules().iter().zip(&strengths).enumerate() {
+            let _ = writeln!(
+                text,
+                "  {:>3}. {:.3}  {}",
+                i + 1,
+                strength,
+                format_rule(&self.system, rule)
+            );
+        }
+        text.push_str("Outputs:\n");
+        for (variable, value) in self.system.outputs().iter().zip(self.system.evaluate(&point)) {
+            let _ = writeln!(text, "  {} = {:.3}", variable.name, value);
+        }
+        Ok(text)
+    }
+
+    fn plot(&self, args: &[&str]) -> Result<String, String> {
+        let Some((&name, values)) = args.split_first() else {
+            return Err("usage: plot <variable> | plot <output> <values...>".to_string());
+        };
+        let variable = self
+            .variable(name)
+            .ok_or_else(|| format!("unknown variable '{}'", name))?;
+        let output = self.system.output_index(name);
+        match (output, values.is_empty()) {
+            (_, true) => Ok(plot_variable(variable, 201).to_braille(60, 10)),
+            (Some(output), false) => {
+                let point = self.point(values)?;
+                let strengths = self.system.firing_strengths(&point);
+                let (xs, aggregated) = self.system.aggregate(output, &strengths);
+                let value = self.system.evaluate(&point)[output];
+                let plot = Plot::new(format!("{} (aggregated)", name))
+                    .with_labels(name, "Membership Degree")
+                    .with_y_range(0.0, 1.0)
+                    .with_series(name, &xs, &aggregated)
+                    .with_marker(value, format!("{} = {:.3}", name, value));
+                Ok(plot.to_braille(60, 10))
+            }
+            (None, false) => Err("values can only be given when plotting an output".to_string()),
+        }
+    }
+}
+
+fn message(err: impl std::fmt::Display) -> String {
+    // Single rules and settings have no meaningful line number
+    err.to_string().trim_start_matches("line 1: ").to_string()
+}
+
+/// Read commands from `input` until `quit` or end of input
+pub fn run(session: &mut Session, input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Fuzzy system REPL. Type `help` for commands and `quit` to leave.")?;
+    let mut line = String::new();
+    loop {
+        write!(out, "fuzzy> ")?;
+        out.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(out)?;
+            return Ok(());
+        }
+        match session.execute(&line) {
+            Ok(Outcome::Continue(text)) => write!(out, "{}", text)?,
+            Ok(Outcome::Quit) => return Ok(()),
+            Err(message) => writeln!(out, "error: {}", message)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_all(session: &mut Session, commands: &[&str]) -> String {
+        let mut text = String::new();
+        for command in commands {
+            match session.execute(command) {
+                Ok(Outcome::Continue(out)) => text.push_str(&out),
+                other => panic!("{} failed: {:?}", command, other),
+            }
+        }
+        text
+    }
+
+    fn tipping(session: &mut Session) {
+        run_all(
+            session,
+            &[
+                "input service 0 10",
+                "term service poor triangular 0 0 10",
+                "term service good triangular 0 10 10",
+                "output tip 0 30",
+                "term tip low triangular 0 0 30",
+                "term tip high triangular 0 30 30",
+                "rule if service is poor then tip is low",
+                "rule if service is good then tip is high",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_build_and_evaluate() {
+        let mut session = Session::new(FuzzySystem::new());
+        tipping(&mut session);
+
+        let low = run_all(&mut session, &["eval 1"]);
+        let high = run_all(&mut session, &["eval service=9"]);
+        let value = |s: &str| s.trim().trim_start_matches("tip = ").parse::<f64>().unwrap();
+        assert!(value(&low) < 15.0 && value(&high) > 15.0);
+
+        let trace = run_all(&mut session, &["trace 3"]);
+        assert!(trace.contains("poor         0.700"));
+        assert!(trace.contains("2. 0.300  if service is good then tip is high"));
+    }
+
+    #[test]
+    fn test_errors_leave_session_intact() {
+        let mut session = Session::new(FuzzySystem::new());
+        tipping(&mut session);
+
+        assert_eq!(
+            session.execute("rule if service is great then tip is low"),
+            Err("input 'service' has no term 'great'".to_string())
+        );
+        assert!(session.execute("term service good triangular 0 5 10").is_err());
+        assert!(session.execute("input tip 0 1").is_err());
+        assert!(session.execute("and fastest").is_err());
+        assert!(session.execute("frobnicate").is_err());
+        assert_eq!(session.system.rules().len(), 2);
+    }
+
+    #[test]
+    fn test_settings_rules_and_plots() {
+        let mut session = Session::new(FuzzySystem::new());
+        tipping(&mut session);
+
+        run_all(&mut session, &["defuzzification mom", "delete rule 1"]);
+        assert!(run_all(&mut session, &["rules"]).starts_with("  1. if service is good"));
+        assert!(run_all(&mut session, &["show"]).contains("defuzzification: mom"));
+        assert!(run_all(&mut session, &["plot service"]).contains("1. poor"));
+        assert!(run_all(&mut session, &["plot tip 8"]).contains("marker at"));
+        assert!(session.execute("plot service 3").is_err());
+        assert_eq!(session.execute("quit"), Ok(Outcome::Quit));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("fuzzy_repl_{}.fuzzy", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut session = Session::new(FuzzySystem::new());
+        tipping(&mut session);
+        run_all(&mut session, &["input unused 0 1", &format!("save {}", path)]);
+
+        let mut restored = Session::new(FuzzySystem::new());
+        let loaded = run_all(&mut restored, &[&format!("load {}", path)]);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(loaded.starts_with("loaded 2 inputs, 1 outputs and 2 rules"));
+        assert_eq!(format_system(&restored.system), format_system(&session.system));
+    }
+
+    #[test]
+    fn test_run_loop() {
+        let mut session = Session::new(FuzzySystem::new());
+        let mut out = Vec::new();
+        run(&mut session, &mut "input x 0 1\nbogus\nquit\ninput y 0 1\n".as_bytes(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("defined input x on [0, 1]"));
+        assert!(text.contains("error: unknown command 'bogus'"));
+        assert_eq!(session.system.inputs().len(), 1);
+    }
+}
@@ -0,0 +1,751 @@
+//! HTTP Evaluation Server
+//!
+//! Serves system definition files over HTTP/1.1 with JSON bodies, so other
+//! services can evaluate controllers without linking Rust:
+//!
+//! - `GET /health` reports the server status
+//! - `GET /systems` lists the loaded systems with their variables
+//! - `GET /systems/{name}` describes one system
+//! - `POST /systems/{name}/evaluate` evaluates
+//!   `{"inputs": {"service": 3, "food": 8}, "trace": true}`
+//! - `POST /reload` re-reads changed definition files immediately
+//!
+//! A system is named after its file (`tipping.fuzzy` is `tipping`). Files
+//! are polled for changes and reloaded while the server runs; a file that
+//! fails to reload keeps serving its last good version and reports the
+//! error. Files are read before the registry is locked, so requests are
+//! served while a reload reads the disk.
+//!
+//! A fixed pool of worker threads handles the connections, each closed
+//! after one response. Connections that arrive while every worker is busy
+//! queue up to a limit; beyond it they get `503` straight away. A request
+//! must arrive in full within a fixed time and keep its headers within
+//! fixed limits, or it gets `408` or `431`.
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use fuzzy_logic::definition::{format_rule, load_system};
+use fuzzy_logic::{FuzzySystem, LinguisticVariable};
+
+use super::json::{json_number, json_string, json_value, parse_object, push_field};
+use super::CliError;
+
+/// Largest request body accepted
+const MAX_BODY: usize = 1 << 20;
+
+/// How long a client may take to send its whole request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest request line or header line accepted
+const MAX_LINE: usize = 8 << 10;
+
+/// Most header lines accepted in one request
+const MAX_HEADERS: usize = 64;
+
+/// Largest total size of the header lines of one request
+const MAX_HEADER_BYTES: usize = 16 << 10;
+
+/// Extension of definition files picked up from directories
+const EXTENSION: &str = "fuzzy";
+
+/// Threads handling connections
+const WORKERS: usize = 8;
+
+/// Accepted connections waiting for a worker before new ones are refused
+const QUEUE: usize = 64;
+
+/// A definition file and the system last loaded from it
+struct Entry {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    system: Option<Arc<FuzzySystem>>,
+    error: Option<String>,
+}
+
+/// All served systems, keyed by name
+pub struct Registry {
+    sources: Vec<PathBuf>,
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Registry {
+    /// Create a registry for definition files and directories of them
+    pub fn new(sources: Vec<PathBuf>) -> Self {
+        Registry {
+            sources,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Load new and changed files and drop removed ones
+    ///
+    /// Returns one line per change, for logging.
+    pub fn refresh(&mut self) -> Vec<String> {
+        let update = Update::read(&self.sources, &self.stamps());
+        self.apply(update)
+    }
+
+    /// Path and modification time of each loaded file, by name
+    fn stamps(&self) -> BTreeMap<String, (PathBuf, Option<SystemTime>)> {
+        self.entries
+            .iter()
+            .map(|(name, e)| (name.clone(), (e.path.clone(), e.modified)))
+            .collect()
+    }
+
+    /// Bring the entries in line with files read by [`Update::read`]
+    fn apply(&mut self, update: Update) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        let removed: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|name| !update.files.iter().any(|(n, _, _)| n == *name))
+            .cloned()
+            .collect();
+        for name in removed {
+            self.entries.remove(&name);
+            changes.push(format!("removed {}", name));
+        }
+
+        for (name, result) in update.loaded {
+            let Some((_, path, modified)) = update.files.iter().find(|(n, _, _)| *n == name) else {
+                continue;
+            };
+            // Another refresh may have applied the same file meanwhile
+            if let Some(entry) = self.entries.get(&name) {
+                if entry.path == *path && entry.modified == *modified && modified.is_some() {
+                    continue;
+                }
+            }
+            let entry = self.entries.entry(name.clone()).or_insert(Entry {
+                path: path.clone(),
+                modified: None,
+                system: None,
+                error: None,
+            });
+            entry.path = path.clone();
+            entry.modified = *modified;
+            match result {
+                Ok(system) => {
+                    let verb = if entry.system.is_some() { "reloaded" } else { "loaded" };
+                    entry.system = Some(Arc::new(system));
+                    entry.error = None;
+                    changes.push(format!("{} {} from {}", verb, name, path.display()));
+                }
+                Err(message) => {
+                    changes.push(format!("failed to load {}: {}", name, message));
+                    entry.error = Some(message);
+                }
+            }
+        }
+        changes
+    }
+
+    /// Load errors by system name
+    pub fn errors(&self) -> Vec<(String, String)> {
+        self.entries
+            .iter()
+            .filter_map(|(name, e)| e.error.clone().map(|err| (name.clone(), err)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Refresh a shared registry, holding the write lock only to apply files
+/// already read from disk
+///
+/// Returns `None` if the lock is poisoned.
+fn refresh_shared(registry: &RwLock<Registry>) -> Option<Vec<String>> {
+    let (sources, stamps) = {
+        let registry = registry.read().ok()?;
+        (registry.sources.clone(), registry.stamps())
+    };
+    let update = Update::read(&sources, &stamps);
+    Some(registry.write().ok()?.apply(update))
+}
+
+/// Definition files found on disk and the new or changed ones read
+struct Update {
+    /// Every file named by the sources, with its modification time
+    files: Vec<(String, PathBuf, Option<SystemTime>)>,
+    /// Load result of each file that differs from its stamp, by name
+    loaded: Vec<(String, Result<FuzzySystem, String>)>,
+}
+
+impl Update {
+    fn read(sources: &[PathBuf], stamps: &BTreeMap<String, (PathBuf, Option<SystemTime>)>) -> Self {
+        let mut files = Vec::new();
+        let mut loaded = Vec::new();
+        for (name, path) in files_in(sources) {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            let unchanged = stamps
+                .get(&name)
+                .is_some_and(|(p, m)| *p == path && *m == modified && modified.is_some());
+            if !unchanged {
+                loaded.push((name.clone(), load_system(&path).map_err(|err| err.to_string())));
+            }
+            files.push((name, path, modified));
+        }
+        Update { files, loaded }
+    }
+}
+
+/// Definition files currently named by the sources
+fn files_in(sources: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    for source in sources {
+        if source.is_dir() {
+            let Ok(dir) = std::fs::read_dir(source) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = dir
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == EXTENSION))
+                .collect();
+            paths.sort();
+            files.extend(paths.into_iter().filter_map(|p| Some((system_name(&p)?, p))));
+        } else if let Some(name) = system_name(source) {
+            files.push((name, source.clone()));
+        }
+    }
+    files
+}
+
+fn system_name(path: &Path) -> Option<String> {
+    path.file_stem().map(|s| s.to_string_lossy().into_owned())
+}
+
+/// Serve forever on `listener`, polling files every `watch_interval`
+pub fn serve(
+    listener: TcpListener,
+    registry: Registry,
+    watch_interval: Option<Duration>,
+) -> Result<(), CliError> {
+    let registry = Arc::new(RwLock::new(registry));
+
+    if let Some(interval) = watch_interval {
+        let registry = Arc::clone(&registry);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let Some(changes) = refresh_shared(&registry) else {
+                return;
+            };
+            for change in changes {
+                eprintln!("{}", change);
+            }
+        });
+    }
+
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUE);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..WORKERS {
+        let registry = Arc::clone(&registry);
+        let receiver = Arc::clone(&receiver);
+        thread::spawn(move || loop {
+            // The queue lock is released once a connection is taken
+            let stream = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            let Ok(stream) = stream else {
+                return;
+            };
+            if let Err(err) = handle_connection(stream, &registry) {
+                eprintln!("connection error: {}", err);
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        match sender.try_send(stream) {
+            Ok(()) => {}
+            Err(TrySendError::Full(stream)) => {
+                if let Err(err) = refuse(stream) {
+                    eprintln!("connection error: {}", err);
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => break,
+        }
+    }
+    Ok(())
+}
+
+/// Answer `503` without parsing the request
+///
+/// The socket is non-blocking and the response fits in its send buffer,
+/// so a slow client cannot hold up the accept loop.
+fn refuse(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(true)?;
+    write_response(&mut stream, &Response::error(503, "server is busy"))?;
+    discard_input(&mut stream)
+}
+
+/// Close the write side and drop request bytes that already arrived
+///
+/// Closing with unread data resets the connection, which can lose the
+/// response sent just before.
+fn discard_input(stream: &mut TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(true)?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut buffer = [0; 4096];
+    for _ in 0..16 {
+        if !matches!(stream.read(&mut buffer), Ok(n) if n > 0) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// A connection read until a fixed deadline
+///
+/// Each read waits at most for the time left, so a client sending its
+/// request a byte at a time cannot hold a worker past the deadline.
+struct Deadline {
+    stream: TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.until.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request deadline passed"));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buffer)
+    }
+}
+
+/// A parsed request
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// A response status and JSON body
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(message)),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, registry: &RwLock<Registry>) -> io::Result<()> {
+    let mut reader = BufReader::new(Deadline {
+        stream: stream.try_clone()?,
+        until: Instant::now() + REQUEST_TIMEOUT,
+    });
+    let response = match read_request(&mut reader) {
+        Ok(Ok(request)) => return write_response(&mut stream, &route(&request, registry)),
+        Ok(Err(response)) => response,
+        Err(err) if matches!(err.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {
+            Response::error(408, "request timed out")
+        }
+        Err(err) => return Err(err),
+    };
+    // The request may not have been read to its end
+    write_response(&mut stream, &response)?;
+    discard_input(&mut stream)
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Read one line of at most `MAX_LINE` bytes, or `None` if it is longer
+fn read_line(reader: &mut dyn BufRead, line: &mut String) -> io::Result<Option<usize>> {
+    line.clear();
+    let n = Read::take(&mut *reader, MAX_LINE as u64).read_line(line)?;
+    if n == MAX_LINE && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(n))
+}
+
+/// Read one request; protocol errors become error responses
+fn read_request(reader: &mut dyn BufRead) -> io::Result<Result<Request, Response>> {
+    let mut line = String::new();
+    if read_line(reader, &mut line)?.is_none() {
+        return Ok(Err(Response::error(400, "request line is too long")));
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Err(Response::error(400, "malformed request line")));
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or("").to_string();
+
+    let too_large = || Ok(Err(Response::error(431, "request headers are too large")));
+    let mut length = 0;
+    let mut headers = 0;
+    let mut header_bytes = 0;
+    loop {
+        let n = match read_line(reader, &mut line)? {
+            Some(0) => break,
+            Some(n) => n,
+            None => return too_large(),
+        };
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        header_bytes += n;
+        if headers > MAX_HEADERS || header_bytes > MAX_HEADER_BYTES {
+            return too_large();
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                match value.trim().parse::<usize>() {
+                    Ok(n) => length = n,
+                    Err(_) => return Ok(Err(Response::error(400, "invalid Content-Length"))),
+                }
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Ok(Err(Response::error(413, "request body is too large")));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let Ok(body) = String::from_utf8(body) else {
+        return Ok(Err(Response::error(400, "request body is not UTF-8")));
+    };
+    Ok(Ok(Request { method, path, body }))
+}
+
+fn route(request: &Request, registry: &RwLock<Registry>) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = request.method.as_str();
+    let Ok(guard) = registry.read() else {
+        return Response::error(500, "registry is unavailable");
+    };
+
+    match (method, segments.as_slice()) {
+        ("GET", ["health"]) => Response::ok(format!(
+            "{{\"status\":\"ok\",\"systems\":{},\"errors\":{}}}",
+            guard.entries.len(),
+            guard.errors().len()
+        )),
+        ("GET", ["systems"]) => {
+            let systems: Vec<String> = guard
+                .entries
+                .iter()
+                .map(|(name, entry)| describe(name, entry))
+                .collect();
+            Response::ok(format!("{{\"systems\":[{}]}}", systems.join(",")))
+        }
+        ("GET", ["systems", name]) => match guard.entries.get(*name) {
+            Some(entry) => Response::ok(describe(name, entry)),
+            None => Response::error(404, &format!("unknown system '{}'", name)),
+        },
+        ("POST", ["systems", name, "evaluate"]) => match guard.entries.get(*name) {
+            Some(Entry {
+                system: Some(system),
+                ..
+            }) => {
+                let system = Arc::clone(system);
+                drop(guard);
+                evaluate(name, &system, &request.body)
+            }
+            Some(entry) => Response::error(
+                503,
+                &format!(
+                    "system '{}' failed to load: {}",
+                    name,
+                    entry.error.as_deref().unwrap_or("unknown error")
+                ),
+            ),
+            None => Response::error(404, &format!("unknown system '{}'", name)),
+        },
+        ("POST", ["reload"]) => {
+            drop(guard);
+            let Some(changes) = refresh_shared(registry) else {
+                return Response::error(500, "registry is unavailable");
+            };
+            for change in &changes {
+                eprintln!("{}", change);
+            }
+            let quoted: Vec<String> = changes.iter().map(|c| json_string(c)).collect();
+            Response::ok(format!("{{\"changes\":[{}]}}", quoted.join(",")))
+        }
+        (_, ["health"]) | (_, ["systems"]) | (_, ["systems", _]) | (_, ["systems", _, "evaluate"])
+        | (_, ["reload"]) => Response::error(405, &format!("{} is not allowed here", method)),
+        _ => Response::error(404, &format!("no route for {}", request.path)),
+    }
+}
+
+fn describe_variables(variables: &[LinguisticVariable]) -> String {
+    let described: Vec<String> = variables
+        .iter()
+        .map(|v| {
+            let terms: Vec<String> = v.terms.iter().map(|t| json_string(&t.name)).collect();
+            format!(
+                "{{\"name\":{},\"min\":{},\"max\":{},\"terms\":[{}]}}",
+                json_string(&v.name),
+                json_value(v.min),
+                json_value(v.max),
+                terms.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", described.join(","))
+}
+
+fn describe(name: &str, entry: &Entry) -> String {
+    let mut json = String::from("{");
+    push_field(&mut json, "name", &json_string(name));
+    push_field(&mut json, "path", &json_string(&entry.path.display().to_string()));
+    if let Some(system) = &entry.system {
+        push_field(&mut json, "inputs", &describe_variables(system.inputs()));
+        push_field(&mut json, "outputs", &describe_variables(system.outputs()));
+        push_field(&mut json, "rules", &system.rules().len().to_string());
+    }
+    let error = entry.error.as_deref().map_or("null".to_string(), json_string);
+    push_field(&mut json, "error", &error);
+    json.push('}');
+    json
+}
+
+/// Handle `{"inputs": {...}, "trace": bool}`
+fn evaluate(name: &str, system: &FuzzySystem, body: &str) -> Response {
+    let fields = match parse_object(body.trim()) {
+        Ok(fields) => fields,
+        Err(message) => return Response::error(400, &message),
+    };
+    let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| *v);
+
+    let trace = match field("trace") {
+        None | Some("false") => false,
+        Some("true") => true,
+        Some(_) => return Response::error(400, "trace must be true or false"),
+    };
+    let Some(raw_inputs) = field("inputs") else {
+        return Response::error(400, "the body needs an \"inputs\" object");
+    };
+    let inputs = match parse_object(raw_inputs) {
+        Ok(inputs) => inputs,
+        Err(message) => return Response::error(400, &format!("inputs: {}", message)),
+    };
+
+    for (key, _) in &inputs {
+        if system.input_index(key).is_none() {
+            return Response::error(400, &format!("unknown input '{}'", key));
+        }
+    }
+    let mut point = Vec::with_capacity(system.inputs().len());
+    for variable in system.inputs() {
+        let Some((_, raw)) = inputs.iter().find(|(k, _)| *k == variable.name) else {
+            return Response::error(400, &format!("missing input '{}'", variable.name));
+        };
+        match json_number(raw, &variable.name) {
+            Ok(value) => point.push(value),
+            Err(message) => return Response::error(400, &message),
+        }
+    }
+
+    let mut json = String::from("{");
+    push_field(&mut json, "system", &json_string(name));
+    let mut outputs = String::from("{");
+    for (variable, value) in system.outputs().iter().zip(system.evaluate(&point)) {
+        push_field(&mut outputs, &variable.name, &json_value(value));
+    }
+    outputs.push('}');
+    push_field(&mut json, "outputs", &outputs);
+    if trace {
+        push_field(&mut json, "trace", &trace_json(system, &point));
+    }
+    json.push('}');
+    Response::ok(json)
+}
+
+/// Term memberships and rule firing strengths for one evaluation
+fn trace_json(system: &FuzzySystem, point: &[f64]) -> String {
+    let mut memberships = String::from("{");
+    for (variable, &x) in system.inputs().iter().zip(point) {
+        let mut terms = String::from("{");
+        for (term, mu) in variable.terms.iter().zip(variable.fuzzify(x)) {
+            push_field(&mut terms, &term.name, &json_value(mu));
+        }
+        terms.push('}');
+        push_field(&mut memberships, &variable.name, &terms);
+    }
+    memberships.push('}');
+
+    let rules: Vec<String> = system
+        .rules()
+        .iter()
+        .zip(system.firing_strengths(point))
+        .map(|(rule, strength)| {
+            format!(
+                "{{\"rule\":{},\"strength\":{}}}",
+                json_string(&format_rule(system, rule)),
+                json_value(strength)
+            )
+        })
+        .collect();
+
+    let mut json = String::from("{");
+    push_field(&mut json, "memberships", &memberships);
+    push_field(&mut json, "rules", &format!("[{}]", rules.join(",")));
+    json.push('}');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new(vec![PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/systems"
+        ))]);
+        registry.refresh();
+        registry
+    }
+
+    fn request(method: &str, path: &str, body: &str) -> Response {
+        let request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        };
+        route(&request, &RwLock::new(registry()))
+    }
+
+    fn read(text: &str) -> Result<Request, Response> {
+        read_request(&mut text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_read_request_limits() {
+        let request = read("POST /reload HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}").ok().unwrap();
+        assert_eq!((request.method.as_str(), request.body.as_str()), ("POST", "{}"));
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(read(&long_line).err().unwrap().status, 400);
+
+        let long_header = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(read(&long_header).err().unwrap().status, 431);
+
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: 1\r\n".repeat(MAX_HEADERS + 1));
+        assert_eq!(read(&many_headers).err().unwrap().status, 431);
+
+        let header = format!("X-Wide: {}\r\n", "a".repeat(4000));
+        let wide_headers = format!("GET / HTTP/1.1\r\n{}\r\n", header.repeat(MAX_HEADER_BYTES / 4000 + 1));
+        assert_eq!(read(&wide_headers).err().unwrap().status, 431);
+    }
+
+    #[test]
+    fn test_registry_loads_directory() {
+        let registry = registry();
+        assert!(registry.entries.contains_key("tipping"));
+        assert!(registry.entries.contains_key("temperature"));
+        assert!(registry.errors().is_empty());
+    }
+
+    #[test]
+    fn test_shared_refresh_drops_removed_files() {
+        let dir = std::env::temp_dir().join(format!("fuzzy_logic_registry_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("systems/tipping.fuzzy");
+        std::fs::copy(&source, dir.join("tipping.fuzzy")).unwrap();
+        let registry = RwLock::new(Registry::new(vec![dir.clone()]));
+
+        let changes = refresh_shared(&registry).unwrap();
+        assert!(changes[0].starts_with("loaded tipping from "));
+        // Unchanged files are not read again
+        assert!(refresh_shared(&registry).unwrap().is_empty());
+
+        std::fs::remove_file(dir.join("tipping.fuzzy")).unwrap();
+        assert_eq!(refresh_shared(&registry).unwrap(), vec!["removed tipping".to_string()]);
+        assert!(registry.read().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(request("GET", "/health", "").status, 200);
+        assert!(request("GET", "/systems", "").body.contains("\"name\":\"tipping\""));
+        assert_eq!(request("GET", "/systems/nope", "").status, 404);
+        assert_eq!(request("DELETE", "/systems/tipping", "").status, 405);
+        assert_eq!(request("GET", "/elsewhere", "").status, 404);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let response = request(
+            "POST",
+            "/systems/tipping/evaluate",
+            r#"{"inputs": {"service": 3, "food": "8"}, "trace": true}"#,
+        );
+        assert_eq!(response.status, 200);
+        let fields = parse_object(&response.body).unwrap();
+        let outputs = parse_object(fields[1].1).unwrap();
+        let tip = json_number(outputs[0].1, "tip").unwrap();
+        assert!(tip > 10.0 && tip < 20.0);
+        assert!(response.body.contains("\"memberships\":{\"service\":{\"poor\":0.4"));
+
+        let missing = request("POST", "/systems/tipping/evaluate", r#"{"inputs": {"service": 3}}"#);
+        assert_eq!(missing.status, 400);
+        assert!(missing.body.contains("missing input 'food'"));
+        let unknown = request(
+            "POST",
+            "/systems/tipping/evaluate",
+            r#"{"inputs": {"service": 3, "food": 1, "wine": 2}}"#,
+        );
+        assert!(unknown.body.contains("unknown input 'wine'"));
+        assert_eq!(request("POST", "/systems/tipping/evaluate", "nope").status, 400);
+    }
+}
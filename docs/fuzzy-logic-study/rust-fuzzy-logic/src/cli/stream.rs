@@ -0,0 +1,602 @@
+//! Streaming Record Evaluation
+//!
+//! Evaluates a system for every record of a CSV file (with a header row) or
+//! of newline-delimited JSON objects. Records are read, evaluated and
+//! written one at a time through reused buffers, so memory use does not
+//! grow with the size of the input.
+//!
+//! CSV fields may be quoted (`"a, b"`, with `""` for a quote) but must not
+//! contain line breaks.
+
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+
+use fuzzy_logic::system::SystemScratch;
+use fuzzy_logic::FuzzySystem;
+
+use super::json::{json_number, json_string, json_value, parse_object, push_field};
+use super::CliError;
+
+/// Record format of the input (and output) stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    JsonLines,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Format::Csv),
+            "jsonl" | "ndjson" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from a file name, defaulting to CSV
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".jsonl") || path.ends_with(".ndjson") {
+            Format::JsonLines
+        } else {
+            Format::Csv
+        }
+    }
+}
+
+/// What to do with a record that cannot be evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// Stop with an error
+    Fail,
+    /// Report the record on stderr and leave it out of the output
+    Skip,
+    /// Write the record with empty outputs and an `error` column/field
+    Emit,
+}
+
+impl OnError {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "fail" => Some(OnError::Fail),
+            "skip" => Some(OnError::Skip),
+            "emit" => Some(OnError::Emit),
+            _ => None,
+        }
+    }
+}
+
+/// How records are read and written
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    pub format: Format,
+    /// `(column, input)` pairs; other inputs read the column of their own name
+    pub mapping: Vec<(String, String)>,
+    /// Add term memberships and rule firing strengths to every record
+    pub trace: bool,
+    pub on_error: OnError,
+    /// Copy every input column/field to the output, not only mapped ones
+    pub passthrough: bool,
+}
+
+/// Counts of a finished stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSummary {
+    pub records: usize,
+    pub failed: usize,
+}
+
+/// Evaluate every record of `input` and write the results to `out`
+pub fn evaluate_stream(
+    system: &FuzzySystem,
+    options: &StreamOptions,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<StreamSummary, CliError> {
+    for (_, name) in &options.mapping {
+        if system.input_index(name).is_none() {
+            return Err(CliError::Usage(format!("--map refers to unknown input '{}'", name)));
+        }
+    }
+    let mut evaluator = Evaluator::new(system, options);
+    match options.format {
+        Format::Csv => stream_csv(&mut evaluator, input, out),
+        Format::JsonLines => stream_json_lines(&mut evaluator, input, out),
+    }
+}
+
+/// Column or field name feeding each input
+fn source_names(system: &FuzzySystem, options: &StreamOptions) -> Vec<String> {
+    system
+        .inputs()
+        .iter()
+        .map(|v| {
+            options
+                .mapping
+                .iter()
+                .find(|(_, input)| *input == v.name)
+                .map(|(column, _)| column.clone())
+                .unwrap_or_else(|| v.name.clone())
+        })
+        .collect()
+}
+
+/// Evaluation state reused across records
+struct Evaluator<'a> {
+    system: &'a FuzzySystem,
+    options: &'a StreamOptions,
+    sources: Vec<String>,
+    scratch: SystemScratch,
+    point: Vec<f64>,
+    outputs: Vec<f64>,
+    summary: StreamSummary,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(system: &'a FuzzySystem, options: &'a StreamOptions) -> Self {
+        Evaluator {
+            system,
+            options,
+            sources: source_names(system, options),
+            scratch: system.scratch(),
+            point: vec![0.0; system.inputs().len()],
+            outputs: vec![0.0; system.outputs().len()],
+            summary: StreamSummary {
+                records: 0,
+                failed: 0,
+            },
+        }
+    }
+
+    /// Evaluate `self.point` into `self.outputs`
+    fn evaluate(&mut self) {
+        self.system
+            .evaluate_with(&self.point, &mut self.outputs, &mut self.scratch);
+    }
+
+    /// Names of the trace values, in the order [`Self::trace_values`] yields them
+    fn trace_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for variable in self.system.inputs() {
+            for term in &variable.terms {
+                names.push(format!("{}.{}", variable.name, term.name));
+            }
+        }
+        for i in 0..self.system.rules().len() {
+            names.push(format!("rule.{}", i + 1));
+        }
+        names
+    }
+
+    fn trace_values(&self) -> Vec<f64> {
+        let mut values = Vec::new();
+        for (variable, &x) in self.system.inputs().iter().zip(&self.point) {
+            values.extend(variable.fuzzify(x));
+        }
+        values.extend(self.system.firing_strengths(&self.point));
+        values
+    }
+
+    /// Handle a bad record; `Ok(true)` means an error record should be written
+    fn record_error(&mut self, line: usize, message: &str) -> Result<bool, CliError> {
+        self.summary.failed += 1;
+        match self.options.on_error {
+            OnError::Fail => Err(CliError::Data(format!("line {}: {}", line, message))),
+            OnError::Skip => {
+                eprintln!("warning: skipping line {}: {}", line, message);
+                Ok(false)
+            }
+            OnError::Emit => Ok(true),
+        }
+    }
+}
+
+fn parse_number(text: &str, source: &str) -> Result<f64, String> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("{}: '{}' is not a number", source, text.trim()))
+}
+
+/// Read the next line into `buffer` without its line ending
+fn next_line(input: &mut dyn BufRead, buffer: &mut String) -> Result<bool, CliError> {
+    buffer.clear();
+    if input.read_line(buffer)? == 0 {
+        return Ok(false);
+    }
+    while buffer.ends_with('\n') || buffer.ends_with('\r') {
+        buffer.pop();
+    }
+    Ok(true)
+}
+
+fn stream_csv(
+    ev: &mut Evaluator,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<StreamSummary, CliError> {
+    let mut line = String::new();
+    if !next_line(input, &mut line)? {
+        return Err(CliError::Data("the CSV input is empty".to_string()));
+    }
+    let header: Vec<String> = split_csv(&line)
+        .map_err(|m| CliError::Data(format!("line 1: {}", m)))?
+        .into_iter()
+        .map(|c| c.trim().to_string())
+        .collect();
+    let columns = ev
+        .sources
+        .iter()
+        .map(|name| {
+            header
+                .iter()
+                .position(|c| c == name)
+                .ok_or_else(|| CliError::Data(format!("CSV header has no column '{}'", name)))
+        })
+        .collect::<Result<Vec<usize>, CliError>>()?;
+
+    let mut names: Vec<String> = if ev.options.passthrough {
+        header.clone()
+    } else {
+        columns.iter().map(|&c| header[c].clone()).collect()
+    };
+    names.extend(ev.system.outputs().iter().map(|v| v.name.clone()));
+    if ev.options.trace {
+        names.extend(ev.trace_names());
+    }
+    if ev.options.on_error == OnError::Emit {
+        names.push("error".to_string());
+    }
+    let quoted: Vec<String> = names.iter().map(|n| quote_csv(n)).collect();
+    writeln!(out, "{}", quoted.join(","))?;
+
+    let empty_outputs = ",".repeat(ev.outputs.len());
+    let empty_trace = if ev.options.trace {
+        ",".repeat(ev.trace_names().len())
+    } else {
+        String::new()
+    };
+    let mut record = String::new();
+    let mut number = 1;
+    while next_line(input, &mut line)? {
+        number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        ev.summary.records += 1;
+
+        let result = split_csv(&line).and_then(|fields| {
+            if fields.len() != header.len() {
+                return Err(format!("expected {} fields, got {}", header.len(), fields.len()));
+            }
+            record.clear();
+            if ev.options.passthrough {
+                record.push_str(&line);
+            } else {
+                let mapped: Vec<String> = columns.iter().map(|&c| quote_csv(&fields[c])).collect();
+                record.push_str(&mapped.join(","));
+            }
+            for (i, &c) in columns.iter().enumerate() {
+                ev.point[i] = parse_number(&fields[c], &header[c])?;
+            }
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => {
+                ev.evaluate();
+                for v in &ev.outputs {
+                    let _ = write!(record, ",{}", v);
+                }
+                if ev.options.trace {
+                    for v in ev.trace_values() {
+                        let _ = write!(record, ",{}", v);
+                    }
+                }
+                if ev.options.on_error == OnError::Emit {
+                    record.push(',');
+                }
+                writeln!(out, "{}", record)?;
+            }
+            Err(message) => {
+                if ev.record_error(number, &message)? {
+                    // Keep the input part when it was readable
+                    let fields = split_csv(&line).ok().filter(|f| f.len() == header.len());
+                    let input_part = match (&fields, ev.options.passthrough) {
+                        (Some(_), true) => line.clone(),
+                        (Some(f), false) => columns
+                            .iter()
+                            .map(|&c| quote_csv(&f[c]))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                        (None, _) => {
+                            let width = if ev.options.passthrough { header.len() } else { columns.len() };
+                            ",".repeat(width.saturating_sub(1))
+                        }
+                    };
+                    writeln!(
+                        out,
+                        "{}{}{},{}",
+                        input_part,
+                        empty_outputs,
+                        empty_trace,
+                        quote_csv(&message)
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(ev.summary)
+}
+
+/// Split one CSV line into unquoted fields
+pub(super) fn split_csv(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.trim().is_empty() && !was_quoted => {
+                field.clear();
+                quoted = true;
+                was_quoted = true;
+            }
+            ',' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn stream_json_lines(
+    ev: &mut Evaluator,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<StreamSummary, CliError> {
+    let trace_names = if ev.options.trace { ev.trace_names() } else { Vec::new() };
+    let mut line = String::new();
+    let mut record = String::new();
+    let mut number = 0;
+    while next_line(input, &mut line)? {
+        number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        ev.summary.records += 1;
+
+        let result = parse_object(line.trim()).and_then(|fields| {
+            for (i, source) in ev.sources.iter().enumerate() {
+                let value = fields
+                    .iter()
+                    .find(|(key, _)| key == source)
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| format!("missing field '{}'", source))?;
+                ev.point[i] = json_number(value, source)?;
+            }
+            Ok(fields)
+        });
+
+        record.clear();
+        match result {
+            Ok(fields) => {
+                ev.evaluate();
+                open_record(ev, &fields, false, &mut record);
+                for (variable, v) in ev.system.outputs().iter().zip(&ev.outputs) {
+                    push_field(&mut record, &variable.name, &json_value(*v));
+                }
+                if ev.options.trace {
+                    let values: Vec<String> = trace_names
+                        .iter()
+                        .zip(ev.trace_values())
+                        .map(|(name, v)| format!("{}:{}", json_string(name), json_value(v)))
+                        .collect();
+                    push_field(&mut record, "trace", &format!("{{{}}}", values.join(",")));
+                }
+                record.push('}');
+                writeln!(out, "{}", record)?;
+            }
+            Err(message) => {
+                if ev.record_error(number, &message)? {
+                    match parse_object(line.trim()) {
+                        Ok(fields) => open_record(ev, &fields, true, &mut record),
+                        Err(_) => record.push('{'),
+                    }
+                    for variable in ev.system.outputs() {
+                        push_field(&mut record, &variable.name, "null");
+                    }
+                    push_field(&mut record, "error", &json_string(&message));
+                    record.push('}');
+                    writeln!(out, "{}", record)?;
+                }
+            }
+        }
+    }
+    Ok(ev.summary)
+}
+
+/// Start an output object with the passed-through or mapped fields, unclosed
+///
+/// Passed-through fields named like an output, or like the `trace` or
+/// `error` field this record gets, are dropped so every key stays unique.
+fn open_record(ev: &Evaluator, fields: &[(String, &str)], error: bool, record: &mut String) {
+    record.push('{');
+    if ev.options.passthrough {
+        let written = |key: &str| {
+            ev.system.outputs().iter().any(|v| v.name == key)
+                || (key == "trace" && ev.options.trace && !error)
+                || (key == "error" && error)
+        };
+        for (key, value) in fields {
+            if !written(key) {
+                push_field(record, key, value);
+            }
+        }
+    } else {
+        for source in &ev.sources {
+            if let Some((key, value)) = fields.iter().find(|(key, _)| key == source) {
+                push_field(record, key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_logic::definition::parse_system;
+
+    const SYSTEM: &str = "
+        input temp 0 40
+          term cold triangular 0 0 40
+          term hot triangular 0 40 40
+        output fan 0 100
+          term slow triangular 0 0 100
+          term fast triangular 0 100 100
+        rule if temp is cold then fan is slow
+        rule if temp is hot then fan is fast
+    ";
+
+    fn run(input: &str, options: &StreamOptions) -> (Result<StreamSummary, CliError>, String) {
+        let system = parse_system(SYSTEM).unwrap();
+        let mut out = Vec::new();
+        let result = evaluate_stream(&system, options, &mut input.as_bytes(), &mut out);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    fn options(format: Format) -> StreamOptions {
+        StreamOptions {
+            format,
+            mapping: Vec::new(),
+            trace: false,
+            on_error: OnError::Fail,
+            passthrough: false,
+        }
+    }
+
+    #[test]
+    fn test_split_csv() {
+        assert_eq!(split_csv("a,\"b, c\",\"say \"\"hi\"\"\"").unwrap(), vec!["a", "b, c", "say \"hi\""]);
+        assert_eq!(split_csv("1,,3").unwrap(), vec!["1", "", "3"]);
+        assert!(split_csv("\"open").is_err());
+    }
+
+    #[test]
+    fn test_csv_mapping_and_passthrough() {
+        let mut opts = options(Format::Csv);
+        opts.mapping = vec![("t".to_string(), "temp".to_string())];
+        let input = "id,t\nA,0\nB,40\n";
+
+        let (_, mapped) = run(input, &opts);
+        let lines: Vec<&str> = mapped.lines().collect();
+        assert_eq!(lines[0], "t,fan");
+        assert!(lines[1].starts_with("0,"));
+
+        opts.passthrough = true;
+        let (_, passed) = run(input, &opts);
+        let lines: Vec<&str> = passed.lines().collect();
+        assert_eq!(lines[0], "id,t,fan");
+        assert!(lines[2].starts_with("B,40,"));
+    }
+
+    #[test]
+    fn test_csv_error_handling() {
+        let input = "temp\n10\nwarm\n30\n";
+        let (result, _) = run(input, &options(Format::Csv));
+        assert!(matches!(result, Err(CliError::Data(m)) if m.starts_with("line 3:")));
+
+        let mut opts = options(Format::Csv);
+        opts.on_error = OnError::Skip;
+        let (result, out) = run(input, &opts);
+        assert_eq!(result.unwrap(), StreamSummary { records: 3, failed: 1 });
+        assert_eq!(out.lines().count(), 3);
+
+        opts.on_error = OnError::Emit;
+        let (_, out) = run(input, &opts);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "temp,fan,error");
+        assert!(lines[1].ends_with(','));
+        assert_eq!(lines[2], "warm,,temp: 'warm' is not a number");
+    }
+
+    #[test]
+    fn test_csv_trace() {
+        let mut opts = options(Format::Csv);
+        opts.trace = true;
+        let (_, out) = run("temp\n10\n", &opts);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], "temp,fan,temp.cold,temp.hot,rule.1,rule.2");
+        assert!(lines[1].ends_with(",0.75,0.25,0.75,0.25"));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut opts = options(Format::JsonLines);
+        opts.passthrough = true;
+        opts.trace = true;
+        let (result, out) = run("{\"temp\": 20, \"site\": \"north\"}\n\n{\"temp\": \"40\"}\n", &opts);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(result.unwrap().records, 2);
+        assert!(lines[0].starts_with("{\"temp\":20,\"site\":\"north\",\"fan\":"));
+        assert!(lines[0].contains("\"trace\":{\"temp.cold\":0.5,\"temp.hot\":0.5,"));
+        assert!(parse_object(lines[0]).is_ok());
+        assert!(parse_object(lines[1]).is_ok());
+    }
+
+    #[test]
+    fn test_json_lines_errors() {
+        let mut opts = options(Format::JsonLines);
+        opts.on_error = OnError::Emit;
+        let (result, out) = run("{\"temp\": null}\nnot json\n{\"other\": 1}\n", &opts);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(result.unwrap().failed, 3);
+        assert_eq!(lines[0], "{\"temp\":null,\"fan\":null,\"error\":\"temp: value is null\"}");
+        assert_eq!(lines[1], "{\"fan\":null,\"error\":\"invalid JSON: expected an object\"}");
+        assert_eq!(lines[2], "{\"fan\":null,\"error\":\"missing field 'temp'\"}");
+    }
+
+    #[test]
+    fn test_json_lines_passthrough_keeps_keys_unique() {
+        let mut opts = options(Format::JsonLines);
+        opts.passthrough = true;
+        opts.trace = true;
+        opts.on_error = OnError::Emit;
+        let input = "{\"temp\": 20, \"fan\": 1, \"trace\": 2, \"error\": 3}\n{\"temp\": null, \"error\": \"old\"}\n";
+        let (_, out) = run(input, &opts);
+        let lines: Vec<&str> = out.lines().collect();
+
+        let fields = parse_object(lines[0]).unwrap();
+        let keys: Vec<&str> = fields.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["temp", "error", "fan", "trace"]);
+        assert_eq!(fields[1].1, "3");
+        assert_eq!(lines[1], "{\"temp\":null,\"fan\":null,\"error\":\"temp: value is null\"}");
+    }
+}
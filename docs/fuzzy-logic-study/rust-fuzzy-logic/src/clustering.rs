@@ -0,0 +1,743 @@
+//! Fuzzy Clustering
+//!
+//! Finds clusters in numeric data and turns them into membership functions
+//! and rule bases:
+//! - [`fuzzy_c_means`]: Bezdek's fuzzy C-means with Euclidean distances
+//! - [`gustafson_kessel`]: fuzzy C-means with a distance norm adapted to the
+//!   covariance of each cluster, for elongated clusters
+//! - [`subtractive`]: Chiu's subtractive clustering, which also chooses the
+//!   number of clusters
+//!
+//! A [`Clustering`] projects each cluster onto the axes as Gaussian terms,
+//! initializes a first-order Sugeno system with one rule per cluster, and
+//! scores the partition with validity indices.
+//!
+//! ```
+//! use fuzzy_logic::clustering::{fuzzy_c_means, FcmConfig};
+//!
+//! let data = [[0.0, 0.1], [0.2, 0.0], [5.0, 5.1], [5.2, 4.9]];
+//! let config = FcmConfig { clusters: 2, ..FcmConfig::default() };
+//! let clustering = fuzzy_c_means(&data, &config).unwrap();
+//!
+//! let labels = clustering.labels();
+//! assert_eq!(labels[0], labels[1]);
+//! assert_ne!(labels[0], labels[2]);
+//! assert!(clustering.partition_coefficient() > 0.9);
+//! ```
+
+use std::fmt;
+
+use crate::linalg::{invert, NormalEquations};
+use crate::membership::MembershipFunction;
+use crate::operations::TNorm;
+use crate::rng::Rng;
+use crate::sugeno::{OwnedSugenoRule, OwnedSugenoSystem};
+
+/// Settings for [`fuzzy_c_means`] and [`gustafson_kessel`]
+#[derive(Debug, Clone)]
+pub struct FcmConfig {
+    pub clusters: usize,
+    /// Fuzzifier m > 1; larger values give softer partitions
+    pub fuzzifier: f64,
+    /// Iteration stops once no membership changes by more than this
+    pub tolerance: f64,
+    pub max_iterations: usize,
+    /// Seed for the initial partition
+    pub seed: u64,
+}
+
+impl Default for FcmConfig {
+    fn default() -> Self {
+        FcmConfig {
+            clusters: 2,
+            fuzzifier: 2.0,
+            tolerance: 1e-6,
+            max_iterations: 300,
+            seed: 0,
+        }
+    }
+}
+
+/// Settings for [`subtractive`]
+///
+/// Radii are fractions of the data range on each axis.
+#[derive(Debug, Clone)]
+pub struct SubtractiveConfig {
+    /// Neighbourhood radius of a cluster center
+    pub radius: f64,
+    /// Potential near an accepted center is reduced within
+    /// `squash × radius`
+    pub squash: f64,
+    /// Potential, relative to the first center, above which a point is
+    /// always accepted as a center
+    pub accept: f64,
+    /// Relative potential below which the search stops
+    pub reject: f64,
+}
+
+impl Default for SubtractiveConfig {
+    fn default() -> Self {
+        SubtractiveConfig {
+            radius: 0.5,
+            squash: 1.25,
+            accept: 0.5,
+            reject: 0.15,
+        }
+    }
+}
+
+/// Errors from clustering and [`Clustering::sugeno_system`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClusteringError {
+    NoSamples,
+    /// Samples have no values
+    NoFeatures,
+    /// A sample has a different number of values from the first
+    InconsistentSample { sample: usize, expected: usize, actual: usize },
+    /// A sample or target is NaN or infinite
+    NonFinite(usize),
+    /// Samples for [`Clustering::sugeno_system`] have more values than the
+    /// clustered data had axes
+    DimensionMismatch { dimensions: usize, actual: usize },
+    /// Fewer samples than clusters
+    TooFewSamples { samples: usize, clusters: usize },
+    /// The number of targets differs from the number of samples
+    LengthMismatch { samples: usize, targets: usize },
+    /// No sample fires any rule, so the consequents cannot be fitted
+    NoCoverage,
+    InvalidConfig(&'static str),
+}
+
+impl fmt::Display for ClusteringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClusteringError::NoSamples => write!(f, "no samples"),
+            ClusteringError::NoFeatures => write!(f, "samples have no values"),
+            ClusteringError::InconsistentSample { sample, expected, actual } => write!(
+                f,
+                "sample {} has {} values, expected {}",
+                sample, actual, expected
+            ),
+            ClusteringError::NonFinite(sample) => write!(f, "sample {} is not finite", sample),
+            ClusteringError::DimensionMismatch { dimensions, actual } => write!(
+                f,
+                "samples have {} values, the clusters have {} axes",
+                actual, dimensions
+            ),
+            ClusteringError::TooFewSamples { samples, clusters } => {
+                write!(f, "{} samples are too few for {} clusters", samples, clusters)
+            }
+            ClusteringError::LengthMismatch { samples, targets } => {
+                write!(f, "{} samples but {} targets", samples, targets)
+            }
+            ClusteringError::NoCoverage => write!(f, "no sample fires any rule"),
+            ClusteringError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClusteringError {}
+
+/// A fuzzy partition of a data set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clustering {
+    /// One center per cluster
+    pub centers: Vec<Vec<f64>>,
+    /// Membership of each sample in each cluster; every row sums to one
+    pub memberships: Vec<Vec<f64>>,
+    /// Fuzzifier the memberships were computed with
+    pub fuzzifier: f64,
+    /// Width of each cluster along each axis
+    pub spreads: Vec<Vec<f64>>,
+    pub iterations: usize,
+    /// False when the iteration limit was reached first
+    pub converged: bool,
+    /// Σ uᵐ d² over samples and clusters
+    pub objective: f64,
+}
+
+impl Clustering {
+    /// Cluster with the highest membership for each sample
+    pub fn labels(&self) -> Vec<usize> {
+        self.memberships
+            .iter()
+            .map(|row| (0..row.len()).max_by(|&a, &b| row[a].total_cmp(&row[b])).unwrap_or(0))
+            .collect()
+    }
+
+    /// Projection of the clusters onto each axis: per axis, one Gaussian
+    /// term per cluster centered on it with the cluster's spread
+    pub fn gaussian_terms(&self) -> Vec<Vec<MembershipFunction>> {
+        let dimensions = self.centers.first().map_or(0, Vec::len);
+        (0..dimensions)
+            .map(|k| {
+                self.centers
+                    .iter()
+                    .zip(&self.spreads)
+                    .map(|(center, spread)| MembershipFunction::Gaussian {
+                        mean: center[k],
+                        sigma: spread[k],
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// A first-order Sugeno system with one rule per cluster
+    ///
+    /// Rule j combines term j of every input with the product t-norm; its
+    /// linear consequent is fitted to `targets` by least squares. Inputs
+    /// take the leading axes of the clustered data, so clusters found in
+    /// the product space of inputs and output can be used directly.
+    pub fn sugeno_system<S: AsRef<[f64]>>(
+        &self,
+        inputs: &[S],
+        targets: &[f64],
+    ) -> Result<OwnedSugenoSystem, ClusteringError> {
+        let dimensions = self.centers.first().map_or(0, Vec::len);
+        if inputs.len() != targets.len() {
+            return Err(ClusteringError::LengthMismatch {
+                samples: inputs.len(),
+                targets: targets.len(),
+            });
+        }
+        let width = validate(inputs)?;
+        if width > dimensions {
+            return Err(ClusteringError::DimensionMismatch {
+                dimensions,
+                actual: width,
+            });
+        }
+        if let Some(sample) = targets.iter().position(|y| !y.is_finite()) {
+            return Err(ClusteringError::NonFinite(sample));
+        }
+
+        let mut terms = self.gaussian_terms();
+        terms.truncate(width);
+        let rules = self.centers.len();
+        let mut equations = NormalEquations::new(rules * (width + 1));
+        let mut row = vec![0.0; rules * (width + 1)];
+        let mut covered = false;
+        for (x, &y) in inputs.iter().zip(targets) {
+            let x = x.as_ref();
+            let strengths: Vec<f64> = (0..rules)
+                .map(|j| terms.iter().zip(x).map(|(axis, &xi)| axis[j].evaluate(xi)).product())
+                .collect();
+            let total: f64 = strengths.iter().sum();
+            if total <= 0.0 {
+                continue;
+            }
+            covered = true;
+            for (j, w) in strengths.iter().enumerate() {
+                let normalised = w / total;
+                let params = &mut row[j * (width + 1)..(j + 1) * (width + 1)];
+                for (p, xi) in params.iter_mut().zip(x) {
+                    *p = normalised * xi;
+                }
+                params[width] = normalised;
+            }
+            equations.add(&row, y);
+        }
+        let consequents = equations.solve().filter(|_| covered).ok_or(ClusteringError::NoCoverage)?;
+
+        let mut system = OwnedSugenoSystem::new(terms).with_and_method(TNorm::AlgebraicProduct);
+        for (j, params) in consequents.chunks(width + 1).enumerate() {
+            system.add_rule(OwnedSugenoRule::linear(
+                vec![Some(j); width],
+                params[..width].to_vec(),
+                params[width],
+            ));
+        }
+        Ok(system)
+    }
+
+    /// Bezdek's partition coefficient Σ u² / n, from 1/c for a uniform
+    /// partition up to 1 for a crisp one
+    pub fn partition_coefficient(&self) -> f64 {
+        let sum: f64 = self.memberships.iter().flatten().map(|u| u * u).sum();
+        sum / self.memberships.len() as f64
+    }
+
+    /// Partition entropy -Σ u ln u / n, from 0 for a crisp partition up to
+    /// ln c for a uniform one
+    pub fn partition_entropy(&self) -> f64 {
+        let sum: f64 = self
+            .memberships
+            .iter()
+            .flatten()
+            .filter(|&&u| u > 0.0)
+            .map(|u| u * u.ln())
+            .sum();
+        -sum / self.memberships.len() as f64
+    }
+
+    /// Xie–Beni index of the partition of `data`: compactness over
+    /// separation, lower is better
+    ///
+    /// Infinite with fewer than two clusters or coinciding centers.
+    pub fn xie_beni<S: AsRef<[f64]>>(&self, data: &[S]) -> f64 {
+        let mut separation = f64::INFINITY;
+        for (j, a) in self.centers.iter().enumerate() {
+            for b in &self.centers[j + 1..] {
+                separation = separation.min(squared_distance(a, b));
+            }
+        }
+        if !(separation > 0.0 && separation.is_finite()) {
+            return f64::INFINITY;
+        }
+        let compactness: f64 = data
+            .iter()
+            .zip(&self.memberships)
+            .map(|(x, row)| {
+                row.iter()
+                    .zip(&self.centers)
+                    .map(|(u, center)| u.powf(self.fuzzifier) * squared_distance(x.as_ref(), center))
+                    .sum::<f64>()
+            })
+            .sum();
+        compactness / (data.len() as f64 * separation)
+    }
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Number of values per sample, once every sample has been checked
+fn validate<S: AsRef<[f64]>>(data: &[S]) -> Result<usize, ClusteringError> {
+    let Some(first) = data.first() else {
+        return Err(ClusteringError::NoSamples);
+    };
+    let dimensions = first.as_ref().len();
+    if dimensions == 0 {
+        return Err(ClusteringError::NoFeatures);
+    }
+    for (sample, x) in data.iter().enumerate() {
+        let x = x.as_ref();
+        if x.len() != dimensions {
+            return Err(ClusteringError::InconsistentSample {
+                sample,
+                expected: dimensions,
+                actual: x.len(),
+            });
+        }
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(ClusteringError::NonFinite(sample));
+        }
+    }
+    Ok(dimensions)
+}
+
+fn validate_fcm<S: AsRef<[f64]>>(data: &[S], config: &FcmConfig) -> Result<usize, ClusteringError> {
+    if config.clusters == 0 {
+        return Err(ClusteringError::InvalidConfig("clusters must be at least 1"));
+    }
+    if !(config.fuzzifier > 1.0 && config.fuzzifier.is_finite()) {
+        return Err(ClusteringError::InvalidConfig("fuzzifier must be greater than 1"));
+    }
+    if config.tolerance.is_nan() || config.tolerance <= 0.0 {
+        return Err(ClusteringError::InvalidConfig("tolerance must be positive"));
+    }
+    if config.max_iterations == 0 {
+        return Err(ClusteringError::InvalidConfig("max_iterations must be at least 1"));
+    }
+    let dimensions = validate(data)?;
+    if data.len() < config.clusters {
+        return Err(ClusteringError::TooFewSamples {
+            samples: data.len(),
+            clusters: config.clusters,
+        });
+    }
+    Ok(dimensions)
+}
+
+/// Memberships from squared distances to each center (one row per sample)
+///
+/// A sample on a center belongs to it (shared among coinciding centers).
+fn memberships(distances: &[Vec<f64>], fuzzifier: f64) -> Vec<Vec<f64>> {
+    let exponent = 1.0 / (fuzzifier - 1.0);
+    distances
+        .iter()
+        .map(|row| {
+            let on_center = row.iter().filter(|&&d| d <= 0.0).count();
+            if on_center > 0 {
+                return row
+                    .iter()
+                    .map(|&d| if d <= 0.0 { 1.0 / on_center as f64 } else { 0.0 })
+                    .collect();
+            }
+            // u_j = 1 / Σ_k (d_j / d_k)^(1/(m-1)) with squared distances
+            row.iter()
+                .map(|&dj| 1.0 / row.iter().map(|&dk| (dj / dk).powf(exponent)).sum::<f64>())
+                .collect()
+        })
+        .collect()
+}
+
+/// Centers as the uᵐ-weighted means of the samples
+fn weighted_centers(points: &[&[f64]], weights: &[Vec<f64>], clusters: usize) -> Vec<Vec<f64>> {
+    let dimensions = points[0].len();
+    (0..clusters)
+        .map(|j| {
+            let mut center = vec![0.0; dimensions];
+            let mut total = 0.0;
+            for (x, w) in points.iter().zip(weights) {
+                total += w[j];
+                for (c, xi) in center.iter_mut().zip(x.iter()) {
+                    *c += w[j] * xi;
+                }
+            }
+            if total > 0.0 {
+                center.iter_mut().for_each(|c| *c /= total);
+            }
+            center
+        })
+        .collect()
+}
+
+/// Per cluster and axis, the uᵐ-weighted standard deviation, kept above a
+/// millionth of the axis range so that every term has some width
+fn weighted_spreads(points: &[&[f64]], centers: &[Vec<f64>], weights: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let floors: Vec<f64> = (0..centers[0].len())
+        .map(|k| {
+            let (lo, hi) = points
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x[k]), hi.max(x[k])));
+            1e-6 * (hi - lo).max(1.0)
+        })
+        .collect();
+    centers
+        .iter()
+        .enumerate()
+        .map(|(j, center)| {
+            let total: f64 = weights.iter().map(|w| w[j]).sum();
+            center
+                .iter()
+                .enumerate()
+                .map(|(k, c)| {
+                    let variance: f64 = points
+                        .iter()
+                        .zip(weights)
+                        .map(|(x, w)| w[j] * (x[k] - c) * (x[k] - c))
+                        .sum::<f64>()
+                        / total.max(f64::MIN_POSITIVE);
+                    variance.sqrt().max(floors[k])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Alternating optimization shared by fuzzy C-means and Gustafson–Kessel;
+/// `norm` gives the squared distance of every sample to every center
+fn alternate<S, N>(data: &[S], config: &FcmConfig, mut norm: N) -> Clustering
+where
+    S: AsRef<[f64]>,
+    N: FnMut(&[&[f64]], &[Vec<f64>], &[Vec<f64>]) -> Vec<Vec<f64>>,
+{
+    let points: Vec<&[f64]> = data.iter().map(AsRef::as_ref).collect();
+    let m = config.fuzzifier;
+    let clusters = config.clusters;
+
+    let mut rng = Rng::new(config.seed);
+    let mut u: Vec<Vec<f64>> = points
+        .iter()
+        .map(|_| {
+            let row: Vec<f64> = (0..clusters).map(|_| rng.unit() + 1e-3).collect();
+            let total: f64 = row.iter().sum();
+            row.into_iter().map(|v| v / total).collect()
+        })
+        .collect();
+
+    let mut iterations = 0;
+    let mut converged = false;
+    let mut centers = Vec::new();
+    while iterations < config.max_iterations {
+        iterations += 1;
+        let weights: Vec<Vec<f64>> = u.iter().map(|row| row.iter().map(|v| v.powf(m)).collect()).collect();
+        centers = weighted_centers(&points, &weights, clusters);
+        let next = memberships(&norm(&points, &centers, &weights), m);
+        let change = u
+            .iter()
+            .flatten()
+            .zip(next.iter().flatten())
+            .fold(0.0_f64, |c, (a, b)| c.max((a - b).abs()));
+        u = next;
+        if change < config.tolerance {
+            converged = true;
+            break;
+        }
+    }
+
+    let weights: Vec<Vec<f64>> = u.iter().map(|row| row.iter().map(|v| v.powf(m)).collect()).collect();
+    let objective = norm(&points, &centers, &weights)
+        .iter()
+        .flatten()
+        .zip(weights.iter().flatten())
+        .map(|(d, w)| d * w)
+        .sum();
+    let spreads = weighted_spreads(&points, &centers, &weights);
+    Clustering {
+        centers,
+        memberships: u,
+        fuzzifier: m,
+        spreads,
+        iterations,
+        converged,
+        objective,
+    }
+}
+
+fn euclidean(points: &[&[f64]], centers: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    points
+        .iter()
+        .map(|x| centers.iter().map(|c| squared_distance(x, c)).collect())
+        .collect()
+}
+
+/// Fuzzy C-means clustering of `data` (one slice of values per sample)
+pub fn fuzzy_c_means<S: AsRef<[f64]>>(data: &[S], config: &FcmConfig) -> Result<Clustering, ClusteringError> {
+    validate_fcm(data, config)?;
+    Ok(alternate(data, config, |points, centers, _| euclidean(points, centers)))
+}
+
+/// Gustafson–Kessel clustering of `data`
+///
+/// Each cluster measures distance with `det(F)^(1/n) F⁻¹`, where F is its
+/// fuzzy covariance matrix, so clusters of equal volume can take different
+/// shapes and orientations. F is regularized slightly so that flat
+/// clusters remain invertible.
+pub fn gustafson_kessel<S: AsRef<[f64]>>(data: &[S], config: &FcmConfig) -> Result<Clustering, ClusteringError> {
+    let n = validate_fcm(data, config)?;
+    Ok(alternate(data, config, |points, centers, weights| {
+        let norms: Vec<Option<Vec<f64>>> = centers
+            .iter()
+            .enumerate()
+            .map(|(j, center)| {
+                let mut covariance = vec![0.0; n * n];
+                let mut total = 0.0;
+                for (x, w) in points.iter().zip(weights) {
+                    total += w[j];
+                    for a in 0..n {
+                        for b in 0..n {
+                            covariance[a * n + b] += w[j] * (x[a] - center[a]) * (x[b] - center[b]);
+                        }
+                    }
+                }
+                let trace: f64 = (0..n).map(|a| covariance[a * n + a]).sum::<f64>() / total.max(f64::MIN_POSITIVE);
+                let ridge = 1e-6 * (trace / n as f64).max(1e-12);
+                for a in 0..n {
+                    for b in 0..n {
+                        covariance[a * n + b] /= total.max(f64::MIN_POSITIVE);
+                    }
+                    covariance[a * n + a] += ridge;
+                }
+                let (inverse, determinant) = invert(&covariance, n)?;
+                let scale = determinant.abs().powf(1.0 / n as f64);
+                Some(inverse.into_iter().map(|v| v * scale).collect())
+            })
+            .collect();
+
+        points
+            .iter()
+            .map(|x| {
+                centers
+                    .iter()
+                    .zip(&norms)
+                    .map(|(center, norm)| match norm {
+                        Some(a) => {
+                            let mut d = 0.0;
+                            for p in 0..n {
+                                for q in 0..n {
+                                    d += (x[p] - center[p]) * a[p * n + q] * (x[q] - center[q]);
+                                }
+                            }
+                            d.max(0.0)
+                        }
+                        // An empty cluster falls back to the Euclidean norm
+                        None => squared_distance(x, center),
+                    })
+                    .collect()
+            })
+            .collect()
+    }))
+}
+
+/// Subtractive clustering of `data`
+///
+/// Every sample is a candidate center with a potential that grows with the
+/// number of neighbours within `radius`. The sample with the highest
+/// potential becomes a center, the potential around it is reduced, and the
+/// search repeats until the remaining potential is too low. Spreads are
+/// `radius · range / √8` on each axis and memberships follow the fuzzy
+/// C-means formula with m = 2.
+pub fn subtractive<S: AsRef<[f64]>>(data: &[S], config: &SubtractiveConfig) -> Result<Clustering, ClusteringError> {
+    if !(config.radius > 0.0 && config.radius.is_finite()) {
+        return Err(ClusteringError::InvalidConfig("radius must be positive"));
+    }
+    if !(config.squash > 0.0 && config.squash.is_finite()) {
+        return Err(ClusteringError::InvalidConfig("squash must be positive"));
+    }
+    if !(0.0 <= config.reject && config.reject <= config.accept && config.accept <= 1.0) {
+        return Err(ClusteringError::InvalidConfig("thresholds must satisfy 0 <= reject <= accept <= 1"));
+    }
+    let dimensions = validate(data)?;
+    let points: Vec<&[f64]> = data.iter().map(AsRef::as_ref).collect();
+
+    // Work in the unit hypercube so that one radius suits every axis
+    let ranges: Vec<(f64, f64)> = (0..dimensions)
+        .map(|k| {
+            let (lo, hi) = points
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x[k]), hi.max(x[k])));
+            (lo, if hi > lo { hi - lo } else { 1.0 })
+        })
+        .collect();
+    let scaled: Vec<Vec<f64>> = points
+        .iter()
+        .map(|x| x.iter().zip(&ranges).map(|(v, (lo, span))| (v - lo) / span).collect())
+        .collect();
+
+    let alpha = 4.0 / (config.radius * config.radius);
+    let beta = 4.0 / (config.squash * config.radius).powi(2);
+    let mut potential: Vec<f64> = scaled
+        .iter()
+        .map(|x| scaled.iter().map(|y| (-alpha * squared_distance(x, y)).exp()).sum())
+        .collect();
+
+    let mut chosen: Vec<usize> = Vec::new();
+    let mut first = 0.0;
+    let mut iterations = 0;
+    loop {
+        iterations += 1;
+        let candidate = (0..potential.len())
+            .max_by(|&a, &b| potential[a].total_cmp(&potential[b]))
+            .unwrap();
+        let p = potential[candidate];
+        if chosen.is_empty() {
+            first = p;
+        } else if p <= config.reject * first || p <= 0.0 {
+            break;
+        } else if p < config.accept * first {
+            // Grey zone: accept only if far enough from the existing centers
+            let nearest = chosen
+                .iter()
+                .map(|&c| squared_distance(&scaled[candidate], &scaled[c]))
+                .fold(f64::INFINITY, f64::min)
+                .sqrt();
+            if nearest / config.radius + p / first < 1.0 {
+                potential[candidate] = 0.0;
+                continue;
+            }
+        }
+        chosen.push(candidate);
+        for (x, q) in scaled.iter().zip(potential.iter_mut()) {
+            *q -= p * (-beta * squared_distance(x, &scaled[candidate])).exp();
+        }
+    }
+
+    let centers: Vec<Vec<f64>> = chosen.iter().map(|&i| points[i].to_vec()).collect();
+    let spread: Vec<f64> = ranges
+        .iter()
+        .map(|(_, span)| config.radius * span / 8f64.sqrt())
+        .collect();
+    let distances = euclidean(&points, &centers);
+    let u = memberships(&distances, 2.0);
+    let objective = distances
+        .iter()
+        .flatten()
+        .zip(u.iter().flatten())
+        .map(|(d, u)| u * u * d)
+        .sum();
+    Ok(Clustering {
+        spreads: vec![spread; centers.len()],
+        centers,
+        memberships: u,
+        fuzzifier: 2.0,
+        iterations,
+        converged: true,
+        objective,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRS: [[f64; 1]; 6] = [[0.0], [0.1], [0.2], [10.0], [10.1], [10.2]];
+
+    #[test]
+    fn test_memberships_sum_to_one_and_handle_centers() {
+        let u = memberships(&[vec![1.0, 4.0], vec![0.0, 9.0], vec![0.0, 0.0]], 2.0);
+        assert!((u[0][0] - 0.8).abs() < 1e-12);
+        assert!((u[0][1] - 0.2).abs() < 1e-12);
+        assert_eq!(u[1], vec![1.0, 0.0]);
+        assert_eq!(u[2], vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_fcm_is_seeded_and_converges() {
+        let config = FcmConfig { seed: 3, ..FcmConfig::default() };
+        let clustering = fuzzy_c_means(&PAIRS, &config).unwrap();
+        assert!(clustering.converged);
+        assert_eq!(clustering, fuzzy_c_means(&PAIRS, &config).unwrap());
+
+        let mut centers: Vec<f64> = clustering.centers.iter().map(|c| c[0]).collect();
+        centers.sort_by(f64::total_cmp);
+        assert!((centers[0] - 0.1).abs() < 0.01 && (centers[1] - 10.1).abs() < 0.01);
+        for row in &clustering.memberships {
+            assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_larger_fuzzifier_softens_the_partition() {
+        let crisp = fuzzy_c_means(&PAIRS, &FcmConfig { fuzzifier: 1.5, ..FcmConfig::default() }).unwrap();
+        let soft = fuzzy_c_means(&PAIRS, &FcmConfig { fuzzifier: 4.0, ..FcmConfig::default() }).unwrap();
+        assert!(soft.partition_coefficient() < crisp.partition_coefficient());
+        assert!(soft.partition_entropy() > crisp.partition_entropy());
+    }
+
+    #[test]
+    fn test_subtractive_finds_both_groups() {
+        let clustering = subtractive(&PAIRS, &SubtractiveConfig::default()).unwrap();
+        assert_eq!(clustering.centers.len(), 2);
+        // Spreads are radius · range / √8 on every cluster
+        let expected = 0.5 * 10.2 / 8f64.sqrt();
+        assert!(clustering.spreads.iter().all(|s| (s[0] - expected).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let config = FcmConfig::default();
+        let empty: [[f64; 1]; 0] = [];
+        assert_eq!(fuzzy_c_means(&empty, &config), Err(ClusteringError::NoSamples));
+        assert_eq!(
+            fuzzy_c_means(&[[1.0]], &config),
+            Err(ClusteringError::TooFewSamples { samples: 1, clusters: 2 })
+        );
+        assert_eq!(
+            fuzzy_c_means(&[vec![1.0], vec![1.0, 2.0]], &config),
+            Err(ClusteringError::InconsistentSample { sample: 1, expected: 1, actual: 2 })
+        );
+        assert_eq!(
+            fuzzy_c_means(&[[1.0], [f64::NAN]], &config),
+            Err(ClusteringError::NonFinite(1))
+        );
+        let fuzzifier = FcmConfig { fuzzifier: 1.0, ..FcmConfig::default() };
+        assert!(matches!(
+            gustafson_kessel(&PAIRS, &fuzzifier),
+            Err(ClusteringError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_sugeno_system_rejects_wider_inputs() {
+        let clustering = fuzzy_c_means(&PAIRS, &FcmConfig::default()).unwrap();
+        let inputs = [[0.0, 1.0], [10.0, 1.0]];
+        assert_eq!(
+            clustering.sugeno_system(&inputs, &[0.0, 1.0]).err(),
+            Some(ClusteringError::DimensionMismatch { dimensions: 1, actual: 2 })
+        );
+    }
+}
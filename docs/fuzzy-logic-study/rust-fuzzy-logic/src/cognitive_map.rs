@@ -0,0 +1,604 @@
+//! Fuzzy Cognitive Maps
+//!
+//! A [`FuzzyCognitiveMap`] models causal influence between concepts. Each
+//! concept has an activation, and a signed weight in `[-1, 1]` on the edge
+//! from concept `j` to concept `i` says how strongly `j` increases (or,
+//! when negative, decreases) `i`. Activations are updated together:
+//!
+//! ```text
+//! A_i(t+1) = f(Σ_j w_ji A_j(t))            Kosko
+//! A_i(t+1) = f(A_i(t) + Σ_j w_ji A_j(t))   modified Kosko (with memory)
+//! ```
+//!
+//! where `f` is a [`Squashing`] function. Iterating the update from an
+//! initial state ends in a fixed point, settles into a limit cycle, or
+//! neither within the iteration limit; [`FuzzyCognitiveMap::run`] reports
+//! which. [`FuzzyCognitiveMap::what_if`] holds chosen concepts at fixed
+//! values to explore scenarios.
+//!
+//! [`nonlinear_hebbian`] adjusts the edge weights to historical state
+//! sequences by data-driven nonlinear Hebbian learning, keeping the signs
+//! an expert gave each edge.
+//!
+//! ```
+//! use fuzzy_logic::cognitive_map::{FuzzyCognitiveMap, Outcome, RunConfig};
+//!
+//! let mut map = FuzzyCognitiveMap::new();
+//! let load = map.add_concept("load");
+//! let temperature = map.add_concept("temperature");
+//! let cooling = map.add_concept("cooling");
+//! map.set_weight(load, temperature, 0.7);
+//! map.set_weight(temperature, cooling, 0.8);
+//! map.set_weight(cooling, temperature, -0.6);
+//!
+//! // What if the load stays high?
+//! let run = map.what_if(&[0.5, 0.5, 0.5], &[(load, 1.0)], &RunConfig::default());
+//! assert_eq!(run.outcome, Outcome::FixedPoint);
+//! assert_eq!(run.final_state()[load], 1.0);
+//! ```
+
+use std::fmt;
+
+/// Threshold function applied to each concept's weighted input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Squashing {
+    /// `1 / (1 + e^(-λx))`, activations in `(0, 1)`
+    Sigmoid { steepness: f64 },
+    /// `tanh(λx)`, activations in `(-1, 1)`
+    Tanh { steepness: f64 },
+    /// 1 for positive input, otherwise 0
+    Bivalent,
+}
+
+impl Squashing {
+    pub fn apply(self, x: f64) -> f64 {
+        match self {
+            Squashing::Sigmoid { steepness } => 1.0 / (1.0 + (-steepness * x).exp()),
+            Squashing::Tanh { steepness } => (steepness * x).tanh(),
+            Squashing::Bivalent => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Range of activations this function produces
+    pub fn range(self) -> (f64, f64) {
+        match self {
+            Squashing::Tanh { .. } => (-1.0, 1.0),
+            Squashing::Sigmoid { .. } | Squashing::Bivalent => (0.0, 1.0),
+        }
+    }
+}
+
+impl Default for Squashing {
+    fn default() -> Self {
+        Squashing::Sigmoid { steepness: 1.0 }
+    }
+}
+
+/// How the next activation combines the weighted inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Update {
+    /// Only the influence of other concepts
+    #[default]
+    Kosko,
+    /// The concept's own activation plus the influence of the others
+    ModifiedKosko,
+}
+
+/// Settings for [`FuzzyCognitiveMap::run`]
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    pub max_iterations: usize,
+    /// Largest activation difference for two states to count as equal
+    pub tolerance: f64,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        RunConfig {
+            max_iterations: 100,
+            tolerance: 1e-5,
+        }
+    }
+}
+
+/// How an iteration ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The state stopped changing
+    FixedPoint,
+    /// The state repeats every `period` iterations
+    LimitCycle { period: usize },
+    /// Neither within the iteration limit (possibly chaotic)
+    NoConvergence,
+}
+
+/// States visited by a run, starting with the initial state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub states: Vec<Vec<f64>>,
+    pub outcome: Outcome,
+    /// Concept names, for reports
+    pub concepts: Vec<String>,
+}
+
+impl Run {
+    /// Iterations performed
+    pub fn iterations(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn final_state(&self) -> &[f64] {
+        self.states.last().expect("a run holds its initial state")
+    }
+
+    /// The states of the limit cycle, or the fixed point alone
+    pub fn attractor(&self) -> &[Vec<f64>] {
+        let n = self.states.len();
+        match self.outcome {
+            Outcome::FixedPoint => &self.states[n - 1..],
+            Outcome::LimitCycle { period } => &self.states[n - period..],
+            Outcome::NoConvergence => &[],
+        }
+    }
+
+    /// `iteration,<concept>...` rows with a header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("iteration");
+        for name in &self.concepts {
+            csv.push(',');
+            csv.push_str(name);
+        }
+        csv.push('\n');
+        for (t, state) in self.states.iter().enumerate() {
+            csv.push_str(&t.to_string());
+            for value in state {
+                csv.push_str(&format!(",{}", value));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.outcome {
+            Outcome::FixedPoint => writeln!(f, "Fixed point after {} iterations", self.iterations())?,
+            Outcome::LimitCycle { period } => writeln!(
+                f,
+                "Limit cycle of period {} after {} iterations",
+                period,
+                self.iterations()
+            )?,
+            Outcome::NoConvergence => writeln!(f, "No convergence after {} iterations", self.iterations())?,
+        }
+        let width = self.concepts.iter().map(|c| c.len()).max().unwrap_or(0);
+        for (name, value) in self.concepts.iter().zip(self.final_state()) {
+            writeln!(f, "  {:width$}  {:.4}", name, value, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+/// Concepts joined by signed, weighted causal edges
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuzzyCognitiveMap {
+    concepts: Vec<String>,
+    /// `weights[j][i]`: influence of concept `j` on concept `i`
+    weights: Vec<Vec<f64>>,
+    squashing: Squashing,
+    update: Update,
+}
+
+impl FuzzyCognitiveMap {
+    /// An empty map with sigmoid squashing and the Kosko update
+    pub fn new() -> Self {
+        FuzzyCognitiveMap::default()
+    }
+
+    pub fn with_squashing(mut self, squashing: Squashing) -> Self {
+        self.squashing = squashing;
+        self
+    }
+
+    pub fn with_update(mut self, update: Update) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Add a concept without edges and return its index
+    pub fn add_concept(&mut self, name: impl Into<String>) -> usize {
+        self.concepts.push(name.into());
+        for row in &mut self.weights {
+            row.push(0.0);
+        }
+        self.weights.push(vec![0.0; self.concepts.len()]);
+        self.concepts.len() - 1
+    }
+
+    /// Set the weight of the edge from `from` to `to`; zero removes it
+    ///
+    /// Panics if the weight is outside `[-1, 1]`.
+    pub fn set_weight(&mut self, from: usize, to: usize, weight: f64) {
+        assert!((-1.0..=1.0).contains(&weight), "Edge weights must be in [-1, 1]");
+        self.weights[from][to] = weight;
+    }
+
+    pub fn weight(&self, from: usize, to: usize) -> f64 {
+        self.weights[from][to]
+    }
+
+    /// Nonzero edges as `(from, to, weight)`
+    pub fn edges(&self) -> Vec<(usize, usize, f64)> {
+        let mut edges = Vec::new();
+        for (from, row) in self.weights.iter().enumerate() {
+            for (to, &weight) in row.iter().enumerate() {
+                if weight != 0.0 {
+                    edges.push((from, to, weight));
+                }
+            }
+        }
+        edges
+    }
+
+    pub fn concepts(&self) -> &[String] {
+        &self.concepts
+    }
+
+    pub fn concept_index(&self, name: &str) -> Option<usize> {
+        self.concepts.iter().position(|c| c == name)
+    }
+
+    pub fn squashing(&self) -> Squashing {
+        self.squashing
+    }
+
+    pub fn update(&self) -> Update {
+        self.update
+    }
+
+    /// The state after one update
+    pub fn step(&self, state: &[f64]) -> Vec<f64> {
+        assert_eq!(state.len(), self.concepts.len(), "Expected one activation per concept");
+        (0..self.concepts.len())
+            .map(|i| {
+                let influence: f64 = state.iter().zip(&self.weights).map(|(a, row)| a * row[i]).sum();
+                let own = match self.update {
+                    Update::Kosko => 0.0,
+                    Update::ModifiedKosko => state[i],
+                };
+                self.squashing.apply(own + influence)
+            })
+            .collect()
+    }
+
+    /// Iterate from `initial` until a fixed point, a limit cycle or the
+    /// iteration limit
+    pub fn run(&self, initial: &[f64], config: &RunConfig) -> Run {
+        self.what_if(initial, &[], config)
+    }
+
+    /// Like [`run`](Self::run), with each `(concept, value)` held at its
+    /// value throughout
+    pub fn what_if(&self, initial: &[f64], clamped: &[(usize, f64)], config: &RunConfig) -> Run {
+        assert_eq!(initial.len(), self.concepts.len(), "Expected one activation per concept");
+        let clamp = |state: &mut Vec<f64>| {
+            for &(concept, value) in clamped {
+                state[concept] = value;
+            }
+        };
+        let same = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() <= config.tolerance);
+
+        let mut state = initial.to_vec();
+        clamp(&mut state);
+        let mut states = vec![state];
+        let mut outcome = Outcome::NoConvergence;
+        for _ in 0..config.max_iterations {
+            let mut next = self.step(states.last().unwrap());
+            clamp(&mut next);
+            // The most recent earlier match gives the shortest period
+            let repeat = states.iter().rposition(|s| same(s, &next));
+            states.push(next);
+            if let Some(k) = repeat {
+                let period = states.len() - 1 - k;
+                outcome = if period == 1 {
+                    Outcome::FixedPoint
+                } else {
+                    Outcome::LimitCycle { period }
+                };
+                break;
+            }
+        }
+        Run {
+            states,
+            outcome,
+            concepts: self.concepts.clone(),
+        }
+    }
+}
+
+/// Settings for [`nonlinear_hebbian`]
+#[derive(Debug, Clone)]
+pub struct HebbianConfig {
+    /// Learning rate η
+    pub learning_rate: f64,
+    /// Weight decay γ applied at every update; 1 disables it
+    pub decay: f64,
+    pub max_epochs: usize,
+    /// Stop once no weight changes by more than this over an epoch
+    pub tolerance: f64,
+}
+
+impl Default for HebbianConfig {
+    fn default() -> Self {
+        HebbianConfig {
+            learning_rate: 0.5,
+            decay: 1.0,
+            max_epochs: 500,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// Errors learning from state sequences
+#[derive(Debug, Clone, PartialEq)]
+pub enum CognitiveMapError {
+    /// No sequence has two states
+    NoTransitions,
+    /// A state does not have one activation per concept
+    WrongStateLength {
+        sequence: usize,
+        step: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A state holds an activation outside the squashing function's range
+    OutOfRange { sequence: usize, step: usize },
+    /// The map has no edges to learn
+    NoEdges,
+    InvalidConfig(String),
+}
+
+impl fmt::Display for CognitiveMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CognitiveMapError::NoTransitions => write!(f, "no sequence has more than one state"),
+            CognitiveMapError::WrongStateLength {
+                sequence,
+                step,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "sequence {} step {}: expected {} activations, got {}",
+                sequence, step, expected, actual
+            ),
+            CognitiveMapError::OutOfRange { sequence, step } => write!(
+                f,
+                "sequence {} step {}: activation outside the squashing range",
+                sequence, step
+            ),
+            CognitiveMapError::NoEdges => write!(f, "the map has no edges to learn"),
+            CognitiveMapError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CognitiveMapError {}
+
+/// A learned map and how learning went
+#[derive(Debug, Clone)]
+pub struct Learning {
+    pub map: FuzzyCognitiveMap,
+    pub epochs: usize,
+    /// Whether the weights settled within the tolerance
+    pub converged: bool,
+    /// Mean squared one-step prediction error before learning and after
+    /// each epoch
+    pub errors: Vec<f64>,
+}
+
+/// Mean squared error of the map's one-step predictions over the sequences
+pub fn prediction_error(map: &FuzzyCognitiveMap, sequences: &[Vec<Vec<f64>>]) -> f64 {
+    let mut total = 0.0;
+    let mut count = 0;
+    for sequence in sequences {
+        for pair in sequence.windows(2) {
+            let predicted = map.step(&pair[0]);
+            total += predicted.iter().zip(&pair[1]).map(|(p, a)| (p - a) * (p - a)).sum::<f64>();
+            count += pair[1].len();
+        }
+    }
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+/// Learn the weights of the existing edges from observed state sequences
+///
+/// This is data-driven nonlinear Hebbian learning (DD-NHL, after Stach,
+/// Kurgan and Pedrycz). Every observed transition `A(t) → A(t+1)` changes
+/// each concept by `ΔA = A(t+1) - A(t)`, and each edge `j → i` follows
+/// Oja's rule on those changes,
+///
+/// ```text
+/// w_ji ← γ w_ji + η ΔA_j (ΔA_i - w_ji ΔA_j)
+/// ```
+///
+/// The Hebbian term `ΔA_j ΔA_i` strengthens edges whose ends change
+/// together, in the same direction for positive edges and in opposite
+/// directions for negative ones. The nonlinear term keeps the weight
+/// bounded: without decay it settles where
+/// `w_ji = Σ ΔA_j ΔA_i / Σ ΔA_j²`. The rule is unsupervised, so it fits
+/// co-movement rather than minimizing the map's prediction error;
+/// [`Learning::errors`] records that error per epoch for comparison.
+///
+/// Edges keep the sign they started with (a weight that would cross zero
+/// stops at zero), stay within `[-1, 1]`, and no edges are added, so the
+/// causal structure stays as the expert drew it.
+pub fn nonlinear_hebbian(
+    map: &FuzzyCognitiveMap,
+    sequences: &[Vec<Vec<f64>>],
+    config: &HebbianConfig,
+) -> Result<Learning, CognitiveMapError> {
+    if config.learning_rate.is_nan() || config.learning_rate <= 0.0 {
+        return Err(CognitiveMapError::InvalidConfig("learning_rate must be positive".to_string()));
+    }
+    if !(0.0..=1.0).contains(&config.decay) || config.decay == 0.0 {
+        return Err(CognitiveMapError::InvalidConfig("decay must be in (0, 1]".to_string()));
+    }
+    let n = map.concepts.len();
+    let (low, high) = map.squashing.range();
+    for (s, sequence) in sequences.iter().enumerate() {
+        for (t, state) in sequence.iter().enumerate() {
+            if state.len() != n {
+                return Err(CognitiveMapError::WrongStateLength {
+                    sequence: s,
+                    step: t,
+                    expected: n,
+                    actual: state.len(),
+                });
+            }
+            if state.iter().any(|a| a.is_nan() || *a < low || *a > high) {
+                return Err(CognitiveMapError::OutOfRange { sequence: s, step: t });
+            }
+        }
+    }
+    if sequences.iter().all(|s| s.len() < 2) {
+        return Err(CognitiveMapError::NoTransitions);
+    }
+    let edges = map.edges();
+    if edges.is_empty() {
+        return Err(CognitiveMapError::NoEdges);
+    }
+
+    let mut learned = map.clone();
+    let mut errors = vec![prediction_error(&learned, sequences)];
+    let mut converged = false;
+    let mut epochs = 0;
+    while epochs < config.max_epochs && !converged {
+        epochs += 1;
+        let before = learned.weights.clone();
+        for sequence in sequences {
+            for pair in sequence.windows(2) {
+                let change: Vec<f64> = pair[1].iter().zip(&pair[0]).map(|(next, current)| next - current).collect();
+                for &(j, i, original) in &edges {
+                    let w = learned.weights[j][i];
+                    let updated = config.decay * w + config.learning_rate * change[j] * (change[i] - w * change[j]);
+                    // Keep the expert's sign
+                    learned.weights[j][i] = if original > 0.0 {
+                        updated.clamp(0.0, 1.0)
+                    } else {
+                        updated.clamp(-1.0, 0.0)
+                    };
+                }
+            }
+        }
+        let change = edges
+            .iter()
+            .map(|&(j, i, _)| (learned.weights[j][i] - before[j][i]).abs())
+            .fold(0.0, f64::max);
+        converged = change <= config.tolerance;
+        errors.push(prediction_error(&learned, sequences));
+    }
+
+    Ok(Learning {
+        map: learned,
+        epochs,
+        converged,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squashing_functions() {
+        assert_eq!(Squashing::Sigmoid { steepness: 1.0 }.apply(0.0), 0.5);
+        assert!((Squashing::Sigmoid { steepness: 5.0 }.apply(1.0) - 0.99331).abs() < 1e-5);
+        assert!((Squashing::Tanh { steepness: 2.0 }.apply(-0.5) + 0.76159).abs() < 1e-5);
+        assert_eq!(Squashing::Bivalent.apply(0.0), 0.0);
+        assert_eq!(Squashing::Bivalent.apply(0.1), 1.0);
+    }
+
+    #[test]
+    fn test_step_applies_the_weighted_influence() {
+        let mut map = FuzzyCognitiveMap::new().with_squashing(Squashing::Tanh { steepness: 1.0 });
+        let a = map.add_concept("a");
+        let b = map.add_concept("b");
+        map.set_weight(a, b, 0.5);
+        map.set_weight(b, a, -1.0);
+        assert_eq!(map.edges(), vec![(0, 1, 0.5), (1, 0, -1.0)]);
+
+        let next = map.step(&[0.4, 0.2]);
+        assert!((next[0] - (-0.2f64).tanh()).abs() < 1e-12);
+        assert!((next[1] - 0.2f64.tanh()).abs() < 1e-12);
+
+        let memory = map.clone().with_update(Update::ModifiedKosko).step(&[0.4, 0.2]);
+        assert!((memory[0] - 0.2f64.tanh()).abs() < 1e-12);
+        assert!((memory[1] - 0.4f64.tanh()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fixed_points_and_limit_cycles_are_told_apart() {
+        // Bivalent inhibition around a ring of three oscillates
+        let mut ring = FuzzyCognitiveMap::new().with_squashing(Squashing::Bivalent);
+        for name in ["a", "b", "c"] {
+            ring.add_concept(name);
+        }
+        for i in 0..3 {
+            ring.set_weight(i, (i + 1) % 3, 1.0);
+        }
+        let run = ring.run(&[1.0, 0.0, 0.0], &RunConfig::default());
+        assert_eq!(run.outcome, Outcome::LimitCycle { period: 3 });
+        assert_eq!(run.iterations(), 3);
+        assert_eq!(run.attractor(), &[vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0], vec![1.0, 0.0, 0.0]]);
+        assert!(run.to_string().starts_with("Limit cycle of period 3 after 3 iterations\n  a  1.0000\n"));
+        assert!(run.to_csv().starts_with("iteration,a,b,c\n0,1,0,0\n1,0,1,0\n"));
+
+        // Sigmoid squashing with weak edges contracts to a fixed point
+        let mut calm = FuzzyCognitiveMap::new();
+        let x = calm.add_concept("x");
+        let y = calm.add_concept("y");
+        calm.set_weight(x, y, 0.5);
+        calm.set_weight(y, x, -0.5);
+        let run = calm.run(&[0.9, 0.1], &RunConfig::default());
+        assert_eq!(run.outcome, Outcome::FixedPoint);
+        let point = run.final_state().to_vec();
+        let next = calm.step(&point);
+        assert!(point.iter().zip(&next).all(|(a, b)| (a - b).abs() < 1e-4));
+
+        let short = calm.run(&[0.9, 0.1], &RunConfig { max_iterations: 2, ..RunConfig::default() });
+        assert_eq!(short.outcome, Outcome::NoConvergence);
+        assert!(short.attractor().is_empty());
+    }
+
+    #[test]
+    fn test_what_if_holds_clamped_concepts() {
+        let mut map = FuzzyCognitiveMap::new().with_squashing(Squashing::Bivalent);
+        let a = map.add_concept("a");
+        let b = map.add_concept("b");
+        map.set_weight(a, b, 1.0);
+        map.set_weight(b, a, -1.0);
+
+        // Free, `a` switches `b` on, which switches `a` off, and both die out
+        let free = map.run(&[1.0, 0.0], &RunConfig::default());
+        assert_eq!(free.outcome, Outcome::FixedPoint);
+        assert_eq!(free.states, vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 0.0], vec![0.0, 0.0]]);
+        // Holding `a` on settles `b` on
+        let held = map.what_if(&[0.0, 0.0], &[(a, 1.0)], &RunConfig::default());
+        assert_eq!(held.outcome, Outcome::FixedPoint);
+        assert_eq!(held.final_state(), &[1.0, 1.0]);
+        assert!(held.states.iter().all(|s| s[a] == 1.0));
+    }
+}
@@ -0,0 +1,729 @@
+//! System Definition Files
+//!
+//! This module reads and writes [`FuzzySystem`]s in a small line-based text
+//! format, so systems can be kept in files and loaded by the command-line
+//! tool. Blank lines are ignored and `#` starts a comment.
+//!
+//! ```text
+//! # Tipping problem
+//! input service 0 10
+//!   term poor triangular 0 0 5
+//!   term good triangular 0 5 10
+//!   term excellent triangular 5 10 10
+//!
+//! output tip 0 30
+//!   term low triangular 0 0 15
+//!   term high triangular 15 30 30
+//!
+//! and min
+//! defuzzification centroid
+//!
+//! rule if service is poor then tip is low
+//! rule if service is not poor then tip is high with 0.5
+//! ```
+//!
+//! `term` lines belong to the most recent `input` or `output`. Membership
+//! functions are `triangular a b c`, `trapezoidal a b c d`,
+//! `gaussian mean sigma`, `sigmoid a c` and `bell a b c`. The settings are `and`
+//! (`min`, `prod`, `bounded`, `drastic`), `or` (`max`, `probor`, `bounded`,
+//! `drastic`), `implication` (as `and`), `aggregation` (as `or`),
+//! `defuzzification` (`centroid`, `mom`, `som`, `lom`, `bisector`) and
+//! `resolution` (number of output samples).
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::defuzzification::DefuzzificationMethod;
+use crate::membership::MembershipFunction;
+use crate::system::{
+    Antecedent, Connective, Consequent, FuzzySystem, LinguisticVariable, Rule, SNorm, TNorm,
+};
+
+/// Words that cannot be used as variable or term names
+const RESERVED: [&str; 7] = ["if", "is", "not", "and", "or", "then", "with"];
+
+/// Errors loading a system definition
+#[derive(Debug)]
+pub enum DefinitionError {
+    /// The definition file could not be read
+    Io(io::Error),
+    /// The definition is malformed; `line` is 1-based
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::Io(err) => write!(f, "{}", err),
+            DefinitionError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DefinitionError::Io(err) => Some(err),
+            DefinitionError::Syntax { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for DefinitionError {
+    fn from(err: io::Error) -> Self {
+        DefinitionError::Io(err)
+    }
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> DefinitionError {
+    DefinitionError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+impl FromStr for FuzzySystem {
+    type Err = DefinitionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse_system(text)
+    }
+}
+
+/// Which list a variable declaration was added to
+#[derive(Clone, Copy)]
+enum Side {
+    Input,
+    Output,
+}
+
+/// Parse a system definition
+pub fn parse_system(text: &str) -> Result<FuzzySystem, DefinitionError> {
+    parse_definition(text, true)
+}
+
+/// Parse a definition that may still be incomplete
+///
+/// Unlike [`parse_system`], a draft may lack inputs, outputs or terms, so
+/// work in progress (e.g. a saved REPL session) can be loaded again.
+pub fn parse_draft(text: &str) -> Result<FuzzySystem, DefinitionError> {
+    parse_definition(text, false)
+}
+
+fn parse_definition(text: &str, complete: bool) -> Result<FuzzySystem, DefinitionError> {
+    let mut inputs: Vec<(usize, LinguisticVariable)> = Vec::new();
+    let mut outputs: Vec<(usize, LinguisticVariable)> = Vec::new();
+    let mut current: Option<Side> = None;
+    let mut rules: Vec<(usize, &str)> = Vec::new();
+    let mut system = FuzzySystem::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let content = raw.split('#').next().unwrap_or("").trim();
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        let Some(&keyword) = tokens.first() else {
+            continue;
+        };
+        let args = &tokens[1..];
+
+        match keyword {
+            "input" | "output" => {
+                let variable = parse_variable(line, args)?;
+                let taken = inputs.iter().chain(&outputs).any(|(_, v)| v.name == variable.name);
+                if taken {
+                    return Err(syntax(line, format!("variable '{}' is already defined", variable.name)));
+                }
+                if keyword == "input" {
+                    inputs.push((line, variable));
+                    current = Some(Side::Input);
+                } else {
+                    outputs.push((line, variable));
+                    current = Some(Side::Output);
+                }
+            }
+            "term" => {
+                let variable = match current {
+                    Some(Side::Input) => inputs.last_mut(),
+                    Some(Side::Output) => outputs.last_mut(),
+                    None => None,
+                };
+                let Some((_, variable)) = variable else {
+                    return Err(syntax(line, "term must follow an input or output"));
+                };
+                let (name, mf) = parse_term(line, args)?;
+                if variable.term_index(&name).is_some() {
+                    return Err(syntax(
+                        line,
+                        format!("term '{}' is already defined for '{}'", name, variable.name),
+                    ));
+                }
+                variable.add_term(name, mf);
+            }
+            "and" | "or" | "implication" | "aggregation" | "defuzzification" | "resolution" => {
+                apply_setting(&mut system, line, keyword, args)?
+            }
+            "rule" => {
+                let start = content.find("rule").unwrap_or(0) + "rule".len();
+                rules.push((line, content[start..].trim()));
+            }
+            other => return Err(syntax(line, format!("unknown keyword '{}'", other))),
+        }
+    }
+
+    if complete {
+        if inputs.is_empty() {
+            return Err(syntax(1, "the system has no inputs"));
+        }
+        if outputs.is_empty() {
+            return Err(syntax(1, "the system has no outputs"));
+        }
+        for (line, variable) in inputs.iter().chain(&outputs) {
+            if variable.terms.is_empty() {
+                return Err(syntax(*line, format!("variable '{}' has no terms", variable.name)));
+            }
+        }
+    }
+
+    for (_, variable) in inputs {
+        system.add_input(variable);
+    }
+    for (_, variable) in outputs {
+        system.add_output(variable);
+    }
+    for (line, text) in rules {
+        let rule = parse_rule_text(&system, text).map_err(|message| syntax(line, message))?;
+        system.add_rule(rule);
+    }
+    Ok(system)
+}
+
+/// Change an operator setting, e.g. `set_option(&mut system, "and", "prod")`
+///
+/// The settings are those of a definition file: `and`, `or`,
+/// `implication`, `aggregation`, `defuzzification` and `resolution`.
+pub fn set_option(system: &mut FuzzySystem, setting: &str, value: &str) -> Result<(), DefinitionError> {
+    apply_setting(system, 1, setting, &[value])
+}
+
+fn apply_setting(
+    system: &mut FuzzySystem,
+    line: usize,
+    setting: &str,
+    args: &[&str],
+) -> Result<(), DefinitionError> {
+    match setting {
+        "and" => system.set_and_method(parse_tnorm(line, args)?),
+        "or" => system.set_or_method(parse_snorm(line, args)?),
+        "implication" => system.set_implication(parse_tnorm(line, args)?),
+        "aggregation" => system.set_aggregation(parse_snorm(line, args)?),
+        "defuzzification" => system.set_defuzzification(parse_defuzzification(line, args)?),
+        "resolution" => {
+            let steps = match args {
+                [value] => value.parse::<usize>().ok().filter(|&s| s >= 2),
+                _ => None,
+            };
+            let steps =
+                steps.ok_or_else(|| syntax(line, "resolution must be an integer of at least 2"))?;
+            system.set_resolution(steps);
+        }
+        other => return Err(syntax(line, format!("unknown setting '{}'", other))),
+    }
+    Ok(())
+}
+
+/// Read and parse a system definition file
+pub fn load_system(path: impl AsRef<Path>) -> Result<FuzzySystem, DefinitionError> {
+    parse_system(&std::fs::read_to_string(path)?)
+}
+
+/// Write a system in the definition format
+///
+/// The result parses back into an equivalent system.
+pub fn format_system(system: &FuzzySystem) -> String {
+    let mut text = String::new();
+    let sections = [("input", system.inputs()), ("output", system.outputs())];
+    for (keyword, variables) in sections {
+        for variable in variables {
+            text.push_str(&format!("{} {} {} {}\n", keyword, variable.name, variable.min, variable.max));
+            for term in &variable.terms {
+                text.push_str(&format!(
+                    "  term {} {}\n",
+                    term.name,
+                    format_membership_function(&term.membership_function)
+                ));
+            }
+            text.push('\n');
+        }
+    }
+
+    text.push_str(&format!("and {}\n", tnorm_name(system.and_method())));
+    text.push_str(&format!("or {}\n", snorm_name(system.or_method())));
+    text.push_str(&format!("implication {}\n", tnorm_name(system.implication())));
+    text.push_str(&format!("aggregation {}\n", snorm_name(system.aggregation())));
+    text.push_str(&format!(
+        "defuzzification {}\n",
+        defuzzification_name(system.defuzzification())
+    ));
+    text.push_str(&format!("resolution {}\n", system.resolution()));
+
+    if !system.rules().is_empty() {
+        text.push('\n');
+    }
+    for rule in system.rules() {
+        text.push_str(&format!("rule {}\n", format_rule(system, rule)));
+    }
+    text
+}
+
+/// Parse the text of a single rule against a system's variables
+///
+/// The text has the form of a `rule` line without the keyword, e.g.
+/// `if service is poor or food is not good then tip is low with 0.5`.
+pub fn parse_rule(system: &FuzzySystem, text: &str) -> Result<Rule, DefinitionError> {
+    parse_rule_text(system, text.trim()).map_err(|message| syntax(1, message))
+}
+
+/// Write a rule as text, e.g. `if service is poor then tip is low`
+pub fn format_rule(system: &FuzzySystem, rule: &Rule) -> String {
+    let joiner = match rule.connective {
+        Connective::And => " and ",
+        Connective::Or => " or ",
+    };
+    let antecedents: Vec<String> = rule
+        .antecedents
+        .iter()
+        .map(|a| {
+            let variable = &system.inputs()[a.variable];
+            let not = if a.negated { "not " } else { "" };
+            format!("{} is {}{}", variable.name, not, variable.terms[a.term].name)
+        })
+        .collect();
+    let consequents: Vec<String> = rule
+        .consequents
+        .iter()
+        .map(|c| {
+            let variable = &system.outputs()[c.variable];
+            format!("{} is {}", variable.name, variable.terms[c.term].name)
+        })
+        .collect();
+
+    let mut text = format!(
+        "if {} then {}",
+        antecedents.join(joiner),
+        consequents.join(" and ")
+    );
+    if rule.weight != 1.0 {
+        text.push_str(&format!(" with {}", rule.weight));
+    }
+    text
+}
+
+/// Write a membership function as `kind p1 p2 ...`
+pub fn format_membership_function(mf: &MembershipFunction) -> String {
+    match mf {
+        MembershipFunction::Triangular { a, b, c } => format!("triangular {} {} {}", a, b, c),
+        MembershipFunction::Trapezoidal { a, b, c, d } => {
+            format!("trapezoidal {} {} {} {}", a, b, c, d)
+        }
+        MembershipFunction::Gaussian { mean, sigma } => format!("gaussian {} {}", mean, sigma),
+        MembershipFunction::Sigmoid { a, c } => format!("sigmoid {} {}", a, c),
+        MembershipFunction::Bell { a, b, c } => format!("bell {} {} {}", a, b, c),
+    }
+}
+
+/// Parse a membership function written as `kind p1 p2 ...`
+pub fn parse_membership_function(tokens: &[&str]) -> Result<MembershipFunction, String> {
+    let Some((&kind, params)) = tokens.split_first() else {
+        return Err("missing membership function".to_string());
+    };
+    let values = params
+        .iter()
+        .map(|p| parse_number(p))
+        .collect::<Result<Vec<f64>, String>>()?;
+    let expect = |count: usize| {
+        if values.len() == count {
+            Ok(())
+        } else {
+            Err(format!("{} takes {} parameters, got {}", kind, count, values.len()))
+        }
+    };
+
+    match kind {
+        "triangular" => {
+            expect(3)?;
+            let (a, b, c) = (values[0], values[1], values[2]);
+            if !(a <= b && b <= c) {
+                return Err("triangular parameters must satisfy a <= b <= c".to_string());
+            }
+            Ok(MembershipFunction::Triangular { a, b, c })
+        }
+        "trapezoidal" => {
+            expect(4)?;
+            let (a, b, c, d) = (values[0], values[1], values[2], values[3]);
+            if !(a <= b && b <= c && c <= d) {
+                return Err("trapezoidal parameters must satisfy a <= b <= c <= d".to_string());
+            }
+            Ok(MembershipFunction::Trapezoidal { a, b, c, d })
+        }
+        "gaussian" => {
+            expect(2)?;
+            if values[1] <= 0.0 {
+                return Err("gaussian sigma must be positive".to_string());
+            }
+            Ok(MembershipFunction::Gaussian {
+                mean: values[0],
+                sigma: values[1],
+            })
+        }
+        "sigmoid" => {
+            expect(2)?;
+            Ok(MembershipFunction::Sigmoid {
+                a: values[0],
+                c: values[1],
+            })
+        }
+        "bell" => {
+            expect(3)?;
+            let (a, b, c) = (values[0], values[1], values[2]);
+            if a <= 0.0 || b <= 0.0 {
+                return Err("bell width and slope must be positive".to_string());
+            }
+            Ok(MembershipFunction::Bell { a, b, c })
+        }
+        other => Err(format!("unknown membership function '{}'", other)),
+    }
+}
+
+fn parse_number(token: &str) -> Result<f64, String> {
+    token
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("'{}' is not a number", token))
+}
+
+/// Check that a variable or term name is not a reserved word
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if RESERVED.contains(&name) {
+        Err(format!("'{}' is a reserved word", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// `name min max`
+fn parse_variable(line: usize, args: &[&str]) -> Result<LinguisticVariable, DefinitionError> {
+    let [name, min, max] = args else {
+        return Err(syntax(line, "expected: <name> <min> <max>"));
+    };
+    validate_name(name).map_err(|m| syntax(line, m))?;
+    let min = parse_number(min).map_err(|m| syntax(line, m))?;
+    let max = parse_number(max).map_err(|m| syntax(line, m))?;
+    if max <= min {
+        return Err(syntax(line, "the universe maximum must be greater than the minimum"));
+    }
+    Ok(LinguisticVariable::new(*name, min, max))
+}
+
+/// `name kind p1 p2 ...`
+fn parse_term(line: usize, args: &[&str]) -> Result<(String, MembershipFunction), DefinitionError> {
+    let Some((&name, mf)) = args.split_first() else {
+        return Err(syntax(line, "expected: term <name> <function> <parameters>"));
+    };
+    validate_name(name).map_err(|m| syntax(line, m))?;
+    let mf = parse_membership_function(mf).map_err(|m| syntax(line, m))?;
+    Ok((name.to_string(), mf))
+}
+
+fn single<'a>(line: usize, args: &[&'a str]) -> Result<&'a str, DefinitionError> {
+    match args {
+        [value] => Ok(value),
+        _ => Err(syntax(line, "expected exactly one value")),
+    }
+}
+
+fn parse_tnorm(line: usize, args: &[&str]) -> Result<TNorm, DefinitionError> {
+    tnorm_from_name(single(line, args)?).map_err(|message| syntax(line, message))
+}
+
+fn parse_snorm(line: usize, args: &[&str]) -> Result<SNorm, DefinitionError> {
+    snorm_from_name(single(line, args)?).map_err(|message| syntax(line, message))
+}
+
+fn parse_defuzzification(line: usize, args: &[&str]) -> Result<DefuzzificationMethod, DefinitionError> {
+    defuzzification_from_name(single(line, args)?).map_err(|message| syntax(line, message))
+}
+
+/// T-norm for a name in the definition format (`min`, `prod`, ...)
+pub fn tnorm_from_name(name: &str) -> Result<TNorm, String> {
+    match name {
+        "min" => Ok(TNorm::Minimum),
+        "prod" => Ok(TNorm::AlgebraicProduct),
+        "bounded" => Ok(TNorm::BoundedDifference),
+        "drastic" => Ok(TNorm::DrasticProduct),
+        other => Err(format!(
+            "unknown t-norm '{}' (expected min, prod, bounded or drastic)",
+            other
+        )),
+    }
+}
+
+/// S-norm for a name in the definition format (`max`, `probor`, ...)
+pub fn snorm_from_name(name: &str) -> Result<SNorm, String> {
+    match name {
+        "max" => Ok(SNorm::Maximum),
+        "probor" => Ok(SNorm::AlgebraicSum),
+        "bounded" => Ok(SNorm::BoundedSum),
+        "drastic" => Ok(SNorm::DrasticSum),
+        other => Err(format!(
+            "unknown s-norm '{}' (expected max, probor, bounded or drastic)",
+            other
+        )),
+    }
+}
+
+/// Defuzzification method for a name in the definition format
+/// (`centroid`, `mom`, `som`, `lom`, `bisector`)
+pub fn defuzzification_from_name(name: &str) -> Result<DefuzzificationMethod, String> {
+    match name {
+        "centroid" => Ok(DefuzzificationMethod::Centroid),
+        "mom" => Ok(DefuzzificationMethod::MeanOfMaximum),
+        "som" => Ok(DefuzzificationMethod::SmallestOfMaximum),
+        "lom" => Ok(DefuzzificationMethod::LargestOfMaximum),
+        "bisector" => Ok(DefuzzificationMethod::Bisector),
+        other => Err(format!(
+            "unknown defuzzification method '{}' (expected centroid, mom, som, lom or bisector)",
+            other
+        )),
+    }
+}
+
+/// Name of a t-norm in the definition format
+pub fn tnorm_name(method: TNorm) -> &'static str {
+    match method {
+        TNorm::Minimum => "min",
+        TNorm::AlgebraicProduct => "prod",
+        TNorm::BoundedDifference => "bounded",
+        TNorm::DrasticProduct => "drastic",
+    }
+}
+
+/// Name of an s-norm in the definition format
+pub fn snorm_name(method: SNorm) -> &'static str {
+    match method {
+        SNorm::Maximum => "max",
+        SNorm::AlgebraicSum => "probor",
+        SNorm::BoundedSum => "bounded",
+        SNorm::DrasticSum => "drastic",
+    }
+}
+
+/// Name of a defuzzification method in the definition format
+pub fn defuzzification_name(method: DefuzzificationMethod) -> &'static str {
+    match method {
+        DefuzzificationMethod::Centroid => "centroid",
+        DefuzzificationMethod::MeanOfMaximum => "mom",
+        DefuzzificationMethod::SmallestOfMaximum => "som",
+        DefuzzificationMethod::LargestOfMaximum => "lom",
+        DefuzzificationMethod::Bisector => "bisector",
+    }
+}
+
+/// Parse `if <clauses> then <conclusions> [with <weight>]`
+fn parse_rule_text(system: &FuzzySystem, text: &str) -> Result<Rule, String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.first() != Some(&"if") {
+        return Err("a rule must start with 'if'".to_string());
+    }
+    let then = tokens
+        .iter()
+        .position(|&t| t == "then")
+        .ok_or("a rule needs a 'then' part")?;
+    let (mut tail, weight) = match tokens.iter().position(|&t| t == "with") {
+        Some(w) => {
+            let [value] = &tokens[w + 1..] else {
+                return Err("'with' must be followed by a single weight".to_string());
+            };
+            let weight = parse_number(value)?;
+            if !(0.0..=1.0).contains(&weight) {
+                return Err("rule weight must be between 0 and 1".to_string());
+            }
+            (&tokens[then + 1..w], weight)
+        }
+        None => (&tokens[then + 1..], 1.0),
+    };
+
+    let mut antecedents = Vec::new();
+    let mut connective = None;
+    let mut head = &tokens[1..then];
+    loop {
+        let (clause, rest) = split_clause(head);
+        let (name, negated, term) = parse_clause(clause, true)?;
+        let variable = system
+            .input_index(name)
+            .ok_or_else(|| format!("unknown input '{}'", name))?;
+        let term = system.inputs()[variable]
+            .term_index(term)
+            .ok_or_else(|| format!("input '{}' has no term '{}'", name, term))?;
+        antecedents.push(Antecedent {
+            variable,
+            term,
+            negated,
+        });
+
+        let Some((&joiner, rest)) = rest.split_first() else {
+            break;
+        };
+        let next = if joiner == "and" { Connective::And } else { Connective::Or };
+        if connective.is_some_and(|c| c != next) {
+            return Err("a rule cannot mix 'and' and 'or'".to_string());
+        }
+        connective = Some(next);
+        head = rest;
+    }
+
+    let mut consequents = Vec::new();
+    loop {
+        let (clause, rest) = split_clause(tail);
+        let (name, _, term) = parse_clause(clause, false)?;
+        let variable = system
+            .output_index(name)
+            .ok_or_else(|| format!("unknown output '{}'", name))?;
+        let term = system.outputs()[variable]
+            .term_index(term)
+            .ok_or_else(|| format!("output '{}' has no term '{}'", name, term))?;
+        consequents.push(Consequent { variable, term });
+
+        match rest.split_first() {
+            None => break,
+            Some((&"and", rest)) => tail = rest,
+            Some(_) => return Err("conclusions must be joined with 'and'".to_string()),
+        }
+    }
+
+    Ok(Rule::new(antecedents, consequents)
+        .with_connective(connective.unwrap_or(Connective::And))
+        .with_weight(weight))
+}
+
+/// Split tokens at the first `and`/`or`, keeping the joiner in the rest
+fn split_clause<'a, 'b>(tokens: &'a [&'b str]) -> (&'a [&'b str], &'a [&'b str]) {
+    let end = tokens
+        .iter()
+        .position(|&t| t == "and" || t == "or")
+        .unwrap_or(tokens.len());
+    tokens.split_at(end)
+}
+
+/// `variable is [not] term`
+fn parse_clause<'a>(clause: &[&'a str], allow_not: bool) -> Result<(&'a str, bool, &'a str), String> {
+    match clause {
+        [name, "is", term] if !RESERVED.contains(term) => Ok((name, false, term)),
+        [name, "is", "not", term] if allow_not && !RESERVED.contains(term) => Ok((name, true, term)),
+        _ => Err(format!(
+            "expected '<variable> is {}<term>', got '{}'",
+            if allow_not { "[not] " } else { "" },
+            clause.join(" ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIPPING: &str = "
+        # Two inputs, one output
+        input service 0 10
+          term poor triangular 0 0 5
+          term good triangular 0 5 10
+          term excellent triangular 5 10 10
+        input food 0 10
+          term rancid trapezoidal 0 0 1 3
+          term delicious gaussian 10 2
+        output tip 0 30
+          term low triangular 0 0 15
+          term high triangular 15 30 30
+
+        and prod
+        defuzzification mom
+        resolution 201
+
+        rule if service is poor or food is rancid then tip is low
+        rule if service is excellent and food is not rancid then tip is high with 0.5
+    ";
+
+    #[test]
+    fn test_parse_system() {
+        let system = parse_system(TIPPING).unwrap();
+
+        assert_eq!(system.inputs().len(), 2);
+        assert_eq!(system.outputs()[0].terms.len(), 2);
+        assert_eq!(system.and_method(), TNorm::AlgebraicProduct);
+        assert_eq!(system.resolution(), 201);
+        assert!(matches!(system.defuzzification(), DefuzzificationMethod::MeanOfMaximum));
+
+        let rules = system.rules();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].connective, Connective::Or);
+        assert_eq!(rules[1].antecedents[1], Antecedent::not(1, 0));
+        assert_eq!(rules[1].weight, 0.5);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let system = parse_system(TIPPING).unwrap();
+        let text = format_system(&system);
+        let again: FuzzySystem = text.parse().unwrap();
+
+        assert_eq!(format_system(&again), text);
+        assert_eq!(system.evaluate(&[3.0, 8.0]), again.evaluate(&[3.0, 8.0]));
+        assert!(text.contains("rule if service is excellent and food is not rancid then tip is high with 0.5"));
+
+        let bell = parse_membership_function(&["bell", "2", "3", "5"]).unwrap();
+        assert_eq!(bell, MembershipFunction::Bell { a: 2.0, b: 3.0, c: 5.0 });
+        assert_eq!(format_membership_function(&bell), "bell 2 3 5");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error_line = |text: &str| match parse_system(text) {
+            Err(DefinitionError::Syntax { line, message }) => (line, message),
+            other => panic!("expected a syntax error, got {:?}", other.map(|_| ())),
+        };
+
+        let (line, message) = error_line("input x 0 10\n  term a triangular 0 5\n");
+        assert_eq!(line, 2);
+        assert!(message.contains("3 parameters"));
+
+        let (_, message) = error_line("input x 0 10\n  term a bell 0 2 5\n");
+        assert!(message.contains("positive"));
+
+        let (line, _) = error_line("term a triangular 0 0 1\n");
+        assert_eq!(line, 1);
+
+        let base = "input x 0 1\nterm a triangular 0 0 1\noutput y 0 1\nterm b triangular 0 1 1\n";
+        let (line, message) = error_line(&format!("{}rule if x is c then y is b\n", base));
+        assert_eq!(line, 5);
+        assert_eq!(message, "input 'x' has no term 'c'");
+
+        let (_, message) = error_line(&format!("{}rule if x is a and x is a or x is a then y is b\n", base));
+        assert!(message.contains("mix"));
+
+        let (_, message) = error_line("input x 0 1\nterm a triangular 0 0 1\n");
+        assert!(message.contains("no outputs"));
+    }
+
+    #[test]
+    fn test_parse_rule_against_system() {
+        let system = parse_system(TIPPING).unwrap();
+        let rule = parse_rule(&system, "if food is delicious then tip is high").unwrap();
+
+        assert_eq!(rule.antecedents, vec![Antecedent::new(1, 1)]);
+        assert_eq!(rule.consequents, vec![Consequent::new(0, 1)]);
+        assert_eq!(format_rule(&system, &rule), "if food is delicious then tip is high");
+        assert!(parse_rule(&system, "if tip is low then tip is high").is_err());
+    }
+}
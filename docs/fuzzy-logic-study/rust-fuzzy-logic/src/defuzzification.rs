@@ -2,6 +2,11 @@
 //!
 //! This module provides various methods for converting fuzzy sets
 //! back to crisp values.
+//!
+//! The functions work on `f64`; [`DefuzzificationMethod::apply`] runs the
+//! same methods over any [`Real`] type.
+
+use crate::numeric::Real;
 
 /// Centroid (Center of Gravity) defuzzification method
 ///
@@ -16,26 +21,7 @@
 ///
 /// The crisp output value
 pub fn centroid(x_values: &[f64], membership: &[f64]) -> f64 {
-    assert_eq!(
-        x_values.len(),
-        membership.len(),
-        "x_values and membership must have same length"
-    );
-
-    let numerator: f64 = x_values
-        .iter()
-        .zip(membership.iter())
-        .map(|(x, m)| x * m)
-        .sum();
-
-    let denominator: f64 = membership.iter().sum();
-
-    if denominator == 0.0 {
-        // Return midpoint if no membership
-        (x_values[0] + x_values[x_values.len() - 1]) / 2.0
-    } else {
-        numerator / denominator
-    }
+    DefuzzificationMethod::Centroid.apply(x_values, membership)
 }
 
 /// Mean of Maximum defuzzification method
@@ -51,73 +37,21 @@ pub fn centroid(x_values: &[f64], membership: &[f64]) -> f64 {
 ///
 /// The crisp output value
 pub fn mean_of_maximum(x_values: &[f64], membership: &[f64]) -> f64 {
-    assert_eq!(x_values.len(), membership.len());
-
-    let max_membership = membership
-        .iter()
-        .cloned()
-        .fold(f64::NEG_INFINITY, f64::max);
-
-    if max_membership == 0.0 {
-        return (x_values[0] + x_values[x_values.len() - 1]) / 2.0;
-    }
-
-    let max_x_values: Vec<f64> = x_values
-        .iter()
-        .zip(membership.iter())
-        .filter(|(_, &m)| m == max_membership)
-        .map(|(&x, _)| x)
-        .collect();
-
-    max_x_values.iter().sum::<f64>() / max_x_values.len() as f64
+    DefuzzificationMethod::MeanOfMaximum.apply(x_values, membership)
 }
 
 /// Smallest of Maximum defuzzification method
 ///
 /// Returns the smallest x value where membership is maximum.
 pub fn smallest_of_maximum(x_values: &[f64], membership: &[f64]) -> f64 {
-    assert_eq!(x_values.len(), membership.len());
-
-    let max_membership = membership
-        .iter()
-        .cloned()
-        .fold(f64::NEG_INFINITY, f64::max);
-
-    if max_membership == 0.0 {
-        return x_values[0];
-    }
-
-    x_values
-        .iter()
-        .zip(membership.iter())
-        .filter(|(_, &m)| m == max_membership)
-        .map(|(&x, _)| x)
-        .min_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
+    DefuzzificationMethod::SmallestOfMaximum.apply(x_values, membership)
 }
 
 /// Largest of Maximum defuzzification method
 ///
 /// Returns the largest x value where membership is maximum.
 pub fn largest_of_maximum(x_values: &[f64], membership: &[f64]) -> f64 {
-    assert_eq!(x_values.len(), membership.len());
-
-    let max_membership = membership
-        .iter()
-        .cloned()
-        .fold(f64::NEG_INFINITY, f64::max);
-
-    if max_membership == 0.0 {
-        return x_values[x_values.len() - 1];
-    }
-
-    x_values
-        .iter()
-        .zip(membership.iter())
-        .filter(|(_, &m)| m == max_membership)
-        .map(|(&x, _)| x)
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
+    DefuzzificationMethod::LargestOfMaximum.apply(x_values, membership)
 }
 
 /// Bisector defuzzification method
@@ -133,25 +67,7 @@ pub fn largest_of_maximum(x_values: &[f64], membership: &[f64]) -> f64 {
 ///
 /// The crisp output value
 pub fn bisector(x_values: &[f64], membership: &[f64]) -> f64 {
-    assert_eq!(x_values.len(), membership.len());
-
-    let total_area: f64 = membership.iter().sum();
-
-    if total_area == 0.0 {
-        return (x_values[0] + x_values[x_values.len() - 1]) / 2.0;
-    }
-
-    let half_area = total_area / 2.0;
-    let mut cumulative_area = 0.0;
-
-    for (i, &m) in membership.iter().enumerate() {
-        cumulative_area += m;
-        if cumulative_area >= half_area {
-            return x_values[i];
-        }
-    }
-
-    x_values[x_values.len() - 1]
+    DefuzzificationMethod::Bisector.apply(x_values, membership)
 }
 
 /// Defuzzification method enum
@@ -164,18 +80,128 @@ pub enum DefuzzificationMethod {
     Bisector,
 }
 
+impl DefuzzificationMethod {
+    /// Apply this method to a sampled fuzzy set in any [`Real`] type
+    pub fn apply<T: Real>(self, x_values: &[T], membership: &[T]) -> T {
+        match self {
+            DefuzzificationMethod::Centroid => generic::centroid(x_values, membership),
+            DefuzzificationMethod::MeanOfMaximum => generic::mean_of_maximum(x_values, membership),
+            DefuzzificationMethod::SmallestOfMaximum => generic::smallest_of_maximum(x_values, membership),
+            DefuzzificationMethod::LargestOfMaximum => generic::largest_of_maximum(x_values, membership),
+            DefuzzificationMethod::Bisector => generic::bisector(x_values, membership),
+        }
+    }
+}
+
 /// Apply specified defuzzification method
 pub fn defuzzify(
     x_values: &[f64],
     membership: &[f64],
     method: DefuzzificationMethod,
 ) -> f64 {
-    match method {
-        DefuzzificationMethod::Centroid => centroid(x_values, membership),
-        DefuzzificationMethod::MeanOfMaximum => mean_of_maximum(x_values, membership),
-        DefuzzificationMethod::SmallestOfMaximum => smallest_of_maximum(x_values, membership),
-        DefuzzificationMethod::LargestOfMaximum => largest_of_maximum(x_values, membership),
-        DefuzzificationMethod::Bisector => bisector(x_values, membership),
+    method.apply(x_values, membership)
+}
+
+/// The methods over any [`Real`] type, behind the `f64` functions above
+mod generic {
+    use crate::numeric::Real;
+
+    /// Middle of the sampled universe
+    fn midpoint<T: Real>(x_values: &[T]) -> T {
+        (x_values[0] + x_values[x_values.len() - 1]).mul_ratio(1, 2)
+    }
+
+    /// Largest membership degree (zero for an empty or all-zero set)
+    fn max_membership<T: Real>(membership: &[T]) -> T {
+        membership.iter().copied().fold(T::ZERO, T::max)
+    }
+
+    pub fn centroid<T: Real>(x_values: &[T], membership: &[T]) -> T {
+        assert_eq!(
+            x_values.len(),
+            membership.len(),
+            "x_values and membership must have same length"
+        );
+
+        let pairs = x_values.iter().copied().zip(membership.iter().copied());
+        // Return midpoint if no membership
+        T::weighted_mean(pairs).unwrap_or_else(|| midpoint(x_values))
+    }
+
+    pub fn mean_of_maximum<T: Real>(x_values: &[T], membership: &[T]) -> T {
+        assert_eq!(x_values.len(), membership.len());
+
+        let max_membership = max_membership(membership);
+
+        if max_membership == T::ZERO {
+            return midpoint(x_values);
+        }
+
+        let maxima = x_values
+            .iter()
+            .zip(membership.iter())
+            .filter(|(_, &m)| m == max_membership)
+            .map(|(&x, _)| (x, T::ONE));
+
+        T::weighted_mean(maxima).unwrap_or_else(|| midpoint(x_values))
+    }
+
+    pub fn smallest_of_maximum<T: Real>(x_values: &[T], membership: &[T]) -> T {
+        assert_eq!(x_values.len(), membership.len());
+
+        let max_membership = max_membership(membership);
+
+        if max_membership == T::ZERO {
+            return x_values[0];
+        }
+
+        x_values
+            .iter()
+            .zip(membership.iter())
+            .filter(|(_, &m)| m == max_membership)
+            .map(|(&x, _)| x)
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap()
+    }
+
+    pub fn largest_of_maximum<T: Real>(x_values: &[T], membership: &[T]) -> T {
+        assert_eq!(x_values.len(), membership.len());
+
+        let max_membership = max_membership(membership);
+
+        if max_membership == T::ZERO {
+            return x_values[x_values.len() - 1];
+        }
+
+        x_values
+            .iter()
+            .zip(membership.iter())
+            .filter(|(_, &m)| m == max_membership)
+            .map(|(&x, _)| x)
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap()
+    }
+
+    pub fn bisector<T: Real>(x_values: &[T], membership: &[T]) -> T {
+        assert_eq!(x_values.len(), membership.len());
+
+        let total_area = membership.iter().fold(T::ZERO, |sum, &m| sum + m);
+
+        if total_area == T::ZERO {
+            return midpoint(x_values);
+        }
+
+        let half_area = total_area.mul_ratio(1, 2);
+        let mut cumulative_area = T::ZERO;
+
+        for (i, &m) in membership.iter().enumerate() {
+            cumulative_area = cumulative_area + m;
+            if cumulative_area >= half_area {
+                return x_values[i];
+            }
+        }
+
+        x_values[x_values.len() - 1]
     }
 }
 
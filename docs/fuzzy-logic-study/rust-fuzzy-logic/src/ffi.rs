@@ -0,0 +1,413 @@
+//! C API
+//!
+//! A stable C interface for using fuzzy systems from C, C#/.NET (P/Invoke)
+//! and other languages with a C FFI. The declarations are in
+//! `include/fuzzy_logic.h`, generated from this module by cbindgen; the
+//! shared library is built by the `capi` workspace member with
+//! `cargo build --release -p fuzzy_logic_capi`.
+//!
+//! Systems are opaque handles created from definition text (see
+//! [`crate::definition`]) and released with [`fuzzy_system_free`]. Calls
+//! that can fail return a status code (or `NULL`) and leave a message for
+//! [`fuzzy_last_error`]. Panics never cross the boundary: they are caught
+//! and reported as [`FUZZY_ERROR_PANIC`].
+//!
+//! A handle may be shared between threads; evaluations on the same handle
+//! are serialized.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::Mutex;
+
+use crate::definition::parse_system;
+use crate::system::{FuzzySystem, SystemScratch};
+
+/// The call succeeded
+pub const FUZZY_OK: i32 = 0;
+/// A required pointer was `NULL`
+pub const FUZZY_ERROR_NULL_POINTER: i32 = -1;
+/// An argument was invalid (wrong array length, bad UTF-8, bad definition)
+pub const FUZZY_ERROR_INVALID_ARGUMENT: i32 = -2;
+/// The library panicked; the handle should not be used again
+pub const FUZZY_ERROR_PANIC: i32 = -3;
+
+/// A system and the buffers used to evaluate it
+pub struct FuzzyHandle {
+    system: FuzzySystem,
+    scratch: Mutex<SystemScratch>,
+    input_names: Vec<CString>,
+    output_names: Vec<CString>,
+}
+
+impl FuzzyHandle {
+    fn new(system: FuzzySystem) -> Self {
+        let names = |vars: &[crate::system::LinguisticVariable]| {
+            vars.iter()
+                .map(|v| CString::new(v.name.as_str()).unwrap_or_default())
+                .collect()
+        };
+        FuzzyHandle {
+            scratch: Mutex::new(system.scratch()),
+            input_names: names(system.inputs()),
+            output_names: names(system.outputs()),
+            system,
+        }
+    }
+}
+
+/// A failed call: status code and message
+struct Failure(i32, String);
+
+fn invalid(message: impl Into<String>) -> Failure {
+    Failure(FUZZY_ERROR_INVALID_ARGUMENT, message.into())
+}
+
+fn null(name: &str) -> Failure {
+    Failure(FUZZY_ERROR_NULL_POINTER, format!("{} is NULL", name))
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: Option<String>) {
+    let message = message.map(|m| CString::new(m.replace('\0', " ")).unwrap_or_default());
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+}
+
+/// Run `body`, recording its error (or panic) as the last error
+fn guard<T>(body: impl FnOnce() -> Result<T, Failure>) -> Result<T, i32> {
+    let result = catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(Failure(FUZZY_ERROR_PANIC, format!("internal error: {}", message)))
+    });
+    match result {
+        Ok(value) => {
+            set_last_error(None);
+            Ok(value)
+        }
+        Err(Failure(code, message)) => {
+            set_last_error(Some(message));
+            Err(code)
+        }
+    }
+}
+
+fn status(result: Result<(), i32>) -> i32 {
+    result.err().unwrap_or(FUZZY_OK)
+}
+
+/// Borrow a handle, failing on `NULL`
+///
+/// # Safety
+///
+/// `handle` must be `NULL` or a live pointer from this library.
+unsafe fn handle<'a>(handle: *const FuzzyHandle) -> Result<&'a FuzzyHandle, Failure> {
+    handle.as_ref().ok_or_else(|| null("system"))
+}
+
+/// Borrow `len` values, failing on `NULL` (an empty array may be `NULL`)
+///
+/// # Safety
+///
+/// `data` must be `NULL` or point to `len` readable values.
+unsafe fn slice<'a>(data: *const f64, len: usize, name: &str) -> Result<&'a [f64], Failure> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(null(name)),
+        (false, _) => Ok(std::slice::from_raw_parts(data, len)),
+    }
+}
+
+/// Mutable counterpart of [`slice`]
+///
+/// # Safety
+///
+/// `data` must be `NULL` or point to `len` writable values.
+unsafe fn slice_mut<'a>(data: *mut f64, len: usize, name: &str) -> Result<&'a mut [f64], Failure> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&mut []),
+        (true, _) => Err(null(name)),
+        (false, _) => Ok(std::slice::from_raw_parts_mut(data, len)),
+    }
+}
+
+fn expect_len(actual: usize, expected: usize, what: &str) -> Result<(), Failure> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(invalid(format!("expected {} {}, got {}", expected, what, actual)))
+    }
+}
+
+/// Create a system from definition text
+///
+/// Returns `NULL` on failure; see [`fuzzy_last_error`].
+///
+/// # Safety
+///
+/// `definition` must be `NULL` or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_from_definition(definition: *const c_char) -> *mut FuzzyHandle {
+    guard(|| {
+        if definition.is_null() {
+            return Err(null("definition"));
+        }
+        let text = CStr::from_ptr(definition)
+            .to_str()
+            .map_err(|_| invalid("definition is not valid UTF-8"))?;
+        let system = parse_system(text).map_err(|e| invalid(e.to_string()))?;
+        Ok(Box::into_raw(Box::new(FuzzyHandle::new(system))))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Release a system; `NULL` is ignored
+///
+/// # Safety
+///
+/// `system` must be `NULL` or a handle that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_free(system: *mut FuzzyHandle) {
+    if !system.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(system))));
+    }
+}
+
+/// Number of input variables (0 for `NULL`)
+///
+/// # Safety
+///
+/// `system` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_input_count(system: *const FuzzyHandle) -> usize {
+    system.as_ref().map_or(0, |h| h.input_names.len())
+}
+
+/// Number of output variables (0 for `NULL`)
+///
+/// # Safety
+///
+/// `system` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_output_count(system: *const FuzzyHandle) -> usize {
+    system.as_ref().map_or(0, |h| h.output_names.len())
+}
+
+/// Name of an input variable, owned by the handle (`NULL` if out of range)
+///
+/// # Safety
+///
+/// `system` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_input_name(system: *const FuzzyHandle, index: usize) -> *const c_char {
+    system
+        .as_ref()
+        .and_then(|h| h.input_names.get(index))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Name of an output variable, owned by the handle (`NULL` if out of range)
+///
+/// # Safety
+///
+/// `system` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_output_name(system: *const FuzzyHandle, index: usize) -> *const c_char {
+    system
+        .as_ref()
+        .and_then(|h| h.output_names.get(index))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Evaluate one set of inputs
+///
+/// `inputs` holds one value per input variable and `outputs` receives one
+/// value per output variable, both in definition order. Returns
+/// [`FUZZY_OK`] or a negative error code.
+///
+/// # Safety
+///
+/// `system` must be a live handle; `inputs` and `outputs` must point to
+/// `input_count` and `output_count` values.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_evaluate(
+    system: *const FuzzyHandle,
+    inputs: *const f64,
+    input_count: usize,
+    outputs: *mut f64,
+    output_count: usize,
+) -> i32 {
+    status(guard(|| {
+        let handle = handle(system)?;
+        let inputs = slice(inputs, input_count, "inputs")?;
+        let outputs = slice_mut(outputs, output_count, "outputs")?;
+        expect_len(inputs.len(), handle.input_names.len(), "inputs")?;
+        expect_len(outputs.len(), handle.output_names.len(), "outputs")?;
+
+        let mut scratch = handle.scratch.lock().unwrap_or_else(|e| e.into_inner());
+        handle.system.evaluate_with(inputs, outputs, &mut scratch);
+        Ok(())
+    }))
+}
+
+/// Evaluate `rows` sets of inputs
+///
+/// `inputs` is row-major with one row per sample (`rows * input_count`
+/// values); `outputs` receives `rows * output_count` values, also
+/// row-major.
+///
+/// # Safety
+///
+/// `system` must be a live handle; `inputs` and `outputs` must point to
+/// `rows` times as many values as the system has inputs and outputs.
+#[no_mangle]
+pub unsafe extern "C" fn fuzzy_system_evaluate_batch(
+    system: *const FuzzyHandle,
+    inputs: *const f64,
+    outputs: *mut f64,
+    rows: usize,
+) -> i32 {
+    status(guard(|| {
+        let handle = handle(system)?;
+        let width_in = handle.input_names.len();
+        let width_out = handle.output_names.len();
+        let size = |width: usize| {
+            rows.checked_mul(width)
+                .ok_or_else(|| invalid("rows is too large"))
+        };
+        let inputs = slice(inputs, size(width_in)?, "inputs")?;
+        let outputs = slice_mut(outputs, size(width_out)?, "outputs")?;
+
+        // Rows are indexed rather than chunked: a system without inputs
+        // still writes one row of outputs per sample
+        let mut scratch = handle.scratch.lock().unwrap_or_else(|e| e.into_inner());
+        for row in 0..rows {
+            handle.system.evaluate_with(
+                &inputs[row * width_in..][..width_in],
+                &mut outputs[row * width_out..][..width_out],
+                &mut scratch,
+            );
+        }
+        Ok(())
+    }))
+}
+
+/// Message describing the most recent failed call on this thread
+///
+/// Returns `NULL` if the last call succeeded. The string is owned by the
+/// library and stays valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn fuzzy_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Library version as a NUL-terminated string
+#[no_mangle]
+pub extern "C" fn fuzzy_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIPPING: &str = include_str!("../systems/tipping.fuzzy");
+
+    fn create(text: &str) -> *mut FuzzyHandle {
+        let text = CString::new(text).unwrap();
+        unsafe { fuzzy_system_from_definition(text.as_ptr()) }
+    }
+
+    fn last_error() -> String {
+        let message = fuzzy_last_error();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_evaluate_matches_system() {
+        let handle = create(TIPPING);
+        assert!(!handle.is_null());
+        let system: FuzzySystem = TIPPING.parse().unwrap();
+
+        unsafe {
+            assert_eq!(fuzzy_system_input_count(handle), 2);
+            assert_eq!(fuzzy_system_output_count(handle), 1);
+            let name = CStr::from_ptr(fuzzy_system_input_name(handle, 1));
+            assert_eq!(name.to_str().unwrap(), "food");
+            assert!(fuzzy_system_output_name(handle, 1).is_null());
+
+            let mut output = [0.0];
+            let code = fuzzy_system_evaluate(handle, [3.0, 8.0].as_ptr(), 2, output.as_mut_ptr(), 1);
+            assert_eq!(code, FUZZY_OK);
+            assert!(fuzzy_last_error().is_null());
+            assert_eq!(output[0], system.evaluate(&[3.0, 8.0])[0]);
+
+            let mut batch = [0.0; 3];
+            let inputs = [3.0, 8.0, 1.0, 1.0, 9.0, 9.0];
+            let code = fuzzy_system_evaluate_batch(handle, inputs.as_ptr(), batch.as_mut_ptr(), 3);
+            assert_eq!(code, FUZZY_OK);
+            assert_eq!(batch[0], output[0]);
+            assert_eq!(batch[2], system.evaluate(&[9.0, 9.0])[0]);
+
+            fuzzy_system_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(create("input x 0 10\n  term low triangular 0 0\n").is_null());
+        assert!(last_error().starts_with("line 2"));
+
+        unsafe {
+            assert!(fuzzy_system_from_definition(ptr::null()).is_null());
+            assert_eq!(last_error(), "definition is NULL");
+
+            let mut output = [0.0];
+            let code = fuzzy_system_evaluate(ptr::null(), ptr::null(), 0, output.as_mut_ptr(), 1);
+            assert_eq!(code, FUZZY_ERROR_NULL_POINTER);
+
+            let handle = create(TIPPING);
+            let code = fuzzy_system_evaluate(handle, [3.0].as_ptr(), 1, output.as_mut_ptr(), 1);
+            assert_eq!(code, FUZZY_ERROR_INVALID_ARGUMENT);
+            assert_eq!(last_error(), "expected 2 inputs, got 1");
+            fuzzy_system_free(handle);
+            fuzzy_system_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_batch_without_inputs() {
+        // Definitions need an input, but the batch must not rely on it
+        let mut system = FuzzySystem::new();
+        let mut level = crate::system::LinguisticVariable::new("level", 0.0, 10.0);
+        level.add_term("mid", crate::membership::MembershipFunction::Triangular { a: 0.0, b: 5.0, c: 10.0 });
+        system.add_output(level);
+        let handle = Box::into_raw(Box::new(FuzzyHandle::new(system)));
+
+        unsafe {
+            let mut outputs = [f64::NAN; 3];
+            let code = fuzzy_system_evaluate_batch(handle, ptr::null(), outputs.as_mut_ptr(), 3);
+            assert_eq!(code, FUZZY_OK);
+            let mut single = [f64::NAN];
+            assert_eq!(fuzzy_system_evaluate(handle, ptr::null(), 0, single.as_mut_ptr(), 1), FUZZY_OK);
+            assert!(single[0].is_finite());
+            assert_eq!(outputs, [single[0]; 3]);
+            fuzzy_system_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_panics_are_caught() {
+        let result: Result<(), i32> = guard(|| panic!("boom"));
+        assert_eq!(result, Err(FUZZY_ERROR_PANIC));
+        assert_eq!(last_error(), "internal error: boom");
+    }
+}
@@ -0,0 +1,289 @@
+//! Fixed-Point Numbers
+//!
+//! Signed 32-bit values with `FRAC` fractional bits (1 to 31) for targets
+//! without a floating-point unit. [`Q16_16`] spans ±32768 in steps of 2⁻¹⁶
+//! and suits most controllers; [`Q1_31`] spans [-1, 1) in steps of 2⁻³¹ and
+//! suits universes normalised to the unit interval.
+//!
+//! Arithmetic saturates instead of wrapping and `exp` uses integer
+//! arithmetic only. Weighted means (centroid and mean-of-maximum
+//! defuzzification, Sugeno outputs) accumulate in 128 bits, so they do not
+//! overflow however many points are summed; other sums (such as the
+//! bisector's running area) saturate at the end of the range.
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::numeric::Real;
+
+/// A signed fixed-point number with `FRAC` fractional bits
+///
+/// `FRAC` must be between 1 and 31; converting or multiplying values of
+/// any other width fails to compile:
+///
+/// ```compile_fail
+/// use fuzzy_logic::fixed::Fixed;
+/// use fuzzy_logic::Real;
+///
+/// let x = Fixed::<32>::from_f64(1.0);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<const FRAC: u32>(i32);
+
+/// 16 integer bits (including the sign) and 16 fractional bits
+#[allow(non_camel_case_types)]
+pub type Q16_16 = Fixed<16>;
+
+/// Sign bit and 31 fractional bits
+#[allow(non_camel_case_types)]
+pub type Q1_31 = Fixed<31>;
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    pub const MIN: Self = Fixed(i32::MIN);
+    pub const MAX: Self = Fixed(i32::MAX);
+    /// The smallest positive value
+    pub const EPSILON: Self = Fixed(1);
+
+    /// Rejects `FRAC` outside 1..=31 at compile time wherever it is used
+    const VALID: () = assert!(FRAC >= 1 && FRAC <= 31, "Fixed needs 1 to 31 fractional bits");
+
+    const SCALE: f64 = {
+        let () = Self::VALID;
+        (1u64 << FRAC) as f64
+    };
+
+    /// The value whose underlying representation is `bits`
+    pub const fn from_bits(bits: i32) -> Self {
+        let () = Self::VALID;
+        Fixed(bits)
+    }
+
+    /// The underlying representation, `value * 2^FRAC`
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    fn saturate(wide: i128) -> Self {
+        let () = Self::VALID;
+        Fixed(wide.clamp(i32::MIN as i128, i32::MAX as i128) as i32)
+    }
+}
+
+/// `numerator / denominator` rounded to the nearest integer
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if 2 * remainder.abs() >= denominator.abs() {
+        quotient + (numerator.signum() * denominator.signum())
+    } else {
+        quotient
+    }
+}
+
+impl<const FRAC: u32> Add for Fixed<FRAC> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+impl<const FRAC: u32> Sub for Fixed<FRAC> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Fixed(self.0.saturating_sub(other.0))
+    }
+}
+
+impl<const FRAC: u32> Mul for Fixed<FRAC> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        let product = self.0 as i64 * other.0 as i64;
+        Self::saturate(((product + (1 << (FRAC - 1))) >> FRAC) as i128)
+    }
+}
+
+impl<const FRAC: u32> Div for Fixed<FRAC> {
+    type Output = Self;
+
+    /// Division by zero saturates towards the sign of the dividend
+    #[inline]
+    fn div(self, other: Self) -> Self {
+        if other.0 == 0 {
+            return match self.0 {
+                0 => Fixed(0),
+                n if n > 0 => Self::MAX,
+                _ => Self::MIN,
+            };
+        }
+        Self::saturate(div_round((self.0 as i128) << FRAC, other.0 as i128))
+    }
+}
+
+impl<const FRAC: u32> Neg for Fixed<FRAC> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl<const FRAC: u32> fmt::Display for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+impl<const FRAC: u32> fmt::Debug for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_f64(), f)
+    }
+}
+
+impl<const FRAC: u32> Real for Fixed<FRAC> {
+    const ZERO: Self = Fixed(0);
+    const ONE: Self = {
+        let () = Self::VALID;
+        Fixed(if FRAC >= 31 { i32::MAX } else { 1 << FRAC })
+    };
+
+    fn from_f64(x: f64) -> Self {
+        let scaled = x * Self::SCALE;
+        // Float-to-integer casts saturate (and map NaN to zero)
+        Fixed(if scaled >= 0.0 { scaled + 0.5 } else { scaled - 0.5 } as i32)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE
+    }
+
+    fn from_usize(n: usize) -> Self {
+        Self::saturate((n as i128) << FRAC)
+    }
+
+    /// e^x as 2^k · e^(f·ln 2) with `x·log2(e) = k + f`, evaluating the
+    /// exponential of the fraction by its Taylor series in Q4.60
+    fn exp(self) -> Self {
+        const LOG2E_Q40: i128 = 1_586_259_972_792;
+        const LN2_Q60: i128 = 799_144_290_325_165_979;
+        const ONE_Q60: i128 = 1 << 60;
+
+        let product = self.0 as i128 * LOG2E_Q40;
+        let y = if FRAC <= 20 {
+            product << (20 - FRAC)
+        } else {
+            product >> (FRAC - 20)
+        };
+        let k = y >> 60;
+        if k + FRAC as i128 >= 31 {
+            return Self::MAX;
+        }
+        let shift = 60 - FRAC as i128 - k;
+        if shift >= 100 {
+            return Self::ZERO;
+        }
+
+        let t = ((y - (k << 60)) * LN2_Q60) >> 60;
+        let mut sum = ONE_Q60;
+        for n in (1..=13).rev() {
+            sum = ONE_Q60 + ((sum * t) >> 60) / n;
+        }
+        Self::saturate((sum + (1 << (shift - 1))) >> shift)
+    }
+
+    fn mul_ratio(self, numerator: usize, denominator: usize) -> Self {
+        Self::saturate(div_round(self.0 as i128 * numerator as i128, denominator as i128))
+    }
+
+    fn abs(self) -> Self {
+        Fixed(self.0.saturating_abs())
+    }
+
+    fn weighted_mean(pairs: impl Iterator<Item = (Self, Self)>) -> Option<Self> {
+        let (numerator, denominator) = pairs.fold((0i128, 0i128), |(num, den), (x, w)| {
+            (num + x.0 as i128 * w.0 as i128, den + w.0 as i128)
+        });
+        if denominator == 0 {
+            None
+        } else {
+            Some(Self::saturate(div_round(numerator, denominator)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q16(x: f64) -> Q16_16 {
+        Q16_16::from_f64(x)
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(q16(1.5).to_bits(), 0x18000);
+        assert_eq!(q16(-0.25).to_f64(), -0.25);
+        assert_eq!(Q16_16::from_usize(3), q16(3.0));
+        assert_eq!(q16(1e9), Q16_16::MAX);
+        assert_eq!(Q1_31::ONE, Q1_31::MAX);
+        assert!((Q1_31::from_f64(1.0).to_f64() - 1.0).abs() < 1e-9);
+        assert_eq!(Q1_31::from_f64(-1.0), Q1_31::MIN);
+        assert_eq!(q16(2.5).to_string(), "2.5");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(q16(1.5) + q16(2.25), q16(3.75));
+        assert_eq!(q16(1.5) - q16(2.25), q16(-0.75));
+        assert_eq!(q16(1.5) * q16(-2.0), q16(-3.0));
+        assert_eq!(q16(3.0) / q16(4.0), q16(0.75));
+        assert_eq!((q16(1.0) / q16(3.0)).to_bits(), 21845);
+        assert_eq!(Q1_31::from_f64(0.5) * Q1_31::from_f64(0.5), Q1_31::from_f64(0.25));
+        assert_eq!(Q1_31::from_f64(0.9).mul_ratio(50, 100), Q1_31::from_f64(0.45));
+        assert_eq!(q16(1.0).mul_ratio(1, 3).to_bits(), 21845);
+    }
+
+    #[test]
+    fn test_saturation() {
+        assert_eq!(q16(30000.0) + q16(30000.0), Q16_16::MAX);
+        assert_eq!(q16(-300.0) * q16(300.0), Q16_16::MIN);
+        assert_eq!(q16(1.0) / Q16_16::ZERO, Q16_16::MAX);
+        assert_eq!(-Q16_16::MIN, Q16_16::MAX);
+        assert_eq!(Q1_31::from_f64(0.75) + Q1_31::from_f64(0.75), Q1_31::MAX);
+    }
+
+    #[test]
+    fn test_exp() {
+        for i in -1200..=1000 {
+            let x = i as f64 / 100.0;
+            let expected = x.exp();
+            let actual = q16(x).exp().to_f64();
+            let tolerance = 2.0 / 65536.0 + expected * 1e-5;
+            assert!((actual - expected).abs() <= tolerance, "exp({}) = {}, expected {}", x, actual, expected);
+        }
+        assert_eq!(q16(20.0).exp(), Q16_16::MAX);
+        assert_eq!(q16(-30.0).exp(), Q16_16::ZERO);
+
+        for i in -100..0 {
+            let x = i as f64 / 100.0;
+            let actual = Q1_31::from_f64(x).exp().to_f64();
+            assert!((actual - x.exp()).abs() < 1e-8, "exp({}) = {}", x, actual);
+        }
+        assert_eq!(Q1_31::from_f64(0.5).exp(), Q1_31::MAX);
+    }
+
+    #[test]
+    fn test_weighted_mean_does_not_overflow() {
+        let w = Q1_31::from_f64(0.9);
+        let pairs = (0..1000).map(|i| (Q1_31::from_f64(if i % 2 == 0 { 0.25 } else { 0.75 }), w));
+        let mean = Q1_31::weighted_mean(pairs).unwrap();
+        assert!((mean.to_f64() - 0.5).abs() < 1e-9);
+        assert_eq!(Q16_16::weighted_mean([(q16(1.0), Q16_16::ZERO)].into_iter()), None);
+    }
+}
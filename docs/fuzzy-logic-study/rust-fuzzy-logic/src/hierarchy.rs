@@ -0,0 +1,565 @@
+//! Hierarchical Fuzzy Systems
+//!
+//! A flat rule base over many inputs needs a rule for every combination of
+//! their terms. A [`Hierarchy`] instead composes small [`FuzzySystem`]s:
+//! each input of a node is fed by an external input or by an output of
+//! another node, and the nodes are evaluated in dependency order.
+//!
+//! An output can feed the next node as its crisp (defuzzified) value or as
+//! the aggregated fuzzy set itself. A fuzzy feed keeps the uncertainty of
+//! the upstream conclusion: each downstream term takes the degree
+//! `sup_x min(A(x), term(x))`, the possibility of the term given the
+//! upstream set `A`.
+//!
+//! Feeds that would close a loop are rejected, and evaluation returns a
+//! [`HierarchyTrace`] with the memberships, rule strengths and outputs of
+//! every node.
+//!
+//! ```
+//! use fuzzy_logic::hierarchy::{Feed, Hierarchy};
+//! use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+//! use fuzzy_logic::MembershipFunction;
+//!
+//! // level -> risk, used twice in a chain
+//! let stage = |input: &str, output: &str| {
+//!     let mut system = FuzzySystem::new();
+//!     system.add_input(
+//!         LinguisticVariable::new(input, 0.0, 1.0)
+//!             .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 1.0 })
+//!             .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 1.0 }),
+//!     );
+//!     system.add_output(
+//!         LinguisticVariable::new(output, 0.0, 1.0)
+//!             .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 1.0 })
+//!             .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 1.0 }),
+//!     );
+//!     system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+//!     system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 1)]));
+//!     system
+//! };
+//!
+//! let mut hierarchy = Hierarchy::new();
+//! let level = hierarchy.add_input("level");
+//! let first = hierarchy.add_node("first", stage("level", "risk")).unwrap();
+//! let second = hierarchy.add_node("second", stage("risk", "priority")).unwrap();
+//! hierarchy.connect(first, 0, Feed::Input(level)).unwrap();
+//! hierarchy.connect(second, 0, Feed::Crisp { node: first, output: 0 }).unwrap();
+//!
+//! let trace = hierarchy.evaluate(&[0.8]).unwrap();
+//! assert!(trace.output("second", "priority").unwrap() > 0.5);
+//! // A loop back to the first node is refused
+//! assert!(hierarchy.connect(first, 0, Feed::Crisp { node: second, output: 0 }).is_err());
+//! ```
+
+use std::fmt;
+
+use crate::defuzzification::defuzzify;
+use crate::system::FuzzySystem;
+
+/// Where a node input takes its value from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feed {
+    /// An external input of the hierarchy
+    Input(usize),
+    /// The defuzzified value of another node's output
+    Crisp { node: usize, output: usize },
+    /// The aggregated fuzzy set of another node's output
+    Fuzzy { node: usize, output: usize },
+}
+
+impl Feed {
+    /// The node this feed depends on, if any
+    fn source(self) -> Option<usize> {
+        match self {
+            Feed::Input(_) => None,
+            Feed::Crisp { node, .. } | Feed::Fuzzy { node, .. } => Some(node),
+        }
+    }
+}
+
+/// Errors building or evaluating a [`Hierarchy`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HierarchyError {
+    /// A node with this name already exists
+    DuplicateName(String),
+    /// The feed would make the node depend on itself; the names trace the
+    /// loop from the node back to itself
+    Cycle(Vec<String>),
+    /// A node input has no feed
+    Unconnected { node: String, input: String },
+    /// The number of external input values does not match
+    WrongInputCount { expected: usize, actual: usize },
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::DuplicateName(name) => write!(f, "a node named '{}' already exists", name),
+            HierarchyError::Cycle(path) => write!(f, "the feed would create a cycle: {}", path.join(" -> ")),
+            HierarchyError::Unconnected { node, input } => {
+                write!(f, "input '{}' of node '{}' is not connected", input, node)
+            }
+            HierarchyError::WrongInputCount { expected, actual } => {
+                write!(f, "expected {} input values, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    system: FuzzySystem,
+    /// One feed per system input
+    feeds: Vec<Option<Feed>>,
+}
+
+/// Fuzzy systems wired into a directed acyclic graph
+#[derive(Debug, Clone, Default)]
+pub struct Hierarchy {
+    inputs: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+impl Hierarchy {
+    pub fn new() -> Self {
+        Hierarchy::default()
+    }
+
+    /// Add an external input and return its index
+    pub fn add_input(&mut self, name: impl Into<String>) -> usize {
+        self.inputs.push(name.into());
+        self.inputs.len() - 1
+    }
+
+    /// Add a node evaluating `system` and return its index
+    ///
+    /// Its inputs start unconnected.
+    pub fn add_node(&mut self, name: impl Into<String>, system: FuzzySystem) -> Result<usize, HierarchyError> {
+        let name = name.into();
+        if self.node_index(&name).is_some() {
+            return Err(HierarchyError::DuplicateName(name));
+        }
+        let feeds = vec![None; system.inputs().len()];
+        self.nodes.push(Node { name, system, feeds });
+        Ok(self.nodes.len() - 1)
+    }
+
+    /// Feed input `input` of node `node`, replacing any earlier feed
+    ///
+    /// Panics if a node, input or output index does not exist.
+    pub fn connect(&mut self, node: usize, input: usize, feed: Feed) -> Result<(), HierarchyError> {
+        assert!(
+            input < self.nodes[node].feeds.len(),
+            "Feed refers to an unknown node input"
+        );
+        match feed {
+            Feed::Input(index) => assert!(index < self.inputs.len(), "Feed refers to an unknown hierarchy input"),
+            Feed::Crisp { node: source, output } | Feed::Fuzzy { node: source, output } => {
+                assert!(
+                    source < self.nodes.len() && output < self.nodes[source].system.outputs().len(),
+                    "Feed refers to an unknown node output"
+                );
+                // The feed closes a loop if the source already depends on the node
+                if let Some(path) = self.dependency_path(source, node) {
+                    let mut names: Vec<String> = path.iter().rev().map(|&n| self.nodes[n].name.clone()).collect();
+                    names.push(self.nodes[node].name.clone());
+                    return Err(HierarchyError::Cycle(names));
+                }
+            }
+        }
+        self.nodes[node].feeds[input] = Some(feed);
+        Ok(())
+    }
+
+    /// Nodes from `from` back through its feeds to `to`, if `from` depends on `to`
+    fn dependency_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        if from == to {
+            return Some(vec![from]);
+        }
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![vec![from]];
+        while let Some(path) = stack.pop() {
+            let last = *path.last().unwrap();
+            for source in self.nodes[last].feeds.iter().flatten().filter_map(|f| f.source()) {
+                if source == to {
+                    let mut found = path.clone();
+                    found.push(to);
+                    return Some(found);
+                }
+                if !visited[source] {
+                    visited[source] = true;
+                    let mut next = path.clone();
+                    next.push(source);
+                    stack.push(next);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Index of the external input with the given name
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        self.inputs.iter().position(|n| n == name)
+    }
+
+    /// Index of the node with the given name
+    pub fn node_index(&self, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.name == name)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn node_name(&self, node: usize) -> &str {
+        &self.nodes[node].name
+    }
+
+    pub fn system(&self, node: usize) -> &FuzzySystem {
+        &self.nodes[node].system
+    }
+
+    /// Feed of a node input, if connected
+    pub fn feed(&self, node: usize, input: usize) -> Option<Feed> {
+        self.nodes[node].feeds[input]
+    }
+
+    /// Node indices in an order where every node comes after the nodes it
+    /// depends on; ties keep the order the nodes were added
+    pub fn evaluation_order(&self) -> Result<Vec<usize>, HierarchyError> {
+        for node in &self.nodes {
+            if let Some(input) = node.feeds.iter().position(Option::is_none) {
+                return Err(HierarchyError::Unconnected {
+                    node: node.name.clone(),
+                    input: node.system.inputs()[input].name.clone(),
+                });
+            }
+        }
+
+        // Kahn's algorithm; `connect` keeps the graph acyclic
+        let dependencies = |node: &Node| {
+            let mut sources: Vec<usize> = node.feeds.iter().flatten().filter_map(|f| f.source()).collect();
+            sources.sort_unstable();
+            sources.dedup();
+            sources
+        };
+        let mut pending: Vec<usize> = self.nodes.iter().map(|n| dependencies(n).len()).collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while order.len() < self.nodes.len() {
+            let next = (0..self.nodes.len())
+                .find(|&n| pending[n] == 0 && !order.contains(&n))
+                .expect("connect rejects cycles");
+            order.push(next);
+            for (n, node) in self.nodes.iter().enumerate() {
+                if dependencies(node).contains(&next) {
+                    pending[n] -= 1;
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Evaluate every node for the external input values
+    pub fn evaluate(&self, inputs: &[f64]) -> Result<HierarchyTrace<'_>, HierarchyError> {
+        if inputs.len() != self.inputs.len() {
+            return Err(HierarchyError::WrongInputCount {
+                expected: self.inputs.len(),
+                actual: inputs.len(),
+            });
+        }
+        let order = self.evaluation_order()?;
+
+        let mut traces: Vec<Option<NodeTrace>> = vec![None; self.nodes.len()];
+        for &n in &order {
+            let node = &self.nodes[n];
+            let system = &node.system;
+            let mut values = Vec::with_capacity(node.feeds.len());
+            let mut degrees = Vec::with_capacity(node.feeds.len());
+            for (variable, feed) in system.inputs().iter().zip(&node.feeds) {
+                match feed.expect("evaluation_order checks every feed") {
+                    Feed::Input(index) => {
+                        values.push(inputs[index]);
+                        degrees.push(variable.fuzzify(inputs[index]));
+                    }
+                    Feed::Crisp { node: source, output } => {
+                        let value = traces[source].as_ref().expect("sources come first").outputs[output];
+                        values.push(value);
+                        degrees.push(variable.fuzzify(value));
+                    }
+                    Feed::Fuzzy { node: source, output } => {
+                        let upstream = traces[source].as_ref().expect("sources come first");
+                        let (universe, set) = &upstream.sets[output];
+                        values.push(upstream.outputs[output]);
+                        degrees.push(
+                            variable
+                                .terms
+                                .iter()
+                                .map(|term| {
+                                    universe
+                                        .iter()
+                                        .zip(set)
+                                        .map(|(&x, &mu)| mu.min(term.membership(x)))
+                                        .fold(0.0, f64::max)
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+            }
+
+            let strengths: Vec<f64> = system
+                .rules()
+                .iter()
+                .map(|rule| system.firing_strength_from_degrees(rule, &degrees))
+                .collect();
+            let sets: Vec<(Vec<f64>, Vec<f64>)> =
+                (0..system.outputs().len()).map(|o| system.aggregate(o, &strengths)).collect();
+            let outputs = sets
+                .iter()
+                .map(|(universe, set)| defuzzify(universe, set, system.defuzzification()))
+                .collect();
+            traces[n] = Some(NodeTrace {
+                node: n,
+                name: &node.name,
+                system,
+                feeds: node.feeds.iter().flatten().copied().collect(),
+                inputs: values,
+                degrees,
+                strengths,
+                outputs,
+                sets,
+            });
+        }
+
+        let nodes = order.iter().map(|&n| traces[n].take().expect("every node is evaluated")).collect();
+        Ok(HierarchyTrace { nodes })
+    }
+}
+
+/// Intermediate results of one node
+#[derive(Debug, Clone)]
+pub struct NodeTrace<'a> {
+    /// Index of the node in its hierarchy
+    pub node: usize,
+    pub name: &'a str,
+    pub system: &'a FuzzySystem,
+    /// Feed of each input
+    pub feeds: Vec<Feed>,
+    /// Crisp value of each input; for fuzzy feeds, the defuzzified value
+    /// of the upstream set
+    pub inputs: Vec<f64>,
+    /// Degree of every term of every input
+    pub degrees: Vec<Vec<f64>>,
+    /// Firing strength of every rule
+    pub strengths: Vec<f64>,
+    /// Crisp value of each output
+    pub outputs: Vec<f64>,
+    /// Aggregated set of each output as `(universe, membership)`
+    pub sets: Vec<(Vec<f64>, Vec<f64>)>,
+}
+
+/// Node results of a [`Hierarchy::evaluate`] call, in evaluation order
+#[derive(Debug, Clone)]
+pub struct HierarchyTrace<'a> {
+    pub nodes: Vec<NodeTrace<'a>>,
+}
+
+impl<'a> HierarchyTrace<'a> {
+    pub fn node(&self, name: &str) -> Option<&NodeTrace<'a>> {
+        self.nodes.iter().find(|n| n.name == name)
+    }
+
+    /// Crisp value of a node output, by name
+    pub fn output(&self, node: &str, output: &str) -> Option<f64> {
+        let trace = self.node(node)?;
+        let index = trace.system.output_index(output)?;
+        Some(trace.outputs[index])
+    }
+}
+
+impl fmt::Display for HierarchyTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Feeds name their source nodes
+        let name = |node: usize| self.nodes.iter().find(|n| n.node == node).map_or("?", |n| n.name);
+        for trace in &self.nodes {
+            writeln!(f, "Node {}", trace.name)?;
+            for (i, variable) in trace.system.inputs().iter().enumerate() {
+                let source = match trace.feeds[i] {
+                    Feed::Input(_) => "input".to_string(),
+                    Feed::Crisp { node, output } => {
+                        format!("crisp {}.{}", name(node), self.output_name(node, output))
+                    }
+                    Feed::Fuzzy { node, output } => {
+                        format!("fuzzy {}.{}", name(node), self.output_name(node, output))
+                    }
+                };
+                let degrees: Vec<String> = variable
+                    .terms
+                    .iter()
+                    .zip(&trace.degrees[i])
+                    .map(|(term, mu)| format!("{} {:.3}", term.name, mu))
+                    .collect();
+                writeln!(
+                    f,
+                    "  {} = {:.3} ({}): {}",
+                    variable.name,
+                    trace.inputs[i],
+                    source,
+                    degrees.join(", ")
+                )?;
+            }
+            let strengths: Vec<String> = trace.strengths.iter().map(|s| format!("{:.3}", s)).collect();
+            writeln!(f, "  Rules: {}", strengths.join(" "))?;
+            for (variable, value) in trace.system.outputs().iter().zip(&trace.outputs) {
+                writeln!(f, "  {} -> {:.3}", variable.name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl HierarchyTrace<'_> {
+    fn output_name(&self, node: usize, output: usize) -> &str {
+        self.nodes
+            .iter()
+            .find(|n| n.node == node)
+            .map_or("?", |n| n.system.outputs()[output].name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::membership::MembershipFunction;
+    use crate::system::{Antecedent, Consequent, LinguisticVariable, Rule};
+
+    /// `inputs` low/high inputs on [0, 1]; the output is high when any is high
+    fn any_high(inputs: &[&str], output: &str) -> FuzzySystem {
+        let low = MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 1.0 };
+        let high = MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 1.0 };
+        let mut system = FuzzySystem::new();
+        for name in inputs {
+            system.add_input(
+                LinguisticVariable::new(*name, 0.0, 1.0)
+                    .with_term("low", low.clone())
+                    .with_term("high", high.clone()),
+            );
+        }
+        system.add_output(
+            LinguisticVariable::new(output, 0.0, 1.0)
+                .with_term("low", low)
+                .with_term("high", high),
+        );
+        let all_low = (0..inputs.len()).map(|i| Antecedent::new(i, 0)).collect();
+        system.add_rule(Rule::new(all_low, vec![Consequent::new(0, 0)]));
+        for i in 0..inputs.len() {
+            system.add_rule(Rule::new(vec![Antecedent::new(i, 1)], vec![Consequent::new(0, 1)]));
+        }
+        system
+    }
+
+    #[test]
+    fn test_crisp_chain_matches_manual_evaluation() {
+        let first = any_high(&["a", "b"], "x");
+        let second = any_high(&["x", "c"], "y");
+        let mut hierarchy = Hierarchy::new();
+        let a = hierarchy.add_input("a");
+        let b = hierarchy.add_input("b");
+        let c = hierarchy.add_input("c");
+        // Added out of order: evaluation follows the feeds
+        let n2 = hierarchy.add_node("second", second.clone()).unwrap();
+        let n1 = hierarchy.add_node("first", first.clone()).unwrap();
+        hierarchy.connect(n1, 0, Feed::Input(a)).unwrap();
+        hierarchy.connect(n1, 1, Feed::Input(b)).unwrap();
+        hierarchy.connect(n2, 0, Feed::Crisp { node: n1, output: 0 }).unwrap();
+        hierarchy.connect(n2, 1, Feed::Input(c)).unwrap();
+        assert_eq!(hierarchy.evaluation_order().unwrap(), vec![n1, n2]);
+
+        let trace = hierarchy.evaluate(&[0.2, 0.7, 0.1]).unwrap();
+        let x = first.evaluate(&[0.2, 0.7])[0];
+        let y = second.evaluate(&[x, 0.1])[0];
+        assert!((trace.output("first", "x").unwrap() - x).abs() < 1e-12);
+        assert!((trace.output("second", "y").unwrap() - y).abs() < 1e-12);
+        assert_eq!(trace.node("second").unwrap().inputs, vec![x, 0.1]);
+        assert_eq!(trace.nodes[0].strengths, first.firing_strengths(&[0.2, 0.7]));
+    }
+
+    #[test]
+    fn test_fuzzy_feed_uses_the_possibility_of_each_term() {
+        let mut hierarchy = Hierarchy::new();
+        let a = hierarchy.add_input("a");
+        let n1 = hierarchy.add_node("first", any_high(&["a"], "x")).unwrap();
+        let n2 = hierarchy.add_node("second", any_high(&["x"], "y")).unwrap();
+        hierarchy.connect(n1, 0, Feed::Input(a)).unwrap();
+        hierarchy.connect(n2, 0, Feed::Fuzzy { node: n1, output: 0 }).unwrap();
+
+        let trace = hierarchy.evaluate(&[0.3]).unwrap();
+        // The upstream set is low clipped at 0.7 joined with high clipped at
+        // 0.3; where the terms cross at 0.5 the set still has degree 0.5, so
+        // "high" is possible to 0.5 rather than the 0.3 it fired with
+        let degrees = &trace.node("second").unwrap().degrees[0];
+        assert!((degrees[0] - 0.7).abs() < 1e-9 && (degrees[1] - 0.5).abs() < 1e-9, "{:?}", degrees);
+        let crisp = trace.node("first").unwrap().outputs[0];
+        assert_eq!(trace.node("second").unwrap().inputs, vec![crisp]);
+    }
+
+    #[test]
+    fn test_cycles_and_missing_feeds_are_reported() {
+        let mut hierarchy = Hierarchy::new();
+        let a = hierarchy.add_input("a");
+        let names = ["one", "two", "three"];
+        let nodes: Vec<usize> = names
+            .iter()
+            .map(|name| hierarchy.add_node(*name, any_high(&["in"], "out")).unwrap())
+            .collect();
+        assert_eq!(
+            hierarchy.add_node("two", any_high(&["in"], "out")),
+            Err(HierarchyError::DuplicateName("two".to_string()))
+        );
+        assert_eq!(
+            hierarchy.evaluate(&[0.5]).err(),
+            Some(HierarchyError::Unconnected {
+                node: "one".to_string(),
+                input: "in".to_string()
+            })
+        );
+
+        hierarchy.connect(nodes[1], 0, Feed::Crisp { node: nodes[0], output: 0 }).unwrap();
+        hierarchy.connect(nodes[2], 0, Feed::Fuzzy { node: nodes[1], output: 0 }).unwrap();
+        let cycle = hierarchy.connect(nodes[0], 0, Feed::Crisp { node: nodes[2], output: 0 });
+        assert_eq!(
+            cycle,
+            Err(HierarchyError::Cycle(vec![
+                "one".to_string(),
+                "two".to_string(),
+                "three".to_string(),
+                "one".to_string()
+            ]))
+        );
+        assert_eq!(
+            cycle.unwrap_err().to_string(),
+            "the feed would create a cycle: one -> two -> three -> one"
+        );
+        let own = hierarchy.connect(nodes[1], 0, Feed::Crisp { node: nodes[1], output: 0 });
+        assert_eq!(own, Err(HierarchyError::Cycle(vec!["two".to_string(), "two".to_string()])));
+        // The rejected feeds left the earlier ones in place
+        assert_eq!(hierarchy.feed(nodes[1], 0), Some(Feed::Crisp { node: nodes[0], output: 0 }));
+
+        hierarchy.connect(nodes[0], 0, Feed::Input(a)).unwrap();
+        assert_eq!(
+            hierarchy.evaluate(&[]).err(),
+            Some(HierarchyError::WrongInputCount { expected: 1, actual: 0 })
+        );
+        let trace = hierarchy.evaluate(&[0.9]).unwrap();
+        let text = trace.to_string();
+        assert!(text.starts_with("Node one\n  in = 0.900 (input): low 0.100, high 0.900\n"), "{}", text);
+        assert!(text.contains("(fuzzy two.out)"));
+    }
+}
@@ -2,53 +2,120 @@
 //!
 //! This module provides a simple implementation of a fuzzy inference system
 //! using Mamdani-style inference.
+//!
+//! Rules and controllers are `Send + Sync` and cheap to clone, so a
+//! controller can be wrapped in an `Arc` and shared between worker threads.
+//! With the `rayon` feature, batch evaluation runs in parallel.
+//!
+//! Rules and controllers are generic over [`Real`] and default to `f64`;
+//! `new` builds the `f64` versions, while `from_functions` and `with_range`
+//! build them over any number type.
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+
+use crate::defuzzification::DefuzzificationMethod;
+use crate::numeric::Real;
+use crate::operations::{SNorm, TNorm};
 
-use crate::operations::{fuzzy_intersection, fuzzy_union};
-use crate::defuzzification::centroid;
+/// A shareable membership or condition function
+pub type MembershipFn<T = f64> = Arc<dyn Fn(T) -> T + Send + Sync>;
+
+/// Rows handed to each rayon task by the batch evaluators
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_CHUNK: usize = 256;
 
 /// A fuzzy rule: IF condition THEN conclusion
-pub struct FuzzyRule {
+#[derive(Clone)]
+pub struct FuzzyRule<T = f64> {
     /// Function that evaluates the antecedent (IF part)
-    pub condition: Box<dyn Fn(f64) -> f64>,
+    pub condition: MembershipFn<T>,
     /// Function that evaluates the consequent (THEN part) for a given output value
-    pub conclusion: Box<dyn Fn(f64) -> f64>,
+    pub conclusion: MembershipFn<T>,
 }
 
 impl FuzzyRule {
     /// Create a new fuzzy rule
     pub fn new(
-        condition: Box<dyn Fn(f64) -> f64>,
-        conclusion: Box<dyn Fn(f64) -> f64>,
+        condition: Box<dyn Fn(f64) -> f64 + Send + Sync>,
+        conclusion: Box<dyn Fn(f64) -> f64 + Send + Sync>,
     ) -> Self {
         FuzzyRule {
-            condition,
-            conclusion,
+            condition: condition.into(),
+            conclusion: conclusion.into(),
+        }
+    }
+}
+
+impl<T: Real> FuzzyRule<T> {
+    /// Create a fuzzy rule over any number type
+    pub fn from_functions(
+        condition: impl Fn(T) -> T + Send + Sync + 'static,
+        conclusion: impl Fn(T) -> T + Send + Sync + 'static,
+    ) -> Self {
+        FuzzyRule {
+            condition: Arc::new(condition),
+            conclusion: Arc::new(conclusion),
         }
     }
 
     /// Evaluate the rule for a given input
     ///
     /// Returns the rule firing strength
-    pub fn evaluate_condition(&self, input: f64) -> f64 {
+    pub fn evaluate_condition(&self, input: T) -> T {
         (self.condition)(input)
     }
 
     /// Apply rule strength to conclusion membership function
-    pub fn apply(&self, input: f64, output: f64) -> f64 {
+    pub fn apply(&self, input: T, output: T) -> T {
         let strength = self.evaluate_condition(input);
         let conclusion_membership = (self.conclusion)(output);
-        fuzzy_intersection(strength, conclusion_membership)
+        TNorm::Minimum.apply(strength, conclusion_membership)
+    }
+}
+
+/// Reusable aggregation buffer for allocation-free controller evaluation
+///
+/// Obtain one from [`FuzzyController::scratch`] or
+/// [`TwoInputFuzzyController::scratch`] and reuse it across calls.
+#[derive(Debug, Clone)]
+pub struct BatchScratch<T = f64> {
+    aggregated: Vec<T>,
+}
+
+impl<T> Default for BatchScratch<T> {
+    fn default() -> Self {
+        BatchScratch {
+            aggregated: Vec::new(),
+        }
+    }
+}
+
+impl<T: Real> BatchScratch<T> {
+    fn reset(&mut self, steps: usize) -> &mut [T] {
+        self.aggregated.clear();
+        self.aggregated.resize(steps, T::ZERO);
+        &mut self.aggregated
     }
 }
 
 /// Fuzzy controller with multiple rules
-pub struct FuzzyController {
-    rules: Vec<FuzzyRule>,
-    output_range: (f64, f64, usize), // (min, max, steps)
+///
+/// Cloning is cheap: rules and precomputed tables are shared, and only
+/// copied when a clone is modified.
+#[derive(Clone)]
+pub struct FuzzyController<T = f64> {
+    rules: Arc<Vec<FuzzyRule<T>>>,
+    /// Precomputed output universe
+    output_values: Arc<[T]>,
+    /// Each rule's conclusion sampled on the output universe
+    conclusions: Arc<Vec<Vec<T>>>,
 }
 
 impl FuzzyController {
-    /// Create a new fuzzy controller
+    /// Create a new `f64` fuzzy controller
+    ///
+    /// `FuzzyController::<T>::with_range` creates one over another number
+    /// type.
     ///
     /// # Arguments
     ///
@@ -56,118 +123,270 @@ impl FuzzyController {
     /// * `output_max` - Maximum output value
     /// * `output_steps` - Number of discrete steps in output range
     pub fn new(output_min: f64, output_max: f64, output_steps: usize) -> Self {
+        Self::with_range(output_min, output_max, output_steps)
+    }
+}
+
+impl<T: Real> FuzzyController<T> {
+    /// Create a fuzzy controller over any number type
+    ///
+    /// Takes the same arguments as [`FuzzyController::new`].
+    pub fn with_range(output_min: T, output_max: T, output_steps: usize) -> Self {
         FuzzyController {
-            rules: Vec::new(),
-            output_range: (output_min, output_max, output_steps),
+            rules: Arc::new(Vec::new()),
+            output_values: output_universe(output_min, output_max, output_steps).into(),
+            conclusions: Arc::new(Vec::new()),
         }
     }
 
     /// Add a rule to the controller
-    pub fn add_rule(&mut self, rule: FuzzyRule) {
-        self.rules.push(rule);
+    ///
+    /// The rule's conclusion is sampled on the output universe once, here.
+    pub fn add_rule(&mut self, rule: FuzzyRule<T>) {
+        let conclusion = self.output_values.iter().map(|&y| (rule.conclusion)(y)).collect();
+        Arc::make_mut(&mut self.conclusions).push(conclusion);
+        Arc::make_mut(&mut self.rules).push(rule);
+    }
+
+    /// The discretized output universe used for defuzzification
+    pub fn output_values(&self) -> &[T] {
+        &self.output_values
+    }
+
+    /// Create a scratch buffer sized for this controller
+    pub fn scratch(&self) -> BatchScratch<T> {
+        BatchScratch {
+            aggregated: vec![T::ZERO; self.output_values.len()],
+        }
     }
 
     /// Evaluate the controller for a given input
     ///
     /// Uses Mamdani inference with centroid defuzzification
-    pub fn evaluate(&self, input: f64) -> f64 {
-        let (min, max, steps) = self.output_range;
-        let step_size = (max - min) / (steps as f64);
-
-        // Create output universe
-        let output_values: Vec<f64> = (0..steps)
-            .map(|i| min + i as f64 * step_size)
-            .collect();
+    pub fn evaluate(&self, input: T) -> T {
+        self.evaluate_with(input, &mut self.scratch())
+    }
 
-        // Aggregate all rules
-        let mut aggregated: Vec<f64> = vec![0.0; steps];
+    /// Evaluate the controller reusing a scratch buffer (no allocation)
+    pub fn evaluate_with(&self, input: T, scratch: &mut BatchScratch<T>) -> T {
+        let aggregated = scratch.reset(self.output_values.len());
 
-        for rule in &self.rules {
-            for (i, &output_val) in output_values.iter().enumerate() {
-                let rule_output = rule.apply(input, output_val);
+        for (rule, conclusion) in self.rules.iter().zip(self.conclusions.iter()) {
+            let strength = rule.evaluate_condition(input);
+            for (agg, &c) in aggregated.iter_mut().zip(conclusion) {
                 // Aggregation using maximum (fuzzy union)
-                aggregated[i] = fuzzy_union(aggregated[i], rule_output);
+                *agg = SNorm::Maximum.apply(*agg, TNorm::Minimum.apply(strength, c));
             }
         }
 
         // Defuzzify using centroid method
-        centroid(&output_values, &aggregated)
+        DefuzzificationMethod::Centroid.apply(&self.output_values, aggregated)
+    }
+
+    /// Evaluate the controller for every input value
+    ///
+    /// With the `rayon` feature the inputs are split across threads; the
+    /// results are identical to sequential evaluation.
+    pub fn evaluate_batch(&self, inputs: &[T]) -> Vec<T> {
+        let mut outputs = vec![T::ZERO; inputs.len()];
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            outputs
+                .par_chunks_mut(PARALLEL_CHUNK)
+                .zip(inputs.par_chunks(PARALLEL_CHUNK))
+                .for_each_init(
+                    || self.scratch(),
+                    |scratch, (out, chunk)| self.evaluate_into(chunk, out, scratch),
+                );
+        }
+        #[cfg(not(feature = "rayon"))]
+        self.evaluate_into(inputs, &mut outputs, &mut self.scratch());
+
+        outputs
+    }
+
+    /// Evaluate every input value into `outputs` without allocating
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Input values
+    /// * `outputs` - Destination, same length as `inputs`
+    /// * `scratch` - Buffer from [`FuzzyController::scratch`]
+    pub fn evaluate_into(&self, inputs: &[T], outputs: &mut [T], scratch: &mut BatchScratch<T>) {
+        assert_eq!(
+            inputs.len(),
+            outputs.len(),
+            "inputs and outputs must have same length"
+        );
+        for (out, &input) in outputs.iter_mut().zip(inputs) {
+            *out = self.evaluate_with(input, scratch);
+        }
     }
 }
 
 /// Two-input fuzzy controller
-pub struct TwoInputFuzzyController {
-    rules: Vec<TwoInputFuzzyRule>,
-    output_range: (f64, f64, usize),
+#[derive(Clone)]
+pub struct TwoInputFuzzyController<T = f64> {
+    rules: Arc<Vec<TwoInputFuzzyRule<T>>>,
+    output_values: Arc<[T]>,
+    conclusions: Arc<Vec<Vec<T>>>,
 }
 
 /// A fuzzy rule with two inputs
-pub struct TwoInputFuzzyRule {
-    pub condition1: Box<dyn Fn(f64) -> f64>,
-    pub condition2: Box<dyn Fn(f64) -> f64>,
-    pub conclusion: Box<dyn Fn(f64) -> f64>,
+#[derive(Clone)]
+pub struct TwoInputFuzzyRule<T = f64> {
+    pub condition1: MembershipFn<T>,
+    pub condition2: MembershipFn<T>,
+    pub conclusion: MembershipFn<T>,
 }
 
 impl TwoInputFuzzyRule {
     pub fn new(
-        condition1: Box<dyn Fn(f64) -> f64>,
-        condition2: Box<dyn Fn(f64) -> f64>,
-        conclusion: Box<dyn Fn(f64) -> f64>,
+        condition1: Box<dyn Fn(f64) -> f64 + Send + Sync>,
+        condition2: Box<dyn Fn(f64) -> f64 + Send + Sync>,
+        conclusion: Box<dyn Fn(f64) -> f64 + Send + Sync>,
+    ) -> Self {
+        TwoInputFuzzyRule {
+            condition1: condition1.into(),
+            condition2: condition2.into(),
+            conclusion: conclusion.into(),
+        }
+    }
+}
+
+impl<T: Real> TwoInputFuzzyRule<T> {
+    /// Create a two-input rule over any number type
+    pub fn from_functions(
+        condition1: impl Fn(T) -> T + Send + Sync + 'static,
+        condition2: impl Fn(T) -> T + Send + Sync + 'static,
+        conclusion: impl Fn(T) -> T + Send + Sync + 'static,
     ) -> Self {
         TwoInputFuzzyRule {
-            condition1,
-            condition2,
-            conclusion,
+            condition1: Arc::new(condition1),
+            condition2: Arc::new(condition2),
+            conclusion: Arc::new(conclusion),
         }
     }
 
     /// Evaluate rule with AND operation on conditions
-    pub fn apply(&self, input1: f64, input2: f64, output: f64) -> f64 {
+    pub fn apply(&self, input1: T, input2: T, output: T) -> T {
         let strength1 = (self.condition1)(input1);
         let strength2 = (self.condition2)(input2);
-        let combined_strength = fuzzy_intersection(strength1, strength2);
+        let combined_strength = TNorm::Minimum.apply(strength1, strength2);
         let conclusion_membership = (self.conclusion)(output);
-        fuzzy_intersection(combined_strength, conclusion_membership)
+        TNorm::Minimum.apply(combined_strength, conclusion_membership)
     }
 }
 
 impl TwoInputFuzzyController {
     pub fn new(output_min: f64, output_max: f64, output_steps: usize) -> Self {
+        Self::with_range(output_min, output_max, output_steps)
+    }
+}
+
+impl<T: Real> TwoInputFuzzyController<T> {
+    /// Create a two-input controller over any number type
+    pub fn with_range(output_min: T, output_max: T, output_steps: usize) -> Self {
         TwoInputFuzzyController {
-            rules: Vec::new(),
-            output_range: (output_min, output_max, output_steps),
+            rules: Arc::new(Vec::new()),
+            output_values: output_universe(output_min, output_max, output_steps).into(),
+            conclusions: Arc::new(Vec::new()),
         }
     }
 
-    pub fn add_rule(&mut self, rule: TwoInputFuzzyRule) {
-        self.rules.push(rule);
+    pub fn add_rule(&mut self, rule: TwoInputFuzzyRule<T>) {
+        let conclusion = self.output_values.iter().map(|&y| (rule.conclusion)(y)).collect();
+        Arc::make_mut(&mut self.conclusions).push(conclusion);
+        Arc::make_mut(&mut self.rules).push(rule);
     }
 
-    pub fn evaluate(&self, input1: f64, input2: f64) -> f64 {
-        let (min, max, steps) = self.output_range;
-        let step_size = (max - min) / (steps as f64);
+    /// The discretized output universe used for defuzzification
+    pub fn output_values(&self) -> &[T] {
+        &self.output_values
+    }
 
-        let output_values: Vec<f64> = (0..steps)
-            .map(|i| min + i as f64 * step_size)
-            .collect();
+    /// Create a scratch buffer sized for this controller
+    pub fn scratch(&self) -> BatchScratch<T> {
+        BatchScratch {
+            aggregated: vec![T::ZERO; self.output_values.len()],
+        }
+    }
 
-        let mut aggregated: Vec<f64> = vec![0.0; steps];
+    pub fn evaluate(&self, input1: T, input2: T) -> T {
+        self.evaluate_with(input1, input2, &mut self.scratch())
+    }
+
+    /// Evaluate the controller reusing a scratch buffer (no allocation)
+    pub fn evaluate_with(&self, input1: T, input2: T, scratch: &mut BatchScratch<T>) -> T {
+        let aggregated = scratch.reset(self.output_values.len());
 
-        for rule in &self.rules {
-            for (i, &output_val) in output_values.iter().enumerate() {
-                let rule_output = rule.apply(input1, input2, output_val);
-                aggregated[i] = fuzzy_union(aggregated[i], rule_output);
+        for (rule, conclusion) in self.rules.iter().zip(self.conclusions.iter()) {
+            let strength = TNorm::Minimum.apply((rule.condition1)(input1), (rule.condition2)(input2));
+            for (agg, &c) in aggregated.iter_mut().zip(conclusion) {
+                *agg = SNorm::Maximum.apply(*agg, TNorm::Minimum.apply(strength, c));
             }
         }
 
-        centroid(&output_values, &aggregated)
+        DefuzzificationMethod::Centroid.apply(&self.output_values, aggregated)
+    }
+
+    /// Evaluate the controller for every pair of values in two input columns
+    ///
+    /// With the `rayon` feature the rows are split across threads.
+    pub fn evaluate_batch(&self, input1: &[T], input2: &[T]) -> Vec<T> {
+        assert_eq!(input1.len(), input2.len(), "Input columns must have same length");
+        let mut outputs = vec![T::ZERO; input1.len()];
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            outputs
+                .par_chunks_mut(PARALLEL_CHUNK)
+                .zip(input1.par_chunks(PARALLEL_CHUNK))
+                .zip(input2.par_chunks(PARALLEL_CHUNK))
+                .for_each_init(
+                    || self.scratch(),
+                    |scratch, ((out, x1), x2)| self.evaluate_into(x1, x2, out, scratch),
+                );
+        }
+        #[cfg(not(feature = "rayon"))]
+        self.evaluate_into(input1, input2, &mut outputs, &mut self.scratch());
+
+        outputs
+    }
+
+    /// Evaluate two input columns into `outputs` without allocating
+    pub fn evaluate_into(
+        &self,
+        input1: &[T],
+        input2: &[T],
+        outputs: &mut [T],
+        scratch: &mut BatchScratch<T>,
+    ) {
+        assert_eq!(input1.len(), input2.len(), "Input columns must have same length");
+        assert_eq!(
+            input1.len(),
+            outputs.len(),
+            "inputs and outputs must have same length"
+        );
+        for ((out, &x1), &x2) in outputs.iter_mut().zip(input1).zip(input2) {
+            *out = self.evaluate_with(x1, x2, scratch);
+        }
     }
 }
 
+/// Output universe: `steps` points starting at `min`, spaced `(max - min) / steps`
+fn output_universe<T: Real>(min: T, max: T, steps: usize) -> Vec<T> {
+    let step_size = (max - min) / T::from_usize(steps);
+    (0..steps).map(|i| min + T::from_usize(i) * step_size).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::membership::MembershipFunction;
+    use crate::membership::{Evaluate, MembershipFunction};
 
     #[test]
     fn test_simple_controller() {
@@ -250,4 +469,146 @@ mod tests {
         let output = controller.evaluate(15.0, 80.0);
         assert!(output > 50.0, "Should produce high output");
     }
+
+    #[test]
+    fn test_batch_matches_single_evaluation() {
+        let mut controller = FuzzyController::new(0.0, 100.0, 100);
+        let cold = MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 20.0 };
+        let hot = MembershipFunction::Triangular { a: 15.0, b: 40.0, c: 40.0 };
+        let low = MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 50.0 };
+        let high = MembershipFunction::Triangular { a: 50.0, b: 100.0, c: 100.0 };
+        controller.add_rule(FuzzyRule::new(
+            Box::new(move |x| cold.evaluate(x)),
+            Box::new(move |x| low.evaluate(x)),
+        ));
+        controller.add_rule(FuzzyRule::new(
+            Box::new(move |x| hot.evaluate(x)),
+            Box::new(move |x| high.evaluate(x)),
+        ));
+
+        let inputs: Vec<f64> = (0..=40).map(|i| i as f64).collect();
+        let expected: Vec<f64> = inputs.iter().map(|&x| controller.evaluate(x)).collect();
+
+        assert_eq!(controller.evaluate_batch(&inputs), expected);
+
+        let mut outputs = vec![0.0; inputs.len()];
+        let mut scratch = controller.scratch();
+        controller.evaluate_into(&inputs, &mut outputs, &mut scratch);
+        assert_eq!(outputs, expected);
+    }
+
+    #[test]
+    fn test_two_input_batch_matches_single_evaluation() {
+        let mut controller = TwoInputFuzzyController::new(0.0, 100.0, 100);
+        let cold = MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 20.0 };
+        let humid = MembershipFunction::Triangular { a: 20.0, b: 100.0, c: 100.0 };
+        let fast = MembershipFunction::Triangular { a: 60.0, b: 100.0, c: 100.0 };
+        controller.add_rule(TwoInputFuzzyRule::new(
+            Box::new(move |x| cold.evaluate(x)),
+            Box::new(move |x| humid.evaluate(x)),
+            Box::new(move |x| fast.evaluate(x)),
+        ));
+
+        let temps = [5.0, 10.0, 15.0, 30.0];
+        let humidity = [90.0, 50.0, 70.0, 80.0];
+        let expected: Vec<f64> = temps
+            .iter()
+            .zip(&humidity)
+            .map(|(&t, &h)| controller.evaluate(t, h))
+            .collect();
+
+        assert_eq!(controller.evaluate_batch(&temps, &humidity), expected);
+    }
+
+    fn ramp_controller() -> FuzzyController {
+        let mut controller = FuzzyController::new(0.0, 100.0, 100);
+        for k in 0..5 {
+            let center = k as f64 * 10.0;
+            let condition = MembershipFunction::Triangular { a: center - 10.0, b: center, c: center + 10.0 };
+            let conclusion = MembershipFunction::Gaussian { mean: center * 2.5, sigma: 12.0 };
+            controller.add_rule(FuzzyRule::new(
+                Box::new(move |x| condition.evaluate(x)),
+                Box::new(move |y| conclusion.evaluate(y)),
+            ));
+        }
+        controller
+    }
+
+    #[test]
+    fn test_controllers_are_send_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone>() {}
+        assert_send_sync::<FuzzyRule>();
+        assert_send_sync::<FuzzyController>();
+        assert_send_sync::<TwoInputFuzzyRule>();
+        assert_send_sync::<TwoInputFuzzyController>();
+    }
+
+    #[test]
+    fn test_shared_controller_across_threads() {
+        let controller = std::sync::Arc::new(ramp_controller());
+        let inputs: Vec<f64> = (0..400).map(|i| i as f64 / 10.0).collect();
+        let expected: Vec<f64> = inputs.iter().map(|&x| controller.evaluate(x)).collect();
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let controller = std::sync::Arc::clone(&controller);
+                let chunk = inputs[t * 100..(t + 1) * 100].to_vec();
+                std::thread::spawn(move || controller.evaluate_batch(&chunk))
+            })
+            .collect();
+        let results: Vec<f64> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_clone_is_copy_on_write() {
+        let original = ramp_controller();
+        let mut copy = original.clone();
+        let before = original.evaluate(12.0);
+
+        let high = MembershipFunction::Triangular { a: 80.0, b: 100.0, c: 100.0 };
+        copy.add_rule(FuzzyRule::new(
+            Box::new(|_| 1.0),
+            Box::new(move |y| high.evaluate(y)),
+        ));
+
+        assert_eq!(original.evaluate(12.0), before);
+        assert!(copy.evaluate(12.0) > before);
+    }
+
+    #[test]
+    fn test_f32_controller_matches_f64() {
+        let reference = ramp_controller();
+        let mut controller = FuzzyController::<f32>::with_range(0.0, 100.0, 100);
+        for k in 0..5 {
+            let center = k as f64 * 10.0;
+            let condition = MembershipFunction::Triangular { a: center - 10.0, b: center, c: center + 10.0 }.cast::<f32>();
+            let conclusion = MembershipFunction::Gaussian { mean: center * 2.5, sigma: 12.0 }.cast::<f32>();
+            controller.add_rule(FuzzyRule::from_functions(
+                move |x| condition.evaluate(x),
+                move |y| conclusion.evaluate(y),
+            ));
+        }
+
+        for x in [3.0_f32, 12.5, 27.0, 38.0] {
+            let expected = reference.evaluate(x as f64);
+            assert!((controller.evaluate(x) as f64 - expected).abs() < 1e-3);
+        }
+        let batch = controller.evaluate_batch(&[3.0, 12.5]);
+        assert_eq!(batch, vec![controller.evaluate(3.0), controller.evaluate(12.5)]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_batch_matches_sequential() {
+        let controller = ramp_controller();
+        let inputs: Vec<f64> = (0..5_000).map(|i| i as f64 * 40.0 / 5_000.0).collect();
+        let sequential: Vec<f64> = inputs.iter().map(|&x| controller.evaluate(x)).collect();
+
+        assert_eq!(controller.evaluate_batch(&inputs), sequential);
+    }
 }
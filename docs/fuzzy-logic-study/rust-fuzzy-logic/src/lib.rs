@@ -1,10 +1,43 @@
 //! Fuzzy Logic Library
 //!
 //! This library provides implementations of fuzzy logic concepts including:
-//! - Membership functions (triangular, trapezoidal, Gaussian, sigmoid)
+//! - Membership functions (triangular, trapezoidal, Gaussian, sigmoid, bell)
 //! - Fuzzy operations (union, intersection, complement)
-//! - Fuzzy inference systems
+//! - Fuzzy inference systems (Mamdani and Sugeno)
+//! - Rule-based systems built from linguistic variables
+//! - Hierarchical systems wired as a DAG of crisp and fuzzy feeds ([`hierarchy`])
+//! - Text definition files for rule-based systems
+//! - Rule base analysis (completeness, consistency, redundancy)
+//! - Training Sugeno systems from data ([`anfis`])
+//! - Generating rule bases from data ([`wang_mendel`])
+//! - Fuzzy clustering and membership functions from clusters ([`clustering`])
+//! - Derivative-free tuning with genetic algorithms and differential evolution ([`tuning`])
 //! - Defuzzification methods
+//! - Computation in `f64`, `f32` or fixed point ([`numeric`], [`fixed`])
+//! - Precompiled lookup-table controllers
+//! - Fuzzy PID and gain-scheduled PID controllers ([`pid`])
+//! - Closed-loop simulation of controllers against plant models ([`simulation`])
+//! - Fuzzy cognitive maps with scenario analysis and Hebbian learning ([`cognitive_map`])
+//! - Fuzzy time-series forecasting with walk-forward evaluation ([`time_series`])
+//! - Response curves and control surfaces (CSV, gnuplot, SVG)
+//! - Plots of membership functions and operations (SVG, ASCII, braille)
+//! - A C API for other languages (`ffi` feature)
+//! - JavaScript bindings for WebAssembly (`wasm` feature)
+//! - Python bindings compatible with `fuzzy_utils.py` (`python` feature)
+//!
+//! # `no_std`
+//!
+//! The `std` feature is on by default. Without it the crate is `no_std`:
+//! membership functions, operators, defuzzification and
+//! [`sugeno`] systems remain available, using `libm` (the `libm` feature)
+//! for transcendental functions. The `alloc` feature adds the
+//! heap-allocated rule bases ([`inference`], [`system`], [`lookup`]).
+//!
+//! The library target is an `rlib` only: Cargo builds every listed crate
+//! type, and a `cdylib` cannot link without `std`'s panic handler, so a
+//! `no_std` dependent would fail to build. The C API and the JavaScript
+//! bindings get their `cdylib` targets from the `capi` and `wasm`
+//! workspace members.
 //!
 //! # Example
 //!
@@ -21,15 +54,72 @@
 //! let union = fuzzy_union(cold_degree, warm_degree);
 //! ```
 
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod membership;
 pub mod operations;
-pub mod inference;
 pub mod defuzzification;
+pub mod sugeno;
+pub mod numeric;
+pub mod fixed;
+#[cfg(feature = "alloc")]
+pub mod inference;
+#[cfg(feature = "alloc")]
+pub mod system;
+#[cfg(feature = "alloc")]
+pub mod lookup;
+#[cfg(feature = "alloc")]
+pub mod pid;
+#[cfg(feature = "std")]
+pub mod definition;
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod anfis;
+#[cfg(feature = "std")]
+pub mod wang_mendel;
+#[cfg(feature = "std")]
+pub mod clustering;
+#[cfg(feature = "std")]
+pub mod tuning;
+#[cfg(feature = "std")]
+pub mod simulation;
+#[cfg(feature = "std")]
+pub mod hierarchy;
+#[cfg(feature = "std")]
+pub mod cognitive_map;
+#[cfg(feature = "std")]
+pub mod time_series;
+#[cfg(feature = "std")]
+pub mod surface;
+#[cfg(feature = "std")]
+pub mod plot;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+pub mod python;
+
+mod math;
+#[cfg(feature = "std")]
+mod linalg;
+#[cfg(feature = "std")]
+mod rng;
+#[cfg(feature = "std")]
+mod svg;
 
 // Re-export commonly used types and functions
-pub use membership::MembershipFunction;
+pub use membership::{Evaluate, MembershipFunction};
 pub use operations::{fuzzy_union, fuzzy_intersection, fuzzy_complement};
 pub use defuzzification::{centroid, mean_of_maximum, bisector};
+pub use numeric::Real;
+pub use fixed::{Q16_16, Q1_31};
+#[cfg(feature = "alloc")]
+pub use system::{FuzzySystem, LinguisticVariable, Rule};
 
 #[cfg(test)]
 mod tests {
@@ -56,6 +146,6 @@ mod tests {
 
         assert_eq!(fuzzy_union(a, b), 0.7);
         assert_eq!(fuzzy_intersection(a, b), 0.5);
-        assert_eq!(fuzzy_complement(a), 0.3);
+        assert!((fuzzy_complement(a) - 0.3).abs() < 1e-10);
     }
 }
@@ -0,0 +1,184 @@
+//! Dense Linear Algebra
+//!
+//! The few matrix routines the learning modules need, on row-major
+//! `Vec<f64>` storage.
+
+/// Solve `a · x = b` for square `a` (`n × n`, row-major) by Gaussian
+/// elimination with partial pivoting
+///
+/// Returns `None` when `a` is singular to working precision.
+pub(crate) fn solve(mut a: Vec<f64>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    assert_eq!(a.len(), n * n, "Expected a square matrix");
+    let scale = a.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    let tiny = scale * f64::EPSILON * n as f64;
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap();
+        if a[pivot * n + col].abs() <= tiny {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(col * n + k, pivot * n + k);
+            }
+            b.swap(col, pivot);
+        }
+        for row in col + 1..n {
+            let factor = a[row * n + col] / a[col * n + col];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                a[row * n + k] -= factor * a[col * n + k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row * n + k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row * n + row];
+    }
+    Some(x)
+}
+
+/// Accumulated normal equations `AᵀA x = Aᵀy` of a linear least-squares
+/// problem, added to one row of `A` at a time
+pub(crate) struct NormalEquations {
+    normal: Vec<f64>,
+    rhs: Vec<f64>,
+}
+
+impl NormalEquations {
+    pub fn new(columns: usize) -> Self {
+        NormalEquations {
+            normal: vec![0.0; columns * columns],
+            rhs: vec![0.0; columns],
+        }
+    }
+
+    /// Add the equation `row · x = y`
+    pub fn add(&mut self, row: &[f64], y: f64) {
+        let n = self.rhs.len();
+        for (i, &ri) in row.iter().enumerate() {
+            if ri == 0.0 {
+                continue;
+            }
+            self.rhs[i] += ri * y;
+            for (j, &rj) in row.iter().enumerate() {
+                self.normal[i * n + j] += ri * rj;
+            }
+        }
+    }
+
+    /// The least-squares solution
+    ///
+    /// A small ridge term keeps the system solvable when some unknowns
+    /// never appear in a row.
+    pub fn solve(mut self) -> Option<Vec<f64>> {
+        let n = self.rhs.len();
+        let trace: f64 = (0..n).map(|i| self.normal[i * n + i]).sum();
+        let ridge = 1e-9 * (trace / n as f64).max(1e-12);
+        for i in 0..n {
+            self.normal[i * n + i] += ridge;
+        }
+        solve(self.normal, self.rhs)
+    }
+}
+
+/// Inverse and determinant of square `a` (`n × n`, row-major) by
+/// Gauss–Jordan elimination with partial pivoting
+///
+/// Returns `None` when `a` is singular to working precision.
+pub(crate) fn invert(a: &[f64], n: usize) -> Option<(Vec<f64>, f64)> {
+    assert_eq!(a.len(), n * n, "Expected a square matrix");
+    let mut a = a.to_vec();
+    let mut inverse = vec![0.0; n * n];
+    for i in 0..n {
+        inverse[i * n + i] = 1.0;
+    }
+    let scale = a.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    let tiny = scale * f64::EPSILON * n as f64;
+    let mut determinant = 1.0;
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap();
+        if a[pivot * n + col].abs() <= tiny {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(col * n + k, pivot * n + k);
+                inverse.swap(col * n + k, pivot * n + k);
+            }
+            determinant = -determinant;
+        }
+        let p = a[col * n + col];
+        determinant *= p;
+        for k in 0..n {
+            a[col * n + k] /= p;
+            inverse[col * n + k] /= p;
+        }
+        for row in 0..n {
+            let factor = a[row * n + col];
+            if row == col || factor == 0.0 {
+                continue;
+            }
+            for k in 0..n {
+                a[row * n + k] -= factor * a[col * n + k];
+                inverse[row * n + k] -= factor * inverse[col * n + k];
+            }
+        }
+    }
+    Some((inverse, determinant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        // Needs a row swap: the first pivot is zero
+        let a = vec![0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+        let x = solve(a, vec![7.0, 6.0, 13.0]).unwrap();
+        for (actual, expected) in x.iter().zip([1.0, 2.0, 3.0]) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+        assert_eq!(solve(vec![1.0, 2.0, 2.0, 4.0], vec![1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn test_least_squares() {
+        // y = 2x + 1 with the second unknown as the intercept; the third never appears
+        let mut equations = NormalEquations::new(3);
+        for (x, y) in [(0.0, 1.1), (1.0, 2.9), (2.0, 5.1), (3.0, 6.9)] {
+            equations.add(&[x, 1.0, 0.0], y);
+        }
+        let fit = equations.solve().unwrap();
+        assert!((fit[0] - 1.96).abs() < 1e-6);
+        assert!((fit[1] - 1.06).abs() < 1e-6);
+        assert_eq!(fit[2], 0.0);
+    }
+
+    #[test]
+    fn test_invert() {
+        let a = [0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+        let (inverse, determinant) = invert(&a, 3).unwrap();
+        assert!((determinant - -3.0).abs() < 1e-12);
+        for i in 0..3 {
+            for j in 0..3 {
+                let product: f64 = (0..3).map(|k| a[i * 3 + k] * inverse[k * 3 + j]).sum();
+                let identity = if i == j { 1.0 } else { 0.0 };
+                assert!((product - identity).abs() < 1e-12);
+            }
+        }
+        assert_eq!(invert(&[1.0, 2.0, 2.0, 4.0], 2), None);
+    }
+}
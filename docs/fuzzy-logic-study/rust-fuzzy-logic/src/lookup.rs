@@ -0,0 +1,505 @@
+//! Precompiled Lookup Tables
+//!
+//! This module compiles a controller into a dense N-dimensional table
+//! sampled over its input universes. At runtime the table is evaluated by
+//! multilinear interpolation between the surrounding grid nodes, which takes
+//! constant time (independent of the rule count) and never allocates. This
+//! makes it suitable for hard real-time targets.
+//!
+//! Tables can be written to and read from a compact binary format with
+//! [`LookupTable::to_bytes`] and [`LookupTable::from_bytes`].
+
+use core::fmt;
+
+use alloc::{vec, vec::Vec};
+
+use crate::inference::{FuzzyController, TwoInputFuzzyController};
+use crate::math::floor;
+use crate::system::{linspace, FuzzySystem};
+
+/// Identifies the binary table format
+const MAGIC: &[u8; 4] = b"FZLT";
+const FORMAT_VERSION: u32 = 1;
+
+/// One input axis of a lookup table
+///
+/// The fields are private so every axis goes through [`Axis::new`] and has
+/// at least one cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Axis {
+    min: f64,
+    max: f64,
+    /// Number of grid nodes, including both ends (at least 2)
+    points: usize,
+}
+
+impl Axis {
+    pub fn new(min: f64, max: f64, points: usize) -> Self {
+        assert!(points >= 2, "An axis needs at least 2 points");
+        assert!(max > min, "Axis max must be greater than min");
+        Axis { min, max, points }
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Number of grid nodes, including both ends
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Cell index and position within the cell for `x` (clamped to the axis)
+    #[inline]
+    fn locate(&self, x: f64) -> (usize, f64) {
+        let cells = (self.points - 1) as f64;
+        let pos = ((x - self.min) / (self.max - self.min) * cells).clamp(0.0, cells);
+        let cell = (floor(pos) as usize).min(self.points - 2);
+        (cell, pos - cell as f64)
+    }
+}
+
+/// Errors reading a serialized lookup table
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupError {
+    /// The data does not start with the table magic bytes
+    BadMagic,
+    /// The data was written by an unsupported format version
+    UnsupportedVersion(u32),
+    /// The data ended early or has trailing bytes
+    BadLength { expected: usize, actual: usize },
+    /// An axis has invalid bounds or too few points, or the axes have too
+    /// many nodes to address (reported as axis 0)
+    InvalidAxis(usize),
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::BadMagic => write!(f, "not a lookup table (bad magic bytes)"),
+            LookupError::UnsupportedVersion(v) => {
+                write!(f, "unsupported lookup table version {}", v)
+            }
+            LookupError::BadLength { expected, actual } => write!(
+                f,
+                "lookup table data has {} bytes, expected {}",
+                actual, expected
+            ),
+            LookupError::InvalidAxis(i) => write!(f, "lookup table axis {} is invalid", i),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LookupError {}
+
+/// Interpolation error of a table measured against the exact function
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub max_error: f64,
+    pub mean_error: f64,
+    /// Input point where the largest error occurred
+    pub worst_point: Vec<f64>,
+    /// Number of validation points
+    pub samples: usize,
+}
+
+/// Dense N-dimensional table evaluated by multilinear interpolation
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupTable {
+    axes: Vec<Axis>,
+    /// Row-major node values; the last axis varies fastest
+    values: Vec<f64>,
+}
+
+impl LookupTable {
+    /// Sample an arbitrary function on a grid
+    ///
+    /// # Arguments
+    ///
+    /// * `axes` - One axis per input
+    /// * `f` - Exact function, called once per grid node
+    pub fn compile<F>(axes: Vec<Axis>, mut f: F) -> Self
+    where
+        F: FnMut(&[f64]) -> f64,
+    {
+        assert!(!axes.is_empty(), "A lookup table needs at least one axis");
+        let len: usize = axes.iter().map(|a| a.points).product();
+        let grids: Vec<Vec<f64>> = axes
+            .iter()
+            .map(|a| linspace(a.min, a.max, a.points))
+            .collect();
+
+        let mut values = Vec::with_capacity(len);
+        let mut point = vec![0.0; axes.len()];
+        for flat in 0..len {
+            let mut rest = flat;
+            for d in (0..axes.len()).rev() {
+                point[d] = grids[d][rest % axes[d].points];
+                rest /= axes[d].points;
+            }
+            values.push(f(&point));
+        }
+
+        LookupTable { axes, values }
+    }
+
+    /// Compile a single-input controller over `[min, max]`
+    pub fn from_controller(controller: &FuzzyController, min: f64, max: f64, points: usize) -> Self {
+        let mut scratch = controller.scratch();
+        Self::compile(vec![Axis::new(min, max, points)], |x| {
+            controller.evaluate_with(x[0], &mut scratch)
+        })
+    }
+
+    /// Compile a two-input controller over the given input ranges
+    pub fn from_two_input_controller(
+        controller: &TwoInputFuzzyController,
+        axis1: Axis,
+        axis2: Axis,
+    ) -> Self {
+        let mut scratch = controller.scratch();
+        Self::compile(vec![axis1, axis2], |x| {
+            controller.evaluate_with(x[0], x[1], &mut scratch)
+        })
+    }
+
+    /// Compile one output of a system over the universes of its inputs
+    pub fn from_system(system: &FuzzySystem, output: usize, points_per_axis: usize) -> Self {
+        assert!(output < system.outputs().len(), "Unknown output variable");
+        let axes = system
+            .inputs()
+            .iter()
+            .map(|v| Axis::new(v.min, v.max, points_per_axis))
+            .collect();
+        let mut scratch = system.scratch();
+        let mut out = vec![0.0; system.outputs().len()];
+        Self::compile(axes, |x| {
+            system.evaluate_with(x, &mut out, &mut scratch);
+            out[output]
+        })
+    }
+
+    pub fn axes(&self) -> &[Axis] {
+        &self.axes
+    }
+
+    /// Node values in row-major order (last axis fastest)
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Interpolated value at `inputs` (one per axis)
+    ///
+    /// Inputs outside an axis are clamped to its range. Runs in constant time
+    /// for a given number of axes and does not allocate.
+    pub fn evaluate(&self, inputs: &[f64]) -> f64 {
+        assert_eq!(inputs.len(), self.axes.len(), "Expected one value per axis");
+        let dims = self.axes.len();
+
+        let mut result = 0.0;
+        for corner in 0..(1usize << dims) {
+            let mut weight = 1.0;
+            let mut index = 0;
+            for (d, (axis, &x)) in self.axes.iter().zip(inputs).enumerate() {
+                let (cell, t) = axis.locate(x);
+                let upper = (corner >> (dims - 1 - d)) & 1 == 1;
+                index = index * axis.points + cell + upper as usize;
+                weight *= if upper { t } else { 1.0 - t };
+            }
+            if weight != 0.0 {
+                result += weight * self.values[index];
+            }
+        }
+        result
+    }
+
+    /// Compare the table against the exact function on a validation grid
+    ///
+    /// The grid has `samples_per_axis` evenly spaced points per axis. Choose
+    /// a count that does not line up with the table nodes, so that points
+    /// fall between nodes where interpolation error is largest.
+    pub fn validate<F>(&self, mut exact: F, samples_per_axis: usize) -> ValidationReport
+    where
+        F: FnMut(&[f64]) -> f64,
+    {
+        let steps = samples_per_axis.max(2);
+        let grids: Vec<Vec<f64>> = self
+            .axes
+            .iter()
+            .map(|a| linspace(a.min, a.max, steps))
+            .collect();
+        let samples = steps.pow(self.axes.len() as u32);
+
+        let mut max_error = 0.0;
+        let mut total_error = 0.0;
+        let mut worst_point = grids.iter().map(|g| g[0]).collect();
+        let mut point = vec![0.0; self.axes.len()];
+        for flat in 0..samples {
+            let mut rest = flat;
+            for d in (0..self.axes.len()).rev() {
+                point[d] = grids[d][rest % steps];
+                rest /= steps;
+            }
+            let error = (self.evaluate(&point) - exact(&point)).abs();
+            total_error += error;
+            if error > max_error {
+                max_error = error;
+                worst_point = point.clone();
+            }
+        }
+
+        ValidationReport {
+            max_error,
+            mean_error: total_error / samples as f64,
+            worst_point,
+            samples,
+        }
+    }
+
+    /// Serialize the table (little-endian binary)
+    ///
+    /// Layout: magic `FZLT`, version (u32), axis count (u32), then per axis
+    /// min (f64), max (f64), points (u32), then all node values (f64).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.axes.len() * 20 + self.values.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.axes.len() as u32).to_le_bytes());
+        for axis in &self.axes {
+            bytes.extend_from_slice(&axis.min.to_le_bytes());
+            bytes.extend_from_slice(&axis.max.to_le_bytes());
+            bytes.extend_from_slice(&(axis.points as u32).to_le_bytes());
+        }
+        for v in &self.values {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Read a table written by [`LookupTable::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LookupError> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+
+        if reader.take(4).map(|m| m != MAGIC).unwrap_or(true) {
+            return Err(LookupError::BadMagic);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(LookupError::UnsupportedVersion(version));
+        }
+
+        let dims = reader.u32()? as usize;
+        let mut axes = Vec::with_capacity(dims.min(64));
+        for i in 0..dims {
+            let min = reader.f64()?;
+            let max = reader.f64()?;
+            let points = reader.u32()? as usize;
+            if points < 2 || !min.is_finite() || !max.is_finite() || max <= min {
+                return Err(LookupError::InvalidAxis(i));
+            }
+            axes.push(Axis { min, max, points });
+        }
+        if axes.is_empty() {
+            return Err(LookupError::InvalidAxis(0));
+        }
+
+        let len = axes
+            .iter()
+            .try_fold(1usize, |acc, a| acc.checked_mul(a.points))
+            .ok_or(LookupError::InvalidAxis(0))?;
+        let expected = len
+            .checked_mul(8)
+            .and_then(|n| n.checked_add(reader.pos))
+            .ok_or(LookupError::InvalidAxis(0))?;
+        if bytes.len() != expected {
+            return Err(LookupError::BadLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        let values = (0..len).map(|_| reader.f64()).collect::<Result<_, _>>()?;
+
+        Ok(LookupTable { axes, values })
+    }
+}
+
+/// Cursor over serialized table bytes
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], LookupError> {
+        let end = self.pos + n;
+        if end > self.bytes.len() {
+            return Err(LookupError::BadLength {
+                expected: end,
+                actual: self.bytes.len(),
+            });
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, LookupError> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn f64(&mut self) -> Result<f64, LookupError> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(f64::from_le_bytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::FuzzyRule;
+    use crate::membership::MembershipFunction;
+
+    fn fan_controller() -> FuzzyController {
+        let mut controller = FuzzyController::new(0.0, 100.0, 100);
+        let pairs = [
+            ((0.0, 0.0, 20.0), (0.0, 0.0, 50.0)),
+            ((10.0, 25.0, 35.0), (20.0, 50.0, 80.0)),
+            ((30.0, 40.0, 40.0), (50.0, 100.0, 100.0)),
+        ];
+        for ((a, b, c), (d, e, f)) in pairs {
+            let condition = MembershipFunction::Triangular { a, b, c };
+            let conclusion = MembershipFunction::Triangular { a: d, b: e, c: f };
+            controller.add_rule(FuzzyRule::new(
+                Box::new(move |x| condition.evaluate(x)),
+                Box::new(move |y| conclusion.evaluate(y)),
+            ));
+        }
+        controller
+    }
+
+    #[test]
+    fn test_multilinear_function_is_exact() {
+        let f = |x: &[f64]| 2.0 * x[0] - 3.0 * x[1] + 0.5 * x[0] * x[1] + 1.0;
+        let table = LookupTable::compile(
+            vec![Axis::new(0.0, 10.0, 3), Axis::new(-5.0, 5.0, 4)],
+            f,
+        );
+
+        assert_eq!(table.values().len(), 12);
+        for &(x, y) in &[(0.0, -5.0), (3.3, 1.7), (10.0, 5.0), (7.25, -0.1)] {
+            assert!((table.evaluate(&[x, y]) - f(&[x, y])).abs() < 1e-9);
+        }
+        assert!(table.validate(f, 17).max_error < 1e-9);
+    }
+
+    #[test]
+    fn test_inputs_are_clamped() {
+        let table = LookupTable::compile(vec![Axis::new(0.0, 1.0, 2)], |x| x[0] * 10.0);
+
+        assert_eq!(table.evaluate(&[-3.0]), 0.0);
+        assert_eq!(table.evaluate(&[0.5]), 5.0);
+        assert_eq!(table.evaluate(&[42.0]), 10.0);
+    }
+
+    #[test]
+    fn test_controller_table_error_shrinks_with_resolution() {
+        let controller = fan_controller();
+        let exact = |x: &[f64]| controller.evaluate(x[0]);
+
+        let coarse = LookupTable::from_controller(&controller, 0.0, 40.0, 9);
+        let fine = LookupTable::from_controller(&controller, 0.0, 40.0, 161);
+        let coarse_report = coarse.validate(exact, 401);
+        let fine_report = fine.validate(exact, 401);
+
+        assert_eq!(fine_report.samples, 401);
+        assert!(fine_report.max_error < coarse_report.max_error);
+        assert!(fine_report.max_error < 1.0, "{:?}", fine_report);
+        assert!(fine_report.mean_error <= fine_report.max_error);
+    }
+
+    #[test]
+    fn test_system_table_matches_nodes() {
+        use crate::system::{Antecedent, Consequent, LinguisticVariable, Rule};
+
+        let mut system = FuzzySystem::new();
+        system.add_input(
+            LinguisticVariable::new("a", 0.0, 10.0)
+                .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 })
+                .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 }),
+        );
+        system.add_input(
+            LinguisticVariable::new("b", 0.0, 10.0)
+                .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 }),
+        );
+        system.add_output(
+            LinguisticVariable::new("y", 0.0, 1.0)
+                .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 1.0 })
+                .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 1.0 }),
+        );
+        system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 1), Antecedent::new(1, 0)],
+            vec![Consequent::new(0, 1)],
+        ));
+
+        let table = LookupTable::from_system(&system, 0, 11);
+        assert_eq!(table.axes().len(), 2);
+        for &(a, b) in &[(0.0, 0.0), (3.0, 7.0), (10.0, 10.0)] {
+            assert!((table.evaluate(&[a, b]) - system.evaluate(&[a, b])[0]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let controller = fan_controller();
+        let table = LookupTable::from_controller(&controller, 0.0, 40.0, 41);
+
+        let bytes = table.to_bytes();
+        let restored = LookupTable::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored, table);
+        assert_eq!(restored.evaluate(&[17.3]), table.evaluate(&[17.3]));
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_bad_data() {
+        let table = LookupTable::compile(vec![Axis::new(0.0, 1.0, 2)], |x| x[0]);
+        let bytes = table.to_bytes();
+
+        assert_eq!(LookupTable::from_bytes(b"nope"), Err(LookupError::BadMagic));
+        assert!(matches!(
+            LookupTable::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(LookupError::BadLength { .. })
+        ));
+
+        let mut future = bytes.clone();
+        future[4] = 9;
+        assert_eq!(
+            LookupTable::from_bytes(&future),
+            Err(LookupError::UnsupportedVersion(9))
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_oversized_tables() {
+        // Two axes of u32::MAX points: the node count fits in a usize but
+        // its byte length does not
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        for _ in 0..2 {
+            bytes.extend_from_slice(&0.0f64.to_le_bytes());
+            bytes.extend_from_slice(&1.0f64.to_le_bytes());
+            bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        }
+        assert_eq!(LookupTable::from_bytes(&bytes), Err(LookupError::InvalidAxis(0)));
+    }
+}
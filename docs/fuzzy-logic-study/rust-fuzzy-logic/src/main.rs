@@ -1,15 +1,25 @@
 //! Interactive Fuzzy Logic CLI Study Program
 //!
 //! This program provides step-by-step lessons on fuzzy logic concepts
-//! with interactive examples in Rust.
+//! with interactive examples in Rust, and a REPL for building your own
+//! systems. Given a command (`eval`, `validate`, `describe`, `surface`,
+//! `plot`, `repl`), it runs that command instead; see `fuzzy_logic help`.
+
+mod cli;
 
 use fuzzy_logic::membership::{FuzzySet, MembershipFunction};
 use fuzzy_logic::operations::*;
 use fuzzy_logic::defuzzification::*;
 use fuzzy_logic::inference::{FuzzyController, FuzzyRule};
+use fuzzy_logic::plot::{plot_defuzzification, plot_fuzzy_operations, Plot};
 use std::io::{self, Write};
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     println!("\n{}", "=".repeat(60));
     println!("FUZZY LOGIC INTERACTIVE STUDY PROGRAM (Rust)");
     println!("{}", "=".repeat(60));
@@ -22,11 +32,12 @@ fn main() {
         println!("  3. Fuzzy Sets");
         println!("  4. Defuzzification Methods");
         println!("  5. Simple Fuzzy Controller");
+        println!("  6. Build Your Own System (REPL)");
         println!();
         println!("  0. Exit");
         println!();
 
-        print!("Enter your choice (0-5): ");
+        print!("Enter your choice (0-6): ");
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -38,6 +49,11 @@ fn main() {
             "3" => lesson_3_fuzzy_sets(),
             "4" => lesson_4_defuzzification(),
             "5" => lesson_5_fuzzy_controller(),
+            "6" => {
+                if let Err(err) = cli::run_repl() {
+                    println!("\nREPL stopped: {}", err);
+                }
+            }
             "0" => {
                 println!("\nThank you for learning fuzzy logic! 🧠✨");
                 break;
@@ -112,6 +128,14 @@ fn lesson_1_membership_functions() {
     println!("  Evaluation at x=5.0: {:.3}", sigmoid.evaluate(5.0));
     println!("  Evaluation at x=10.0: {:.3}", sigmoid.evaluate(10.0));
 
+    println!();
+    let plot = Plot::new("Membership Functions")
+        .with_membership_function("Triangular", &triangular, 0.0, 10.0, 101)
+        .with_membership_function("Trapezoidal", &trapezoidal, 0.0, 10.0, 101)
+        .with_membership_function("Gaussian", &gaussian, 0.0, 10.0, 101)
+        .with_membership_function("Sigmoid", &sigmoid, 0.0, 10.0, 101);
+    print!("{}", plot.to_ascii(60, 12));
+
     println!("\nKey Points:");
     println!("  - Membership values are always between 0.0 and 1.0");
     println!("  - Each shape serves different purposes");
@@ -167,6 +191,18 @@ fn lesson_2_fuzzy_operations() {
     println!("Bounded Sum:");
     println!("  Result: {:.3}", bounded_sum(a, b));
     println!("  Formula: min(1, a + b)");
+    println!();
+
+    println!("Applied to whole fuzzy sets (A peaks at 3, B peaks at 7):");
+    println!();
+    let xs: Vec<f64> = (0..=100).map(|i| i as f64 / 10.0).collect();
+    let set_a = MembershipFunction::Triangular { a: 0.0, b: 3.0, c: 6.0 };
+    let set_b = MembershipFunction::Triangular { a: 4.0, b: 7.0, c: 10.0 };
+    let a_values: Vec<f64> = xs.iter().map(|&x| set_a.evaluate(x)).collect();
+    let b_values: Vec<f64> = xs.iter().map(|&x| set_b.evaluate(x)).collect();
+    for plot in plot_fuzzy_operations(&xs, &a_values, &b_values, "A", "B") {
+        println!("{}", plot.to_braille(50, 6));
+    }
 }
 
 fn lesson_3_fuzzy_sets() {
@@ -224,6 +260,16 @@ fn lesson_3_fuzzy_sets() {
         println!();
     }
 
+    let plot = Plot::new("Temperature")
+        .with_labels("Temperature (°C)", "Membership Degree")
+        .with_fuzzy_set(&cold, 0.0, 40.0, 81)
+        .with_fuzzy_set(&warm, 0.0, 40.0, 81)
+        .with_fuzzy_set(&hot, 0.0, 40.0, 81)
+        .with_alpha_cut(0.5);
+    println!("{}", plot.to_ascii(60, 12));
+    println!("The dashed line is the 0.5 alpha cut; '=' marks where a set reaches it.");
+    println!();
+
     println!("Notice how:");
     println!("  - At 15°C: Mostly cold");
     println!("  - At 25°C: Mix of warm (and a bit cold/hot)");
@@ -287,6 +333,9 @@ fn lesson_4_defuzzification() {
     println!("   Method: Divides area in half");
     println!();
 
+    let plot = plot_defuzzification(&x_values, &membership, DefuzzificationMethod::Centroid);
+    println!("{}", plot.to_braille(50, 8));
+
     println!("When to use:");
     println!("  - Centroid: Most common, balanced");
     println!("  - MOM: When you want the peak response");
@@ -368,6 +417,14 @@ fn lesson_5_fuzzy_controller() {
         println!("  Temperature: {}°C → Fan Speed: {:.1}%", temp, fan_speed);
     }
 
+    let temps: Vec<f64> = (0..=80).map(|i| i as f64 / 2.0).collect();
+    let speeds = controller.evaluate_batch(&temps);
+    let plot = Plot::new("Fan Speed Response")
+        .with_labels("Temperature (°C)", "Fan Speed (%)")
+        .with_series("Fan speed", &temps, &speeds);
+    println!();
+    print!("{}", plot.to_braille(50, 10));
+
     println!();
     println!("Notice the smooth transitions!");
     println!("This is the power of fuzzy logic - no abrupt jumps.");
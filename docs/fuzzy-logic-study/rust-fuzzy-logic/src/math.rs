@@ -0,0 +1,106 @@
+//! Floating-Point Functions
+//!
+//! Transcendental functions come from `std` when it is available and from
+//! `libm` otherwise, so the core builds for targets without an operating
+//! system.
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("without the `std` feature, enable `libm` for floating-point functions");
+
+/// e^x
+#[cfg(feature = "std")]
+#[inline]
+pub fn exp(x: f64) -> f64 {
+    x.exp()
+}
+
+/// e^x
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn exp(x: f64) -> f64 {
+    libm::exp(x)
+}
+
+/// Largest integer not greater than x
+#[cfg(feature = "std")]
+#[inline]
+pub fn floor(x: f64) -> f64 {
+    x.floor()
+}
+
+/// Largest integer not greater than x
+#[cfg(all(not(feature = "std"), feature = "libm", feature = "alloc"))]
+#[inline]
+pub fn floor(x: f64) -> f64 {
+    libm::floor(x)
+}
+
+/// e^x in single precision
+#[cfg(feature = "std")]
+#[inline]
+pub fn expf(x: f32) -> f32 {
+    x.exp()
+}
+
+/// e^x in single precision
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn expf(x: f32) -> f32 {
+    libm::expf(x)
+}
+
+/// x raised to the power y
+#[cfg(feature = "std")]
+#[inline]
+pub fn pow(x: f64, y: f64) -> f64 {
+    x.powf(y)
+}
+
+/// x raised to the power y
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn pow(x: f64, y: f64) -> f64 {
+    libm::pow(x, y)
+}
+
+/// x raised to the power y in single precision
+#[cfg(feature = "std")]
+#[inline]
+pub fn powf(x: f32, y: f32) -> f32 {
+    x.powf(y)
+}
+
+/// x raised to the power y in single precision
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn powf(x: f32, y: f32) -> f32 {
+    libm::powf(x, y)
+}
+
+/// Natural logarithm
+#[cfg(feature = "std")]
+#[inline]
+pub fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+/// Natural logarithm
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
+
+/// Natural logarithm in single precision
+#[cfg(feature = "std")]
+#[inline]
+pub fn lnf(x: f32) -> f32 {
+    x.ln()
+}
+
+/// Natural logarithm in single precision
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+#[inline]
+pub fn lnf(x: f32) -> f32 {
+    libm::logf(x)
+}
@@ -2,36 +2,52 @@
 //!
 //! This module provides various types of membership functions used in fuzzy logic.
 
-use std::f64::consts::E;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+use crate::numeric::Real;
 
 /// Types of membership functions
-#[derive(Debug, Clone)]
-pub enum MembershipFunction {
+///
+/// Parameters and degrees are `f64` unless another [`Real`] type is chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MembershipFunction<T = f64> {
     /// Triangular membership function
     /// - a: Left foot (membership = 0)
     /// - b: Peak (membership = 1)
     /// - c: Right foot (membership = 0)
-    Triangular { a: f64, b: f64, c: f64 },
+    Triangular { a: T, b: T, c: T },
 
     /// Trapezoidal membership function
     /// - a: Left foot
     /// - b: Left shoulder (start of plateau)
     /// - c: Right shoulder (end of plateau)
     /// - d: Right foot
-    Trapezoidal { a: f64, b: f64, c: f64, d: f64 },
+    Trapezoidal { a: T, b: T, c: T, d: T },
 
     /// Gaussian membership function
     /// - mean: Center of the bell curve
     /// - sigma: Standard deviation (controls width)
-    Gaussian { mean: f64, sigma: f64 },
+    Gaussian { mean: T, sigma: T },
 
     /// Sigmoid membership function
     /// - a: Controls steepness (larger = steeper)
     /// - c: Center point (inflection point)
-    Sigmoid { a: f64, c: f64 },
+    Sigmoid { a: T, c: T },
+
+    /// Generalized bell membership function, 1 / (1 + |(x - c) / a|^(2b))
+    /// - a: Half width (membership = 0.5 at c ± a)
+    /// - b: Controls the slope at the crossover points (larger = steeper)
+    /// - c: Center
+    Bell { a: T, b: T, c: T },
 }
 
-impl MembershipFunction {
+/// Evaluation of a membership function in any [`Real`] type
+///
+/// `MembershipFunction<f64>` also has an inherent `evaluate`, which is what
+/// lets functions written with float literals infer as `f64`. Import this
+/// trait to evaluate functions over `f32` or fixed point.
+pub trait Evaluate<T> {
     /// Evaluate the membership function at a given point
     ///
     /// # Arguments
@@ -41,7 +57,11 @@ impl MembershipFunction {
     /// # Returns
     ///
     /// The membership degree in the range [0.0, 1.0]
-    pub fn evaluate(&self, x: f64) -> f64 {
+    fn evaluate(&self, x: T) -> T;
+}
+
+impl<T: Real> Evaluate<T> for MembershipFunction<T> {
+    fn evaluate(&self, x: T) -> T {
         match self {
             MembershipFunction::Triangular { a, b, c } => {
                 Self::triangular(x, *a, *b, *c)
@@ -55,16 +75,36 @@ impl MembershipFunction {
             MembershipFunction::Sigmoid { a, c } => {
                 Self::sigmoid(x, *a, *c)
             }
+            MembershipFunction::Bell { a, b, c } => {
+                Self::bell(x, *a, *b, *c)
+            }
         }
     }
+}
 
+impl MembershipFunction {
+    /// Evaluate the membership function at a given point
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The point at which to evaluate the function
+    ///
+    /// # Returns
+    ///
+    /// The membership degree in the range [0.0, 1.0]
+    pub fn evaluate(&self, x: f64) -> f64 {
+        Evaluate::evaluate(self, x)
+    }
+}
+
+impl<T: Real> MembershipFunction<T> {
     /// Triangular membership function
     #[inline]
-    fn triangular(x: f64, a: f64, b: f64, c: f64) -> f64 {
-        if x <= a || x >= c {
-            0.0
-        } else if x == b {
-            1.0
+    fn triangular(x: T, a: T, b: T, c: T) -> T {
+        if x == b {
+            T::ONE
+        } else if x <= a || x >= c {
+            T::ZERO
         } else if x < b {
             (x - a) / (b - a)
         } else {
@@ -74,11 +114,11 @@ impl MembershipFunction {
 
     /// Trapezoidal membership function
     #[inline]
-    fn trapezoidal(x: f64, a: f64, b: f64, c: f64, d: f64) -> f64 {
-        if x <= a || x >= d {
-            0.0
-        } else if x >= b && x <= c {
-            1.0
+    fn trapezoidal(x: T, a: T, b: T, c: T, d: T) -> T {
+        if x >= b && x <= c {
+            T::ONE
+        } else if x <= a || x >= d {
+            T::ZERO
         } else if x < b {
             (x - a) / (b - a)
         } else {
@@ -88,17 +128,155 @@ impl MembershipFunction {
 
     /// Gaussian membership function
     #[inline]
-    fn gaussian(x: f64, mean: f64, sigma: f64) -> f64 {
-        E.powf(-0.5 * ((x - mean) / sigma).powi(2))
+    fn gaussian(x: T, mean: T, sigma: T) -> T {
+        let z = (x - mean) / sigma;
+        (-(z * z).mul_ratio(1, 2)).exp()
     }
 
     /// Sigmoid membership function
     #[inline]
-    fn sigmoid(x: f64, a: f64, c: f64) -> f64 {
-        1.0 / (1.0 + E.powf(-a * (x - c)))
+    fn sigmoid(x: T, a: T, c: T) -> T {
+        T::ONE / (T::ONE + (-(a * (x - c))).exp())
+    }
+
+    /// Generalized bell membership function
+    #[inline]
+    fn bell(x: T, a: T, b: T, c: T) -> T {
+        let z = ((x - c) / a).abs();
+        T::ONE / (T::ONE + z.powf(b.mul_ratio(2, 1)))
+    }
+
+    /// Partial derivatives of the membership degree at `x` with respect to
+    /// each parameter
+    ///
+    /// The result has the same variant as `self`, with each field holding
+    /// dμ(x)/d(that parameter), e.g. `Gaussian { mean: dμ/dmean, sigma:
+    /// dμ/dsigma }`.
+    ///
+    /// The piecewise shapes have kinks where `x` equals a breakpoint. There
+    /// the result is the mean of the one-sided derivatives, a subgradient
+    /// that lies between them. A side of zero width (such as the left side
+    /// of `Triangular { a: 0.0, b: 0.0, c: 1.0 }`) is a step, and contributes
+    /// nothing. At the center of a bell every partial is zero.
+    pub fn gradient(&self, x: T) -> MembershipFunction<T> {
+        match *self {
+            MembershipFunction::Triangular { a, b, c } => {
+                let (da, db_rising) = Self::rising_gradient(x, a, b);
+                let (db_falling, dc) = Self::falling_gradient(x, b, c);
+                MembershipFunction::Triangular { a: da, b: db_rising + db_falling, c: dc }
+            }
+            MembershipFunction::Trapezoidal { a, b, c, d } => {
+                let (da, db) = Self::rising_gradient(x, a, b);
+                let (dc, dd) = Self::falling_gradient(x, c, d);
+                MembershipFunction::Trapezoidal { a: da, b: db, c: dc, d: dd }
+            }
+            MembershipFunction::Gaussian { mean, sigma } => {
+                let z = (x - mean) / sigma;
+                let mu = Self::gaussian(x, mean, sigma);
+                MembershipFunction::Gaussian {
+                    mean: mu * z / sigma,
+                    sigma: mu * z * z / sigma,
+                }
+            }
+            MembershipFunction::Sigmoid { a, c } => {
+                let mu = Self::sigmoid(x, a, c);
+                let slope = mu * (T::ONE - mu);
+                MembershipFunction::Sigmoid {
+                    a: slope * (x - c),
+                    c: -slope * a,
+                }
+            }
+            MembershipFunction::Bell { a, b, c } => {
+                let z = ((x - c) / a).abs();
+                if z == T::ZERO {
+                    return MembershipFunction::Bell { a: T::ZERO, b: T::ZERO, c: T::ZERO };
+                }
+                let two_b = b.mul_ratio(2, 1);
+                let u = z.powf(two_b);
+                let mu = T::ONE / (T::ONE + u);
+                let k = mu * mu * u;
+                MembershipFunction::Bell {
+                    a: k * two_b / a,
+                    b: -(k * z.ln()).mul_ratio(2, 1),
+                    c: k * two_b / (x - c),
+                }
+            }
+        }
+    }
+
+    /// How much the rising piece `(x - lo) / (hi - lo)` on `[lo, hi]`
+    /// counts at `x`: fully inside, half at an end, not at all outside or
+    /// when the piece has zero width
+    #[inline]
+    fn piece_weight(x: T, lo: T, hi: T) -> Option<T> {
+        if lo >= hi || x < lo || x > hi {
+            None
+        } else if x == lo || x == hi {
+            Some(T::ONE.mul_ratio(1, 2))
+        } else {
+            Some(T::ONE)
+        }
+    }
+
+    /// (dμ/da, dμ/db) of the rising side from `a` to `b`
+    #[inline]
+    fn rising_gradient(x: T, a: T, b: T) -> (T, T) {
+        match Self::piece_weight(x, a, b) {
+            Some(weight) => {
+                let width = b - a;
+                let scale = weight / (width * width);
+                ((x - b) * scale, -((x - a) * scale))
+            }
+            None => (T::ZERO, T::ZERO),
+        }
+    }
+
+    /// (dμ/dc, dμ/dd) of the falling side from `c` to `d`
+    #[inline]
+    fn falling_gradient(x: T, c: T, d: T) -> (T, T) {
+        match Self::piece_weight(x, c, d) {
+            Some(weight) => {
+                let width = d - c;
+                let scale = weight / (width * width);
+                ((d - x) * scale, (x - c) * scale)
+            }
+            None => (T::ZERO, T::ZERO),
+        }
+    }
+
+    /// The same shape with parameters converted to another number type
+    pub fn cast<U: Real>(&self) -> MembershipFunction<U> {
+        let to = U::from_f64;
+        match *self {
+            MembershipFunction::Triangular { a, b, c } => MembershipFunction::Triangular {
+                a: to(a.to_f64()),
+                b: to(b.to_f64()),
+                c: to(c.to_f64()),
+            },
+            MembershipFunction::Trapezoidal { a, b, c, d } => MembershipFunction::Trapezoidal {
+                a: to(a.to_f64()),
+                b: to(b.to_f64()),
+                c: to(c.to_f64()),
+                d: to(d.to_f64()),
+            },
+            MembershipFunction::Gaussian { mean, sigma } => MembershipFunction::Gaussian {
+                mean: to(mean.to_f64()),
+                sigma: to(sigma.to_f64()),
+            },
+            MembershipFunction::Sigmoid { a, c } => MembershipFunction::Sigmoid {
+                a: to(a.to_f64()),
+                c: to(c.to_f64()),
+            },
+            MembershipFunction::Bell { a, b, c } => MembershipFunction::Bell {
+                a: to(a.to_f64()),
+                b: to(b.to_f64()),
+                c: to(c.to_f64()),
+            },
+        }
     }
 
     /// Get a textual description of the membership function
+    #[cfg(feature = "alloc")]
     pub fn description(&self) -> String {
         match self {
             MembershipFunction::Triangular { a, b, c } => {
@@ -113,20 +291,25 @@ impl MembershipFunction {
             MembershipFunction::Sigmoid { a, c } => {
                 format!("Sigmoid(a={}, c={})", a, c)
             }
+            MembershipFunction::Bell { a, b, c } => {
+                format!("Bell(a={}, b={}, c={})", a, b, c)
+            }
         }
     }
 }
 
 /// A fuzzy set with a name and membership function
-#[derive(Debug, Clone)]
-pub struct FuzzySet {
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzySet<T = f64> {
     pub name: String,
-    pub membership_function: MembershipFunction,
+    pub membership_function: MembershipFunction<T>,
 }
 
-impl FuzzySet {
+#[cfg(feature = "alloc")]
+impl<T: Real> FuzzySet<T> {
     /// Create a new fuzzy set
-    pub fn new(name: impl Into<String>, membership_function: MembershipFunction) -> Self {
+    pub fn new(name: impl Into<String>, membership_function: MembershipFunction<T>) -> Self {
         FuzzySet {
             name: name.into(),
             membership_function,
@@ -134,9 +317,14 @@ impl FuzzySet {
     }
 
     /// Evaluate membership degree for a value
-    pub fn membership(&self, x: f64) -> f64 {
+    pub fn membership(&self, x: T) -> T {
         self.membership_function.evaluate(x)
     }
+
+    /// The same set with another number type
+    pub fn cast<U: Real>(&self) -> FuzzySet<U> {
+        FuzzySet::new(self.name.clone(), self.membership_function.cast())
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +385,124 @@ mod tests {
         assert!(mf.evaluate(0.0) < 0.1);
     }
 
+    #[test]
+    fn test_bell() {
+        let mf = MembershipFunction::Bell { a: 2.0, b: 3.0, c: 5.0 };
+
+        assert_eq!(mf.evaluate(5.0), 1.0);
+        assert!((mf.evaluate(3.0) - 0.5).abs() < 1e-12);
+        assert!((mf.evaluate(7.0) - 0.5).abs() < 1e-12);
+        assert!(mf.evaluate(6.0) > 0.98);
+        assert!(mf.evaluate(10.0) < 0.01);
+    }
+
+    /// `mf` with parameter `index` (in declaration order) moved by `h`
+    fn nudge(mf: &MembershipFunction, index: usize, h: f64) -> MembershipFunction {
+        let mut params = parameters(mf);
+        params[index] += h;
+        match mf {
+            MembershipFunction::Triangular { .. } => {
+                MembershipFunction::Triangular { a: params[0], b: params[1], c: params[2] }
+            }
+            MembershipFunction::Trapezoidal { .. } => MembershipFunction::Trapezoidal {
+                a: params[0],
+                b: params[1],
+                c: params[2],
+                d: params[3],
+            },
+            MembershipFunction::Gaussian { .. } => {
+                MembershipFunction::Gaussian { mean: params[0], sigma: params[1] }
+            }
+            MembershipFunction::Sigmoid { .. } => MembershipFunction::Sigmoid { a: params[0], c: params[1] },
+            MembershipFunction::Bell { .. } => MembershipFunction::Bell { a: params[0], b: params[1], c: params[2] },
+        }
+    }
+
+    fn parameters(mf: &MembershipFunction) -> Vec<f64> {
+        match *mf {
+            MembershipFunction::Triangular { a, b, c } | MembershipFunction::Bell { a, b, c } => vec![a, b, c],
+            MembershipFunction::Trapezoidal { a, b, c, d } => vec![a, b, c, d],
+            MembershipFunction::Gaussian { mean, sigma } => vec![mean, sigma],
+            MembershipFunction::Sigmoid { a, c } => vec![a, c],
+        }
+    }
+
+    /// Central difference, which at a kink is the mean of the one-sided
+    /// derivatives
+    fn finite_difference(mf: &MembershipFunction, index: usize, x: f64) -> f64 {
+        let h = 1e-6;
+        (nudge(mf, index, h).evaluate(x) - nudge(mf, index, -h).evaluate(x)) / (2.0 * h)
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let shapes = [
+            MembershipFunction::Triangular { a: 1.0, b: 4.0, c: 8.0 },
+            MembershipFunction::Trapezoidal { a: 0.0, b: 2.0, c: 6.0, d: 9.0 },
+            MembershipFunction::Gaussian { mean: 5.0, sigma: 1.5 },
+            MembershipFunction::Sigmoid { a: 1.2, c: 4.5 },
+            MembershipFunction::Bell { a: 2.0, b: 1.5, c: 5.0 },
+            MembershipFunction::Bell { a: 1.0, b: 0.4, c: 3.0 },
+        ];
+        // Every 0.25 from -1 to 10, which includes every breakpoint
+        for mf in &shapes {
+            for i in -4..=40 {
+                let x = i as f64 / 4.0;
+                let analytic = parameters(&mf.gradient(x));
+                for (index, value) in analytic.iter().enumerate() {
+                    let numeric = finite_difference(mf, index, x);
+                    assert!(
+                        (value - numeric).abs() < 1e-6,
+                        "{:?} at {}: parameter {} is {}, expected {}",
+                        mf, x, index, value, numeric
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gradient_subgradients_at_kinks() {
+        let triangle = MembershipFunction::Triangular { a: 0.0, b: 2.0, c: 6.0 };
+        // Peak: -1/2 from the rising side, +1/4 from the falling side, halved
+        assert_eq!(triangle.gradient(2.0), MembershipFunction::Triangular { a: 0.0, b: -0.125, c: 0.0 });
+        assert_eq!(triangle.gradient(0.0), MembershipFunction::Triangular { a: -0.25, b: 0.0, c: 0.0 });
+        assert_eq!(triangle.gradient(6.0), MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 0.125 });
+        assert_eq!(triangle.gradient(7.0), MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 0.0 });
+
+        // A shoulder has a step on its vertical side, which contributes nothing
+        let shoulder = MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 4.0 };
+        assert_eq!(shoulder.gradient(0.0), MembershipFunction::Triangular { a: 0.0, b: 0.125, c: 0.0 });
+
+        let plateau = MembershipFunction::Trapezoidal { a: 0.0, b: 2.0, c: 6.0, d: 10.0 };
+        assert_eq!(
+            plateau.gradient(2.0),
+            MembershipFunction::Trapezoidal { a: 0.0, b: -0.25, c: 0.0, d: 0.0 }
+        );
+        assert_eq!(
+            plateau.gradient(4.0),
+            MembershipFunction::Trapezoidal { a: 0.0, b: 0.0, c: 0.0, d: 0.0 }
+        );
+
+        let bell = MembershipFunction::Bell { a: 1.0, b: 0.5, c: 3.0 };
+        assert_eq!(bell.gradient(3.0), MembershipFunction::Bell { a: 0.0, b: 0.0, c: 0.0 });
+    }
+
+    #[test]
+    fn test_gradient_in_single_precision() {
+        let mf = MembershipFunction::Gaussian { mean: 5.0, sigma: 2.0 };
+        let single = mf.cast::<f32>().gradient(6.0);
+        let double = mf.gradient(6.0);
+        let (MembershipFunction::Gaussian { mean: m32, sigma: s32 }, MembershipFunction::Gaussian { mean, sigma }) =
+            (single, double)
+        else {
+            panic!("gradient changed the variant");
+        };
+        assert!((m32 as f64 - mean).abs() < 1e-6);
+        assert!((s32 as f64 - sigma).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_fuzzy_set() {
         let fuzzy_set = FuzzySet::new(
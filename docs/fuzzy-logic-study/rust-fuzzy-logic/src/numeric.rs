@@ -0,0 +1,249 @@
+//! Numeric Types
+//!
+//! Membership functions, operators, defuzzifiers and controllers are generic
+//! over [`Real`], implemented for `f64` (the default everywhere), `f32` and
+//! the fixed-point types in [`crate::fixed`]. A system is usually designed
+//! in `f64` and converted with `cast` for deployment:
+//!
+//! ```
+//! use fuzzy_logic::fixed::Q16_16;
+//! use fuzzy_logic::membership::{Evaluate, MembershipFunction};
+//! use fuzzy_logic::numeric::Real;
+//!
+//! let warm = MembershipFunction::Triangular { a: 10.0, b: 25.0, c: 35.0 };
+//! let fixed = warm.cast::<Q16_16>();
+//! let degree = fixed.evaluate(Q16_16::from_f64(20.0));
+//! assert!((degree.to_f64() - warm.evaluate(20.0)).abs() < 1e-4);
+//! ```
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::math::{exp, expf, ln, lnf, pow, powf};
+
+/// A number type the fuzzy engine can compute with
+pub trait Real:
+    Copy
+    + PartialOrd
+    + fmt::Debug
+    + fmt::Display
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    /// One, or the largest value below it for types that cannot represent it
+    const ONE: Self;
+
+    /// Nearest representable value, saturating at the ends of the range
+    fn from_f64(x: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    /// Nearest representable value, saturating at the end of the range
+    fn from_usize(n: usize) -> Self;
+
+    /// e^self
+    fn exp(self) -> Self;
+
+    /// self raised to the power `exponent`
+    ///
+    /// The default goes through `f64`, which on targets without a
+    /// floating-point unit means software floating point.
+    fn powf(self, exponent: Self) -> Self {
+        Self::from_f64(pow(self.to_f64(), exponent.to_f64()))
+    }
+
+    /// Natural logarithm, through `f64` by default like [`Real::powf`]
+    fn ln(self) -> Self {
+        Self::from_f64(ln(self.to_f64()))
+    }
+
+    /// self · numerator / denominator, for types that cannot represent the
+    /// counts themselves (such as Q1.31) as well as those that can
+    fn mul_ratio(self, numerator: usize, denominator: usize) -> Self {
+        Self::from_usize(numerator) * (self / Self::from_usize(denominator))
+    }
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other < self {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other > self {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Σ wᵢxᵢ / Σ wᵢ over `(x, w)` pairs, or `None` when the weights sum to zero
+    ///
+    /// Types with a narrow range override this to accumulate in a wider one.
+    fn weighted_mean(pairs: impl Iterator<Item = (Self, Self)>) -> Option<Self> {
+        let (numerator, denominator) = pairs.fold((Self::ZERO, Self::ZERO), |(num, den), (x, w)| {
+            (num + x * w, den + w)
+        });
+        if denominator == Self::ZERO {
+            None
+        } else {
+            Some(numerator / denominator)
+        }
+    }
+}
+
+impl Real for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    #[inline]
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    #[inline]
+    fn from_usize(n: usize) -> Self {
+        n as f64
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        exp(self)
+    }
+
+    #[inline]
+    fn powf(self, exponent: Self) -> Self {
+        pow(self, exponent)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        ln(self)
+    }
+
+    #[inline]
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    #[inline]
+    fn min(self, other: Self) -> Self {
+        f64::min(self, other)
+    }
+
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        f64::max(self, other)
+    }
+}
+
+impl Real for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    #[inline]
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    #[inline]
+    fn from_usize(n: usize) -> Self {
+        n as f32
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        expf(self)
+    }
+
+    #[inline]
+    fn powf(self, exponent: Self) -> Self {
+        powf(self, exponent)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        lnf(self)
+    }
+
+    /// Multiplies first so that sampled universes end exactly at their
+    /// maximum, which `f32` rounding of the step would otherwise overshoot
+    #[inline]
+    fn mul_ratio(self, numerator: usize, denominator: usize) -> Self {
+        self * numerator as f32 / denominator as f32
+    }
+
+    #[inline]
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    #[inline]
+    fn min(self, other: Self) -> Self {
+        f32::min(self, other)
+    }
+
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        f32::max(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::membership::{Evaluate, MembershipFunction};
+
+    #[test]
+    fn test_f32_matches_f64() {
+        let shapes = [
+            MembershipFunction::Triangular { a: 0.0, b: 5.0, c: 10.0 },
+            MembershipFunction::Trapezoidal { a: 0.0, b: 2.0, c: 8.0, d: 10.0 },
+            MembershipFunction::Gaussian { mean: 5.0, sigma: 2.0 },
+            MembershipFunction::Sigmoid { a: 1.5, c: 5.0 },
+            MembershipFunction::Bell { a: 2.0, b: 1.5, c: 5.0 },
+        ];
+        for shape in &shapes {
+            let single = shape.cast::<f32>();
+            for i in 0..=100 {
+                let x = i as f64 / 10.0;
+                let error = (single.evaluate(x as f32) as f64 - shape.evaluate(x)).abs();
+                assert!(error < 1e-6, "{:?} at {}: {}", shape, x, error);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        let pairs = [(1.0, 1.0), (3.0, 3.0)];
+        assert_eq!(f64::weighted_mean(pairs.into_iter()), Some(2.5));
+        assert_eq!(f32::weighted_mean([(1.0, 0.0)].into_iter()), None);
+    }
+}
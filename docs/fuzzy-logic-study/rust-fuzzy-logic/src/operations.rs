@@ -2,6 +2,14 @@
 //!
 //! This module provides basic fuzzy set operations including
 //! union, intersection, complement, and various T-norms and S-norms.
+//!
+//! The free functions work on `f64`. [`TNorm`] and [`SNorm`] apply the
+//! same operators to any [`Real`] type, and the complement is `T::ONE - a`.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::numeric::Real;
 
 /// Fuzzy union (OR operation) using maximum
 ///
@@ -15,7 +23,7 @@
 /// max(a, b)
 #[inline]
 pub fn fuzzy_union(a: f64, b: f64) -> f64 {
-    a.max(b)
+    SNorm::Maximum.apply(a, b)
 }
 
 /// Fuzzy intersection (AND operation) using minimum
@@ -30,7 +38,7 @@ pub fn fuzzy_union(a: f64, b: f64) -> f64 {
 /// min(a, b)
 #[inline]
 pub fn fuzzy_intersection(a: f64, b: f64) -> f64 {
-    a.min(b)
+    TNorm::Minimum.apply(a, b)
 }
 
 /// Fuzzy complement (NOT operation)
@@ -59,7 +67,7 @@ pub fn fuzzy_complement(a: f64) -> f64 {
 /// a * b
 #[inline]
 pub fn algebraic_product(a: f64, b: f64) -> f64 {
-    a * b
+    TNorm::AlgebraicProduct.apply(a, b)
 }
 
 /// Algebraic sum (S-norm alternative to maximum)
@@ -74,7 +82,7 @@ pub fn algebraic_product(a: f64, b: f64) -> f64 {
 /// a + b - a * b
 #[inline]
 pub fn algebraic_sum(a: f64, b: f64) -> f64 {
-    a + b - a * b
+    SNorm::AlgebraicSum.apply(a, b)
 }
 
 /// Bounded sum (S-norm)
@@ -89,7 +97,7 @@ pub fn algebraic_sum(a: f64, b: f64) -> f64 {
 /// min(1, a + b)
 #[inline]
 pub fn bounded_sum(a: f64, b: f64) -> f64 {
-    (a + b).min(1.0)
+    SNorm::BoundedSum.apply(a, b)
 }
 
 /// Bounded difference (T-norm)
@@ -104,31 +112,19 @@ pub fn bounded_sum(a: f64, b: f64) -> f64 {
 /// max(0, a + b - 1)
 #[inline]
 pub fn bounded_difference(a: f64, b: f64) -> f64 {
-    (a + b - 1.0).max(0.0)
+    TNorm::BoundedDifference.apply(a, b)
 }
 
 /// Drastic sum (S-norm)
 #[inline]
 pub fn drastic_sum(a: f64, b: f64) -> f64 {
-    if a == 0.0 {
-        b
-    } else if b == 0.0 {
-        a
-    } else {
-        1.0
-    }
+    SNorm::DrasticSum.apply(a, b)
 }
 
 /// Drastic product (T-norm)
 #[inline]
 pub fn drastic_product(a: f64, b: f64) -> f64 {
-    if a == 1.0 {
-        b
-    } else if b == 1.0 {
-        a
-    } else {
-        0.0
-    }
+    TNorm::DrasticProduct.apply(a, b)
 }
 
 /// Apply fuzzy operation element-wise to vectors
@@ -142,9 +138,10 @@ pub fn drastic_product(a: f64, b: f64) -> f64 {
 /// # Returns
 ///
 /// Vector of results
-pub fn apply_operation<F>(a: &[f64], b: &[f64], op: F) -> Vec<f64>
+#[cfg(feature = "alloc")]
+pub fn apply_operation<T: Real, F>(a: &[T], b: &[T], op: F) -> Vec<T>
 where
-    F: Fn(f64, f64) -> f64,
+    F: Fn(T, T) -> T,
 {
     assert_eq!(a.len(), b.len(), "Vectors must have the same length");
     a.iter()
@@ -165,7 +162,8 @@ where
 /// # Returns
 ///
 /// Indices where membership >= alpha
-pub fn alpha_cut(membership: &[f64], alpha: f64) -> Vec<usize> {
+#[cfg(feature = "alloc")]
+pub fn alpha_cut<T: Real>(membership: &[T], alpha: T) -> Vec<usize> {
     membership
         .iter()
         .enumerate()
@@ -177,7 +175,8 @@ pub fn alpha_cut(membership: &[f64], alpha: f64) -> Vec<usize> {
 /// Strong alpha cut of a fuzzy set
 ///
 /// Returns indices where membership > alpha
-pub fn strong_alpha_cut(membership: &[f64], alpha: f64) -> Vec<usize> {
+#[cfg(feature = "alloc")]
+pub fn strong_alpha_cut<T: Real>(membership: &[T], alpha: T) -> Vec<usize> {
     membership
         .iter()
         .enumerate()
@@ -186,6 +185,66 @@ pub fn strong_alpha_cut(membership: &[f64], alpha: f64) -> Vec<usize> {
         .collect()
 }
 
+/// T-norm used for AND and for rule implication
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TNorm {
+    Minimum,
+    AlgebraicProduct,
+    BoundedDifference,
+    DrasticProduct,
+}
+
+impl TNorm {
+    /// Combine two membership degrees
+    #[inline]
+    pub fn apply<T: Real>(self, a: T, b: T) -> T {
+        match self {
+            TNorm::Minimum => a.min(b),
+            TNorm::AlgebraicProduct => a * b,
+            TNorm::BoundedDifference => (a + b - T::ONE).max(T::ZERO),
+            TNorm::DrasticProduct => {
+                if a == T::ONE {
+                    b
+                } else if b == T::ONE {
+                    a
+                } else {
+                    T::ZERO
+                }
+            }
+        }
+    }
+}
+
+/// S-norm used for OR and for rule aggregation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SNorm {
+    Maximum,
+    AlgebraicSum,
+    BoundedSum,
+    DrasticSum,
+}
+
+impl SNorm {
+    /// Combine two membership degrees
+    #[inline]
+    pub fn apply<T: Real>(self, a: T, b: T) -> T {
+        match self {
+            SNorm::Maximum => a.max(b),
+            SNorm::AlgebraicSum => a + b - a * b,
+            SNorm::BoundedSum => (a + b).min(T::ONE),
+            SNorm::DrasticSum => {
+                if a == T::ZERO {
+                    b
+                } else if b == T::ZERO {
+                    a
+                } else {
+                    T::ONE
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,7 +265,7 @@ mod tests {
 
     #[test]
     fn test_fuzzy_complement() {
-        assert_eq!(fuzzy_complement(0.7), 0.3);
+        assert!((fuzzy_complement(0.7) - 0.3).abs() < 1e-10);
         assert_eq!(fuzzy_complement(0.0), 1.0);
         assert_eq!(fuzzy_complement(1.0), 0.0);
     }
@@ -226,9 +285,10 @@ mod tests {
     #[test]
     fn test_bounded_operations() {
         assert_eq!(bounded_sum(0.7, 0.5), 1.0);
-        assert_eq!(bounded_difference(0.7, 0.5), 0.2);
+        assert!((bounded_difference(0.7, 0.5) - 0.2).abs() < 1e-10);
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_apply_operation() {
         let a = vec![0.3, 0.5, 0.7];
@@ -241,6 +301,7 @@ mod tests {
         assert_eq!(intersection, vec![0.3, 0.5, 0.2]);
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_alpha_cut() {
         let membership = vec![0.2, 0.5, 0.8, 0.3, 0.9];
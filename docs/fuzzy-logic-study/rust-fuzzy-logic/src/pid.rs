@@ -0,0 +1,587 @@
+//! Fuzzy PID Control
+//!
+//! Closed-loop controllers built around a [`FuzzySystem`] whose inputs are
+//! the control error and its rate of change:
+//! - [`FuzzyPid`]: the fuzzy system computes the control action, in PD,
+//!   incremental PI or PD+I form, with scaling gains in the notation of
+//!   Jantzen's *Foundations of Fuzzy Control*
+//! - [`GainScheduledPid`]: a classical PID whose gains the fuzzy system
+//!   adjusts at every step
+//!
+//! Both keep their state between [`Controller::step`] calls, limit their
+//! output to a range, and stop the integral from winding up while the
+//! output is saturated.
+//!
+//! ```
+//! use fuzzy_logic::pid::{pid_rule_base, Controller, FuzzyPid, FuzzyPidGains, PidMode};
+//!
+//! let gains = FuzzyPidGains::from_pid(PidMode::Pi, 2.0, 1.0, 0.0, 10.0);
+//! let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pi, gains).with_output_limits(-5.0, 5.0);
+//!
+//! // First-order plant y' = u - y
+//! let mut y = 0.0;
+//! for _ in 0..2000 {
+//!     let u = pid.step(1.0, y, 0.01);
+//!     y += (u - y) * 0.01;
+//! }
+//! assert!((y - 1.0).abs() < 0.01);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::membership::MembershipFunction;
+use crate::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule, SystemScratch};
+
+/// A feedback controller driven at a fixed or varying time step
+pub trait Controller {
+    /// Control action for the current `setpoint` and `measurement`, `dt`
+    /// time units after the previous step
+    ///
+    /// A `dt` that is not positive and finite (zero, negative or NaN)
+    /// counts as no time elapsed: the integral and the error's rate of
+    /// change are left as they were.
+    fn step(&mut self, setpoint: f64, measurement: f64, dt: f64) -> f64;
+
+    /// Forget the loop state (integral, previous error and output)
+    fn reset(&mut self);
+}
+
+/// Structure of a [`FuzzyPid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PidMode {
+    /// u = GU · f(GE·e, GCE·ė)
+    Pd,
+    /// Incremental: u̇ = GU · f(GE·e, GCE·ė)
+    Pi,
+    /// PD+I: u = GU · (f(GE·e, GCE·ė) + GIE·∫e dt)
+    Pid,
+}
+
+/// How the integral is kept from winding up while the output is saturated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AntiWindup {
+    /// Integrate regardless of saturation
+    None,
+    /// Pause integration while the error would drive the output further
+    /// into saturation; the incremental PI form holds its output at the
+    /// limit instead
+    Conditional,
+    /// Feed the saturation excess back into the integral with this tracking
+    /// gain (error units per output unit)
+    BackCalculation(f64),
+}
+
+/// Scaling gains of a [`FuzzyPid`]
+///
+/// The rule base works on normalized values, typically in `[-1, 1]`; the
+/// gains map the loop's signals onto them and the result back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyPidGains {
+    /// GE: error to normalized error
+    pub error: f64,
+    /// GCE: rate of change of the error to its normalized value
+    pub change: f64,
+    /// GIE: integral of the error, added to the fuzzy output (PD+I only)
+    pub integral: f64,
+    /// GU: fuzzy output to control action (its rate of change for PI)
+    pub output: f64,
+}
+
+impl Default for FuzzyPidGains {
+    fn default() -> Self {
+        FuzzyPidGains {
+            error: 1.0,
+            change: 1.0,
+            integral: 0.0,
+            output: 1.0,
+        }
+    }
+}
+
+impl FuzzyPidGains {
+    /// Gains that make a fuzzy controller with an approximately linear
+    /// rule base, such as [`pid_rule_base`], behave like a classical PID
+    /// with gains `kp`, `ki` and `kd` for errors up to `error_range`
+    ///
+    /// The PD form ignores `ki` and the PI form ignores `kd`.
+    ///
+    /// Panics if `error_range` is not positive, or if the gain that sets
+    /// the output scale is zero: `kp` for PD and PID, `ki` for PI.
+    pub fn from_pid(mode: PidMode, kp: f64, ki: f64, kd: f64, error_range: f64) -> Self {
+        assert!(error_range > 0.0, "Error range must be positive");
+        let error = 1.0 / error_range;
+        match mode {
+            PidMode::Pd | PidMode::Pid => {
+                assert!(kp != 0.0, "kp must be nonzero for PD and PID");
+                let output = kp * error_range;
+                FuzzyPidGains {
+                    error,
+                    change: kd / output,
+                    integral: if mode == PidMode::Pid { ki / output } else { 0.0 },
+                    output,
+                }
+            }
+            PidMode::Pi => {
+                assert!(ki != 0.0, "ki must be nonzero for PI");
+                let output = ki * error_range;
+                FuzzyPidGains {
+                    error,
+                    change: kp / output,
+                    integral: 0.0,
+                    output,
+                }
+            }
+        }
+    }
+}
+
+/// `dt` as elapsed time: itself if positive and finite, otherwise zero
+fn elapsed(dt: f64) -> f64 {
+    if dt > 0.0 && dt.is_finite() {
+        dt
+    } else {
+        0.0
+    }
+}
+
+/// Output limits, anti-windup and the integral they act on
+#[derive(Debug, Clone)]
+struct LoopState {
+    limits: Option<(f64, f64)>,
+    anti_windup: AntiWindup,
+    /// ∫e dt, or the accumulated output for the incremental PI form
+    integral: f64,
+    previous_error: Option<f64>,
+}
+
+impl LoopState {
+    fn new() -> Self {
+        LoopState {
+            limits: None,
+            anti_windup: AntiWindup::Conditional,
+            integral: 0.0,
+            previous_error: None,
+        }
+    }
+
+    /// Error and its rate of change, zero on the first step
+    ///
+    /// `dt` comes from [`elapsed`]; with no time elapsed the rate is zero
+    /// and the previous error is kept for the next step.
+    fn error(&mut self, setpoint: f64, measurement: f64, dt: f64) -> (f64, f64) {
+        let error = setpoint - measurement;
+        let change = match self.previous_error {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        };
+        if dt > 0.0 || self.previous_error.is_none() {
+            self.previous_error = Some(error);
+        }
+        (error, change)
+    }
+
+    fn saturate(&self, u: f64) -> f64 {
+        match self.limits {
+            Some((min, max)) => u.clamp(min, max),
+            None => u,
+        }
+    }
+
+    /// Advance ∫e dt after an output of `raw` was limited to `limited`
+    fn integrate(&mut self, error: f64, dt: f64, raw: f64, limited: f64) {
+        match self.anti_windup {
+            AntiWindup::None => self.integral += error * dt,
+            // Integrating is fine unless saturated with the error pushing
+            // the same way
+            AntiWindup::Conditional => {
+                if raw == limited || error * (raw - limited) < 0.0 {
+                    self.integral += error * dt;
+                }
+            }
+            AntiWindup::BackCalculation(gain) => self.integral += (error + gain * (limited - raw)) * dt,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = None;
+    }
+}
+
+/// Evaluation of a two-input fuzzy system without allocating
+#[derive(Debug, Clone)]
+struct Evaluator {
+    system: FuzzySystem,
+    scratch: SystemScratch,
+    outputs: Vec<f64>,
+}
+
+impl Evaluator {
+    fn new(system: FuzzySystem, outputs: usize) -> Self {
+        assert_eq!(system.inputs().len(), 2, "Expected inputs for the error and its change");
+        assert_eq!(system.outputs().len(), outputs, "Expected {} outputs", outputs);
+        Evaluator {
+            scratch: system.scratch(),
+            outputs: vec![0.0; outputs],
+            system,
+        }
+    }
+
+    /// Outputs for the normalized error and change, clamped to the input
+    /// universes
+    fn evaluate(&mut self, error: f64, change: f64) -> &[f64] {
+        let inputs = self.system.inputs();
+        let x = [
+            error.clamp(inputs[0].min, inputs[0].max),
+            change.clamp(inputs[1].min, inputs[1].max),
+        ];
+        self.system.evaluate_with(&x, &mut self.outputs, &mut self.scratch);
+        &self.outputs
+    }
+}
+
+/// A fuzzy PD, PI or PD+I controller
+#[derive(Debug, Clone)]
+pub struct FuzzyPid {
+    evaluator: Evaluator,
+    mode: PidMode,
+    gains: FuzzyPidGains,
+    state: LoopState,
+}
+
+impl FuzzyPid {
+    /// Controller around `system`, whose two inputs take the normalized
+    /// error and change of error and whose single output is the normalized
+    /// action
+    ///
+    /// Panics if the system has a different number of inputs or outputs.
+    pub fn new(system: FuzzySystem, mode: PidMode, gains: FuzzyPidGains) -> Self {
+        FuzzyPid {
+            evaluator: Evaluator::new(system, 1),
+            mode,
+            gains,
+            state: LoopState::new(),
+        }
+    }
+
+    /// Limit the control action to `[min, max]`
+    pub fn with_output_limits(mut self, min: f64, max: f64) -> Self {
+        assert!(min <= max, "Output min must not exceed max");
+        self.state.limits = Some((min, max));
+        self
+    }
+
+    /// Choose the anti-windup scheme (conditional integration by default)
+    pub fn with_anti_windup(mut self, anti_windup: AntiWindup) -> Self {
+        self.state.anti_windup = anti_windup;
+        self
+    }
+
+    pub fn system(&self) -> &FuzzySystem {
+        &self.evaluator.system
+    }
+
+    pub fn mode(&self) -> PidMode {
+        self.mode
+    }
+
+    pub fn gains(&self) -> FuzzyPidGains {
+        self.gains
+    }
+
+    /// ∫e dt for PD+I, or the current output for the incremental PI form
+    pub fn integral(&self) -> f64 {
+        self.state.integral
+    }
+}
+
+impl Controller for FuzzyPid {
+    fn step(&mut self, setpoint: f64, measurement: f64, dt: f64) -> f64 {
+        let dt = elapsed(dt);
+        let (error, change) = self.state.error(setpoint, measurement, dt);
+        let g = self.gains;
+        let f = self.evaluator.evaluate(g.error * error, g.change * change)[0];
+
+        match self.mode {
+            PidMode::Pd => self.state.saturate(g.output * f),
+            PidMode::Pi => {
+                let raw = self.state.integral + g.output * f * dt;
+                let limited = self.state.saturate(raw);
+                self.state.integral = match self.state.anti_windup {
+                    AntiWindup::None => raw,
+                    AntiWindup::Conditional => limited,
+                    AntiWindup::BackCalculation(gain) => raw + gain * (limited - raw) * dt,
+                };
+                limited
+            }
+            PidMode::Pid => {
+                let raw = g.output * (f + g.integral * self.state.integral);
+                let limited = self.state.saturate(raw);
+                self.state.integrate(error, dt, raw, limited);
+                limited
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+    }
+}
+
+/// A classical PID with fuzzy gain scheduling
+///
+/// The schedule's inputs take the error and its change scaled by
+/// `error_scale` and `change_scale`; its three outputs are Kp, Ki and Kd.
+#[derive(Debug, Clone)]
+pub struct GainScheduledPid {
+    evaluator: Evaluator,
+    error_scale: f64,
+    change_scale: f64,
+    state: LoopState,
+    gains: (f64, f64, f64),
+}
+
+impl GainScheduledPid {
+    /// Panics if the schedule does not have two inputs and three outputs.
+    pub fn new(schedule: FuzzySystem, error_scale: f64, change_scale: f64) -> Self {
+        GainScheduledPid {
+            evaluator: Evaluator::new(schedule, 3),
+            error_scale,
+            change_scale,
+            state: LoopState::new(),
+            gains: (0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Limit the control action to `[min, max]`
+    pub fn with_output_limits(mut self, min: f64, max: f64) -> Self {
+        assert!(min <= max, "Output min must not exceed max");
+        self.state.limits = Some((min, max));
+        self
+    }
+
+    /// Choose the anti-windup scheme (conditional integration by default)
+    pub fn with_anti_windup(mut self, anti_windup: AntiWindup) -> Self {
+        self.state.anti_windup = anti_windup;
+        self
+    }
+
+    pub fn schedule(&self) -> &FuzzySystem {
+        &self.evaluator.system
+    }
+
+    /// Kp, Ki and Kd used by the last step
+    pub fn gains(&self) -> (f64, f64, f64) {
+        self.gains
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.state.integral
+    }
+}
+
+impl Controller for GainScheduledPid {
+    fn step(&mut self, setpoint: f64, measurement: f64, dt: f64) -> f64 {
+        let dt = elapsed(dt);
+        let (error, change) = self.state.error(setpoint, measurement, dt);
+        let gains = self.evaluator.evaluate(self.error_scale * error, self.change_scale * change);
+        let (kp, ki, kd) = (gains[0], gains[1], gains[2]);
+        self.gains = (kp, ki, kd);
+
+        let raw = kp * error + ki * self.state.integral + kd * change;
+        let limited = self.state.saturate(raw);
+        self.state.integrate(error, dt, raw, limited);
+        limited
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+        self.gains = (0.0, 0.0, 0.0);
+    }
+}
+
+const PID_TERMS: [&str; 5] = ["negative_big", "negative_small", "zero", "positive_small", "positive_big"];
+
+/// Five evenly spaced triangles peaking at -1, -0.5, 0, 0.5 and 1
+fn signed_variable(name: &str, min: f64, max: f64) -> LinguisticVariable {
+    let mut variable = LinguisticVariable::new(name, min, max);
+    for (k, term) in PID_TERMS.iter().enumerate() {
+        let peak = k as f64 * 0.5 - 1.0;
+        variable.add_term(*term, MembershipFunction::Triangular { a: peak - 0.5, b: peak, c: peak + 0.5 });
+    }
+    variable
+}
+
+/// A 5 × 5 rule base over normalized `error` and `change` in `[-1, 1]`
+/// whose `output` approximates their sum, limited to `[-1, 1]`
+///
+/// With it a [`FuzzyPid`] behaves close to a linear PID, which makes a good
+/// starting point for tuning.
+pub fn pid_rule_base() -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    system.add_input(signed_variable("error", -1.0, 1.0));
+    system.add_input(signed_variable("change", -1.0, 1.0));
+    // The outer output terms extend past ±1 so that their centroids are ±1
+    system.add_output(signed_variable("output", -1.5, 1.5));
+    for e in 0..5_usize {
+        for c in 0..5 {
+            let out = (e + c).saturating_sub(2).min(4);
+            system.add_rule(Rule::new(
+                vec![Antecedent::new(0, e), Antecedent::new(1, c)],
+                vec![Consequent::new(0, out)],
+            ));
+        }
+    }
+    system
+}
+
+/// A gain schedule for [`GainScheduledPid`] over normalized `error` and
+/// `change` in `[-1, 1]`, with Kp, Ki and Kd ranging over the given
+/// `(low, high)` pairs
+///
+/// Far from the setpoint it uses a high Kp with low Ki and Kd for a fast
+/// approach; near it a lower Kp with higher Ki and Kd to damp overshoot and
+/// remove the offset. A fast-changing error also raises Kd.
+pub fn gain_schedule(kp: (f64, f64), ki: (f64, f64), kd: (f64, f64)) -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    for name in ["error", "change"] {
+        system.add_input(
+            LinguisticVariable::new(name, -1.0, 1.0)
+                .with_term("negative", MembershipFunction::Trapezoidal { a: -2.0, b: -1.0, c: -0.5, d: 0.0 })
+                .with_term("zero", MembershipFunction::Triangular { a: -0.5, b: 0.0, c: 0.5 })
+                .with_term("positive", MembershipFunction::Trapezoidal { a: 0.0, b: 0.5, c: 1.0, d: 2.0 }),
+        );
+    }
+    for (name, (low, high)) in [("kp", kp), ("ki", ki), ("kd", kd)] {
+        assert!(low <= high, "Gain ranges must be (low, high)");
+        // Equal bounds still need a universe to defuzzify over, and it
+        // extends past both so that each term alone defuzzifies to its peak
+        let span = (high - low).max(1e-9);
+        system.add_output(
+            LinguisticVariable::new(name, low - span, low + 2.0 * span)
+                .with_term("low", MembershipFunction::Triangular { a: low - span, b: low, c: low + span })
+                .with_term("high", MembershipFunction::Triangular { a: low, b: low + span, c: low + 2.0 * span }),
+        );
+    }
+    // Output variables and their terms
+    const KP: usize = 0;
+    const KI: usize = 1;
+    const KD: usize = 2;
+    const LOW: usize = 0;
+    const HIGH: usize = 1;
+    for far in [0, 2] {
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, far)],
+            vec![Consequent::new(KP, HIGH), Consequent::new(KI, LOW), Consequent::new(KD, LOW)],
+        ));
+    }
+    system.add_rule(Rule::new(
+        vec![Antecedent::new(0, 1)],
+        vec![Consequent::new(KP, LOW), Consequent::new(KI, HIGH), Consequent::new(KD, HIGH)],
+    ));
+    for fast in [0, 2] {
+        system.add_rule(Rule::new(vec![Antecedent::new(1, fast)], vec![Consequent::new(KD, HIGH)]));
+    }
+    system
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_base_is_close_to_linear() {
+        let system = pid_rule_base();
+        for (e, c) in [(0.0, 0.0), (0.5, 0.0), (0.25, 0.25), (-0.5, -0.25), (1.0, 1.0), (-1.0, 0.3)] {
+            let expected = f64::clamp(e + c, -1.0, 1.0);
+            let actual = system.evaluate(&[e, c])[0];
+            assert!((actual - expected).abs() < 0.1, "f({}, {}) = {}", e, c, actual);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ki must be nonzero for PI")]
+    fn test_from_pid_rejects_zero_scale_gain() {
+        FuzzyPidGains::from_pid(PidMode::Pi, 4.0, 0.0, 0.5, 10.0);
+    }
+
+    #[test]
+    fn test_from_pid_matches_linear_gains() {
+        let g = FuzzyPidGains::from_pid(PidMode::Pid, 4.0, 2.0, 0.5, 10.0);
+        assert!((g.output * g.error - 4.0).abs() < 1e-12);
+        assert!((g.output * g.change - 0.5).abs() < 1e-12);
+        assert!((g.output * g.integral - 2.0).abs() < 1e-12);
+
+        let g = FuzzyPidGains::from_pid(PidMode::Pi, 4.0, 2.0, 0.5, 10.0);
+        assert!((g.output * g.error - 2.0).abs() < 1e-12);
+        assert!((g.output * g.change - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_state_persists_until_reset() {
+        let gains = FuzzyPidGains {
+            integral: 1.0,
+            ..FuzzyPidGains::default()
+        };
+        let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pid, gains);
+        let first = pid.step(0.5, 0.0, 0.1);
+        let second = pid.step(0.5, 0.0, 0.1);
+        assert!(second > first);
+        assert!((pid.integral() - 0.1).abs() < 1e-12);
+
+        pid.reset();
+        assert_eq!(pid.integral(), 0.0);
+        assert_eq!(pid.step(0.5, 0.0, 0.1), first);
+    }
+
+    #[test]
+    fn test_saturation_and_conditional_integration() {
+        let gains = FuzzyPidGains {
+            integral: 1.0,
+            output: 10.0,
+            ..FuzzyPidGains::default()
+        };
+        let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pid, gains).with_output_limits(-1.0, 1.0);
+        for _ in 0..10 {
+            assert_eq!(pid.step(1.0, 0.0, 0.1), 1.0);
+        }
+        // Saturated from the first step, so nothing was integrated
+        assert_eq!(pid.integral(), 0.0);
+    }
+
+    #[test]
+    fn test_invalid_time_step_is_no_time_elapsed() {
+        let gains = FuzzyPidGains {
+            integral: 1.0,
+            ..FuzzyPidGains::default()
+        };
+        let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pid, gains);
+        pid.step(0.5, 0.0, 0.1);
+        let integral = pid.integral();
+        for dt in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            let u = pid.step(0.5, 0.2, dt);
+            assert!(u.is_finite(), "dt = {}", dt);
+            assert_eq!(pid.integral(), integral, "dt = {}", dt);
+        }
+
+        // The next real step measures the change since the last one
+        let mut reference = FuzzyPid::new(pid_rule_base(), PidMode::Pid, gains);
+        reference.step(0.5, 0.0, 0.1);
+        assert_eq!(pid.step(0.5, 0.2, 0.1), reference.step(0.5, 0.2, 0.1));
+
+        let schedule = gain_schedule((2.0, 6.0), (0.5, 2.0), (0.2, 1.0));
+        let mut scheduled = GainScheduledPid::new(schedule, 1.0, 1.0);
+        scheduled.step(0.5, 0.0, 0.1);
+        let integral = scheduled.integral();
+        assert!(scheduled.step(0.5, 0.2, f64::NAN).is_finite());
+        assert_eq!(scheduled.integral(), integral);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected inputs for the error and its change")]
+    fn test_rejects_wrong_system() {
+        let mut system = pid_rule_base();
+        system.add_input(LinguisticVariable::new("extra", 0.0, 1.0));
+        FuzzyPid::new(system, PidMode::Pd, FuzzyPidGains::default());
+    }
+}
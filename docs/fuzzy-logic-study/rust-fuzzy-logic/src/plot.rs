@@ -0,0 +1,694 @@
+//! Plotting
+//!
+//! This module renders membership functions, fuzzy operations, alpha cuts
+//! and defuzzified outputs without any external dependencies. A [`Plot`]
+//! can be written as a standalone SVG file or drawn in the terminal, either
+//! with ASCII characters or with Unicode braille dots (2x4 dots per
+//! character cell, for smoother curves).
+//!
+//! The `plot_*` functions mirror the helpers of the Python study package
+//! (`plot_membership_function`, `plot_multiple_membership_functions` and
+//! `plot_fuzzy_operations`).
+
+use std::io;
+use std::path::Path;
+
+use crate::defuzzification::{defuzzify, DefuzzificationMethod};
+use crate::membership::{FuzzySet, MembershipFunction};
+use crate::operations::{apply_operation, fuzzy_complement, fuzzy_intersection, fuzzy_union};
+use crate::svg::{SvgDocument, PALETTE};
+use crate::system::{linspace, LinguisticVariable};
+
+/// Symbols used for successive series in ASCII plots
+const ASCII_SYMBOLS: [char; 8] = ['*', '+', 'o', 'x', '#', '@', '%', '&'];
+
+/// One curve of a plot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub label: String,
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    /// Shade the area under the curve (SVG only)
+    pub filled: bool,
+}
+
+/// A vertical marker, e.g. a defuzzified output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub x: f64,
+    pub label: String,
+}
+
+/// A chart of one or more curves with optional alpha-cut levels and markers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plot {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub series: Vec<Series>,
+    pub alpha_levels: Vec<f64>,
+    pub markers: Vec<Marker>,
+    /// Fixed y range; by default the range covers the data and `[0, 1]`
+    pub y_range: Option<(f64, f64)>,
+}
+
+impl Plot {
+    /// Create an empty plot with membership-degree axis labels
+    pub fn new(title: impl Into<String>) -> Self {
+        Plot {
+            title: title.into(),
+            x_label: "Universe".to_string(),
+            y_label: "Membership Degree".to_string(),
+            series: Vec::new(),
+            alpha_levels: Vec::new(),
+            markers: Vec::new(),
+            y_range: None,
+        }
+    }
+
+    pub fn with_labels(mut self, x_label: impl Into<String>, y_label: impl Into<String>) -> Self {
+        self.x_label = x_label.into();
+        self.y_label = y_label.into();
+        self
+    }
+
+    pub fn with_y_range(mut self, min: f64, max: f64) -> Self {
+        self.y_range = Some((min, max));
+        self
+    }
+
+    /// Add a curve from sampled values, in any order of `xs`
+    ///
+    /// The samples are stored sorted by x, so the curve is drawn left to
+    /// right.
+    pub fn with_series(mut self, label: impl Into<String>, xs: &[f64], ys: &[f64]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "xs and ys must have same length");
+        let mut points: Vec<(f64, f64)> = xs.iter().copied().zip(ys.iter().copied()).collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.series.push(Series {
+            label: label.into(),
+            xs: points.iter().map(|p| p.0).collect(),
+            ys: points.iter().map(|p| p.1).collect(),
+            filled: true,
+        });
+        self
+    }
+
+    /// Add a membership function sampled at `steps` points over `[min, max]`
+    pub fn with_membership_function(
+        self,
+        label: impl Into<String>,
+        mf: &MembershipFunction,
+        min: f64,
+        max: f64,
+        steps: usize,
+    ) -> Self {
+        let xs = linspace(min, max, steps);
+        let ys: Vec<f64> = xs.iter().map(|&x| mf.evaluate(x)).collect();
+        self.with_series(label, &xs, &ys)
+    }
+
+    /// Add a fuzzy set sampled at `steps` points over `[min, max]`
+    pub fn with_fuzzy_set(self, set: &FuzzySet, min: f64, max: f64, steps: usize) -> Self {
+        self.with_membership_function(set.name.clone(), &set.membership_function, min, max, steps)
+    }
+
+    /// Add the element-wise result of [`apply_operation`] on two sampled sets
+    pub fn with_operation<F>(
+        self,
+        label: impl Into<String>,
+        xs: &[f64],
+        a: &[f64],
+        b: &[f64],
+        op: F,
+    ) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let result = apply_operation(a, b, op);
+        self.with_series(label, xs, &result)
+    }
+
+    /// Draw a horizontal alpha-cut level; the cut of each curve is highlighted
+    pub fn with_alpha_cut(mut self, alpha: f64) -> Self {
+        self.alpha_levels.push(alpha);
+        self
+    }
+
+    /// Draw a vertical marker at `x`
+    pub fn with_marker(mut self, x: f64, label: impl Into<String>) -> Self {
+        self.markers.push(Marker {
+            x,
+            label: label.into(),
+        });
+        self
+    }
+
+    fn x_bounds(&self) -> (f64, f64) {
+        let (lo, hi) = self
+            .series
+            .iter()
+            .flat_map(|s| s.xs.iter())
+            .chain(self.markers.iter().map(|m| &m.x))
+            .filter(|x| x.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| {
+                (lo.min(x), hi.max(x))
+            });
+        if lo.is_finite() && hi > lo {
+            (lo, hi)
+        } else if lo.is_finite() {
+            (lo - 0.5, lo + 0.5)
+        } else {
+            (0.0, 1.0)
+        }
+    }
+
+    fn y_bounds(&self) -> (f64, f64) {
+        if let Some(range) = self.y_range {
+            return range;
+        }
+        self.series
+            .iter()
+            .flat_map(|s| s.ys.iter())
+            .filter(|y| y.is_finite())
+            .fold((0.0, 1.0), |(lo, hi): (f64, f64), &y| (lo.min(y), hi.max(y)))
+    }
+
+    /// Sample ranges `[x_start, x_end]` where a series is at or above `alpha`
+    ///
+    /// Samples are visited in order of x, so the series need not be sorted
+    /// and every range has `x_start <= x_end`; non-finite x values are
+    /// skipped.
+    fn cut_intervals(series: &Series, alpha: f64) -> Vec<(f64, f64)> {
+        let mut order: Vec<usize> = (0..series.xs.len().min(series.ys.len()))
+            .filter(|&i| series.xs[i].is_finite())
+            .collect();
+        order.sort_by(|&a, &b| series.xs[a].total_cmp(&series.xs[b]));
+
+        let mut intervals: Vec<(f64, f64)> = Vec::new();
+        let mut inside = false;
+        for i in order {
+            if series.ys[i] < alpha {
+                inside = false;
+                continue;
+            }
+            match intervals.last_mut() {
+                Some(last) if inside => last.1 = series.xs[i],
+                _ => intervals.push((series.xs[i], series.xs[i])),
+            }
+            inside = true;
+        }
+        intervals
+    }
+
+    /// Render as a standalone SVG document
+    pub fn to_svg(&self) -> String {
+        let (y_lo, y_hi) = self.y_bounds();
+        let pad = (y_hi - y_lo) * 0.1;
+        let mut doc = SvgDocument::new(720.0, 420.0);
+        let frame = doc.frame(self.x_bounds(), (y_lo - pad, y_hi + pad), 160.0);
+        doc.title(&self.title);
+
+        for (i, s) in self.series.iter().enumerate() {
+            if s.filled {
+                doc.filled_area(&frame, &s.xs, &s.ys, PALETTE[i % PALETTE.len()], 0.2);
+            }
+        }
+        for (i, s) in self.series.iter().enumerate() {
+            doc.polyline(&frame, &s.xs, &s.ys, PALETTE[i % PALETTE.len()], 2.0);
+        }
+
+        for &alpha in &self.alpha_levels {
+            let y = frame.y(alpha);
+            doc.dashed_line(frame.left, y, frame.right(), y, "#555555");
+            doc.text(frame.right() - 4.0, y - 4.0, &format!("α = {}", alpha), "end", 11.0);
+            for (i, s) in self.series.iter().enumerate() {
+                for (x0, x1) in Self::cut_intervals(s, alpha) {
+                    doc.line(frame.x(x0), y, frame.x(x1), y, PALETTE[i % PALETTE.len()], 5.0);
+                }
+            }
+        }
+
+        for m in &self.markers {
+            let x = frame.x(m.x);
+            doc.dashed_line(x, frame.top, x, frame.bottom(), "black");
+            doc.circle(x, frame.bottom(), 4.0, "black");
+            doc.text(x + 4.0, frame.top + 14.0, &m.label, "start", 12.0);
+        }
+
+        doc.axes(&frame, &self.x_label, &self.y_label);
+        let entries: Vec<(String, &str)> = self
+            .series
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.label.clone(), PALETTE[i % PALETTE.len()]))
+            .collect();
+        doc.legend(&frame, &entries);
+        doc.finish()
+    }
+
+    /// Write the SVG rendering to a file
+    pub fn save_svg(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+
+    /// Render for the terminal using ASCII characters
+    ///
+    /// `width` and `height` are the size of the plotting area in characters.
+    /// Each curve uses its own symbol; alpha levels are drawn with `-` (and
+    /// `=` along each cut) and markers with `|`.
+    pub fn to_ascii(&self, width: usize, height: usize) -> String {
+        let (width, height) = (width.max(2), height.max(2));
+        let mut grid = vec![vec![' '; width]; height];
+        let map = GridMap::new(self.x_bounds(), self.y_bounds(), width, height);
+
+        for &alpha in &self.alpha_levels {
+            let row = map.row(alpha);
+            grid[row].iter_mut().for_each(|c| *c = '-');
+            for s in &self.series {
+                for (x0, x1) in Self::cut_intervals(s, alpha) {
+                    for cell in &mut grid[row][map.col(x0)..=map.col(x1)] {
+                        *cell = '=';
+                    }
+                }
+            }
+        }
+        for m in &self.markers {
+            let col = map.col(m.x);
+            grid.iter_mut().for_each(|row| row[col] = '|');
+        }
+        for (i, s) in self.series.iter().enumerate() {
+            let symbol = ASCII_SYMBOLS[i % ASCII_SYMBOLS.len()];
+            map.trace(s, |col, row| grid[row][col] = symbol);
+        }
+
+        let rows: Vec<String> = grid.into_iter().map(|r| r.into_iter().collect()).collect();
+        let legend: Vec<String> = self
+            .series
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("{} {}", ASCII_SYMBOLS[i % ASCII_SYMBOLS.len()], s.label))
+            .collect();
+        self.frame_text(&rows, &map, ('|', '+', '-'), legend)
+    }
+
+    /// Render for the terminal using Unicode braille dots
+    ///
+    /// `width` and `height` are the size of the plotting area in characters;
+    /// each character holds 2x4 dots.
+    pub fn to_braille(&self, width: usize, height: usize) -> String {
+        let (width, height) = (width.max(2), height.max(2));
+        let (dots_w, dots_h) = (width * 2, height * 4);
+        let mut cells = vec![vec![0u8; width]; height];
+        let map = GridMap::new(self.x_bounds(), self.y_bounds(), dots_w, dots_h);
+        let mut set = |col: usize, row: usize| {
+            cells[row / 4][col / 2] |= braille_bit(col % 2, row % 4);
+        };
+
+        for &alpha in &self.alpha_levels {
+            let row = map.row(alpha);
+            for col in (0..dots_w).step_by(2) {
+                set(col, row);
+            }
+            for s in &self.series {
+                for (x0, x1) in Self::cut_intervals(s, alpha) {
+                    for col in map.col(x0)..=map.col(x1) {
+                        set(col, row);
+                    }
+                }
+            }
+        }
+        for m in &self.markers {
+            let col = map.col(m.x);
+            for row in (0..dots_h).step_by(2) {
+                set(col, row);
+            }
+        }
+        for s in &self.series {
+            map.trace(s, &mut set);
+        }
+
+        let rows: Vec<String> = cells
+            .into_iter()
+            .map(|r| {
+                r.into_iter()
+                    .map(|bits| char::from_u32(0x2800 + bits as u32).unwrap_or(' '))
+                    .collect()
+            })
+            .collect();
+        let legend: Vec<String> = self
+            .series
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("{}. {}", i + 1, s.label))
+            .collect();
+        self.frame_text(&rows, &map, ('│', '└', '─'), legend)
+    }
+
+    /// Surround terminal rows with a title, y labels, an x axis and a legend
+    fn frame_text(
+        &self,
+        rows: &[String],
+        map: &GridMap,
+        (vertical, corner, horizontal): (char, char, char),
+        legend: Vec<String>,
+    ) -> String {
+        let label = |v: f64| format!("{:.2}", v);
+        let y_labels = [label(map.y_range.1), label((map.y_range.0 + map.y_range.1) / 2.0), label(map.y_range.0)];
+        let label_width = y_labels.iter().map(|l| l.len()).max().unwrap_or(0);
+        let last = rows.len() - 1;
+
+        let mut out = format!("{}\n", self.title);
+        for (r, row) in rows.iter().enumerate() {
+            let y_label = if r == 0 {
+                &y_labels[0]
+            } else if r == last / 2 {
+                &y_labels[1]
+            } else if r == last {
+                &y_labels[2]
+            } else {
+                ""
+            };
+            out.push_str(&format!("{:>w$} {}{}\n", y_label, vertical, row, w = label_width));
+        }
+
+        let width = rows[0].chars().count();
+        out.push_str(&format!(
+            "{:>w$} {}{}\n",
+            "",
+            corner,
+            horizontal.to_string().repeat(width),
+            w = label_width
+        ));
+        let (x_lo, x_hi) = (label(map.x_range.0), label(map.x_range.1));
+        let gap = (width + 1).saturating_sub(x_lo.len() + x_hi.len()).max(1);
+        out.push_str(&format!(
+            "{:>w$} {}{}{}   {}\n",
+            "",
+            x_lo,
+            " ".repeat(gap),
+            x_hi,
+            self.x_label,
+            w = label_width
+        ));
+        for m in &self.markers {
+            out.push_str(&format!("  marker at {:.2}: {}\n", m.x, m.label));
+        }
+        for entry in legend {
+            out.push_str(&format!("  {}\n", entry));
+        }
+        out
+    }
+}
+
+/// Maps data coordinates onto a grid of `width x height` cells or dots
+struct GridMap {
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    width: usize,
+    height: usize,
+}
+
+impl GridMap {
+    fn new(x_range: (f64, f64), y_range: (f64, f64), width: usize, height: usize) -> Self {
+        GridMap {
+            x_range,
+            y_range,
+            width,
+            height,
+        }
+    }
+
+    fn col(&self, x: f64) -> usize {
+        let (lo, hi) = self.x_range;
+        let t = if hi > lo { (x - lo) / (hi - lo) } else { 0.5 };
+        (t.clamp(0.0, 1.0) * (self.width - 1) as f64).round() as usize
+    }
+
+    fn row(&self, y: f64) -> usize {
+        let (lo, hi) = self.y_range;
+        let t = if hi > lo { (hi - y) / (hi - lo) } else { 0.5 };
+        (t.clamp(0.0, 1.0) * (self.height - 1) as f64).round() as usize
+    }
+
+    /// Visit the cells of a series, joining consecutive samples with lines
+    fn trace<F: FnMut(usize, usize)>(&self, series: &Series, mut plot: F) {
+        let points: Vec<(usize, usize)> = series
+            .xs
+            .iter()
+            .zip(&series.ys)
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|(&x, &y)| (self.col(x), self.row(y)))
+            .collect();
+        if let Some(&(c, r)) = points.first() {
+            plot(c, r);
+        }
+        for pair in points.windows(2) {
+            draw_line(pair[0], pair[1], &mut plot);
+        }
+    }
+}
+
+/// Bresenham line between two grid cells, inclusive
+fn draw_line<F: FnMut(usize, usize)>(from: (usize, usize), to: (usize, usize), plot: &mut F) {
+    let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+    let (x1, y1) = (to.0 as i64, to.1 as i64);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    loop {
+        plot(x as usize, y as usize);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Bit of the braille dot at column `x` (0-1) and row `y` (0-3) of a cell
+fn braille_bit(x: usize, y: usize) -> u8 {
+    const BITS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    BITS[x][y]
+}
+
+/// Plot a single sampled membership function
+pub fn plot_membership_function(xs: &[f64], membership: &[f64], title: &str) -> Plot {
+    Plot::new(title).with_series(title, xs, membership)
+}
+
+/// Plot several sampled membership functions on one chart
+///
+/// # Arguments
+///
+/// * `xs` - Sample points shared by all functions
+/// * `memberships` - `(membership, label)` pairs
+/// * `title` - Plot title
+pub fn plot_multiple_membership_functions(
+    xs: &[f64],
+    memberships: &[(&[f64], &str)],
+    title: &str,
+) -> Plot {
+    memberships
+        .iter()
+        .fold(Plot::new(title), |plot, (m, label)| plot.with_series(*label, xs, m))
+}
+
+/// Plot every term of a linguistic variable over its universe
+pub fn plot_variable(variable: &LinguisticVariable, steps: usize) -> Plot {
+    variable.terms.iter().fold(
+        Plot::new(variable.name.clone()).with_labels(variable.name.clone(), "Membership Degree"),
+        |plot, term| plot.with_fuzzy_set(term, variable.min, variable.max, steps),
+    )
+}
+
+/// Plot union, intersection and complement of two sampled sets
+///
+/// Returns four panels like the Python helper: the original sets, the
+/// union (maximum), the intersection (minimum) and the complement of A.
+pub fn plot_fuzzy_operations(
+    xs: &[f64],
+    set_a: &[f64],
+    set_b: &[f64],
+    label_a: &str,
+    label_b: &str,
+) -> Vec<Plot> {
+    let complement: Vec<f64> = set_a.iter().map(|&a| fuzzy_complement(a)).collect();
+    vec![
+        Plot::new("Original Sets")
+            .with_series(label_a, xs, set_a)
+            .with_series(label_b, xs, set_b),
+        Plot::new("Union (OR) - Maximum").with_operation("A ∪ B (Union)", xs, set_a, set_b, fuzzy_union),
+        Plot::new("Intersection (AND) - Minimum").with_operation(
+            "A ∩ B (Intersection)",
+            xs,
+            set_a,
+            set_b,
+            fuzzy_intersection,
+        ),
+        Plot::new("Complement (NOT) - 1 minus membership")
+            .with_series(label_a, xs, set_a)
+            .with_series(format!("NOT {}", label_a), xs, &complement),
+    ]
+}
+
+/// Plot an output fuzzy set with its defuzzified value marked
+pub fn plot_defuzzification(
+    xs: &[f64],
+    membership: &[f64],
+    method: DefuzzificationMethod,
+) -> Plot {
+    let value = defuzzify(xs, membership, method);
+    Plot::new(format!("{:?} defuzzification", method))
+        .with_series("Output set", xs, membership)
+        .with_marker(value, format!("{:?} = {:.2}", method, value))
+}
+
+/// Stack several plots vertically into one SVG document
+pub fn stack_svg(plots: &[Plot]) -> String {
+    const PANEL_HEIGHT: f64 = 420.0;
+    let mut body = String::new();
+    for (i, plot) in plots.iter().enumerate() {
+        let panel = plot.to_svg().replacen("<svg ", &format!("<svg y=\"{}\" ", i as f64 * PANEL_HEIGHT), 1);
+        body.push_str(&panel);
+    }
+    let height = plots.len() as f64 * PANEL_HEIGHT;
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"720\" height=\"{h}\" viewBox=\"0 0 720 {h}\">\n{}</svg>\n",
+        body,
+        h = height
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(a: f64, b: f64, c: f64) -> MembershipFunction {
+        MembershipFunction::Triangular { a, b, c }
+    }
+
+    #[test]
+    fn test_membership_plot_svg() {
+        let plot = Plot::new("Temperature")
+            .with_membership_function("Cold", &triangle(0.0, 0.0, 20.0), 0.0, 40.0, 81)
+            .with_membership_function("Hot", &triangle(20.0, 40.0, 40.0), 0.0, 40.0, 81)
+            .with_alpha_cut(0.5);
+        let svg = plot.to_svg();
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        assert!(svg.contains("α = 0.5"));
+        assert!(svg.contains(">Cold</text>") && svg.contains(">Hot</text>"));
+    }
+
+    #[test]
+    fn test_cut_intervals() {
+        let series = Series {
+            label: "s".to_string(),
+            xs: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            ys: vec![0.2, 0.6, 0.9, 0.1, 0.7, 0.8],
+            filled: false,
+        };
+        assert_eq!(Plot::cut_intervals(&series, 0.5), vec![(1.0, 2.0), (4.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_unsorted_series() {
+        let xs = [4.0, 0.0, 2.0, 5.0, 1.0, 3.0];
+        let ys = [0.9, 0.0, 0.8, 1.0, 0.2, 0.1];
+        // Sorted by x the samples are 0, 0.2, 0.8, 0.1, 0.9, 1.0
+        let series = Series {
+            label: "s".to_string(),
+            xs: xs.to_vec(),
+            ys: ys.to_vec(),
+            filled: false,
+        };
+        assert_eq!(Plot::cut_intervals(&series, 0.5), vec![(2.0, 2.0), (4.0, 5.0)]);
+
+        let plot = Plot::new("Shuffled").with_series("s", &xs, &ys).with_alpha_cut(0.5);
+        assert_eq!(plot.series[0].xs, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(plot.series[0].ys, vec![0.0, 0.2, 0.8, 0.1, 0.9, 1.0]);
+        let text = plot.to_ascii(11, 5);
+        assert_eq!(text.lines().nth(3), Some("0.50 |---*=*-*==="));
+    }
+
+    #[test]
+    fn test_ascii_rendering() {
+        let plot = Plot::new("Ramp")
+            .with_series("ramp", &[0.0, 1.0], &[0.0, 1.0])
+            .with_marker(0.5, "middle");
+        let text = plot.to_ascii(11, 5);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "Ramp");
+        // Marker in the middle column, curve from bottom-left to top-right
+        assert_eq!(lines[1], "1.00 |     |   **");
+        assert_eq!(lines[5], "0.00 |**   |     ");
+        assert_eq!(lines[6], "     +-----------");
+        assert!(text.contains("marker at 0.50: middle"));
+        assert!(text.contains("* ramp"));
+    }
+
+    #[test]
+    fn test_braille_rendering() {
+        let plot = Plot::new("Flat").with_series("one", &[0.0, 1.0], &[1.0, 1.0]);
+        let text = plot.to_braille(4, 2);
+        let lines: Vec<&str> = text.lines().collect();
+
+        // A line at the top of the range lights the top dot row of each cell
+        assert_eq!(lines[1], "1.00 │⠉⠉⠉⠉");
+        assert_eq!(lines[2], "0.00 │⠀⠀⠀⠀");
+        assert_eq!(lines[3], "     └────");
+    }
+
+    #[test]
+    fn test_fuzzy_operation_panels() {
+        let xs = linspace(0.0, 10.0, 11);
+        let a: Vec<f64> = xs.iter().map(|&x| triangle(0.0, 3.0, 6.0).evaluate(x)).collect();
+        let b: Vec<f64> = xs.iter().map(|&x| triangle(4.0, 7.0, 10.0).evaluate(x)).collect();
+
+        let panels = plot_fuzzy_operations(&xs, &a, &b, "A", "B");
+        assert_eq!(panels.len(), 4);
+        assert_eq!(panels[1].series[0].ys, apply_operation(&a, &b, fuzzy_union));
+        assert_eq!(panels[2].series[0].ys, apply_operation(&a, &b, fuzzy_intersection));
+        assert_eq!(panels[3].series[1].label, "NOT A");
+
+        let svg = stack_svg(&panels);
+        assert_eq!(svg.matches("<svg").count(), 5);
+        assert!(svg.contains("<svg y=\"1260\""));
+    }
+
+    #[test]
+    fn test_defuzzification_marker() {
+        let xs = linspace(0.0, 4.0, 5);
+        let m = vec![0.0, 0.5, 1.0, 0.5, 0.0];
+        let plot = plot_defuzzification(&xs, &m, DefuzzificationMethod::Centroid);
+
+        assert_eq!(plot.markers.len(), 1);
+        assert!((plot.markers[0].x - 2.0).abs() < 1e-10);
+        assert_eq!(plot.markers[0].label, "Centroid = 2.00");
+    }
+
+    #[test]
+    fn test_plot_variable() {
+        let variable = LinguisticVariable::new("speed", 0.0, 100.0)
+            .with_term("slow", triangle(0.0, 0.0, 50.0))
+            .with_term("fast", triangle(50.0, 100.0, 100.0));
+        let plot = plot_variable(&variable, 11);
+
+        assert_eq!(plot.series.len(), 2);
+        assert_eq!(plot.series[1].label, "fast");
+        assert_eq!(plot.x_label, "speed");
+    }
+}
@@ -0,0 +1,634 @@
+//! Python Bindings
+//!
+//! A `fuzzy_logic` extension module (`python` feature) whose functions use
+//! the names and signatures of the study package's `fuzzy_utils.py`, so
+//! notebooks can switch engines with
+//! `from fuzzy_logic import triangular_mf, defuzzify_centroid, ...`.
+//! Build it with `maturin develop --release` (see `pyproject.toml`).
+//!
+//! Membership functions and operators accept a float, a list or a NumPy
+//! array and return the same kind of value; arrays are evaluated without
+//! per-element Python calls. Rule-based systems are loaded from
+//! definitions and evaluate single rows or 2-D batches. A `FuzzySet`
+//! accepts any Python callable, as in `fuzzy_utils.py`.
+//!
+//! Where the Rust engine differs from `fuzzy_utils.py`, the bindings keep
+//! the Rust behavior:
+//!
+//! - A degenerate shoulder (`a == b` or `b == c` in a triangle) has full
+//!   membership at its edge; the Python version returns 0 there
+//! - Centroid, mean of maximum and bisector of an all-zero set return the
+//!   middle of the universe instead of 0 (or NaN)
+
+use numpy::{PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyFloat, PyInt, PyList};
+
+use crate::defuzzification::{
+    bisector, centroid, largest_of_maximum, mean_of_maximum, smallest_of_maximum,
+};
+use crate::definition::{format_rule, format_system, load_system, parse_system, set_option};
+use crate::membership::MembershipFunction;
+use crate::operations::{
+    algebraic_product, algebraic_sum, fuzzy_complement, fuzzy_intersection, fuzzy_union,
+};
+use crate::system::FuzzySystem;
+
+/// A float, list or NumPy array argument
+enum Values<'py> {
+    Scalar(f64),
+    List(Vec<f64>),
+    Array(PyReadonlyArray1<'py, f64>),
+}
+
+impl<'py> Values<'py> {
+    fn extract(value: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if value.is_instance_of::<PyFloat>() || value.is_instance_of::<PyInt>() {
+            return Ok(Values::Scalar(value.extract()?));
+        }
+        // Only touch the NumPy API for array-like objects, so the module
+        // also works where NumPy is not installed
+        if value.hasattr("__array_interface__")? {
+            if let Ok(array) = value.extract::<PyReadonlyArray1<'py, f64>>() {
+                return Ok(Values::Array(array));
+            }
+        }
+        value
+            .extract::<Vec<f64>>()
+            .map(Values::List)
+            .map_err(|_| PyValueError::new_err("expected a number, a list of numbers or a 1-D float array"))
+    }
+
+    fn to_vec(&self) -> PyResult<Vec<f64>> {
+        match self {
+            Values::Scalar(x) => Ok(vec![*x]),
+            Values::List(xs) => Ok(xs.clone()),
+            Values::Array(array) => Ok(array.as_array().to_vec()),
+        }
+    }
+
+    /// Apply `f` elementwise, returning the same kind of value
+    fn map(&self, py: Python<'py>, f: impl Fn(f64) -> f64) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            Values::Scalar(x) => Ok(PyFloat::new(py, f(*x)).into_any()),
+            Values::List(xs) => Ok(PyList::new(py, xs.iter().map(|&x| f(x)))?.into_any()),
+            Values::Array(array) => {
+                let mapped: Vec<f64> = array.as_array().iter().map(|&x| f(x)).collect();
+                Ok(PyArray1::from_vec(py, mapped).into_any())
+            }
+        }
+    }
+}
+
+/// Values of a sequence argument, rejecting scalars
+fn samples(value: &Bound<'_, PyAny>, name: &str) -> PyResult<Vec<f64>> {
+    match Values::extract(value)? {
+        Values::Scalar(_) => Err(PyValueError::new_err(format!("{} must be a sequence", name))),
+        values => values.to_vec(),
+    }
+}
+
+/// Apply a binary operator to scalars, or elementwise to sequences
+///
+/// A scalar paired with a sequence is broadcast. The result is an array
+/// if either argument is one, a list for lists and a float for scalars.
+fn binary<'py>(
+    py: Python<'py>,
+    a: &Bound<'py, PyAny>,
+    b: &Bound<'py, PyAny>,
+    op: fn(f64, f64) -> f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (a, b) = (Values::extract(a)?, Values::extract(b)?);
+    if let (Values::Scalar(a), Values::Scalar(b)) = (&a, &b) {
+        return Ok(PyFloat::new(py, op(*a, *b)).into_any());
+    }
+    let is_array = matches!(a, Values::Array(_)) || matches!(b, Values::Array(_));
+    let (xs, ys) = (a.to_vec()?, b.to_vec()?);
+    let len = xs.len().max(ys.len());
+    let at = |values: &[f64], i: usize| if values.len() == 1 { values[0] } else { values[i] };
+    if (xs.len() != len && xs.len() != 1) || (ys.len() != len && ys.len() != 1) {
+        return Err(PyValueError::new_err("operands must have the same length"));
+    }
+    let result: Vec<f64> = (0..len).map(|i| op(at(&xs, i), at(&ys, i))).collect();
+    if is_array {
+        Ok(PyArray1::from_vec(py, result).into_any())
+    } else {
+        Ok(PyList::new(py, result)?.into_any())
+    }
+}
+
+/// A membership function; call it with a float, list or array
+#[pyclass(name = "MembershipFunction", module = "fuzzy_logic", frozen)]
+pub struct PyMembershipFunction {
+    inner: MembershipFunction,
+}
+
+#[pymethods]
+impl PyMembershipFunction {
+    fn __call__<'py>(&self, py: Python<'py>, x: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        Values::extract(x)?.map(py, |x| self.inner.evaluate(x))
+    }
+
+    /// Membership degree at a single point
+    fn evaluate(&self, x: f64) -> f64 {
+        self.inner.evaluate(x)
+    }
+
+    fn __repr__(&self) -> String {
+        self.inner.description()
+    }
+}
+
+impl From<MembershipFunction> for PyMembershipFunction {
+    fn from(inner: MembershipFunction) -> Self {
+        PyMembershipFunction { inner }
+    }
+}
+
+/// Create a triangular membership function
+#[pyfunction]
+fn triangular_mf(a: f64, b: f64, c: f64) -> PyMembershipFunction {
+    MembershipFunction::Triangular { a, b, c }.into()
+}
+
+/// Create a trapezoidal membership function
+#[pyfunction]
+fn trapezoidal_mf(a: f64, b: f64, c: f64, d: f64) -> PyMembershipFunction {
+    MembershipFunction::Trapezoidal { a, b, c, d }.into()
+}
+
+/// Create a Gaussian membership function
+#[pyfunction]
+fn gaussian_mf(mean: f64, sigma: f64) -> PyMembershipFunction {
+    MembershipFunction::Gaussian { mean, sigma }.into()
+}
+
+/// Create a sigmoid membership function
+#[pyfunction]
+fn sigmoid_mf(a: f64, c: f64) -> PyMembershipFunction {
+    MembershipFunction::Sigmoid { a, c }.into()
+}
+
+/// Create a generalized bell membership function
+#[pyfunction]
+fn bell_mf(a: f64, b: f64, c: f64) -> PyMembershipFunction {
+    MembershipFunction::Bell { a, b, c }.into()
+}
+
+/// A named fuzzy set
+///
+/// Like `fuzzy_utils.FuzzySet`, the membership function may be any Python
+/// callable and an optional `universe` is kept alongside it. Functions from
+/// this module are evaluated in Rust; other callables are called from
+/// Python and their result clipped to [0, 1].
+#[pyclass(name = "FuzzySet", module = "fuzzy_logic", frozen)]
+pub struct PyFuzzySet {
+    name: String,
+    membership_fn: Py<PyAny>,
+    /// The Rust function behind `membership_fn`, if it is one
+    native: Option<MembershipFunction>,
+    universe: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl PyFuzzySet {
+    #[new]
+    #[pyo3(signature = (name, membership_fn, universe=None))]
+    fn new(name: String, membership_fn: &Bound<'_, PyAny>, universe: Option<Bound<'_, PyAny>>) -> PyResult<Self> {
+        let native = match membership_fn.cast::<PyMembershipFunction>() {
+            Ok(function) => Some(function.get().inner.clone()),
+            Err(_) if membership_fn.is_callable() => None,
+            Err(_) => return Err(PyTypeError::new_err("membership_fn must be callable")),
+        };
+        Ok(PyFuzzySet {
+            name,
+            membership_fn: membership_fn.clone().unbind(),
+            native,
+            universe: universe.map(Bound::unbind),
+        })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[getter]
+    fn membership_fn(&self, py: Python<'_>) -> Py<PyAny> {
+        self.membership_fn.clone_ref(py)
+    }
+
+    #[getter]
+    fn universe(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.universe.as_ref().map(|universe| universe.clone_ref(py))
+    }
+
+    /// Membership degree of a float, list or array
+    fn membership<'py>(&self, py: Python<'py>, x: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match &self.native {
+            Some(function) => Values::extract(x)?.map(py, |x| function.evaluate(x)),
+            None => {
+                let degree = self.membership_fn.bind(py).call1((x,))?;
+                Values::extract(&degree)?.map(py, |m| m.clamp(0.0, 1.0))
+            }
+        }
+    }
+
+    fn __call__<'py>(&self, py: Python<'py>, x: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        self.membership(py, x)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("FuzzySet('{}')", self.name)
+    }
+}
+
+/// Fuzzy union (OR) using maximum
+#[pyfunction(name = "fuzzy_union")]
+fn py_fuzzy_union<'py>(py: Python<'py>, a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    binary(py, a, b, fuzzy_union)
+}
+
+/// Fuzzy intersection (AND) using minimum
+#[pyfunction(name = "fuzzy_intersection")]
+fn py_fuzzy_intersection<'py>(py: Python<'py>, a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    binary(py, a, b, fuzzy_intersection)
+}
+
+/// Fuzzy complement (NOT): 1 - a
+#[pyfunction(name = "fuzzy_complement")]
+fn py_fuzzy_complement<'py>(py: Python<'py>, a: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    Values::extract(a)?.map(py, fuzzy_complement)
+}
+
+/// Algebraic product t-norm: a * b
+#[pyfunction(name = "algebraic_product")]
+fn py_algebraic_product<'py>(py: Python<'py>, a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    binary(py, a, b, algebraic_product)
+}
+
+/// Algebraic sum s-norm: a + b - a*b
+#[pyfunction(name = "algebraic_sum")]
+fn py_algebraic_sum<'py>(py: Python<'py>, a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    binary(py, a, b, algebraic_sum)
+}
+
+/// Validate a sampled fuzzy set and run a defuzzifier on it
+fn defuzzify_with(
+    x: &Bound<'_, PyAny>,
+    membership: &Bound<'_, PyAny>,
+    method: fn(&[f64], &[f64]) -> f64,
+) -> PyResult<f64> {
+    let xs = samples(x, "x")?;
+    let mu = samples(membership, "membership")?;
+    if xs.is_empty() || xs.len() != mu.len() {
+        return Err(PyValueError::new_err(
+            "x and membership must be non-empty and the same length",
+        ));
+    }
+    Ok(method(&xs, &mu))
+}
+
+/// Defuzzify using the centroid (center of gravity)
+#[pyfunction]
+fn defuzzify_centroid(x: &Bound<'_, PyAny>, membership: &Bound<'_, PyAny>) -> PyResult<f64> {
+    defuzzify_with(x, membership, centroid)
+}
+
+/// Defuzzify using the mean of maximum
+#[pyfunction]
+fn defuzzify_mean_of_maximum(x: &Bound<'_, PyAny>, membership: &Bound<'_, PyAny>) -> PyResult<f64> {
+    defuzzify_with(x, membership, mean_of_maximum)
+}
+
+/// Defuzzify using the smallest of maximum
+#[pyfunction]
+fn defuzzify_smallest_of_maximum(x: &Bound<'_, PyAny>, membership: &Bound<'_, PyAny>) -> PyResult<f64> {
+    defuzzify_with(x, membership, smallest_of_maximum)
+}
+
+/// Defuzzify using the largest of maximum
+#[pyfunction]
+fn defuzzify_largest_of_maximum(x: &Bound<'_, PyAny>, membership: &Bound<'_, PyAny>) -> PyResult<f64> {
+    defuzzify_with(x, membership, largest_of_maximum)
+}
+
+/// Defuzzify using the bisector of the area
+#[pyfunction]
+fn defuzzify_bisector(x: &Bound<'_, PyAny>, membership: &Bound<'_, PyAny>) -> PyResult<f64> {
+    defuzzify_with(x, membership, bisector)
+}
+
+/// A rule-based system loaded from a definition
+#[pyclass(name = "FuzzySystem", module = "fuzzy_logic")]
+pub struct PyFuzzySystem {
+    inner: FuzzySystem,
+}
+
+#[pymethods]
+impl PyFuzzySystem {
+    /// Parse definition text (the format of the `systems/` files)
+    #[new]
+    fn new(definition: &str) -> PyResult<Self> {
+        let inner = parse_system(definition).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyFuzzySystem { inner })
+    }
+
+    /// Load a definition file
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let inner = load_system(path).map_err(|e| PyValueError::new_err(format!("{}: {}", path, e)))?;
+        Ok(PyFuzzySystem { inner })
+    }
+
+    #[getter]
+    fn input_names(&self) -> Vec<String> {
+        self.inner.inputs().iter().map(|v| v.name.clone()).collect()
+    }
+
+    #[getter]
+    fn output_names(&self) -> Vec<String> {
+        self.inner.outputs().iter().map(|v| v.name.clone()).collect()
+    }
+
+    /// Change a setting such as `set_option("and", "prod")`
+    fn set_option(&mut self, setting: &str, value: &str) -> PyResult<()> {
+        set_option(&mut self.inner, setting, value).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Evaluate one row given as a sequence in input order or a dict by name
+    ///
+    /// Returns a dict of outputs for dict inputs, otherwise a list.
+    fn evaluate<'py>(&self, py: Python<'py>, inputs: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        if let Ok(named) = inputs.cast::<PyDict>() {
+            let point = self.point_from_dict(named)?;
+            let outputs = PyDict::new(py);
+            for (variable, value) in self.inner.outputs().iter().zip(self.inner.evaluate(&point)) {
+                outputs.set_item(&variable.name, value)?;
+            }
+            return Ok(outputs.into_any());
+        }
+        let point = samples(inputs, "inputs")?;
+        self.check_inputs(point.len())?;
+        Ok(PyList::new(py, self.inner.evaluate(&point))?.into_any())
+    }
+
+    /// Evaluate a 2-D NumPy array with one row per sample and one column
+    /// per input; returns one column per output
+    fn evaluate_batch<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        // Raise ImportError rather than failing inside the NumPy API
+        py.import("numpy")?;
+        let inputs: PyReadonlyArray2<'py, f64> = inputs.extract()?;
+        let inputs = inputs.as_array();
+        self.check_inputs(inputs.ncols())?;
+        let columns: Vec<Vec<f64>> = inputs.columns().into_iter().map(|c| c.to_vec()).collect();
+        let slices: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+        let outputs = py.detach(|| self.inner.evaluate_batch(&slices));
+
+        let rows = inputs.nrows();
+        let width = outputs.len();
+        let mut row_major = vec![0.0; rows * width];
+        for (o, column) in outputs.iter().enumerate() {
+            for (r, &y) in column.iter().enumerate() {
+                row_major[r * width + o] = y;
+            }
+        }
+        PyArray1::from_vec(py, row_major).reshape([rows, width])
+    }
+
+    /// Term memberships and rule strengths for one row:
+    /// `{"outputs": {...}, "memberships": {var: {term: mu}}, "rules": [(rule, strength)]}`
+    fn trace<'py>(&self, py: Python<'py>, inputs: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyDict>> {
+        let point = match inputs.cast::<PyDict>() {
+            Ok(named) => self.point_from_dict(named)?,
+            Err(_) => samples(inputs, "inputs")?,
+        };
+        self.check_inputs(point.len())?;
+        let system = &self.inner;
+
+        let outputs = PyDict::new(py);
+        for (variable, value) in system.outputs().iter().zip(system.evaluate(&point)) {
+            outputs.set_item(&variable.name, value)?;
+        }
+        let memberships = PyDict::new(py);
+        for (variable, &x) in system.inputs().iter().zip(&point) {
+            let terms = PyDict::new(py);
+            for (term, mu) in variable.terms.iter().zip(variable.fuzzify(x)) {
+                terms.set_item(&term.name, mu)?;
+            }
+            memberships.set_item(&variable.name, terms)?;
+        }
+        let rules: Vec<(String, f64)> = system
+            .rules()
+            .iter()
+            .map(|rule| format_rule(system, rule))
+            .zip(system.firing_strengths(&point))
+            .collect();
+
+        let trace = PyDict::new(py);
+        trace.set_item("outputs", outputs)?;
+        trace.set_item("memberships", memberships)?;
+        trace.set_item("rules", rules)?;
+        Ok(trace)
+    }
+
+    /// The system in the definition format
+    fn definition(&self) -> String {
+        format_system(&self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FuzzySystem(inputs={:?}, outputs={:?}, rules={})",
+            self.input_names(),
+            self.output_names(),
+            self.inner.rules().len()
+        )
+    }
+}
+
+impl PyFuzzySystem {
+    fn check_inputs(&self, count: usize) -> PyResult<()> {
+        let expected = self.inner.inputs().len();
+        if count != expected {
+            return Err(PyValueError::new_err(format!(
+                "expected {} inputs, got {}",
+                expected, count
+            )));
+        }
+        Ok(())
+    }
+
+    fn point_from_dict(&self, named: &Bound<'_, PyDict>) -> PyResult<Vec<f64>> {
+        for key in named.keys() {
+            let key: String = key.extract()?;
+            if self.inner.input_index(&key).is_none() {
+                return Err(PyKeyError::new_err(format!("unknown input '{}'", key)));
+            }
+        }
+        self.inner
+            .inputs()
+            .iter()
+            .map(|variable| match named.get_item(&variable.name)? {
+                Some(value) => value.extract(),
+                None => Err(PyKeyError::new_err(format!("missing input '{}'", variable.name))),
+            })
+            .collect()
+    }
+}
+
+/// The `fuzzy_logic` Python module
+#[pymodule]
+fn fuzzy_logic(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMembershipFunction>()?;
+    m.add_class::<PyFuzzySet>()?;
+    m.add_class::<PyFuzzySystem>()?;
+    m.add_function(wrap_pyfunction!(triangular_mf, m)?)?;
+    m.add_function(wrap_pyfunction!(trapezoidal_mf, m)?)?;
+    m.add_function(wrap_pyfunction!(gaussian_mf, m)?)?;
+    m.add_function(wrap_pyfunction!(sigmoid_mf, m)?)?;
+    m.add_function(wrap_pyfunction!(bell_mf, m)?)?;
+    m.add_function(wrap_pyfunction!(py_fuzzy_union, m)?)?;
+    m.add_function(wrap_pyfunction!(py_fuzzy_intersection, m)?)?;
+    m.add_function(wrap_pyfunction!(py_fuzzy_complement, m)?)?;
+    m.add_function(wrap_pyfunction!(py_algebraic_product, m)?)?;
+    m.add_function(wrap_pyfunction!(py_algebraic_sum, m)?)?;
+    m.add_function(wrap_pyfunction!(defuzzify_centroid, m)?)?;
+    m.add_function(wrap_pyfunction!(defuzzify_mean_of_maximum, m)?)?;
+    m.add_function(wrap_pyfunction!(defuzzify_smallest_of_maximum, m)?)?;
+    m.add_function(wrap_pyfunction!(defuzzify_largest_of_maximum, m)?)?;
+    m.add_function(wrap_pyfunction!(defuzzify_bisector, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    const TIPPING: &str = include_str!("../systems/tipping.fuzzy");
+
+    /// Run Python `code` with the module imported as `fz`
+    fn run(code: &str) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "fuzzy_logic").unwrap();
+            fuzzy_logic(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("fz", module).unwrap();
+            globals.set_item("TIPPING", TIPPING).unwrap();
+            let code = CString::new(code).unwrap();
+            if let Err(err) = py.run(&code, Some(&globals), None) {
+                panic!("{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn test_membership_functions() {
+        run(r#"
+warm = fz.triangular_mf(25, 50, 75)
+assert warm(37.5) == 0.5
+assert warm([25, 50, 62.5]) == [0.0, 1.0, 0.5]
+assert fz.trapezoidal_mf(0, 2, 4, 6)(3) == 1.0
+assert fz.gaussian_mf(5, 1).evaluate(5) == 1.0
+assert abs(fz.sigmoid_mf(2, 5)(5) - 0.5) < 1e-12
+assert abs(fz.bell_mf(2, 3, 5)(3) - 0.5) < 1e-12
+assert repr(warm) == "Triangular(a=25, b=50, c=75)"
+cold = fz.FuzzySet("Cold", fz.triangular_mf(0, 0, 50))
+assert cold.name == "Cold" and cold(25) == 0.5 and cold.universe is None
+"#);
+    }
+
+    #[test]
+    fn test_fuzzy_set_with_callable() {
+        run(r#"
+ramp = lambda x: x / 10
+hot = fz.FuzzySet("Hot", ramp, universe=[0, 5, 10])
+assert hot(5) == 0.5 and hot(20) == 1.0 and hot(-5) == 0.0
+assert hot.membership_fn is ramp and hot.universe == [0, 5, 10]
+assert repr(hot) == "FuzzySet('Hot')"
+try:
+    fz.FuzzySet("Bad", 0.5)
+    raise AssertionError("non-callable accepted")
+except TypeError:
+    pass
+"#);
+    }
+
+    #[test]
+    fn test_operations() {
+        run(r#"
+assert fz.fuzzy_union(0.7, 0.5) == 0.7
+assert fz.fuzzy_intersection(0.7, 0.5) == 0.5
+assert abs(fz.fuzzy_complement(0.7) - 0.3) < 1e-12
+assert fz.algebraic_product(0.5, 0.5) == 0.25
+assert fz.algebraic_sum(0.5, 0.5) == 0.75
+assert fz.fuzzy_union([0.1, 0.9], [0.5, 0.5]) == [0.5, 0.9]
+assert fz.fuzzy_intersection([0.1, 0.9], 0.5) == [0.1, 0.5]
+try:
+    fz.fuzzy_union([0.1, 0.9], [0.5, 0.5, 0.5])
+    raise AssertionError("length mismatch accepted")
+except ValueError:
+    pass
+"#);
+    }
+
+    #[test]
+    fn test_defuzzification() {
+        run(r#"
+x = [0, 1, 2, 3, 4]
+mu = [0, 0.5, 1, 0.5, 0]
+assert fz.defuzzify_centroid(x, mu) == 2.0
+assert fz.defuzzify_mean_of_maximum(x, [0, 1, 1, 0, 0]) == 1.5
+assert fz.defuzzify_bisector(x, mu) == 2
+assert fz.defuzzify_centroid(x, [0] * 5) == 2.0
+for bad in ([], [0.5]):
+    try:
+        fz.defuzzify_centroid([0, 1], bad)
+        raise AssertionError("bad membership accepted")
+    except ValueError:
+        pass
+"#);
+    }
+
+    #[test]
+    fn test_system() {
+        run(r#"
+system = fz.FuzzySystem(TIPPING)
+try:
+    import numpy as np
+    batch = system.evaluate_batch(np.array([[3.0, 8.0], [1.0, 1.0]]))
+    assert batch.shape == (2, 1) and batch[0, 0] == system.evaluate([3, 8])[0]
+    assert list(fz.triangular_mf(0, 5, 10)(np.array([5.0]))) == [1.0]
+except ImportError:
+    pass
+assert system.input_names == ["service", "food"]
+[tip] = system.evaluate([3, 8])
+assert 10 < tip < 20
+assert system.evaluate({"food": 8, "service": 3}) == {"tip": tip}
+trace = system.trace([3, 8])
+assert abs(trace["memberships"]["service"]["poor"] - 0.4) < 1e-12
+assert trace["rules"][0][0] == "if service is poor and food is poor then tip is low"
+for bad in ([3], {"service": 3}, {"service": 3, "food": 8, "wine": 1}):
+    try:
+        system.evaluate(bad)
+        raise AssertionError("bad inputs accepted")
+    except (ValueError, KeyError):
+        pass
+system.set_option("defuzzification", "bisector")
+assert "defuzzification bisector" in system.definition()
+try:
+    fz.FuzzySystem("input x 0 10\n  term low triangular 0 0\n")
+    raise AssertionError("bad definition accepted")
+except ValueError as err:
+    assert str(err).startswith("line 2")
+"#);
+    }
+}
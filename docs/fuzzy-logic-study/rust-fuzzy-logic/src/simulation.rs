@@ -0,0 +1,1047 @@
+//! Closed-Loop Simulation
+//!
+//! Runs a [`Controller`] against a simple plant model and measures the
+//! response. Plants are ordinary differential equations integrated with a
+//! fixed step by Euler's method or fourth-order Runge–Kutta; the control
+//! action is held constant over each step.
+//!
+//! Plant models:
+//! - [`FirstOrder`]: first-order lag
+//! - [`SecondOrder`]: second-order system with natural frequency and damping
+//! - [`ThermalRoom`]: a heated room losing heat to its surroundings
+//! - [`DeadTime`]: any plant behind a transport delay
+//!
+//! A [`Trace`] holds the time series of a run and exports it as CSV, and
+//! [`Trace::metrics`] computes rise time, overshoot, settling time and the
+//! integrated errors. A [`Scenario`] describes a whole run in a small
+//! line-based text format for the command-line tool:
+//!
+//! ```text
+//! # Room heating with a fuzzy PI controller
+//! plant room capacity 200000 conductance 100 ambient 10 power 3000
+//! initial 15
+//! dead-time 30
+//! controller fuzzy pi
+//! pid kp 0.5 ki 0.002 kd 0 range 5
+//! limits 0 1
+//! setpoint 21
+//! setpoint 19 at 7200
+//! step 1
+//! duration 14400
+//! ```
+//!
+//! Plants are `first-order gain K time-constant T`,
+//! `second-order gain K frequency W damping Z` and
+//! `room capacity C conductance UA ambient T power P`; `initial` sets the
+//! starting output and `dead-time` delays the plant input. Controllers are
+//! `fuzzy pd|pi|pid [FILE]` (the standard [`pid_rule_base`] unless a
+//! definition file is given) with `gains error GE change GCE integral GIE
+//! output GU` or `pid kp KP ki KI kd KD range R` (KP nonzero for PD and
+//! PID, KI for PI), or `scheduled FILE` with
+//! `scale error E change C`. Both accept `limits MIN MAX` and
+//! `anti-windup none|conditional|back-calculation GAIN`. `setpoint V [at T]`
+//! changes the setpoint at time T (0 by default); `step`, `duration` and
+//! `integrator euler|rk4` control the integration. Neither the duration
+//! nor the dead time may span more than [`MAX_STEPS`] steps.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::definition::{load_system, DefinitionError};
+use crate::pid::{
+    pid_rule_base, AntiWindup, Controller, FuzzyPid, FuzzyPidGains, GainScheduledPid, PidMode,
+};
+
+/// A plant model: `x' = f(x, u, t)` with a measured output `y = h(x)`
+pub trait Plant {
+    fn initial_state(&self) -> Vec<f64>;
+
+    /// Write the state derivative for input `u` at time `t` into `derivative`
+    fn derivative(&self, state: &[f64], u: f64, t: f64, derivative: &mut [f64]);
+
+    /// Measured output
+    fn output(&self, state: &[f64]) -> f64;
+
+    /// Transport delay of the input, in time units
+    fn delay(&self) -> f64 {
+        0.0
+    }
+}
+
+/// First-order lag: `T y' = K u - y`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstOrder {
+    pub gain: f64,
+    pub time_constant: f64,
+    pub initial: f64,
+}
+
+impl Plant for FirstOrder {
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.initial]
+    }
+
+    fn derivative(&self, state: &[f64], u: f64, _t: f64, derivative: &mut [f64]) {
+        derivative[0] = (self.gain * u - state[0]) / self.time_constant;
+    }
+
+    fn output(&self, state: &[f64]) -> f64 {
+        state[0]
+    }
+}
+
+/// Second-order system: `y'' = ω² (K u - y) - 2ζω y'`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecondOrder {
+    pub gain: f64,
+    /// Natural frequency ω, in radians per time unit
+    pub natural_frequency: f64,
+    /// Damping ratio ζ; below 1 the open-loop step response oscillates
+    pub damping: f64,
+    pub initial: f64,
+}
+
+impl Plant for SecondOrder {
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.initial, 0.0]
+    }
+
+    fn derivative(&self, state: &[f64], u: f64, _t: f64, derivative: &mut [f64]) {
+        let w = self.natural_frequency;
+        derivative[0] = state[1];
+        derivative[1] = w * w * (self.gain * u - state[0]) - 2.0 * self.damping * w * state[1];
+    }
+
+    fn output(&self, state: &[f64]) -> f64 {
+        state[0]
+    }
+}
+
+/// A heated room: `C T' = P u - UA (T - ambient)` with the heater input
+/// `u` as a fraction of full power
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalRoom {
+    /// Heat capacity C of the room and its contents (J/K)
+    pub capacity: f64,
+    /// Heat loss coefficient UA to the surroundings (W/K)
+    pub conductance: f64,
+    /// Outside temperature
+    pub ambient: f64,
+    /// Full heater power P (W)
+    pub heater_power: f64,
+    /// Starting room temperature
+    pub initial: f64,
+}
+
+impl Plant for ThermalRoom {
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.initial]
+    }
+
+    fn derivative(&self, state: &[f64], u: f64, _t: f64, derivative: &mut [f64]) {
+        derivative[0] = (self.heater_power * u - self.conductance * (state[0] - self.ambient)) / self.capacity;
+    }
+
+    fn output(&self, state: &[f64]) -> f64 {
+        state[0]
+    }
+}
+
+/// A plant whose input arrives `delay` time units late
+///
+/// The plant sees zero input until the first control action arrives.
+pub struct DeadTime {
+    pub plant: Box<dyn Plant>,
+    pub delay: f64,
+}
+
+impl Plant for DeadTime {
+    fn initial_state(&self) -> Vec<f64> {
+        self.plant.initial_state()
+    }
+
+    fn derivative(&self, state: &[f64], u: f64, t: f64, derivative: &mut [f64]) {
+        self.plant.derivative(state, u, t, derivative)
+    }
+
+    fn output(&self, state: &[f64]) -> f64 {
+        self.plant.output(state)
+    }
+
+    fn delay(&self) -> f64 {
+        self.delay + self.plant.delay()
+    }
+}
+
+/// Fixed-step integration method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    Euler,
+    /// Classical fourth-order Runge–Kutta
+    RungeKutta4,
+}
+
+/// Most steps a run, or a plant's dead time, may span
+///
+/// Every step of a run adds a [`Trace`] entry and every step of dead time
+/// holds a pending control action, so this bounds both the memory and the
+/// time a run takes.
+pub const MAX_STEPS: usize = 1_000_000;
+
+/// Settings for [`simulate`]
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Integration and control step
+    pub step: f64,
+    pub duration: f64,
+    pub integrator: Integrator,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            step: 0.01,
+            duration: 10.0,
+            integrator: Integrator::RungeKutta4,
+        }
+    }
+}
+
+/// State after one step of `dt` with input `u`
+fn advance(plant: &dyn Plant, state: &[f64], u: f64, t: f64, dt: f64, integrator: Integrator) -> Vec<f64> {
+    let n = state.len();
+    let mut k1 = vec![0.0; n];
+    plant.derivative(state, u, t, &mut k1);
+    match integrator {
+        Integrator::Euler => state.iter().zip(&k1).map(|(x, d)| x + dt * d).collect(),
+        Integrator::RungeKutta4 => {
+            let offset = |k: &[f64], scale: f64| -> Vec<f64> {
+                state.iter().zip(k).map(|(x, d)| x + scale * d).collect()
+            };
+            let mut k2 = vec![0.0; n];
+            plant.derivative(&offset(&k1, dt / 2.0), u, t + dt / 2.0, &mut k2);
+            let mut k3 = vec![0.0; n];
+            plant.derivative(&offset(&k2, dt / 2.0), u, t + dt / 2.0, &mut k3);
+            let mut k4 = vec![0.0; n];
+            plant.derivative(&offset(&k3, dt), u, t + dt, &mut k4);
+            (0..n)
+                .map(|i| state[i] + dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+                .collect()
+        }
+    }
+}
+
+/// Errors running a simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationError {
+    /// The step or the duration is not positive and finite
+    InvalidConfig(&'static str),
+    /// The duration or the plant's dead time spans more than [`MAX_STEPS`]
+    /// steps
+    TooManySteps,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::InvalidConfig(message) => write!(f, "{}", message),
+            SimulationError::TooManySteps => write!(f, "the run spans more than {} steps", MAX_STEPS),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// Number of steps spanning `time`, if at most [`MAX_STEPS`]
+fn step_count(time: f64, step: f64) -> Option<usize> {
+    let steps = (time / step).round();
+    (steps <= MAX_STEPS as f64).then_some(steps as usize)
+}
+
+/// Run `controller` in closed loop with `plant`, following `setpoint(t)`
+pub fn simulate<C: Controller + ?Sized>(
+    plant: &dyn Plant,
+    controller: &mut C,
+    setpoint: &dyn Fn(f64) -> f64,
+    config: &SimulationConfig,
+) -> Result<Trace, SimulationError> {
+    if !(config.step > 0.0 && config.step.is_finite()) {
+        return Err(SimulationError::InvalidConfig("step must be positive"));
+    }
+    if !(config.duration > 0.0 && config.duration.is_finite()) {
+        return Err(SimulationError::InvalidConfig("duration must be positive"));
+    }
+    let dt = config.step;
+    let steps = step_count(config.duration, dt).ok_or(SimulationError::TooManySteps)?;
+    let delay_steps = step_count(plant.delay().max(0.0), dt).ok_or(SimulationError::TooManySteps)?;
+    let mut pending: VecDeque<f64> = std::iter::repeat_n(0.0, delay_steps).collect();
+
+    let mut trace = Trace::default();
+    let mut state = plant.initial_state();
+    for k in 0..=steps {
+        let t = k as f64 * dt;
+        let r = setpoint(t);
+        let y = plant.output(&state);
+        let u = controller.step(r, y, dt);
+        trace.time.push(t);
+        trace.setpoint.push(r);
+        trace.output.push(y);
+        trace.control.push(u);
+
+        pending.push_back(u);
+        let applied = pending.pop_front().unwrap_or(u);
+        state = advance(plant, &state, applied, t, dt, config.integrator);
+    }
+    Ok(trace)
+}
+
+/// Time series of a simulation run, one entry per step
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    pub time: Vec<f64>,
+    pub setpoint: Vec<f64>,
+    /// Measured plant output
+    pub output: Vec<f64>,
+    /// Control action computed at each step
+    pub control: Vec<f64>,
+}
+
+impl Trace {
+    /// `time,setpoint,output,control` rows with a header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,setpoint,output,control\n");
+        for i in 0..self.time.len() {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                self.time[i], self.setpoint[i], self.output[i], self.control[i]
+            ));
+        }
+        csv
+    }
+
+    /// Step-response metrics for the last setpoint change, and integrated
+    /// errors over the whole run
+    ///
+    /// Rise time runs from 10% to 90% of the step and the settling band is
+    /// ±2% of the step. Both are `None` when the output never gets there or
+    /// the step is zero.
+    pub fn metrics(&self) -> Metrics {
+        let n = self.time.len();
+        let dt = if n > 1 { self.time[1] - self.time[0] } else { 0.0 };
+        let (iae, ise) = self
+            .setpoint
+            .iter()
+            .zip(&self.output)
+            .fold((0.0, 0.0), |(iae, ise), (r, y)| (iae + (r - y).abs() * dt, ise + (r - y) * (r - y) * dt));
+        let Some(&target) = self.setpoint.last() else {
+            return Metrics::default();
+        };
+
+        // The response under study starts where the setpoint last changed
+        let start = (0..n).rev().find(|&i| i == 0 || self.setpoint[i - 1] != target).unwrap_or(0);
+        let initial = self.output[start];
+        let step = target - initial;
+        let at = |i: usize| self.time[i] - self.time[start];
+        // Progress towards the target as a fraction of the step
+        let progress = |i: usize| (self.output[i] - initial) / step;
+        let final_output = self.output[n - 1];
+
+        let mut metrics = Metrics {
+            iae,
+            ise,
+            steady_state_error: target - final_output,
+            ..Metrics::default()
+        };
+        if step == 0.0 {
+            return metrics;
+        }
+        let range = start..n;
+        let ten = range.clone().find(|&i| progress(i) >= 0.1);
+        let ninety = range.clone().find(|&i| progress(i) >= 0.9);
+        if let (Some(a), Some(b)) = (ten, ninety) {
+            metrics.rise_time = Some(at(b) - at(a));
+        }
+        let peak = range.clone().map(progress).fold(f64::NEG_INFINITY, f64::max);
+        metrics.overshoot = 100.0 * (peak - 1.0).max(0.0);
+        let outside = range.rev().find(|&i| (1.0 - progress(i)).abs() > 0.02);
+        metrics.settling_time = match outside {
+            None => Some(0.0),
+            Some(i) if i + 1 < n => Some(at(i + 1)),
+            Some(_) => None,
+        };
+        metrics
+    }
+}
+
+/// Standard measures of a closed-loop response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics {
+    /// Time from 10% to 90% of the step
+    pub rise_time: Option<f64>,
+    /// Peak beyond the setpoint, as a percentage of the step
+    pub overshoot: f64,
+    /// Time after the step until the output stays within 2% of it
+    pub settling_time: Option<f64>,
+    /// Integral of the absolute error
+    pub iae: f64,
+    /// Integral of the squared error
+    pub ise: f64,
+    /// Setpoint minus output at the end of the run
+    pub steady_state_error: f64,
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = |t: Option<f64>| t.map_or_else(|| "not reached".to_string(), |t| format!("{:.4}", t));
+        writeln!(f, "Closed-loop response")?;
+        writeln!(f, "  Rise time: {}", time(self.rise_time))?;
+        writeln!(f, "  Overshoot: {:.2}%", self.overshoot)?;
+        writeln!(f, "  Settling time: {}", time(self.settling_time))?;
+        writeln!(f, "  IAE: {:.6}", self.iae)?;
+        writeln!(f, "  ISE: {:.6}", self.ise)?;
+        writeln!(f, "  Steady-state error: {:.6}", self.steady_state_error)
+    }
+}
+
+/// Errors loading a scenario
+#[derive(Debug)]
+pub enum ScenarioError {
+    /// The scenario file could not be read
+    Io(io::Error),
+    /// The scenario is malformed; `line` is 1-based
+    Syntax { line: usize, message: String },
+    /// A system definition named by the scenario could not be loaded
+    Definition { path: String, error: DefinitionError },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "{}", err),
+            ScenarioError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ScenarioError::Definition { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScenarioError::Io(err) => Some(err),
+            ScenarioError::Syntax { .. } => None,
+            ScenarioError::Definition { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ScenarioError {
+    fn from(err: io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> ScenarioError {
+    ScenarioError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A plant, controller, setpoint schedule and run settings
+pub struct Scenario {
+    pub plant: Box<dyn Plant>,
+    pub controller: Box<dyn Controller>,
+    /// `(time, value)` setpoint changes in time order
+    pub setpoints: Vec<(f64, f64)>,
+    pub config: SimulationConfig,
+}
+
+impl Scenario {
+    /// Parse a scenario; definition files are resolved against `base`
+    pub fn parse(text: &str, base: &Path) -> Result<Self, ScenarioError> {
+        parse_scenario(text, base)
+    }
+
+    /// Read and parse a scenario file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        parse_scenario(&text, path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Setpoint in force at time `t`; the first setpoint applies before
+    /// its start time
+    pub fn setpoint(&self, t: f64) -> f64 {
+        setpoint_at(&self.setpoints, t)
+    }
+
+    /// Run the scenario from the start
+    pub fn run(&mut self) -> Result<Trace, SimulationError> {
+        self.controller.reset();
+        let setpoints = &self.setpoints;
+        let setpoint = |t: f64| setpoint_at(setpoints, t);
+        simulate(self.plant.as_ref(), self.controller.as_mut(), &setpoint, &self.config)
+    }
+}
+
+fn setpoint_at(setpoints: &[(f64, f64)], t: f64) -> f64 {
+    setpoints
+        .iter()
+        .take_while(|(start, _)| *start <= t)
+        .last()
+        .or(setpoints.first())
+        .map_or(0.0, |&(_, value)| value)
+}
+
+/// `name value` pairs of a line, requiring exactly the given names
+fn named_values(line: usize, args: &[&str], names: &[&str]) -> Result<Vec<f64>, ScenarioError> {
+    if args.len() != 2 * names.len() {
+        let expected: Vec<String> = names.iter().map(|n| format!("{} VALUE", n)).collect();
+        return Err(syntax(line, format!("expected {}", expected.join(" "))));
+    }
+    let mut values = vec![f64::NAN; names.len()];
+    for pair in args.chunks(2) {
+        let Some(slot) = names.iter().position(|&n| n == pair[0]) else {
+            return Err(syntax(line, format!("unknown parameter '{}'", pair[0])));
+        };
+        values[slot] = number(line, pair[1])?;
+    }
+    if let Some(missing) = values.iter().position(|v| v.is_nan()) {
+        return Err(syntax(line, format!("missing parameter '{}'", names[missing])));
+    }
+    Ok(values)
+}
+
+fn number(line: usize, text: &str) -> Result<f64, ScenarioError> {
+    text.parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| syntax(line, format!("'{}' is not a number", text)))
+}
+
+fn positive(line: usize, what: &str, value: f64) -> Result<f64, ScenarioError> {
+    if value > 0.0 {
+        Ok(value)
+    } else {
+        Err(syntax(line, format!("{} must be positive", what)))
+    }
+}
+
+/// Plant line, kept concrete until `initial` and `dead-time` are known
+enum PlantSpec {
+    FirstOrder(FirstOrder),
+    SecondOrder(SecondOrder),
+    Room(ThermalRoom),
+}
+
+/// Controller line, built once its settings have been read
+enum ControllerSpec {
+    Fuzzy { mode: PidMode, system: Option<String> },
+    Scheduled { schedule: String },
+}
+
+fn parse_scenario(text: &str, base: &Path) -> Result<Scenario, ScenarioError> {
+    let mut plant: Option<PlantSpec> = None;
+    let mut initial = None;
+    let mut dead_time = 0.0;
+    let mut dead_time_line = 1;
+    let mut steps_line = 1;
+    let mut controller: Option<(usize, ControllerSpec)> = None;
+    let mut gains: Option<(usize, FuzzyPidGains)> = None;
+    let mut pid: Option<(usize, [f64; 4])> = None;
+    let mut scale: Option<(usize, [f64; 2])> = None;
+    let mut limits = None;
+    let mut anti_windup = AntiWindup::Conditional;
+    let mut setpoints: Vec<(f64, f64)> = Vec::new();
+    let mut config = SimulationConfig::default();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let content = raw.split('#').next().unwrap_or("").trim();
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        let Some((&keyword, args)) = tokens.split_first() else {
+            continue;
+        };
+
+        match keyword {
+            "plant" => {
+                let Some((&kind, params)) = args.split_first() else {
+                    return Err(syntax(line, "expected first-order, second-order or room"));
+                };
+                let model = match kind {
+                    "first-order" => {
+                        let v = named_values(line, params, &["gain", "time-constant"])?;
+                        PlantSpec::FirstOrder(FirstOrder {
+                            gain: v[0],
+                            time_constant: positive(line, "time-constant", v[1])?,
+                            initial: 0.0,
+                        })
+                    }
+                    "second-order" => {
+                        let v = named_values(line, params, &["gain", "frequency", "damping"])?;
+                        PlantSpec::SecondOrder(SecondOrder {
+                            gain: v[0],
+                            natural_frequency: positive(line, "frequency", v[1])?,
+                            damping: v[2],
+                            initial: 0.0,
+                        })
+                    }
+                    "room" => {
+                        let v = named_values(line, params, &["capacity", "conductance", "ambient", "power"])?;
+                        PlantSpec::Room(ThermalRoom {
+                            capacity: positive(line, "capacity", v[0])?,
+                            conductance: v[1],
+                            ambient: v[2],
+                            heater_power: v[3],
+                            initial: v[2],
+                        })
+                    }
+                    other => return Err(syntax(line, format!("unknown plant '{}'", other))),
+                };
+                if plant.is_some() {
+                    return Err(syntax(line, "the plant is already defined"));
+                }
+                plant = Some(model);
+            }
+            "initial" => match args {
+                [value] => initial = Some(number(line, value)?),
+                _ => return Err(syntax(line, "expected initial VALUE")),
+            },
+            "dead-time" => match args {
+                [value] => {
+                    dead_time = number(line, value)?;
+                    dead_time_line = line;
+                    if dead_time < 0.0 {
+                        return Err(syntax(line, "dead-time must not be negative"));
+                    }
+                }
+                _ => return Err(syntax(line, "expected dead-time VALUE")),
+            },
+            "controller" => {
+                let spec = match args {
+                    ["fuzzy", mode, rest @ ..] if rest.len() <= 1 => {
+                        let mode = match *mode {
+                            "pd" => PidMode::Pd,
+                            "pi" => PidMode::Pi,
+                            "pid" => PidMode::Pid,
+                            other => return Err(syntax(line, format!("unknown mode '{}'", other))),
+                        };
+                        ControllerSpec::Fuzzy {
+                            mode,
+                            system: rest.first().map(|s| s.to_string()),
+                        }
+                    }
+                    ["scheduled", schedule] => ControllerSpec::Scheduled {
+                        schedule: schedule.to_string(),
+                    },
+                    _ => return Err(syntax(line, "expected controller fuzzy pd|pi|pid [FILE] or controller scheduled FILE")),
+                };
+                if controller.is_some() {
+                    return Err(syntax(line, "the controller is already defined"));
+                }
+                controller = Some((line, spec));
+            }
+            "gains" => {
+                let v = named_values(line, args, &["error", "change", "integral", "output"])?;
+                gains = Some((
+                    line,
+                    FuzzyPidGains {
+                        error: v[0],
+                        change: v[1],
+                        integral: v[2],
+                        output: v[3],
+                    },
+                ));
+            }
+            "pid" => {
+                let v = named_values(line, args, &["kp", "ki", "kd", "range"])?;
+                positive(line, "range", v[3])?;
+                pid = Some((line, [v[0], v[1], v[2], v[3]]));
+            }
+            "scale" => {
+                let v = named_values(line, args, &["error", "change"])?;
+                scale = Some((line, [v[0], v[1]]));
+            }
+            "limits" => match args {
+                [min, max] => {
+                    let (min, max) = (number(line, min)?, number(line, max)?);
+                    if min > max {
+                        return Err(syntax(line, "limits must be MIN MAX"));
+                    }
+                    limits = Some((min, max));
+                }
+                _ => return Err(syntax(line, "expected limits MIN MAX")),
+            },
+            "anti-windup" => {
+                anti_windup = match args {
+                    ["none"] => AntiWindup::None,
+                    ["conditional"] => AntiWindup::Conditional,
+                    ["back-calculation", gain] => AntiWindup::BackCalculation(number(line, gain)?),
+                    _ => return Err(syntax(line, "expected anti-windup none|conditional|back-calculation GAIN")),
+                }
+            }
+            "setpoint" => {
+                let (value, time) = match args {
+                    [value] => (number(line, value)?, 0.0),
+                    [value, "at", time] => (number(line, value)?, number(line, time)?),
+                    _ => return Err(syntax(line, "expected setpoint VALUE [at TIME]")),
+                };
+                if setpoints.last().is_some_and(|&(last, _)| time <= last) {
+                    return Err(syntax(line, "setpoints must be in time order"));
+                }
+                setpoints.push((time, value));
+            }
+            "step" | "duration" => match args {
+                [value] => {
+                    let value = positive(line, keyword, number(line, value)?)?;
+                    if keyword == "step" {
+                        config.step = value;
+                    } else {
+                        config.duration = value;
+                    }
+                    steps_line = line;
+                }
+                _ => return Err(syntax(line, format!("expected {} VALUE", keyword))),
+            },
+            "integrator" => {
+                config.integrator = match args {
+                    ["euler"] => Integrator::Euler,
+                    ["rk4"] => Integrator::RungeKutta4,
+                    _ => return Err(syntax(line, "expected integrator euler|rk4")),
+                }
+            }
+            other => return Err(syntax(line, format!("unknown keyword '{}'", other))),
+        }
+    }
+    if step_count(config.duration, config.step).is_none() {
+        return Err(syntax(steps_line, format!("duration / step is more than {} steps", MAX_STEPS)));
+    }
+    if step_count(dead_time, config.step).is_none() {
+        return Err(syntax(dead_time_line, format!("dead-time / step is more than {} steps", MAX_STEPS)));
+    }
+
+    let mut model: Box<dyn Plant> = match (plant, initial) {
+        (None, _) => return Err(syntax(1, "the scenario has no plant")),
+        (Some(PlantSpec::FirstOrder(p)), initial) => Box::new(FirstOrder {
+            initial: initial.unwrap_or(p.initial),
+            ..p
+        }),
+        (Some(PlantSpec::SecondOrder(p)), initial) => Box::new(SecondOrder {
+            initial: initial.unwrap_or(p.initial),
+            ..p
+        }),
+        (Some(PlantSpec::Room(p)), initial) => Box::new(ThermalRoom {
+            initial: initial.unwrap_or(p.initial),
+            ..p
+        }),
+    };
+    if dead_time > 0.0 {
+        model = Box::new(DeadTime {
+            plant: model,
+            delay: dead_time,
+        });
+    }
+    let Some((controller_line, spec)) = controller else {
+        return Err(syntax(1, "the scenario has no controller"));
+    };
+    if setpoints.is_empty() {
+        return Err(syntax(1, "the scenario has no setpoint"));
+    }
+
+    let load = |path: &str| {
+        let resolved = base.join(path);
+        let system = load_system(&resolved).map_err(|error| ScenarioError::Definition {
+            path: resolved.display().to_string(),
+            error,
+        })?;
+        let inputs = system.inputs().len();
+        Ok::<_, ScenarioError>((system, inputs))
+    };
+    let controller: Box<dyn Controller> = match spec {
+        ControllerSpec::Fuzzy { mode, system } => {
+            if let Some((line, _)) = scale {
+                return Err(syntax(line, "scale applies to scheduled controllers"));
+            }
+            let gains = match (gains, pid) {
+                (Some((_, gains)), None) => gains,
+                (None, Some((line, [kp, ki, kd, range]))) => {
+                    // The output scale is kp·range, or ki·range for PI
+                    match mode {
+                        PidMode::Pi if ki == 0.0 => return Err(syntax(line, "ki must be nonzero for a PI controller")),
+                        PidMode::Pd | PidMode::Pid if kp == 0.0 => {
+                            return Err(syntax(line, "kp must be nonzero for a PD or PID controller"))
+                        }
+                        _ => FuzzyPidGains::from_pid(mode, kp, ki, kd, range),
+                    }
+                }
+                (Some(_), Some((line, _))) => return Err(syntax(line, "use either gains or pid, not both")),
+                (None, None) => return Err(syntax(controller_line, "a fuzzy controller needs gains or pid")),
+            };
+            let system = match system {
+                Some(path) => {
+                    let (system, inputs) = load(&path)?;
+                    if inputs != 2 || system.outputs().len() != 1 {
+                        return Err(syntax(controller_line, format!("{} must have 2 inputs and 1 output", path)));
+                    }
+                    system
+                }
+                None => pid_rule_base(),
+            };
+            let mut fuzzy = FuzzyPid::new(system, mode, gains).with_anti_windup(anti_windup);
+            if let Some((min, max)) = limits {
+                fuzzy = fuzzy.with_output_limits(min, max);
+            }
+            Box::new(fuzzy)
+        }
+        ControllerSpec::Scheduled { schedule } => {
+            if let Some(line) = gains.map(|(line, _)| line).or(pid.map(|(line, _)| line)) {
+                return Err(syntax(line, "gains and pid apply to fuzzy controllers"));
+            }
+            let (system, inputs) = load(&schedule)?;
+            if inputs != 2 || system.outputs().len() != 3 {
+                return Err(syntax(controller_line, format!("{} must have 2 inputs and 3 outputs", schedule)));
+            }
+            let [error, change] = scale.map_or([1.0, 1.0], |(_, s)| s);
+            let mut scheduled = GainScheduledPid::new(system, error, change).with_anti_windup(anti_windup);
+            if let Some((min, max)) = limits {
+                scheduled = scheduled.with_output_limits(min, max);
+            }
+            Box::new(scheduled)
+        }
+    };
+
+    Ok(Scenario {
+        plant: model,
+        controller,
+        setpoints,
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Open loop: applies the setpoint as the control action
+    struct OpenLoop;
+
+    impl Controller for OpenLoop {
+        fn step(&mut self, setpoint: f64, _measurement: f64, _dt: f64) -> f64 {
+            setpoint
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn lag() -> FirstOrder {
+        FirstOrder {
+            gain: 2.0,
+            time_constant: 0.5,
+            initial: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_integrators_follow_the_exact_step_response() {
+        let exact = |t: f64| 2.0 * (1.0 - (-t / 0.5).exp());
+        let error = |integrator| {
+            let config = SimulationConfig {
+                step: 0.05,
+                duration: 2.0,
+                integrator,
+            };
+            let trace = simulate(&lag(), &mut OpenLoop, &|_| 1.0, &config).unwrap();
+            assert_eq!(trace.time.len(), 41);
+            trace
+                .time
+                .iter()
+                .zip(&trace.output)
+                .map(|(&t, y)| (y - exact(t)).abs())
+                .fold(0.0, f64::max)
+        };
+
+        let euler = error(Integrator::Euler);
+        let rk4 = error(Integrator::RungeKutta4);
+        assert!(euler < 0.1, "{}", euler);
+        assert!(rk4 < 1e-5, "{}", rk4);
+    }
+
+    #[test]
+    fn test_dead_time_delays_the_input() {
+        let plant = DeadTime {
+            plant: Box::new(lag()),
+            delay: 0.3,
+        };
+        let config = SimulationConfig {
+            step: 0.1,
+            duration: 1.0,
+            ..SimulationConfig::default()
+        };
+        let trace = simulate(&plant, &mut OpenLoop, &|_| 1.0, &config).unwrap();
+
+        // The first input reaches the plant at 0.3 and shows in the output
+        // one step later
+        assert!(trace.output[..4].iter().all(|&y| y == 0.0));
+        assert!(trace.output[4] > 0.0);
+    }
+
+    #[test]
+    fn test_metrics_of_an_underdamped_response() {
+        let plant = SecondOrder {
+            gain: 1.0,
+            natural_frequency: 2.0,
+            damping: 0.3,
+            initial: 0.0,
+        };
+        let config = SimulationConfig {
+            step: 0.001,
+            duration: 12.0,
+            ..SimulationConfig::default()
+        };
+        let trace = simulate(&plant, &mut OpenLoop, &|_| 1.0, &config).unwrap();
+        let metrics = trace.metrics();
+
+        // Textbook values for ζ = 0.3: overshoot exp(-πζ/√(1-ζ²)) ≈ 37.2%,
+        // settling about 4/(ζω) ≈ 6.7
+        assert!((metrics.overshoot - 37.23).abs() < 0.1, "{}", metrics.overshoot);
+        let rise = metrics.rise_time.unwrap();
+        assert!(rise > 0.4 && rise < 0.7, "{}", rise);
+        let settling = metrics.settling_time.unwrap();
+        assert!(settling > 5.0 && settling < 7.5, "{}", settling);
+        assert!(metrics.steady_state_error.abs() < 0.02);
+        assert!(metrics.ise < metrics.iae);
+    }
+
+    #[test]
+    fn test_metrics_use_the_last_setpoint_change() {
+        let trace = Trace {
+            time: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            setpoint: vec![0.0, 0.0, 10.0, 10.0, 10.0, 10.0],
+            output: vec![0.0, 0.0, 0.0, 5.0, 11.0, 10.0],
+            control: vec![0.0; 6],
+        };
+        let metrics = trace.metrics();
+        assert_eq!(metrics.rise_time, Some(1.0));
+        assert!((metrics.overshoot - 10.0).abs() < 1e-9);
+        assert_eq!(metrics.settling_time, Some(3.0));
+        assert_eq!(metrics.iae, 10.0 + 5.0 + 1.0);
+        assert_eq!(metrics.ise, 100.0 + 25.0 + 1.0);
+
+        // A response that never gets there
+        let stuck = Trace {
+            output: vec![0.0; 6],
+            ..trace
+        };
+        let metrics = stuck.metrics();
+        assert_eq!(metrics.rise_time, None);
+        assert_eq!(metrics.settling_time, None);
+        assert_eq!(metrics.steady_state_error, 10.0);
+    }
+
+    #[test]
+    fn test_parse_scenario() {
+        let text = "\
+plant second-order gain 1 frequency 2 damping 0.5   # mass on a spring
+initial 0.5
+controller fuzzy pid
+gains error 1 change 0.5 integral 0.2 output 3
+setpoint 1
+setpoint 0 at 5
+step 0.01
+duration 10
+integrator euler
+";
+        let mut scenario = Scenario::parse(text, Path::new("")).unwrap();
+        assert_eq!(scenario.setpoints, vec![(0.0, 1.0), (5.0, 0.0)]);
+        assert_eq!(scenario.setpoint(4.99), 1.0);
+        assert_eq!(scenario.setpoint(5.0), 0.0);
+        assert_eq!(scenario.config.integrator, Integrator::Euler);
+        let trace = scenario.run().unwrap();
+        assert_eq!(trace.output[0], 0.5);
+        assert_eq!(trace.time.len(), 1001);
+
+        let error = |text: &str| match Scenario::parse(text, Path::new("")) {
+            Err(ScenarioError::Syntax { line, message }) => (line, message),
+            other => panic!("expected a syntax error, got {:?}", other.err()),
+        };
+        assert_eq!(
+            error("plant first-order gain 1 time-constant 0"),
+            (1, "time-constant must be positive".to_string())
+        );
+        assert_eq!(
+            error("plant first-order gain 1 time-constant 1\ncontroller fuzzy pi\nsetpoint 1"),
+            (2, "a fuzzy controller needs gains or pid".to_string())
+        );
+        assert_eq!(
+            error("setpoint 1 at 2\nsetpoint 2 at 1"),
+            (2, "setpoints must be in time order".to_string())
+        );
+        assert_eq!(error("heater on"), (1, "unknown keyword 'heater'".to_string()));
+    }
+
+    #[test]
+    fn test_parse_rejects_zero_scale_gain() {
+        let scenario = |controller: &str, pid: &str| {
+            let text = format!("plant first-order gain 1 time-constant 1\ncontroller fuzzy {}\n{}\nsetpoint 1", controller, pid);
+            match Scenario::parse(&text, Path::new("")) {
+                Err(ScenarioError::Syntax { line, message }) => Some((line, message)),
+                _ => None,
+            }
+        };
+        assert_eq!(
+            scenario("pi", "pid kp 1 ki 0 kd 0 range 1"),
+            Some((3, "ki must be nonzero for a PI controller".to_string()))
+        );
+        assert_eq!(
+            scenario("pd", "pid kp 0 ki 1 kd 1 range 1"),
+            Some((3, "kp must be nonzero for a PD or PID controller".to_string()))
+        );
+        // PI does not divide by kp
+        assert_eq!(scenario("pi", "pid kp 0 ki 1 kd 0 range 1"), None);
+    }
+
+    #[test]
+    fn test_step_count_is_bounded() {
+        let config = |step: f64, duration: f64| SimulationConfig {
+            step,
+            duration,
+            ..SimulationConfig::default()
+        };
+        let run = |plant: &dyn Plant, config: &SimulationConfig| simulate(plant, &mut OpenLoop, &|_| 1.0, config).err();
+        assert_eq!(run(&lag(), &config(1e-6, 1e300)), Some(SimulationError::TooManySteps));
+        assert_eq!(run(&lag(), &config(0.0, 1.0)), Some(SimulationError::InvalidConfig("step must be positive")));
+        assert_eq!(
+            run(&lag(), &config(0.1, f64::NAN)),
+            Some(SimulationError::InvalidConfig("duration must be positive"))
+        );
+        let late = DeadTime {
+            plant: Box::new(lag()),
+            delay: 1e300,
+        };
+        assert_eq!(run(&late, &config(0.1, 1.0)), Some(SimulationError::TooManySteps));
+        assert_eq!(run(&lag(), &config(1.0, MAX_STEPS as f64)), None);
+
+        let error = |settings: &str| {
+            let text = format!("plant first-order gain 1 time-constant 1
+controller fuzzy pi
+pid kp 1 ki 1 kd 0 range 1
+setpoint 1
+{}", settings);
+            match Scenario::parse(&text, Path::new("")) {
+                Err(ScenarioError::Syntax { line, message }) => Some((line, message)),
+                _ => None,
+            }
+        };
+        assert_eq!(
+            error("step 1e-6\nduration 1e300"),
+            Some((6, "duration / step is more than 1000000 steps".to_string()))
+        );
+        assert_eq!(
+            error("dead-time 1e300"),
+            Some((5, "dead-time / step is more than 1000000 steps".to_string()))
+        );
+        assert_eq!(error("dead-time 10\nstep 1\nduration 100"), None);
+    }
+}
@@ -0,0 +1,383 @@
+//! Sugeno Inference
+//!
+//! Takagi–Sugeno–Kang systems, whose rule consequents are crisp functions
+//! of the inputs: a constant (zero order) or a linear function (first
+//! order). The output is the average of the rule outputs weighted by their
+//! firing strengths, so no output universe is sampled and evaluation
+//! neither allocates nor needs `std`.
+//!
+//! Systems borrow their terms and rules, which can therefore live in
+//! `static` items on a microcontroller or in vectors built at runtime:
+//!
+//! ```
+//! use fuzzy_logic::membership::MembershipFunction;
+//! use fuzzy_logic::sugeno::{SugenoRule, SugenoSystem};
+//!
+//! static ERROR: [MembershipFunction; 2] = [
+//!     MembershipFunction::Triangular { a: -10.0, b: -10.0, c: 10.0 },
+//!     MembershipFunction::Triangular { a: -10.0, b: 10.0, c: 10.0 },
+//! ];
+//! static INPUTS: [&[MembershipFunction]; 1] = [&ERROR];
+//! static RULES: [SugenoRule; 2] = [
+//!     SugenoRule::constant(&[Some(0)], -1.0),
+//!     SugenoRule::constant(&[Some(1)], 1.0),
+//! ];
+//! static CONTROLLER: SugenoSystem = SugenoSystem::new(&INPUTS, &RULES);
+//!
+//! assert_eq!(CONTROLLER.evaluate(&[0.0]), Some(0.0));
+//! ```
+//!
+//! With the `alloc` feature, [`OwnedSugenoSystem`] holds its terms and rules
+//! itself, for systems built or trained at runtime.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::membership::{Evaluate, MembershipFunction};
+use crate::numeric::Real;
+use crate::operations::TNorm;
+
+/// A rule: `if x1 is A and x2 is B ... then y = c1*x1 + c2*x2 + ... + c0`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SugenoRule<'a, T = f64> {
+    /// Term index per input, `None` where the rule ignores the input
+    pub antecedents: &'a [Option<usize>],
+    /// Coefficient per input; empty for a zero-order rule
+    pub coefficients: &'a [T],
+    pub constant: T,
+}
+
+impl<'a, T: Real> SugenoRule<'a, T> {
+    /// A zero-order rule with a constant output
+    pub const fn constant(antecedents: &'a [Option<usize>], value: T) -> Self {
+        SugenoRule {
+            antecedents,
+            coefficients: &[],
+            constant: value,
+        }
+    }
+
+    /// A first-order rule with an output linear in the inputs
+    pub const fn linear(antecedents: &'a [Option<usize>], coefficients: &'a [T], constant: T) -> Self {
+        SugenoRule {
+            antecedents,
+            coefficients,
+            constant,
+        }
+    }
+
+    /// Crisp output of the consequent for `inputs`
+    pub fn output(&self, inputs: &[T]) -> T {
+        linear_output(self.coefficients, self.constant, inputs)
+    }
+}
+
+fn linear_output<T: Real>(coefficients: &[T], constant: T, inputs: &[T]) -> T {
+    coefficients
+        .iter()
+        .zip(inputs)
+        .fold(constant, |sum, (&c, &x)| sum + c * x)
+}
+
+/// Antecedent terms combined with `and_method`; `terms` holds each input's terms
+fn firing_strength<T: Real, S: AsRef<[MembershipFunction<T>]>>(
+    and_method: TNorm,
+    antecedents: &[Option<usize>],
+    terms: &[S],
+    inputs: &[T],
+) -> T {
+    antecedents
+        .iter()
+        .zip(terms)
+        .zip(inputs)
+        .filter_map(|((term, terms), &x)| term.map(|t| terms.as_ref()[t].evaluate(x)))
+        .fold(T::ONE, |strength, mu| and_method.apply(strength, mu))
+}
+
+/// A Sugeno system over borrowed terms and rules
+#[derive(Debug, Clone, Copy)]
+pub struct SugenoSystem<'a, T = f64> {
+    inputs: &'a [&'a [MembershipFunction<T>]],
+    rules: &'a [SugenoRule<'a, T>],
+    and_method: TNorm,
+}
+
+impl<'a, T: Real> SugenoSystem<'a, T> {
+    /// Create a system from the terms of each input and a rule base
+    ///
+    /// Antecedents combine with the minimum t-norm.
+    pub const fn new(inputs: &'a [&'a [MembershipFunction<T>]], rules: &'a [SugenoRule<'a, T>]) -> Self {
+        SugenoSystem {
+            inputs,
+            rules,
+            and_method: TNorm::Minimum,
+        }
+    }
+
+    /// Combine antecedents with another t-norm (product is common for Sugeno
+    /// systems)
+    pub const fn with_and_method(mut self, method: TNorm) -> Self {
+        self.and_method = method;
+        self
+    }
+
+    pub fn inputs(&self) -> &'a [&'a [MembershipFunction<T>]] {
+        self.inputs
+    }
+
+    pub fn rules(&self) -> &'a [SugenoRule<'a, T>] {
+        self.rules
+    }
+
+    /// Degree to which `rule` applies to `inputs`
+    ///
+    /// # Panics
+    ///
+    /// If the rule refers to a term an input does not have.
+    pub fn firing_strength(&self, rule: &SugenoRule<T>, inputs: &[T]) -> T {
+        firing_strength(self.and_method, rule.antecedents, self.inputs, inputs)
+    }
+
+    /// Weighted average of the rule outputs for crisp inputs
+    ///
+    /// Returns `None` when no rule fires.
+    ///
+    /// # Panics
+    ///
+    /// If `inputs` does not have one value per input.
+    pub fn evaluate(&self, inputs: &[T]) -> Option<T> {
+        assert_eq!(
+            inputs.len(),
+            self.inputs.len(),
+            "Expected one value per input variable"
+        );
+        T::weighted_mean(
+            self.rules
+                .iter()
+                .map(|rule| (rule.output(inputs), self.firing_strength(rule, inputs))),
+        )
+    }
+}
+
+/// A Sugeno rule that owns its antecedents and coefficients
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedSugenoRule<T = f64> {
+    /// Term index per input, `None` where the rule ignores the input
+    pub antecedents: Vec<Option<usize>>,
+    /// Coefficient per input; empty for a zero-order rule
+    pub coefficients: Vec<T>,
+    pub constant: T,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Real> OwnedSugenoRule<T> {
+    /// A zero-order rule with a constant output
+    pub fn constant(antecedents: Vec<Option<usize>>, value: T) -> Self {
+        OwnedSugenoRule {
+            antecedents,
+            coefficients: Vec::new(),
+            constant: value,
+        }
+    }
+
+    /// A first-order rule with an output linear in the inputs
+    pub fn linear(antecedents: Vec<Option<usize>>, coefficients: Vec<T>, constant: T) -> Self {
+        OwnedSugenoRule {
+            antecedents,
+            coefficients,
+            constant,
+        }
+    }
+
+    /// Borrow the rule in the form a [`SugenoSystem`] uses
+    pub fn as_rule(&self) -> SugenoRule<'_, T> {
+        SugenoRule::linear(&self.antecedents, &self.coefficients, self.constant)
+    }
+
+    /// Crisp output of the consequent for `inputs`
+    pub fn output(&self, inputs: &[T]) -> T {
+        linear_output(&self.coefficients, self.constant, inputs)
+    }
+
+    /// The same rule with another number type
+    pub fn cast<U: Real>(&self) -> OwnedSugenoRule<U> {
+        OwnedSugenoRule {
+            antecedents: self.antecedents.clone(),
+            coefficients: self.coefficients.iter().map(|c| U::from_f64(c.to_f64())).collect(),
+            constant: U::from_f64(self.constant.to_f64()),
+        }
+    }
+}
+
+/// A Sugeno system that owns its terms and rules
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedSugenoSystem<T = f64> {
+    inputs: Vec<Vec<MembershipFunction<T>>>,
+    rules: Vec<OwnedSugenoRule<T>>,
+    and_method: TNorm,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Real> OwnedSugenoSystem<T> {
+    /// Create a system without rules from the terms of each input
+    ///
+    /// Antecedents combine with the minimum t-norm.
+    pub fn new(inputs: Vec<Vec<MembershipFunction<T>>>) -> Self {
+        OwnedSugenoSystem {
+            inputs,
+            rules: Vec::new(),
+            and_method: TNorm::Minimum,
+        }
+    }
+
+    /// Combine antecedents with another t-norm
+    pub fn with_and_method(mut self, method: TNorm) -> Self {
+        self.and_method = method;
+        self
+    }
+
+    /// Add a rule, returning its index
+    ///
+    /// # Panics
+    ///
+    /// If the rule does not have one antecedent per input or refers to a
+    /// term an input does not have.
+    pub fn add_rule(&mut self, rule: OwnedSugenoRule<T>) -> usize {
+        assert_eq!(
+            rule.antecedents.len(),
+            self.inputs.len(),
+            "Expected one antecedent per input variable"
+        );
+        for (term, terms) in rule.antecedents.iter().zip(&self.inputs) {
+            if let Some(t) = term {
+                assert!(*t < terms.len(), "Rule refers to a missing term");
+            }
+        }
+        self.rules.push(rule);
+        self.rules.len() - 1
+    }
+
+    pub fn inputs(&self) -> &[Vec<MembershipFunction<T>>] {
+        &self.inputs
+    }
+
+    pub fn rules(&self) -> &[OwnedSugenoRule<T>] {
+        &self.rules
+    }
+
+    pub fn and_method(&self) -> TNorm {
+        self.and_method
+    }
+
+    /// Degree to which `rule` applies to `inputs`
+    pub fn firing_strength(&self, rule: &OwnedSugenoRule<T>, inputs: &[T]) -> T {
+        firing_strength(self.and_method, &rule.antecedents, &self.inputs, inputs)
+    }
+
+    /// Weighted average of the rule outputs for crisp inputs
+    ///
+    /// Returns `None` when no rule fires.
+    ///
+    /// # Panics
+    ///
+    /// If `inputs` does not have one value per input.
+    pub fn evaluate(&self, inputs: &[T]) -> Option<T> {
+        assert_eq!(
+            inputs.len(),
+            self.inputs.len(),
+            "Expected one value per input variable"
+        );
+        T::weighted_mean(
+            self.rules
+                .iter()
+                .map(|rule| (rule.output(inputs), self.firing_strength(rule, inputs))),
+        )
+    }
+
+    /// The same system with another number type
+    pub fn cast<U: Real>(&self) -> OwnedSugenoSystem<U> {
+        OwnedSugenoSystem {
+            inputs: self
+                .inputs
+                .iter()
+                .map(|terms| terms.iter().map(MembershipFunction::cast).collect())
+                .collect(),
+            rules: self.rules.iter().map(OwnedSugenoRule::cast).collect(),
+            and_method: self.and_method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW_HIGH: [MembershipFunction; 2] = [
+        MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 },
+        MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 },
+    ];
+
+    #[test]
+    fn test_zero_order_interpolates_between_rules() {
+        let inputs: [&[MembershipFunction]; 1] = [&LOW_HIGH];
+        let rules = [
+            SugenoRule::constant(&[Some(0)], 0.0),
+            SugenoRule::constant(&[Some(1)], 100.0),
+        ];
+        let system = SugenoSystem::new(&inputs, &rules);
+
+        assert_eq!(system.evaluate(&[0.0]), Some(0.0));
+        assert_eq!(system.evaluate(&[2.5]), Some(25.0));
+        assert_eq!(system.evaluate(&[10.0]), Some(100.0));
+        assert_eq!(system.evaluate(&[20.0]), None);
+    }
+
+    #[test]
+    fn test_first_order_two_inputs() {
+        let inputs: [&[MembershipFunction]; 2] = [&LOW_HIGH, &LOW_HIGH];
+        let rules = [
+            SugenoRule::linear(&[Some(0), None], &[1.0, 0.0], 0.0),
+            SugenoRule::linear(&[Some(1), Some(1)], &[0.0, 2.0], 1.0),
+        ];
+        let system = SugenoSystem::new(&inputs, &rules).with_and_method(TNorm::AlgebraicProduct);
+
+        // Rule 1 fires at 0.6 with output 4; rule 2 at 0.4 * 0.5 with output 11
+        let expected = (0.6 * 4.0 + 0.2 * 11.0) / 0.8;
+        assert!((system.evaluate(&[4.0, 5.0]).unwrap() - expected).abs() < 1e-12);
+        assert_eq!(system.firing_strength(&rules[1], &[4.0, 5.0]), 0.2);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_owned_system_matches_borrowed() {
+        use alloc::vec;
+
+        let inputs: [&[MembershipFunction]; 2] = [&LOW_HIGH, &LOW_HIGH];
+        let rules = [
+            SugenoRule::linear(&[Some(0), None], &[1.0, 0.0], 0.0),
+            SugenoRule::linear(&[Some(1), Some(1)], &[0.0, 2.0], 1.0),
+        ];
+        let borrowed = SugenoSystem::new(&inputs, &rules).with_and_method(TNorm::AlgebraicProduct);
+
+        let mut owned = OwnedSugenoSystem::new(vec![LOW_HIGH.to_vec(), LOW_HIGH.to_vec()])
+            .with_and_method(TNorm::AlgebraicProduct);
+        owned.add_rule(OwnedSugenoRule::linear(vec![Some(0), None], vec![1.0, 0.0], 0.0));
+        owned.add_rule(OwnedSugenoRule::linear(vec![Some(1), Some(1)], vec![0.0, 2.0], 1.0));
+
+        for point in [[4.0, 5.0], [0.0, 10.0], [7.5, 2.5]] {
+            assert_eq!(owned.evaluate(&point), borrowed.evaluate(&point));
+        }
+        assert_eq!(owned.rules()[1].as_rule(), rules[1]);
+        let single = owned.cast::<f32>().evaluate(&[4.0, 5.0]).unwrap();
+        assert!((single as f64 - owned.evaluate(&[4.0, 5.0]).unwrap()).abs() < 1e-5);
+    }
+
+    #[test]
+    #[should_panic(expected = "one value per input")]
+    fn test_wrong_input_count_panics() {
+        let inputs: [&[MembershipFunction]; 1] = [&LOW_HIGH];
+        SugenoSystem::new(&inputs, &[]).evaluate(&[1.0, 2.0]);
+    }
+}
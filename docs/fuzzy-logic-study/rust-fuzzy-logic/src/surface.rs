@@ -0,0 +1,411 @@
+//! Response Curves and Control Surfaces
+//!
+//! This module sweeps one or two inputs of a controller over their
+//! universes, holding the other inputs fixed, and records the output. The
+//! results can be exported as CSV, gnuplot data files or standalone SVG
+//! charts, all generated in pure Rust.
+
+use std::fmt::Write;
+
+use crate::svg::{color_map, SvgDocument, PALETTE};
+use crate::system::{linspace, FuzzySystem};
+
+/// Output of a controller as one input is swept
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseCurve {
+    pub input: String,
+    pub output: String,
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+}
+
+impl ResponseCurve {
+    /// Sweep an arbitrary single-input function
+    ///
+    /// Useful for the closure-based controllers:
+    /// `ResponseCurve::from_fn("temp", "fan", 0.0, 40.0, 81, |x| controller.evaluate(x))`
+    pub fn from_fn<F>(
+        input: impl Into<String>,
+        output: impl Into<String>,
+        min: f64,
+        max: f64,
+        steps: usize,
+        mut f: F,
+    ) -> Self
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let xs = linspace(min, max, steps);
+        let ys = xs.iter().map(|&x| f(x)).collect();
+        ResponseCurve {
+            input: input.into(),
+            output: output.into(),
+            xs,
+            ys,
+        }
+    }
+
+    /// Sweep one input of a system over its universe
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system to evaluate
+    /// * `input` - Index of the swept input
+    /// * `output` - Index of the recorded output
+    /// * `fixed` - Values for all inputs (the swept one is ignored); pass an
+    ///   empty slice to hold the other inputs at the middle of their universes
+    /// * `steps` - Number of sample points
+    pub fn sweep(
+        system: &FuzzySystem,
+        input: usize,
+        output: usize,
+        fixed: &[f64],
+        steps: usize,
+    ) -> Self {
+        let variable = &system.inputs()[input];
+        let mut point = base_point(system, fixed);
+        let mut scratch = system.scratch();
+        let mut out = vec![0.0; system.outputs().len()];
+
+        Self::from_fn(
+            variable.name.clone(),
+            system.outputs()[output].name.clone(),
+            variable.min,
+            variable.max,
+            steps,
+            |x| {
+                point[input] = x;
+                system.evaluate_with(&point, &mut out, &mut scratch);
+                out[output]
+            },
+        )
+    }
+
+    /// Comma-separated values with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{},{}\n", self.input, self.output);
+        for (x, y) in self.xs.iter().zip(&self.ys) {
+            let _ = writeln!(csv, "{},{}", x, y);
+        }
+        csv
+    }
+
+    /// Whitespace-separated columns for gnuplot (`plot 'file' with lines`)
+    pub fn to_gnuplot(&self) -> String {
+        let mut data = format!("# {} {}\n", self.input, self.output);
+        for (x, y) in self.xs.iter().zip(&self.ys) {
+            let _ = writeln!(data, "{} {}", x, y);
+        }
+        data
+    }
+
+    /// Standalone SVG line plot
+    pub fn to_svg(&self) -> String {
+        let (lo, hi) = value_range(&self.ys);
+        let mut doc = SvgDocument::new(640.0, 420.0);
+        let frame = doc.frame(
+            (first(&self.xs), last(&self.xs)),
+            pad_range(lo, hi),
+            30.0,
+        );
+        doc.title(&format!("{} response to {}", self.output, self.input));
+        doc.axes(&frame, &self.input, &self.output);
+        doc.polyline(&frame, &self.xs, &self.ys, PALETTE[0], 2.0);
+        doc.finish()
+    }
+}
+
+/// Output of a controller as two inputs are swept over a grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseSurface {
+    pub x_input: String,
+    pub y_input: String,
+    pub output: String,
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    /// `values[j][i]` is the output at `(xs[i], ys[j])`
+    pub values: Vec<Vec<f64>>,
+}
+
+impl ResponseSurface {
+    /// Sweep an arbitrary two-input function over a grid
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_fn<F>(
+        x_input: impl Into<String>,
+        y_input: impl Into<String>,
+        output: impl Into<String>,
+        x_range: (f64, f64),
+        y_range: (f64, f64),
+        steps: usize,
+        mut f: F,
+    ) -> Self
+    where
+        F: FnMut(f64, f64) -> f64,
+    {
+        let xs = linspace(x_range.0, x_range.1, steps);
+        let ys = linspace(y_range.0, y_range.1, steps);
+        let values = ys
+            .iter()
+            .map(|&y| xs.iter().map(|&x| f(x, y)).collect())
+            .collect();
+        ResponseSurface {
+            x_input: x_input.into(),
+            y_input: y_input.into(),
+            output: output.into(),
+            xs,
+            ys,
+            values,
+        }
+    }
+
+    /// Sweep two inputs of a system over their universes
+    ///
+    /// `fixed` works as in [`ResponseCurve::sweep`].
+    pub fn sweep(
+        system: &FuzzySystem,
+        x_input: usize,
+        y_input: usize,
+        output: usize,
+        fixed: &[f64],
+        steps: usize,
+    ) -> Self {
+        assert_ne!(x_input, y_input, "Sweep two different inputs");
+        let vx = &system.inputs()[x_input];
+        let vy = &system.inputs()[y_input];
+        let mut point = base_point(system, fixed);
+        let mut scratch = system.scratch();
+        let mut out = vec![0.0; system.outputs().len()];
+
+        Self::from_fn(
+            vx.name.clone(),
+            vy.name.clone(),
+            system.outputs()[output].name.clone(),
+            (vx.min, vx.max),
+            (vy.min, vy.max),
+            steps,
+            |x, y| {
+                point[x_input] = x;
+                point[y_input] = y;
+                system.evaluate_with(&point, &mut out, &mut scratch);
+                out[output]
+            },
+        )
+    }
+
+    /// Smallest and largest output value
+    pub fn value_range(&self) -> (f64, f64) {
+        let all: Vec<f64> = self.values.iter().flatten().copied().collect();
+        value_range(&all)
+    }
+
+    /// Comma-separated values in long format: one `x,y,output` row per point
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{},{},{}\n", self.x_input, self.y_input, self.output);
+        for (row, &y) in self.values.iter().zip(&self.ys) {
+            for (&z, &x) in row.iter().zip(&self.xs) {
+                let _ = writeln!(csv, "{},{},{}", x, y, z);
+            }
+        }
+        csv
+    }
+
+    /// Gnuplot grid data (`splot 'file' with pm3d`): one block per y value,
+    /// separated by blank lines
+    pub fn to_gnuplot(&self) -> String {
+        let mut data = format!("# {} {} {}\n", self.x_input, self.y_input, self.output);
+        for (row, &y) in self.values.iter().zip(&self.ys) {
+            for (&z, &x) in row.iter().zip(&self.xs) {
+                let _ = writeln!(data, "{} {} {}", x, y, z);
+            }
+            data.push('\n');
+        }
+        data
+    }
+
+    /// Standalone SVG heatmap with a color bar
+    pub fn to_svg(&self) -> String {
+        let (lo, hi) = self.value_range();
+        let mut doc = SvgDocument::new(640.0, 480.0);
+        let frame = doc.frame(
+            (first(&self.xs), last(&self.xs)),
+            (first(&self.ys), last(&self.ys)),
+            90.0,
+        );
+        doc.title(&format!("{} over {} and {}", self.output, self.x_input, self.y_input));
+
+        // Each cell is centered on its sample point
+        let cell_w = frame.width / self.xs.len().max(1) as f64;
+        let cell_h = frame.height / self.ys.len().max(1) as f64;
+        let scale = |v: f64| if hi > lo { (v - lo) / (hi - lo) } else { 0.5 };
+        for (j, row) in self.values.iter().enumerate() {
+            for (i, &z) in row.iter().enumerate() {
+                let x = frame.left + i as f64 * cell_w;
+                let y = frame.bottom() - (j + 1) as f64 * cell_h;
+                doc.rect(x, y, cell_w + 0.5, cell_h + 0.5, &color_map(scale(z)));
+            }
+        }
+        let mut axes_frame = frame;
+        axes_frame.x_range = outer_range(&self.xs);
+        axes_frame.y_range = outer_range(&self.ys);
+        doc.axes(&axes_frame, &self.x_input, &self.y_input);
+
+        // Color bar
+        let bar_x = frame.right() + 25.0;
+        let steps = 50;
+        let step_h = frame.height / steps as f64;
+        for k in 0..steps {
+            let t = (k as f64 + 0.5) / steps as f64;
+            let y = frame.bottom() - (k + 1) as f64 * step_h;
+            doc.rect(bar_x, y, 18.0, step_h + 0.5, &color_map(t));
+        }
+        doc.text(bar_x + 22.0, frame.top + 10.0, &format!("{:.2}", hi), "start", 11.0);
+        doc.text(bar_x + 22.0, frame.bottom(), &format!("{:.2}", lo), "start", 11.0);
+        doc.text(bar_x + 9.0, frame.top - 8.0, &self.output, "middle", 12.0);
+
+        doc.finish()
+    }
+}
+
+/// Input vector holding every input at `fixed` or at its universe midpoint
+fn base_point(system: &FuzzySystem, fixed: &[f64]) -> Vec<f64> {
+    if fixed.is_empty() {
+        system
+            .inputs()
+            .iter()
+            .map(|v| (v.min + v.max) / 2.0)
+            .collect()
+    } else {
+        assert_eq!(
+            fixed.len(),
+            system.inputs().len(),
+            "Expected one fixed value per input variable"
+        );
+        fixed.to_vec()
+    }
+}
+
+fn value_range(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        })
+}
+
+/// Widen a data range by 5% so curves do not touch the frame
+fn pad_range(lo: f64, hi: f64) -> (f64, f64) {
+    if !(lo.is_finite() && hi.is_finite()) {
+        return (0.0, 1.0);
+    }
+    let pad = if hi > lo { (hi - lo) * 0.05 } else { 1.0 };
+    (lo - pad, hi + pad)
+}
+
+/// Range covered by heatmap cells centered on the sample points
+fn outer_range(points: &[f64]) -> (f64, f64) {
+    if points.len() < 2 {
+        return (first(points) - 0.5, first(points) + 0.5);
+    }
+    let half = (points[1] - points[0]) / 2.0;
+    (first(points) - half, last(points) + half)
+}
+
+fn first(values: &[f64]) -> f64 {
+    values.first().copied().unwrap_or(0.0)
+}
+
+fn last(values: &[f64]) -> f64 {
+    values.last().copied().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::membership::MembershipFunction;
+    use crate::system::{Antecedent, Consequent, LinguisticVariable, Rule};
+
+    fn tipping_system() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        for name in ["service", "food"] {
+            system.add_input(
+                LinguisticVariable::new(name, 0.0, 10.0)
+                    .with_term("poor", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 })
+                    .with_term("good", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 }),
+            );
+        }
+        system.add_output(
+            LinguisticVariable::new("tip", 0.0, 30.0)
+                .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 15.0 })
+                .with_term("high", MembershipFunction::Triangular { a: 15.0, b: 30.0, c: 30.0 }),
+        );
+        system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 1), Antecedent::new(1, 1)],
+            vec![Consequent::new(0, 1)],
+        ));
+        system
+    }
+
+    #[test]
+    fn test_curve_sweep_holds_other_inputs() {
+        let system = tipping_system();
+        let curve = ResponseCurve::sweep(&system, 0, 0, &[0.0, 8.0], 11);
+
+        assert_eq!(curve.input, "service");
+        assert_eq!(curve.output, "tip");
+        assert_eq!(curve.xs.len(), 11);
+        for (&x, &y) in curve.xs.iter().zip(&curve.ys) {
+            assert_eq!(y, system.evaluate(&[x, 8.0])[0]);
+        }
+    }
+
+    #[test]
+    fn test_surface_sweep_layout() {
+        let system = tipping_system();
+        let surface = ResponseSurface::sweep(&system, 0, 1, 0, &[], 5);
+
+        assert_eq!(surface.values.len(), 5);
+        assert!(surface.values.iter().all(|row| row.len() == 5));
+        // values[j][i] is the output at (xs[i], ys[j])
+        assert_eq!(surface.values[1][3], system.evaluate(&[surface.xs[3], surface.ys[1]])[0]);
+
+        let (lo, hi) = surface.value_range();
+        assert!(lo < hi);
+    }
+
+    #[test]
+    fn test_from_fn_for_closures() {
+        let curve = ResponseCurve::from_fn("x", "y", 0.0, 1.0, 3, |x| 2.0 * x);
+        assert_eq!(curve.ys, vec![0.0, 1.0, 2.0]);
+
+        let surface = ResponseSurface::from_fn("a", "b", "c", (0.0, 1.0), (0.0, 2.0), 2, |a, b| a + b);
+        assert_eq!(surface.values, vec![vec![0.0, 1.0], vec![2.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_csv_and_gnuplot_export() {
+        let curve = ResponseCurve::from_fn("x", "y", 0.0, 1.0, 3, |x| x * x);
+        assert_eq!(curve.to_csv(), "x,y\n0,0\n0.5,0.25\n1,1\n");
+        assert_eq!(curve.to_gnuplot(), "# x y\n0 0\n0.5 0.25\n1 1\n");
+
+        let surface = ResponseSurface::from_fn("a", "b", "c", (0.0, 1.0), (0.0, 1.0), 2, |a, b| a * b);
+        assert_eq!(surface.to_csv(), "a,b,c\n0,0,0\n1,0,0\n0,1,0\n1,1,1\n");
+        assert_eq!(surface.to_gnuplot(), "# a b c\n0 0 0\n1 0 0\n\n0 1 0\n1 1 1\n\n");
+    }
+
+    #[test]
+    fn test_svg_export() {
+        let system = tipping_system();
+
+        let line = ResponseCurve::sweep(&system, 0, 0, &[], 21).to_svg();
+        assert!(line.starts_with("<svg"));
+        assert!(line.trim_end().ends_with("</svg>"));
+        assert_eq!(line.matches("<polyline").count(), 1);
+
+        let heatmap = ResponseSurface::sweep(&system, 0, 1, 0, &[], 4).to_svg();
+        // 16 cells + 50 color bar steps + background + plot frame
+        assert_eq!(heatmap.matches("<rect").count(), 16 + 50 + 1 + 1);
+        assert!(heatmap.contains("tip over service and food"));
+    }
+}
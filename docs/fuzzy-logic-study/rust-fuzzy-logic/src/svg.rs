@@ -0,0 +1,283 @@
+//! Minimal SVG Writer
+//!
+//! Crate-internal helpers for building standalone SVG charts without any
+//! external tools: a plotting frame that maps data to pixels, axes with
+//! ticks, polylines, filled areas, rectangles, text, legends and a color map.
+
+use std::fmt::Write;
+
+/// Line colors used for successive series
+pub(crate) const PALETTE: [&str; 8] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/// Plotting area inside an SVG document, with data ranges for both axes
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Frame {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+    pub x_range: (f64, f64),
+    pub y_range: (f64, f64),
+}
+
+impl Frame {
+    /// Pixel x coordinate of a data value
+    pub fn x(&self, v: f64) -> f64 {
+        let (lo, hi) = self.x_range;
+        self.left + (v - lo) / span(lo, hi) * self.width
+    }
+
+    /// Pixel y coordinate of a data value (larger values are higher up)
+    pub fn y(&self, v: f64) -> f64 {
+        let (lo, hi) = self.y_range;
+        self.top + self.height - (v - lo) / span(lo, hi) * self.height
+    }
+
+    pub fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+}
+
+/// Width of a range, never zero
+fn span(lo: f64, hi: f64) -> f64 {
+    if hi > lo {
+        hi - lo
+    } else {
+        1.0
+    }
+}
+
+/// An SVG document under construction
+pub(crate) struct SvgDocument {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl SvgDocument {
+    pub fn new(width: f64, height: f64) -> Self {
+        let mut doc = SvgDocument {
+            width,
+            height,
+            body: String::new(),
+        };
+        doc.rect(0.0, 0.0, width, height, "white");
+        doc
+    }
+
+    /// Standard frame leaving room for a title, axis labels and a legend
+    pub fn frame(&self, x_range: (f64, f64), y_range: (f64, f64), right_margin: f64) -> Frame {
+        Frame {
+            left: 70.0,
+            top: 40.0,
+            width: self.width - 70.0 - right_margin,
+            height: self.height - 40.0 - 60.0,
+            x_range,
+            y_range,
+        }
+    }
+
+    pub fn title(&mut self, title: &str) {
+        let x = self.width / 2.0;
+        self.text(x, 24.0, title, "middle", 16.0);
+    }
+
+    /// Draw the frame border, ticks and axis labels
+    pub fn axes(&mut self, frame: &Frame, x_label: &str, y_label: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="none" stroke="black"/>"#,
+            frame.left, frame.top, frame.width, frame.height
+        );
+
+        for v in nice_ticks(frame.x_range.0, frame.x_range.1, 6) {
+            let x = frame.x(v);
+            self.line(x, frame.bottom(), x, frame.bottom() + 5.0, "black", 1.0);
+            self.text(x, frame.bottom() + 20.0, &format_tick(v), "middle", 12.0);
+        }
+        for v in nice_ticks(frame.y_range.0, frame.y_range.1, 5) {
+            let y = frame.y(v);
+            self.line(frame.left - 5.0, y, frame.left, y, "black", 1.0);
+            self.text(frame.left - 8.0, y + 4.0, &format_tick(v), "end", 12.0);
+        }
+
+        let cx = frame.left + frame.width / 2.0;
+        self.text(cx, frame.bottom() + 45.0, x_label, "middle", 14.0);
+        let cy = frame.top + frame.height / 2.0;
+        let _ = writeln!(
+            self.body,
+            r#"<text x="18" y="{:.2}" text-anchor="middle" font-family="sans-serif" font-size="14" transform="rotate(-90 18 {:.2})">{}</text>"#,
+            cy,
+            cy,
+            escape(y_label)
+        );
+    }
+
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: &str, width: f64) {
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="{}"/>"#,
+            x1, y1, x2, y2, color, width
+        );
+    }
+
+    /// Dashed line, used for reference levels and markers
+    pub fn dashed_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="1.5" stroke-dasharray="6,4"/>"#,
+            x1, y1, x2, y2, color
+        );
+    }
+
+    /// Series of data points joined by straight lines
+    pub fn polyline(&mut self, frame: &Frame, xs: &[f64], ys: &[f64], color: &str, width: f64) {
+        let points: Vec<String> = xs
+            .iter()
+            .zip(ys)
+            .map(|(&x, &y)| format!("{:.2},{:.2}", frame.x(x), frame.y(y)))
+            .collect();
+        let _ = writeln!(
+            self.body,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            points.join(" "),
+            color,
+            width
+        );
+    }
+
+    /// Area between a series and the x axis
+    pub fn filled_area(&mut self, frame: &Frame, xs: &[f64], ys: &[f64], color: &str, opacity: f64) {
+        if xs.is_empty() {
+            return;
+        }
+        let base = frame.y(frame.y_range.0.max(0.0).min(frame.y_range.1));
+        let mut points = vec![format!("{:.2},{:.2}", frame.x(xs[0]), base)];
+        points.extend(
+            xs.iter()
+                .zip(ys)
+                .map(|(&x, &y)| format!("{:.2},{:.2}", frame.x(x), frame.y(y))),
+        );
+        points.push(format!("{:.2},{:.2}", frame.x(xs[xs.len() - 1]), base));
+        let _ = writeln!(
+            self.body,
+            r#"<polygon points="{}" fill="{}" fill-opacity="{}" stroke="none"/>"#,
+            points.join(" "),
+            color,
+            opacity
+        );
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+            x, y, width, height, fill
+        );
+    }
+
+    pub fn circle(&mut self, x: f64, y: f64, r: f64, fill: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{}" fill="{}"/>"#,
+            x, y, r, fill
+        );
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, text: &str, anchor: &str, size: f64) {
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="{}" font-family="sans-serif" font-size="{}">{}</text>"#,
+            x,
+            y,
+            anchor,
+            size,
+            escape(text)
+        );
+    }
+
+    /// Legend entries stacked in the top-right corner of a frame
+    pub fn legend(&mut self, frame: &Frame, entries: &[(String, &str)]) {
+        for (i, (label, color)) in entries.iter().enumerate() {
+            let y = frame.top + 15.0 + i as f64 * 18.0;
+            let x = frame.right() + 10.0;
+            self.line(x, y - 4.0, x + 20.0, y - 4.0, color, 2.5);
+            self.text(x + 26.0, y, label, "start", 12.0);
+        }
+    }
+
+    /// Close the document and return the SVG markup
+    pub fn finish(self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+            self.body,
+            w = self.width,
+            h = self.height
+        )
+    }
+}
+
+/// Color for `t` in `[0, 1]` on a blue-green-yellow scale
+pub(crate) fn color_map(t: f64) -> String {
+    const STOPS: [(f64, f64, f64); 5] = [
+        (68.0, 1.0, 84.0),
+        (59.0, 82.0, 139.0),
+        (33.0, 145.0, 140.0),
+        (94.0, 201.0, 98.0),
+        (253.0, 231.0, 37.0),
+    ];
+    let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+    let pos = t * (STOPS.len() - 1) as f64;
+    let i = (pos.floor() as usize).min(STOPS.len() - 2);
+    let f = pos - i as f64;
+    let (r0, g0, b0) = STOPS[i];
+    let (r1, g1, b1) = STOPS[i + 1];
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        (r0 + (r1 - r0) * f).round() as u8,
+        (g0 + (g1 - g0) * f).round() as u8,
+        (b0 + (b1 - b0) * f).round() as u8
+    )
+}
+
+/// Roughly `count` round tick values covering `[min, max]`
+pub(crate) fn nice_ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    if !(min.is_finite() && max.is_finite()) || max <= min || count == 0 {
+        return vec![min];
+    }
+    let raw = (max - min) / count as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 2.5, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&s| s >= raw)
+        .unwrap_or(10.0 * magnitude);
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+fn format_tick(v: f64) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Escape text for use inside SVG elements
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,884 @@
+//! Rule-Based Fuzzy Systems
+//!
+//! This module provides a declarative Mamdani inference system. Inputs and
+//! outputs are linguistic variables, and rules refer to their terms by index.
+//! Unlike the closure-based controllers in [`crate::inference`], a
+//! `FuzzySystem` can be inspected, analysed and edited after it is built.
+
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::defuzzification::DefuzzificationMethod;
+#[cfg(feature = "rayon")]
+use crate::inference::PARALLEL_CHUNK;
+use crate::membership::{FuzzySet, MembershipFunction};
+use crate::numeric::Real;
+pub use crate::operations::{SNorm, TNorm};
+
+/// Rule count from which aggregation is split across threads (`rayon` feature)
+#[cfg(feature = "rayon")]
+const PARALLEL_RULE_THRESHOLD: usize = 64;
+
+/// Universe points per aggregation task (`rayon` feature)
+#[cfg(feature = "rayon")]
+const PARALLEL_AGGREGATION_CHUNK: usize = 16;
+
+/// A linguistic variable: a named universe of discourse partitioned into terms
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinguisticVariable<T = f64> {
+    pub name: String,
+    pub min: T,
+    pub max: T,
+    pub terms: Vec<FuzzySet<T>>,
+}
+
+impl<T: Real> LinguisticVariable<T> {
+    /// Create a new variable with an empty term set
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Variable name
+    /// * `min` - Lower bound of the universe of discourse
+    /// * `max` - Upper bound of the universe of discourse
+    pub fn new(name: impl Into<String>, min: T, max: T) -> Self {
+        LinguisticVariable {
+            name: name.into(),
+            min,
+            max,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Add a term and return the variable (builder style)
+    pub fn with_term(mut self, name: impl Into<String>, mf: MembershipFunction<T>) -> Self {
+        self.add_term(name, mf);
+        self
+    }
+
+    /// Add a term and return its index
+    pub fn add_term(&mut self, name: impl Into<String>, mf: MembershipFunction<T>) -> usize {
+        self.terms.push(FuzzySet::new(name, mf));
+        self.terms.len() - 1
+    }
+
+    /// Find a term by name
+    pub fn term_index(&self, name: &str) -> Option<usize> {
+        self.terms.iter().position(|t| t.name == name)
+    }
+
+    /// Membership degree of `x` in every term, in term order
+    pub fn fuzzify(&self, x: T) -> Vec<T> {
+        self.terms.iter().map(|t| t.membership(x)).collect()
+    }
+
+    /// Evenly spaced sample points covering `[min, max]` inclusively
+    pub fn universe(&self, steps: usize) -> Vec<T> {
+        linspace(self.min, self.max, steps)
+    }
+
+    /// The same variable with another number type
+    pub fn cast<U: Real>(&self) -> LinguisticVariable<U> {
+        LinguisticVariable {
+            name: self.name.clone(),
+            min: U::from_f64(self.min.to_f64()),
+            max: U::from_f64(self.max.to_f64()),
+            terms: self.terms.iter().map(FuzzySet::cast).collect(),
+        }
+    }
+}
+
+/// How the antecedents of a rule are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connective {
+    And,
+    Or,
+}
+
+/// One clause of a rule's IF part: `input is [not] term`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Antecedent {
+    /// Index of the input variable
+    pub variable: usize,
+    /// Index of the term within that variable
+    pub term: usize,
+    /// Whether the clause is negated (`is not`)
+    pub negated: bool,
+}
+
+impl Antecedent {
+    /// Create the clause `variable is term`
+    pub fn new(variable: usize, term: usize) -> Self {
+        Antecedent {
+            variable,
+            term,
+            negated: false,
+        }
+    }
+
+    /// Create the clause `variable is not term`
+    pub fn not(variable: usize, term: usize) -> Self {
+        Antecedent {
+            variable,
+            term,
+            negated: true,
+        }
+    }
+}
+
+/// One clause of a rule's THEN part: `output is term`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Consequent {
+    /// Index of the output variable
+    pub variable: usize,
+    /// Index of the term within that variable
+    pub term: usize,
+}
+
+impl Consequent {
+    /// Create the clause `variable is term`
+    pub fn new(variable: usize, term: usize) -> Self {
+        Consequent { variable, term }
+    }
+}
+
+/// A fuzzy rule: IF antecedents THEN consequents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule<T = f64> {
+    pub antecedents: Vec<Antecedent>,
+    pub connective: Connective,
+    pub consequents: Vec<Consequent>,
+    /// Scales the firing strength (1.0 by default)
+    pub weight: T,
+}
+
+impl<T: Real> Rule<T> {
+    /// Create an AND rule with weight 1.0
+    pub fn new(antecedents: Vec<Antecedent>, consequents: Vec<Consequent>) -> Self {
+        Rule {
+            antecedents,
+            connective: Connective::And,
+            consequents,
+            weight: T::ONE,
+        }
+    }
+
+    /// Set how the antecedents are combined
+    pub fn with_connective(mut self, connective: Connective) -> Self {
+        self.connective = connective;
+        self
+    }
+
+    /// Set the rule weight
+    pub fn with_weight(mut self, weight: T) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// The same rule with another number type
+    pub fn cast<U: Real>(&self) -> Rule<U> {
+        Rule {
+            antecedents: self.antecedents.clone(),
+            connective: self.connective,
+            consequents: self.consequents.clone(),
+            weight: U::from_f64(self.weight.to_f64()),
+        }
+    }
+}
+
+/// Multi-input, multi-output Mamdani fuzzy inference system
+///
+/// Systems compute in `f64` unless another [`Real`] type is chosen, usually
+/// by converting a finished design with [`FuzzySystem::cast`].
+#[derive(Debug, Clone)]
+pub struct FuzzySystem<T = f64> {
+    inputs: Vec<LinguisticVariable<T>>,
+    outputs: Vec<LinguisticVariable<T>>,
+    rules: Vec<Rule<T>>,
+    and_method: TNorm,
+    or_method: SNorm,
+    implication: TNorm,
+    aggregation: SNorm,
+    defuzzification: DefuzzificationMethod,
+    resolution: usize,
+}
+
+impl<T: Real> Default for FuzzySystem<T> {
+    fn default() -> Self {
+        FuzzySystem {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            rules: Vec::new(),
+            and_method: TNorm::Minimum,
+            or_method: SNorm::Maximum,
+            implication: TNorm::Minimum,
+            aggregation: SNorm::Maximum,
+            defuzzification: DefuzzificationMethod::Centroid,
+            resolution: 101,
+        }
+    }
+}
+
+impl FuzzySystem {
+    /// Create an empty `f64` system using min/max operators and centroid
+    /// defuzzification
+    ///
+    /// `FuzzySystem::<T>::default()` creates one over another number type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Real> FuzzySystem<T> {
+    /// Add an input variable and return its index
+    pub fn add_input(&mut self, variable: LinguisticVariable<T>) -> usize {
+        self.inputs.push(variable);
+        self.inputs.len() - 1
+    }
+
+    /// Add an output variable and return its index
+    pub fn add_output(&mut self, variable: LinguisticVariable<T>) -> usize {
+        self.outputs.push(variable);
+        self.outputs.len() - 1
+    }
+
+    /// Add a rule and return its index
+    ///
+    /// Panics if the rule refers to a variable or term that does not exist.
+    pub fn add_rule(&mut self, rule: Rule<T>) -> usize {
+        for a in &rule.antecedents {
+            assert!(
+                a.variable < self.inputs.len() && a.term < self.inputs[a.variable].terms.len(),
+                "Antecedent refers to an unknown input term"
+            );
+        }
+        for c in &rule.consequents {
+            assert!(
+                c.variable < self.outputs.len() && c.term < self.outputs[c.variable].terms.len(),
+                "Consequent refers to an unknown output term"
+            );
+        }
+        self.rules.push(rule);
+        self.rules.len() - 1
+    }
+
+    /// Add a term to an input variable and return the term index
+    ///
+    /// Existing rules are unaffected since term indices do not change.
+    pub fn add_input_term(&mut self, input: usize, name: impl Into<String>, mf: MembershipFunction<T>) -> usize {
+        self.inputs[input].add_term(name, mf)
+    }
+
+    /// Add a term to an output variable and return the term index
+    pub fn add_output_term(&mut self, output: usize, name: impl Into<String>, mf: MembershipFunction<T>) -> usize {
+        self.outputs[output].add_term(name, mf)
+    }
+
+    /// Replace the membership function of an input term
+    pub fn set_input_term(&mut self, input: usize, term: usize, mf: MembershipFunction<T>) {
+        self.inputs[input].terms[term].membership_function = mf;
+    }
+
+    /// Replace the membership function of an output term
+    pub fn set_output_term(&mut self, output: usize, term: usize, mf: MembershipFunction<T>) {
+        self.outputs[output].terms[term].membership_function = mf;
+    }
+
+    /// Set the weight of a rule
+    pub fn set_rule_weight(&mut self, index: usize, weight: T) {
+        self.rules[index].weight = weight;
+    }
+
+    /// Remove and return a rule; later rules move down by one
+    pub fn remove_rule(&mut self, index: usize) -> Rule<T> {
+        self.rules.remove(index)
+    }
+
+    pub fn inputs(&self) -> &[LinguisticVariable<T>] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[LinguisticVariable<T>] {
+        &self.outputs
+    }
+
+    pub fn rules(&self) -> &[Rule<T>] {
+        &self.rules
+    }
+
+    /// Find an input variable by name
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        self.inputs.iter().position(|v| v.name == name)
+    }
+
+    /// Find an output variable by name
+    pub fn output_index(&self, name: &str) -> Option<usize> {
+        self.outputs.iter().position(|v| v.name == name)
+    }
+
+    pub fn and_method(&self) -> TNorm {
+        self.and_method
+    }
+
+    pub fn set_and_method(&mut self, method: TNorm) {
+        self.and_method = method;
+    }
+
+    pub fn or_method(&self) -> SNorm {
+        self.or_method
+    }
+
+    pub fn set_or_method(&mut self, method: SNorm) {
+        self.or_method = method;
+    }
+
+    pub fn implication(&self) -> TNorm {
+        self.implication
+    }
+
+    pub fn set_implication(&mut self, method: TNorm) {
+        self.implication = method;
+    }
+
+    pub fn aggregation(&self) -> SNorm {
+        self.aggregation
+    }
+
+    pub fn set_aggregation(&mut self, method: SNorm) {
+        self.aggregation = method;
+    }
+
+    pub fn defuzzification(&self) -> DefuzzificationMethod {
+        self.defuzzification
+    }
+
+    pub fn set_defuzzification(&mut self, method: DefuzzificationMethod) {
+        self.defuzzification = method;
+    }
+
+    /// Number of points used to sample each output universe
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn set_resolution(&mut self, steps: usize) {
+        assert!(steps >= 2, "Resolution must be at least 2");
+        self.resolution = steps;
+    }
+
+    /// The same system with another number type, e.g. `system.cast::<Q16_16>()`
+    /// to run a design on a target without floating point
+    ///
+    /// Parameters are rounded to the nearest representable value.
+    pub fn cast<U: Real>(&self) -> FuzzySystem<U> {
+        FuzzySystem {
+            inputs: self.inputs.iter().map(LinguisticVariable::cast).collect(),
+            outputs: self.outputs.iter().map(LinguisticVariable::cast).collect(),
+            rules: self.rules.iter().map(Rule::cast).collect(),
+            and_method: self.and_method,
+            or_method: self.or_method,
+            implication: self.implication,
+            aggregation: self.aggregation,
+            defuzzification: self.defuzzification,
+            resolution: self.resolution,
+        }
+    }
+
+    /// Firing strength of a rule for crisp input values
+    ///
+    /// The antecedent degrees are combined with the AND or OR operator and
+    /// the result is scaled by the rule weight.
+    pub fn firing_strength(&self, rule: &Rule<T>, inputs: &[T]) -> T {
+        self.combine_antecedents(rule, |a| self.inputs[a.variable].terms[a.term].membership(inputs[a.variable]))
+    }
+
+    /// Firing strength of a rule for inputs already fuzzified into term
+    /// degrees, one vector per input variable as [`LinguisticVariable::fuzzify`]
+    /// returns them
+    ///
+    /// This lets an input be described by a fuzzy set rather than a crisp
+    /// value, e.g. the output of another system.
+    pub fn firing_strength_from_degrees(&self, rule: &Rule<T>, degrees: &[Vec<T>]) -> T {
+        self.combine_antecedents(rule, |a| degrees[a.variable][a.term])
+    }
+
+    fn combine_antecedents(&self, rule: &Rule<T>, degree: impl Fn(&Antecedent) -> T) -> T {
+        let mut degrees = rule.antecedents.iter().map(|a| {
+            let mu = degree(a);
+            if a.negated {
+                T::ONE - mu
+            } else {
+                mu
+            }
+        });
+
+        let first = match degrees.next() {
+            Some(mu) => mu,
+            None => return T::ZERO,
+        };
+        let combined = match rule.connective {
+            Connective::And => degrees.fold(first, |acc, mu| self.and_method.apply(acc, mu)),
+            Connective::Or => degrees.fold(first, |acc, mu| self.or_method.apply(acc, mu)),
+        };
+        combined * rule.weight
+    }
+
+    /// Firing strength of every rule, in rule order
+    pub fn firing_strengths(&self, inputs: &[T]) -> Vec<T> {
+        assert_eq!(
+            inputs.len(),
+            self.inputs.len(),
+            "Expected one value per input variable"
+        );
+        self.rules
+            .iter()
+            .map(|rule| self.firing_strength(rule, inputs))
+            .collect()
+    }
+
+    /// Aggregated output fuzzy set of one output variable, sampled on its universe
+    pub fn aggregate(&self, output: usize, strengths: &[T]) -> (Vec<T>, Vec<T>) {
+        let variable = &self.outputs[output];
+        let universe = variable.universe(self.resolution);
+        let mut aggregated = vec![T::ZERO; universe.len()];
+
+        for (rule, &strength) in self.rules.iter().zip(strengths) {
+            if strength <= T::ZERO {
+                continue;
+            }
+            for c in rule.consequents.iter().filter(|c| c.variable == output) {
+                let term = &variable.terms[c.term];
+                for (agg, &y) in aggregated.iter_mut().zip(&universe) {
+                    let implied = self.implication.apply(strength, term.membership(y));
+                    *agg = self.aggregation.apply(*agg, implied);
+                }
+            }
+        }
+
+        (universe, aggregated)
+    }
+
+    /// Evaluate the system for crisp inputs (one per input variable)
+    ///
+    /// Returns one crisp value per output variable.
+    pub fn evaluate(&self, inputs: &[T]) -> Vec<T> {
+        let mut outputs = vec![T::ZERO; self.outputs.len()];
+        self.evaluate_with(inputs, &mut outputs, &mut self.scratch());
+        outputs
+    }
+
+    /// Precompute output universes and allocate buffers for this system
+    ///
+    /// The scratch reflects the system at the time it was created; build a
+    /// new one after changing variables, terms or the resolution.
+    pub fn scratch(&self) -> SystemScratch<T> {
+        let universes: Vec<Vec<T>> = self
+            .outputs
+            .iter()
+            .map(|v| v.universe(self.resolution))
+            .collect();
+        let consequents = self
+            .outputs
+            .iter()
+            .zip(&universes)
+            .map(|(v, universe)| {
+                v.terms
+                    .iter()
+                    .map(|t| universe.iter().map(|&y| t.membership(y)).collect())
+                    .collect()
+            })
+            .collect();
+
+        SystemScratch {
+            universes,
+            consequents,
+            row: vec![T::ZERO; self.inputs.len()],
+            result: vec![T::ZERO; self.outputs.len()],
+            strengths: vec![T::ZERO; self.rules.len()],
+            aggregated: vec![T::ZERO; self.resolution],
+        }
+    }
+
+    /// Evaluate one set of inputs into `outputs` without allocating
+    pub fn evaluate_with(&self, inputs: &[T], outputs: &mut [T], scratch: &mut SystemScratch<T>) {
+        assert_eq!(
+            inputs.len(),
+            self.inputs.len(),
+            "Expected one value per input variable"
+        );
+        assert_eq!(
+            outputs.len(),
+            self.outputs.len(),
+            "Expected one slot per output variable"
+        );
+        assert_eq!(
+            scratch.strengths.len(),
+            self.rules.len(),
+            "Scratch was built for a different system"
+        );
+
+        for (s, rule) in scratch.strengths.iter_mut().zip(&self.rules) {
+            *s = self.firing_strength(rule, inputs);
+        }
+
+        for (o, out) in outputs.iter_mut().enumerate() {
+            let consequents = &scratch.consequents[o];
+            let aggregated = &mut scratch.aggregated;
+
+            #[cfg(feature = "rayon")]
+            if self.rules.len() >= PARALLEL_RULE_THRESHOLD {
+                use rayon::prelude::*;
+                aggregated
+                    .par_chunks_mut(PARALLEL_AGGREGATION_CHUNK)
+                    .enumerate()
+                    .for_each(|(i, chunk)| {
+                        let offset = i * PARALLEL_AGGREGATION_CHUNK;
+                        self.aggregate_range(o, &scratch.strengths, consequents, offset, chunk);
+                    });
+                *out = self.defuzzification.apply(&scratch.universes[o], aggregated);
+                continue;
+            }
+
+            self.aggregate_range(o, &scratch.strengths, consequents, 0, aggregated);
+            *out = self.defuzzification.apply(&scratch.universes[o], aggregated);
+        }
+    }
+
+    /// Aggregate the implied output sets over `aggregated.len()` universe
+    /// points starting at `offset`
+    ///
+    /// Each point folds the rules in rule order, so splitting the universe
+    /// into ranges gives the same result as aggregating it in one piece.
+    fn aggregate_range(
+        &self,
+        output: usize,
+        strengths: &[T],
+        consequents: &[Vec<T>],
+        offset: usize,
+        aggregated: &mut [T],
+    ) {
+        aggregated.iter_mut().for_each(|m| *m = T::ZERO);
+
+        for (rule, &strength) in self.rules.iter().zip(strengths) {
+            if strength <= T::ZERO {
+                continue;
+            }
+            for c in rule.consequents.iter().filter(|c| c.variable == output) {
+                let samples = &consequents[c.term][offset..offset + aggregated.len()];
+                for (agg, &mu) in aggregated.iter_mut().zip(samples) {
+                    let implied = self.implication.apply(strength, mu);
+                    *agg = self.aggregation.apply(*agg, implied);
+                }
+            }
+        }
+    }
+
+    /// Evaluate many samples given as one column per input variable
+    ///
+    /// Returns one column per output variable. With the `rayon` feature the
+    /// rows are split across threads; the results are identical to
+    /// sequential evaluation.
+    pub fn evaluate_batch(&self, columns: &[&[T]]) -> Vec<Vec<T>> {
+        let rows = columns.first().map_or(0, |c| c.len());
+        let mut results = vec![vec![T::ZERO; rows]; self.outputs.len()];
+
+        #[cfg(feature = "rayon")]
+        if !self.outputs.is_empty() {
+            use rayon::prelude::*;
+            assert_eq!(
+                columns.len(),
+                self.inputs.len(),
+                "Expected one column per input variable"
+            );
+            assert!(
+                columns.iter().all(|c| c.len() == rows),
+                "All columns must have the same length"
+            );
+
+            // Evaluate row-major chunks in parallel, then transpose
+            let width = self.outputs.len();
+            let mut row_major = vec![T::ZERO; rows * width];
+            row_major
+                .par_chunks_mut(PARALLEL_CHUNK * width)
+                .enumerate()
+                .for_each_init(
+                    || (self.scratch(), vec![T::ZERO; self.inputs.len()]),
+                    |(scratch, row), (i, chunk)| {
+                        let first = i * PARALLEL_CHUNK;
+                        for (r, out) in chunk.chunks_mut(width).enumerate() {
+                            for (x, column) in row.iter_mut().zip(columns) {
+                                *x = column[first + r];
+                            }
+                            self.evaluate_with(row, out, scratch);
+                        }
+                    },
+                );
+            for (r, values) in row_major.chunks(width).enumerate() {
+                for (column, &y) in results.iter_mut().zip(values) {
+                    column[r] = y;
+                }
+            }
+            return results;
+        }
+
+        let mut outputs: Vec<&mut [T]> = results.iter_mut().map(|c| c.as_mut_slice()).collect();
+        self.evaluate_into(columns, &mut outputs, &mut self.scratch());
+        results
+    }
+
+    /// Evaluate input columns into output columns without allocating
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - One column per input variable, all the same length
+    /// * `outputs` - One column per output variable, same length as the inputs
+    /// * `scratch` - Buffers from [`FuzzySystem::scratch`]
+    pub fn evaluate_into(
+        &self,
+        columns: &[&[T]],
+        outputs: &mut [&mut [T]],
+        scratch: &mut SystemScratch<T>,
+    ) {
+        assert_eq!(
+            columns.len(),
+            self.inputs.len(),
+            "Expected one column per input variable"
+        );
+        assert_eq!(
+            outputs.len(),
+            self.outputs.len(),
+            "Expected one column per output variable"
+        );
+        let rows = columns.first().map_or(0, |c| c.len());
+        assert!(
+            columns.iter().all(|c| c.len() == rows) && outputs.iter().all(|c| c.len() == rows),
+            "All columns must have the same length"
+        );
+
+        // Temporarily take the row buffers so `scratch` can be lent out below
+        let mut row = core::mem::take(&mut scratch.row);
+        let mut result = core::mem::take(&mut scratch.result);
+        for r in 0..rows {
+            for (x, column) in row.iter_mut().zip(columns) {
+                *x = column[r];
+            }
+            self.evaluate_with(&row, &mut result, scratch);
+            for (column, &y) in outputs.iter_mut().zip(&result) {
+                column[r] = y;
+            }
+        }
+        scratch.row = row;
+        scratch.result = result;
+    }
+}
+
+/// Precomputed universes and reusable buffers for [`FuzzySystem`] evaluation
+#[derive(Debug, Clone)]
+pub struct SystemScratch<T = f64> {
+    /// Output universe per output variable
+    universes: Vec<Vec<T>>,
+    /// Output term memberships sampled on the universe, `[output][term][point]`
+    consequents: Vec<Vec<Vec<T>>>,
+    row: Vec<T>,
+    result: Vec<T>,
+    strengths: Vec<T>,
+    aggregated: Vec<T>,
+}
+
+/// `steps` evenly spaced values from `min` to `max` inclusive
+pub(crate) fn linspace<T: Real>(min: T, max: T, steps: usize) -> Vec<T> {
+    if steps < 2 {
+        return vec![min];
+    }
+    (0..steps).map(|i| min + (max - min).mul_ratio(i, steps - 1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fan_system() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        system.add_input(
+            LinguisticVariable::new("temperature", 0.0, 40.0)
+                .with_term("cold", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 20.0 })
+                .with_term("warm", MembershipFunction::Triangular { a: 10.0, b: 25.0, c: 35.0 })
+                .with_term("hot", MembershipFunction::Triangular { a: 30.0, b: 40.0, c: 40.0 }),
+        );
+        system.add_output(
+            LinguisticVariable::new("fan_speed", 0.0, 100.0)
+                .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 50.0 })
+                .with_term("medium", MembershipFunction::Triangular { a: 20.0, b: 50.0, c: 80.0 })
+                .with_term("high", MembershipFunction::Triangular { a: 50.0, b: 100.0, c: 100.0 }),
+        );
+        for term in 0..3 {
+            system.add_rule(Rule::new(
+                vec![Antecedent::new(0, term)],
+                vec![Consequent::new(0, term)],
+            ));
+        }
+        system
+    }
+
+    #[test]
+    fn test_linguistic_variable() {
+        let var = LinguisticVariable::new("x", 0.0, 10.0)
+            .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 });
+
+        assert_eq!(var.term_index("low"), Some(0));
+        assert_eq!(var.term_index("high"), None);
+        assert_eq!(var.fuzzify(5.0), vec![0.5]);
+        assert_eq!(var.universe(3), vec![0.0, 5.0, 10.0]);
+    }
+
+    #[test]
+    fn test_edit_terms_and_rules() {
+        let mut system = fan_system();
+        let before = system.evaluate(&[20.0]);
+        let term = system.add_input_term(0, "freezing", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 5.0 });
+        system.add_output_term(0, "off", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 });
+
+        assert_eq!(term, 3);
+        assert_eq!(system.evaluate(&[20.0]), before);
+        let removed = system.remove_rule(0);
+        assert_eq!(removed.antecedents, vec![Antecedent::new(0, 0)]);
+        assert_eq!(system.rules().len(), 2);
+
+        let moved = MembershipFunction::Gaussian { mean: 15.0, sigma: 3.0 };
+        system.set_input_term(0, 1, moved.clone());
+        system.set_output_term(0, 3, moved.clone());
+        system.set_rule_weight(1, 0.25);
+        assert_eq!(system.inputs()[0].terms[1].membership_function, moved);
+        assert_eq!(system.outputs()[0].terms[3].membership_function, moved);
+        assert_eq!(system.rules()[1].weight, 0.25);
+    }
+
+    #[test]
+    fn test_firing_strength_connectives() {
+        let mut system = FuzzySystem::new();
+        system.add_input(
+            LinguisticVariable::new("a", 0.0, 1.0)
+                .with_term("up", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 2.0 }),
+        );
+        system.add_input(
+            LinguisticVariable::new("b", 0.0, 1.0)
+                .with_term("up", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 2.0 }),
+        );
+        let both = vec![Antecedent::new(0, 0), Antecedent::new(1, 0)];
+
+        let and_rule = Rule::new(both.clone(), vec![]);
+        let or_rule = Rule::new(both, vec![]).with_connective(Connective::Or);
+        let not_rule = Rule::new(vec![Antecedent::not(0, 0)], vec![]).with_weight(0.5);
+
+        assert!((system.firing_strength(&and_rule, &[0.25, 0.75]) - 0.25).abs() < 1e-10);
+        assert!((system.firing_strength(&or_rule, &[0.25, 0.75]) - 0.75).abs() < 1e-10);
+        assert!((system.firing_strength(&not_rule, &[0.25, 0.75]) - 0.375).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_evaluate_monotonic() {
+        let system = fan_system();
+
+        let cold = system.evaluate(&[5.0])[0];
+        let warm = system.evaluate(&[25.0])[0];
+        let hot = system.evaluate(&[38.0])[0];
+
+        assert!(cold < warm && warm < hot);
+        assert!((warm - 50.0).abs() < 1.0, "Warm maps onto the symmetric medium term");
+    }
+
+    #[test]
+    fn test_evaluate_batch_columns() {
+        let mut system = fan_system();
+        system.add_input(
+            LinguisticVariable::new("humidity", 0.0, 100.0)
+                .with_term("high", MembershipFunction::Triangular { a: 50.0, b: 100.0, c: 100.0 }),
+        );
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 1), Antecedent::new(1, 0)],
+            vec![Consequent::new(0, 2)],
+        ));
+
+        let temperature = [5.0, 18.0, 25.0, 33.0, 40.0];
+        let humidity = [10.0, 90.0, 60.0, 100.0, 0.0];
+        let columns = system.evaluate_batch(&[&temperature, &humidity]);
+
+        assert_eq!(columns.len(), 1);
+        for (r, &y) in columns[0].iter().enumerate() {
+            assert_eq!(y, system.evaluate(&[temperature[r], humidity[r]])[0]);
+        }
+    }
+
+    #[test]
+    fn test_system_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FuzzySystem>();
+        assert_send_sync::<SystemScratch>();
+    }
+
+    /// A system with enough rules to use parallel aggregation
+    #[cfg(feature = "rayon")]
+    fn large_system() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        let mut input = LinguisticVariable::new("x", 0.0, 100.0);
+        let mut output = LinguisticVariable::new("y", 0.0, 100.0);
+        for k in 0..100 {
+            let center = k as f64;
+            input.add_term(format!("x{}", k), MembershipFunction::Gaussian { mean: center, sigma: 3.0 });
+            output.add_term(
+                format!("y{}", k),
+                MembershipFunction::Triangular { a: center - 5.0, b: (center * 1.3) % 100.0, c: center + 40.0 },
+            );
+        }
+        system.add_input(input);
+        system.add_output(output);
+        for k in 0..100 {
+            system.add_rule(Rule::new(
+                vec![Antecedent::new(0, k)],
+                vec![Consequent::new(0, (k * 37) % 100)],
+            ));
+        }
+        system.set_resolution(1001);
+        system.set_aggregation(SNorm::AlgebraicSum);
+        system
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_aggregation_matches_sequential() {
+        let system = large_system();
+        for x in [3.0, 27.5, 50.0, 81.25, 99.0] {
+            let strengths = system.firing_strengths(&[x]);
+            let (universe, aggregated) = system.aggregate(0, &strengths);
+            let sequential = system.defuzzification().apply(&universe, &aggregated);
+
+            assert_eq!(system.evaluate(&[x])[0], sequential);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_batch_matches_sequential() {
+        let system = large_system();
+        let xs: Vec<f64> = (0..600).map(|i| i as f64 / 6.0).collect();
+        let mut scratch = system.scratch();
+        let sequential: Vec<f64> = xs
+            .iter()
+            .map(|&x| {
+                let mut out = [0.0];
+                system.evaluate_with(&[x], &mut out, &mut scratch);
+                out[0]
+            })
+            .collect();
+
+        assert_eq!(system.evaluate_batch(&[&xs])[0], sequential);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown output term")]
+    fn test_add_rule_rejects_unknown_term() {
+        let mut system = fan_system();
+        system.add_rule(Rule::new(
+            vec![Antecedent::new(0, 0)],
+            vec![Consequent::new(0, 7)],
+        ));
+    }
+}
@@ -0,0 +1,626 @@
+//! Fuzzy Time-Series Forecasting
+//!
+//! Forecasts a series from the fuzzy logical relationships between its
+//! consecutive states, after Song and Chissom and Chen:
+//! 1. The universe of discourse is split into equal intervals, each the core
+//!    of a triangular [`FuzzySet`] `A1..An` ([`Partition`])
+//! 2. Each observation is fuzzified to the set of its interval
+//! 3. Every transition `A_i → A_j` is recorded, and transitions with the
+//!    same left-hand side form a [`RelationshipGroup`]
+//! 4. A forecast combines the consequents of the current state's group
+//!    into a fuzzy set and defuzzifies it with
+//!    [`defuzzify`](crate::defuzzification::defuzzify)
+//!
+//! With [`ForecastConfig::order`] above 1 the left-hand side is the last
+//! `order` states (a high-order model). [`Weighting`] chooses how the
+//! consequents are combined: Chen's model weighs each distinct consequent
+//! equally, the trend-weighted model by how often each move recurred, and
+//! Yu's chronological model favours recent moves. A state that never
+//! appeared as a left-hand side forecasts its own interval.
+//!
+//! [`Model::SongChissom`] replaces steps 3 and 4 with Song and Chissom's
+//! time-invariant model: every transition adds `A(t) × A(t+1)` to one
+//! max–min relation `R`, and the forecast set is `A(t) ∘ R`.
+//!
+//! [`walk_forward`] refits the model on a growing window and reports the
+//! MAPE and RMSE of its one-step forecasts.
+//!
+//! ```
+//! use fuzzy_logic::time_series::{ForecastConfig, FuzzyTimeSeries, Partition};
+//!
+//! let alarms = [120.0, 135.0, 150.0, 140.0, 160.0, 175.0, 165.0, 180.0];
+//! let partition = Partition::new(100.0, 200.0, 5);
+//! let model = FuzzyTimeSeries::fit_with_partition(&alarms, partition, &ForecastConfig::default()).unwrap();
+//!
+//! // 180 falls in [180, 200], which was never followed by anything
+//! assert_eq!(model.forecast(), 190.0);
+//! ```
+
+use std::fmt;
+
+use crate::defuzzification::{defuzzify, DefuzzificationMethod};
+use crate::membership::{FuzzySet, MembershipFunction};
+
+/// Universe points per interval when sampling forecast sets
+const SAMPLES_PER_INTERVAL: usize = 20;
+
+/// Equal intervals of a universe of discourse with one fuzzy set each
+///
+/// Set `A_k` is a triangle peaking at the midpoint of interval `k` with its
+/// feet at the neighbouring midpoints, so every value belongs most to the
+/// set of its own interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partition {
+    min: f64,
+    max: f64,
+    sets: Vec<FuzzySet>,
+}
+
+impl Partition {
+    /// `intervals` equal intervals covering `[min, max]`
+    ///
+    /// Panics if there are no intervals or the range is empty.
+    pub fn new(min: f64, max: f64, intervals: usize) -> Self {
+        assert!(intervals > 0, "A partition needs at least one interval");
+        assert!(min < max, "A partition needs min < max");
+        let width = (max - min) / intervals as f64;
+        let sets = (0..intervals)
+            .map(|k| {
+                let mid = min + (k as f64 + 0.5) * width;
+                FuzzySet::new(
+                    format!("A{}", k + 1),
+                    MembershipFunction::Triangular {
+                        a: mid - width,
+                        b: mid,
+                        c: mid + width,
+                    },
+                )
+            })
+            .collect();
+        Partition { min, max, sets }
+    }
+
+    /// Intervals covering the range of `data` widened by `margin` times the
+    /// range on each side
+    ///
+    /// A constant series is widened by its magnitude (or 1 at zero).
+    pub fn from_data(data: &[f64], intervals: usize, margin: f64) -> Self {
+        let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { min.abs().max(1.0) };
+        let pad = margin * range;
+        let (min, max) = if max > min { (min, max) } else { (min - range / 2.0, max + range / 2.0) };
+        Partition::new(min - pad, max + pad, intervals)
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn sets(&self) -> &[FuzzySet] {
+        &self.sets
+    }
+
+    pub fn width(&self) -> f64 {
+        (self.max - self.min) / self.sets.len() as f64
+    }
+
+    /// Bounds of interval `k`
+    pub fn interval(&self, k: usize) -> (f64, f64) {
+        let width = self.width();
+        (self.min + k as f64 * width, self.min + (k + 1) as f64 * width)
+    }
+
+    pub fn midpoint(&self, k: usize) -> f64 {
+        self.min + (k as f64 + 0.5) * self.width()
+    }
+
+    /// Index of the set `x` belongs to most; values outside the universe
+    /// go to the nearest end
+    pub fn fuzzify(&self, x: f64) -> usize {
+        let k = ((x - self.min) / self.width()).floor();
+        k.clamp(0.0, (self.sets.len() - 1) as f64) as usize
+    }
+
+    /// Song and Chissom's fuzzy state for set `k`: degree 1 in interval
+    /// `k` and 0.5 in its neighbours
+    fn state(&self, k: usize) -> Vec<f64> {
+        (0..self.sets.len())
+            .map(|q| match q.abs_diff(k) {
+                0 => 1.0,
+                1 => 0.5,
+                _ => 0.0,
+            })
+            .collect()
+    }
+
+    /// Points covering every set completely, for defuzzification
+    fn universe(&self) -> Vec<f64> {
+        let width = self.width();
+        let start = self.min - width / 2.0;
+        let steps = (self.sets.len() + 1) * SAMPLES_PER_INTERVAL;
+        let step = (self.sets.len() + 1) as f64 * width / steps as f64;
+        (0..=steps).map(|i| start + i as f64 * step).collect()
+    }
+}
+
+/// How the consequents of a relationship group are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Weighting {
+    /// Chen: each distinct consequent counts once
+    #[default]
+    Equal,
+    /// Trend-weighted: each consequent counts as often as the move to it
+    /// recurred, so frequent trends dominate
+    Recurrence,
+    /// Yu: the `n`th occurrence of a move has weight `n`, so recent moves
+    /// dominate
+    Chronological,
+}
+
+/// How a forecast is derived from the recorded transitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Model {
+    /// Chen and its variants: the consequents of the current state's
+    /// relationship group, combined by [`Weighting`]
+    #[default]
+    RelationshipGroups,
+    /// Song and Chissom: the current state composed with the relation
+    /// `R = ∪ A(t) × A(t+1)` by max–min. A state is fuzzified to degree 1
+    /// in its own interval and 0.5 in the neighbouring ones. First order
+    /// only; the weighting is not used.
+    SongChissom,
+}
+
+/// Settings for fitting a [`FuzzyTimeSeries`]
+#[derive(Debug, Clone)]
+pub struct ForecastConfig {
+    pub model: Model,
+    /// Number of past states on the left-hand side of a relationship
+    pub order: usize,
+    pub weighting: Weighting,
+    pub defuzzification: DefuzzificationMethod,
+    /// Intervals of a partition built from the data
+    pub intervals: usize,
+    /// Widening of a partition built from the data, as a fraction of the
+    /// data range on each side
+    pub margin: f64,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        ForecastConfig {
+            model: Model::RelationshipGroups,
+            order: 1,
+            weighting: Weighting::Equal,
+            defuzzification: DefuzzificationMethod::Centroid,
+            intervals: 7,
+            margin: 0.1,
+        }
+    }
+}
+
+/// Errors fitting or evaluating a model
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeSeriesError {
+    /// The series has fewer observations than the model needs
+    TooShort { length: usize, needed: usize },
+    /// The series holds NaN or infinite values
+    NonFinite,
+    InvalidConfig(String),
+}
+
+impl fmt::Display for TimeSeriesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeSeriesError::TooShort { length, needed } => {
+                write!(f, "the series has {} observations, at least {} are needed", length, needed)
+            }
+            TimeSeriesError::NonFinite => write!(f, "the series contains non-finite values"),
+            TimeSeriesError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TimeSeriesError {}
+
+/// Fuzzy logical relationships sharing a left-hand side
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelationshipGroup {
+    /// Set indices of the past states, oldest first
+    pub antecedent: Vec<usize>,
+    /// Set indices of the following states, in order of occurrence
+    pub consequents: Vec<usize>,
+}
+
+impl RelationshipGroup {
+    /// Weight of each distinct consequent, in order of first occurrence
+    pub fn weights(&self, weighting: Weighting) -> Vec<(usize, f64)> {
+        let mut weights: Vec<(usize, f64)> = Vec::new();
+        for (n, &c) in self.consequents.iter().enumerate() {
+            let add = match weighting {
+                Weighting::Equal => 0.0,
+                Weighting::Recurrence => 1.0,
+                Weighting::Chronological => (n + 1) as f64,
+            };
+            match weights.iter_mut().find(|(k, _)| *k == c) {
+                Some((_, w)) => *w += add,
+                None => weights.push((c, if weighting == Weighting::Equal { 1.0 } else { add })),
+            }
+        }
+        weights
+    }
+}
+
+impl fmt::Display for RelationshipGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = |sets: &[usize]| sets.iter().map(|k| format!("A{}", k + 1)).collect::<Vec<_>>().join(", ");
+        write!(f, "{} -> {}", names(&self.antecedent), names(&self.consequents))
+    }
+}
+
+/// A fitted fuzzy time-series model
+#[derive(Debug, Clone)]
+pub struct FuzzyTimeSeries {
+    partition: Partition,
+    config: ForecastConfig,
+    groups: Vec<RelationshipGroup>,
+    /// Song–Chissom relation between consecutive states
+    relation: Vec<Vec<f64>>,
+    /// Last `order` observations of the training series
+    recent: Vec<f64>,
+}
+
+impl FuzzyTimeSeries {
+    /// Fit on `data` with a partition built from it
+    pub fn fit(data: &[f64], config: &ForecastConfig) -> Result<Self, TimeSeriesError> {
+        if config.intervals == 0 {
+            return Err(TimeSeriesError::InvalidConfig("intervals must be at least 1".to_string()));
+        }
+        if config.margin.is_nan() || config.margin < 0.0 {
+            return Err(TimeSeriesError::InvalidConfig("margin must not be negative".to_string()));
+        }
+        check_series(data, config)?;
+        let partition = Partition::from_data(data, config.intervals, config.margin);
+        Self::fit_with_partition(data, partition, config)
+    }
+
+    /// Fit on `data` with a given partition; `intervals` and `margin` in
+    /// the configuration are not used
+    pub fn fit_with_partition(
+        data: &[f64],
+        partition: Partition,
+        config: &ForecastConfig,
+    ) -> Result<Self, TimeSeriesError> {
+        check_series(data, config)?;
+        let states: Vec<usize> = data.iter().map(|&x| partition.fuzzify(x)).collect();
+        let mut groups: Vec<RelationshipGroup> = Vec::new();
+        for window in states.windows(config.order + 1) {
+            let (antecedent, consequent) = window.split_at(config.order);
+            match groups.iter_mut().find(|g| g.antecedent == antecedent) {
+                Some(group) => group.consequents.push(consequent[0]),
+                None => groups.push(RelationshipGroup {
+                    antecedent: antecedent.to_vec(),
+                    consequents: vec![consequent[0]],
+                }),
+            }
+        }
+        let n = partition.sets.len();
+        let mut relation: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
+        for pair in states.windows(2) {
+            let (from, to) = (partition.state(pair[0]), partition.state(pair[1]));
+            for (row, &a) in relation.iter_mut().zip(&from) {
+                for (r, &b) in row.iter_mut().zip(&to) {
+                    *r = r.max(a.min(b));
+                }
+            }
+        }
+        Ok(FuzzyTimeSeries {
+            recent: data[data.len() - config.order..].to_vec(),
+            partition,
+            config: config.clone(),
+            groups,
+            relation,
+        })
+    }
+
+    pub fn partition(&self) -> &Partition {
+        &self.partition
+    }
+
+    pub fn config(&self) -> &ForecastConfig {
+        &self.config
+    }
+
+    /// Relationship groups in order of first occurrence
+    pub fn groups(&self) -> &[RelationshipGroup] {
+        &self.groups
+    }
+
+    /// Song–Chissom relation `R[p][q] = max_t min(A(t)_p, A(t+1)_q)` over
+    /// the training transitions, used by [`Model::SongChissom`]
+    pub fn relation(&self) -> &[Vec<f64>] {
+        &self.relation
+    }
+
+    /// Forecast set following the last `order` values of `history`, sampled
+    /// as `(universe, membership)`
+    ///
+    /// Panics if `history` is shorter than the model order.
+    pub fn forecast_set(&self, history: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let order = self.config.order;
+        assert!(history.len() >= order, "History is shorter than the model order");
+        let key: Vec<usize> = history[history.len() - order..]
+            .iter()
+            .map(|&x| self.partition.fuzzify(x))
+            .collect();
+        if self.config.model == Model::SongChissom {
+            return self.composed_set(key[0]);
+        }
+        let weights = match self.groups.iter().find(|g| g.antecedent == key) {
+            Some(group) => group.weights(self.config.weighting),
+            None => vec![(key[order - 1], 1.0)],
+        };
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+
+        // Weighted average of the consequent sets; for symmetric sets its
+        // centroid is the weighted mean of their midpoints
+        let universe = self.partition.universe();
+        let membership = universe
+            .iter()
+            .map(|&x| {
+                weights
+                    .iter()
+                    .map(|&(k, w)| w * self.partition.sets[k].membership(x))
+                    .sum::<f64>()
+                    / total
+            })
+            .collect();
+        (universe, membership)
+    }
+
+    /// `A(t) ∘ R` for the state in set `k`, each set clipped at its degree
+    /// and the clipped sets joined by maximum
+    fn composed_set(&self, k: usize) -> (Vec<f64>, Vec<f64>) {
+        let state = self.partition.state(k);
+        let mut degrees: Vec<f64> = (0..state.len())
+            .map(|q| {
+                state
+                    .iter()
+                    .zip(&self.relation)
+                    .map(|(&a, row)| a.min(row[q]))
+                    .fold(0.0, f64::max)
+            })
+            .collect();
+        // A state unrelated to every recorded one forecasts itself
+        if degrees.iter().all(|&d| d == 0.0) {
+            degrees = state;
+        }
+
+        let universe = self.partition.universe();
+        let membership = universe
+            .iter()
+            .map(|&x| {
+                degrees
+                    .iter()
+                    .zip(&self.partition.sets)
+                    .map(|(&d, set)| d.min(set.membership(x)))
+                    .fold(0.0, f64::max)
+            })
+            .collect();
+        (universe, membership)
+    }
+
+    /// One-step forecast following `history`
+    pub fn forecast_from(&self, history: &[f64]) -> f64 {
+        let (universe, membership) = self.forecast_set(history);
+        defuzzify(&universe, &membership, self.config.defuzzification)
+    }
+
+    /// Forecast of the value after the training series
+    pub fn forecast(&self) -> f64 {
+        self.forecast_from(&self.recent)
+    }
+}
+
+fn check_series(data: &[f64], config: &ForecastConfig) -> Result<(), TimeSeriesError> {
+    if config.order == 0 {
+        return Err(TimeSeriesError::InvalidConfig("order must be at least 1".to_string()));
+    }
+    if config.model == Model::SongChissom && config.order != 1 {
+        return Err(TimeSeriesError::InvalidConfig("the Song-Chissom model is first order".to_string()));
+    }
+    if data.iter().any(|x| !x.is_finite()) {
+        return Err(TimeSeriesError::NonFinite);
+    }
+    if data.len() <= config.order {
+        return Err(TimeSeriesError::TooShort {
+            length: data.len(),
+            needed: config.order + 1,
+        });
+    }
+    Ok(())
+}
+
+/// One-step forecasts over a held-out stretch of a series
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// `(index, actual, forecast)` for each forecast observation
+    pub forecasts: Vec<(usize, f64, f64)>,
+    /// Mean absolute percentage error over the nonzero actual values; NaN
+    /// when every actual value is zero
+    pub mape: f64,
+    /// Root mean squared error
+    pub rmse: f64,
+}
+
+impl Evaluation {
+    fn new(forecasts: Vec<(usize, f64, f64)>) -> Self {
+        let n = forecasts.len() as f64;
+        let rmse = (forecasts.iter().map(|(_, a, f)| (a - f) * (a - f)).sum::<f64>() / n).sqrt();
+        let percentages: Vec<f64> = forecasts
+            .iter()
+            .filter(|(_, a, _)| *a != 0.0)
+            .map(|(_, a, f)| ((a - f) / a).abs())
+            .collect();
+        let mape = 100.0 * percentages.iter().sum::<f64>() / percentages.len() as f64;
+        Evaluation { forecasts, mape, rmse }
+    }
+
+    /// `index,actual,forecast` rows with a header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("index,actual,forecast\n");
+        for (index, actual, forecast) in &self.forecasts {
+            csv.push_str(&format!("{},{},{}\n", index, actual, forecast));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Walk-forward evaluation: {} forecasts", self.forecasts.len())?;
+        writeln!(f, "  MAPE: {:.3}%", self.mape)?;
+        writeln!(f, "  RMSE: {:.6}", self.rmse)
+    }
+}
+
+/// Forecast each observation from `initial` on with a model fitted to
+/// everything before it
+///
+/// The partition is rebuilt from the training window at every step, so no
+/// information from the forecast values leaks into the model.
+pub fn walk_forward(data: &[f64], initial: usize, config: &ForecastConfig) -> Result<Evaluation, TimeSeriesError> {
+    if initial >= data.len() {
+        return Err(TimeSeriesError::TooShort {
+            length: data.len(),
+            needed: initial + 1,
+        });
+    }
+    let mut forecasts = Vec::with_capacity(data.len() - initial);
+    for t in initial..data.len() {
+        let model = FuzzyTimeSeries::fit(&data[..t], config)?;
+        forecasts.push((t, data[t], model.forecast()));
+    }
+    Ok(Evaluation::new(forecasts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition() {
+        let partition = Partition::new(0.0, 10.0, 5);
+        assert_eq!(partition.width(), 2.0);
+        assert_eq!(partition.interval(1), (2.0, 4.0));
+        assert_eq!(partition.midpoint(4), 9.0);
+        assert_eq!(partition.sets()[2].name, "A3");
+        assert_eq!(partition.fuzzify(4.5), 2);
+        assert_eq!(partition.fuzzify(-3.0), 0);
+        assert_eq!(partition.fuzzify(10.0), 4);
+        // Every value belongs most to the set of its interval
+        for x in [0.5, 2.1, 5.9, 7.99] {
+            let k = partition.fuzzify(x);
+            let best = (0..5)
+                .max_by(|&a, &b| partition.sets()[a].membership(x).total_cmp(&partition.sets()[b].membership(x)))
+                .unwrap();
+            assert_eq!(k, best);
+        }
+
+        let constant = Partition::from_data(&[4.0, 4.0], 3, 0.0);
+        assert_eq!((constant.min(), constant.max()), (2.0, 6.0));
+    }
+
+    #[test]
+    fn test_weightings() {
+        let group = RelationshipGroup {
+            antecedent: vec![0],
+            consequents: vec![1, 2, 1, 1],
+        };
+        assert_eq!(group.weights(Weighting::Equal), vec![(1, 1.0), (2, 1.0)]);
+        assert_eq!(group.weights(Weighting::Recurrence), vec![(1, 3.0), (2, 1.0)]);
+        assert_eq!(group.weights(Weighting::Chronological), vec![(1, 8.0), (2, 2.0)]);
+        assert_eq!(group.to_string(), "A1 -> A2, A3, A2, A2");
+    }
+
+    #[test]
+    fn test_forecasts_combine_consequent_midpoints() {
+        let data = [1.0, 3.0, 1.0, 5.0, 1.0, 3.0, 1.0];
+        let partition = Partition::new(0.0, 6.0, 3);
+        let forecast = |weighting| {
+            let config = ForecastConfig {
+                weighting,
+                ..ForecastConfig::default()
+            };
+            FuzzyTimeSeries::fit_with_partition(&data, partition.clone(), &config).unwrap().forecast()
+        };
+        // A1 was followed by A2, A3 and A2 again (midpoints 3 and 5)
+        assert!((forecast(Weighting::Equal) - 4.0).abs() < 1e-9);
+        assert!((forecast(Weighting::Recurrence) - 11.0 / 3.0).abs() < 1e-9);
+        assert!((forecast(Weighting::Chronological) - (3.0 * 4.0 + 5.0 * 2.0) / 6.0).abs() < 1e-9);
+
+        let second = ForecastConfig {
+            order: 2,
+            ..ForecastConfig::default()
+        };
+        let model = FuzzyTimeSeries::fit_with_partition(&data, partition, &second).unwrap();
+        assert_eq!(model.groups().len(), 4);
+        // (A2, A1) was always followed by A3
+        assert!((model.forecast_from(&[3.0, 1.0]) - 5.0).abs() < 1e-9);
+        // An unseen pair forecasts the latest state's interval
+        assert!((model.forecast_from(&[5.0, 5.0]) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_song_chissom_composition() {
+        // States A1 A2 A1 A3 A1 A2 A1, with A1 = (1, 0.5, 0), A2 = (0.5, 1,
+        // 0.5) and A3 = (0, 0.5, 1)
+        let data = [1.0, 3.0, 1.0, 5.0, 1.0, 3.0, 1.0];
+        let config = ForecastConfig {
+            model: Model::SongChissom,
+            ..ForecastConfig::default()
+        };
+        let model = FuzzyTimeSeries::fit_with_partition(&data, Partition::new(0.0, 6.0, 3), &config).unwrap();
+        assert_eq!(
+            model.relation(),
+            &[vec![0.5, 1.0, 1.0], vec![1.0, 0.5, 0.5], vec![1.0, 0.5, 0.0]]
+        );
+
+        // A1 ∘ R = (0.5, 1, 1): A1 clipped at 0.5 joined with A2 and A3
+        let (universe, membership) = model.forecast_set(&[1.0]);
+        let at = |x: f64| membership[universe.iter().position(|&u| (u - x).abs() < 1e-9).unwrap()];
+        assert!((at(1.0) - 0.5).abs() < 1e-9);
+        assert!((at(3.0) - 1.0).abs() < 1e-9);
+        assert!((at(5.0) - 1.0).abs() < 1e-9);
+        assert!((model.forecast() - 29.0 / 9.0).abs() < 1e-9);
+
+        let second = ForecastConfig { order: 2, ..config };
+        assert!(matches!(
+            FuzzyTimeSeries::fit(&data, &second),
+            Err(TimeSeriesError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_series() {
+        let config = ForecastConfig::default();
+        assert_eq!(
+            FuzzyTimeSeries::fit(&[1.0], &config).err(),
+            Some(TimeSeriesError::TooShort { length: 1, needed: 2 })
+        );
+        assert_eq!(
+            FuzzyTimeSeries::fit(&[1.0, f64::NAN, 2.0], &config).err(),
+            Some(TimeSeriesError::NonFinite)
+        );
+        let zero = ForecastConfig {
+            order: 0,
+            ..ForecastConfig::default()
+        };
+        assert!(matches!(FuzzyTimeSeries::fit(&[1.0, 2.0], &zero), Err(TimeSeriesError::InvalidConfig(_))));
+    }
+}
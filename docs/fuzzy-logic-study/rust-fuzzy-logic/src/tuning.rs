@@ -0,0 +1,712 @@
+//! Evolutionary Tuning
+//!
+//! Tunes the membership functions, and optionally the rule weights, of a
+//! [`FuzzySystem`] against any loss, differentiable or not. Each candidate
+//! system is encoded as a chromosome of real-valued genes:
+//! - Triangles and trapezoids: their corner points, kept in order
+//! - Gaussians: mean and width
+//! - Sigmoids: slope (keeping its sign) and center
+//! - Bells: width, slope and center
+//! - Rule weights: one gene in `[0, 1]` per rule
+//!
+//! Positions may move up to half a universe beyond the variable's range and
+//! widths stay positive. Two optimizers search this space:
+//! - [`Optimizer::Genetic`]: tournament selection, blend crossover,
+//!   Gaussian mutation and elitism
+//! - [`Optimizer::DifferentialEvolution`]: the DE/rand/1/bin scheme of
+//!   Storn and Price
+//!
+//! The template itself is part of the initial population, so the result is
+//! never worse than the starting point. Runs with the same seed are
+//! identical.
+//!
+//! ```
+//! use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+//! use fuzzy_logic::membership::MembershipFunction;
+//! use fuzzy_logic::tuning::{squared_error, tune, TuningConfig};
+//!
+//! let mut system = FuzzySystem::new();
+//! system.add_input(
+//!     LinguisticVariable::new("x", 0.0, 10.0)
+//!         .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 })
+//!         .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 }),
+//! );
+//! system.add_output(
+//!     LinguisticVariable::new("y", 0.0, 10.0)
+//!         .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 })
+//!         .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 }),
+//! );
+//! system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 1)]));
+//! system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 0)]));
+//!
+//! let inputs: Vec<[f64; 1]> = (0..=10).map(|i| [i as f64]).collect();
+//! let targets: Vec<[f64; 1]> = (0..=10).map(|i| [8.0 - 0.6 * i as f64]).collect();
+//! let config = TuningConfig { generations: 10, ..TuningConfig::default() };
+//! let tuning = tune(&system, &inputs, &targets, squared_error, &config).unwrap();
+//!
+//! assert!(tuning.report.best().loss <= tuning.report.generations[0].loss);
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::membership::MembershipFunction;
+use crate::rng::Rng;
+use crate::system::FuzzySystem;
+
+/// Search strategy and its settings
+#[derive(Debug, Clone, PartialEq)]
+pub enum Optimizer {
+    Genetic {
+        /// Probability that two parents are blended rather than copied
+        crossover_rate: f64,
+        /// Probability that each gene of a child is mutated
+        mutation_rate: f64,
+        /// Standard deviation of a mutation, as a fraction of the gene's range
+        mutation_scale: f64,
+        /// Individuals compared to select each parent
+        tournament_size: usize,
+        /// Best individuals carried over unchanged to the next generation
+        elites: usize,
+    },
+    DifferentialEvolution {
+        /// Scale F of the difference vector
+        differential_weight: f64,
+        /// Probability that each gene is taken from the mutant
+        crossover_rate: f64,
+    },
+}
+
+impl Optimizer {
+    /// A genetic algorithm with common settings
+    pub fn genetic() -> Self {
+        Optimizer::Genetic {
+            crossover_rate: 0.9,
+            mutation_rate: 0.1,
+            mutation_scale: 0.1,
+            tournament_size: 3,
+            elites: 1,
+        }
+    }
+
+    /// Differential evolution with common settings
+    pub fn differential_evolution() -> Self {
+        Optimizer::DifferentialEvolution {
+            differential_weight: 0.5,
+            crossover_rate: 0.9,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Optimizer::Genetic { .. } => "genetic algorithm",
+            Optimizer::DifferentialEvolution { .. } => "differential evolution",
+        }
+    }
+}
+
+/// Settings for [`tune`]
+#[derive(Debug, Clone)]
+pub struct TuningConfig {
+    pub optimizer: Optimizer,
+    /// Individuals per generation
+    pub population: usize,
+    /// Generations after the initial population
+    pub generations: usize,
+    /// Also tune rule weights
+    pub rule_weights: bool,
+    /// Seed for the initial population and all random choices
+    pub seed: u64,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        TuningConfig {
+            optimizer: Optimizer::genetic(),
+            population: 30,
+            generations: 50,
+            rule_weights: false,
+            seed: 0,
+        }
+    }
+}
+
+/// Errors from [`tune`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TuningError {
+    NoSamples,
+    /// The system has no terms or rule weights to tune
+    NoParameters,
+    /// The number of targets differs from the number of samples
+    LengthMismatch { samples: usize, targets: usize },
+    /// A sample has a different number of values from the system's inputs
+    WrongInputLength { sample: usize, expected: usize, actual: usize },
+    /// A sample is NaN or infinite
+    NonFinite(usize),
+    InvalidConfig(&'static str),
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuningError::NoSamples => write!(f, "no samples"),
+            TuningError::NoParameters => write!(f, "the system has nothing to tune"),
+            TuningError::LengthMismatch { samples, targets } => {
+                write!(f, "{} samples but {} targets", samples, targets)
+            }
+            TuningError::WrongInputLength { sample, expected, actual } => write!(
+                f,
+                "sample {} has {} values, expected {}",
+                sample, actual, expected
+            ),
+            TuningError::NonFinite(sample) => write!(f, "sample {} is not finite", sample),
+            TuningError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TuningError {}
+
+/// Best system after one generation
+#[derive(Debug, Clone)]
+pub struct GenerationRecord {
+    /// Generation number; 0 is the initial population
+    pub generation: usize,
+    /// Lowest loss found so far
+    pub loss: f64,
+    /// Mean loss of this generation, over candidates with a finite loss
+    pub mean_loss: f64,
+    /// System with the lowest loss found so far
+    pub system: FuzzySystem,
+}
+
+/// Progress of a tuning run
+#[derive(Debug, Clone)]
+pub struct TuningReport {
+    pub optimizer: &'static str,
+    /// Genes per chromosome
+    pub parameters: usize,
+    /// Loss evaluations, one per candidate system
+    pub evaluations: usize,
+    pub generations: Vec<GenerationRecord>,
+}
+
+impl TuningReport {
+    /// Record of the last generation, which holds the best system found
+    pub fn best(&self) -> &GenerationRecord {
+        self.generations.last().expect("a report covers the initial population")
+    }
+
+    /// Losses as `generation,loss,mean_loss` rows
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("generation,loss,mean_loss\n");
+        for record in &self.generations {
+            csv.push_str(&format!("{},{},{}\n", record.generation, record.loss, record.mean_loss));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for TuningReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Tuning by {}: {} parameters, {} evaluations",
+            self.optimizer, self.parameters, self.evaluations
+        )?;
+        writeln!(f, "  generation     best loss     mean loss")?;
+        for record in &self.generations {
+            writeln!(f, "  {:10}  {:12.6}  {:12.6}", record.generation, record.loss, record.mean_loss)?;
+        }
+        let first = self.generations[0].loss;
+        let best = self.best().loss;
+        write!(f, "  Loss {:.6} -> {:.6}", first, best)?;
+        if first > 0.0 && first.is_finite() {
+            write!(f, " ({:.1}% lower)", 100.0 * (first - best) / first)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// The best system found and the run's progress
+#[derive(Debug, Clone)]
+pub struct Tuning {
+    pub system: FuzzySystem,
+    pub report: TuningReport,
+}
+
+/// Mean squared difference between `outputs` and `targets`, for use as the
+/// per-sample loss of [`tune`]
+pub fn squared_error(outputs: &[f64], targets: &[f64]) -> f64 {
+    let sum: f64 = outputs.iter().zip(targets).map(|(o, t)| (o - t) * (o - t)).sum();
+    sum / outputs.len().max(1) as f64
+}
+
+/// What a group of genes encodes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Slot {
+    Input { variable: usize, term: usize },
+    Output { variable: usize, term: usize },
+    Weight(usize),
+}
+
+/// Where each part of the system lives in a chromosome
+struct Layout {
+    slots: Vec<(Slot, Range<usize>)>,
+    /// Lowest and highest value of each gene
+    bounds: Vec<(f64, f64)>,
+    /// Gene ranges whose values must stay in ascending order
+    ordered: Vec<Range<usize>>,
+}
+
+impl Layout {
+    /// Layout and chromosome of `template`
+    fn new(template: &FuzzySystem, rule_weights: bool) -> (Self, Vec<f64>) {
+        let mut layout = Layout {
+            slots: Vec::new(),
+            bounds: Vec::new(),
+            ordered: Vec::new(),
+        };
+        let mut genes = Vec::new();
+        let variables = template
+            .inputs()
+            .iter()
+            .enumerate()
+            .map(|(v, var)| (v, var, true))
+            .chain(template.outputs().iter().enumerate().map(|(v, var)| (v, var, false)));
+        for (variable, var, input) in variables {
+            let span = (var.max - var.min).abs().max(f64::MIN_POSITIVE);
+            let position = |value: f64| ((var.min - span / 2.0).min(value), (var.max + span / 2.0).max(value));
+            let width = |value: f64| ((1e-3 * span).min(value), span.max(value));
+            for (term, set) in var.terms.iter().enumerate() {
+                let start = genes.len();
+                let (values, bounds, ordered) = match set.membership_function {
+                    MembershipFunction::Triangular { a, b, c } => {
+                        let values = vec![a, b, c];
+                        let bounds = values.iter().map(|&v| position(v)).collect();
+                        (values, bounds, true)
+                    }
+                    MembershipFunction::Trapezoidal { a, b, c, d } => {
+                        let values = vec![a, b, c, d];
+                        let bounds = values.iter().map(|&v| position(v)).collect();
+                        (values, bounds, true)
+                    }
+                    MembershipFunction::Gaussian { mean, sigma } => {
+                        (vec![mean, sigma], vec![position(mean), width(sigma)], false)
+                    }
+                    MembershipFunction::Sigmoid { a, c } => {
+                        let slope = if a < 0.0 { (10.0 * a, 0.1 * a) } else { (0.1 * a, 10.0 * a) };
+                        (vec![a, c], vec![slope, position(c)], false)
+                    }
+                    MembershipFunction::Bell { a, b, c } => (
+                        vec![a, b, c],
+                        vec![width(a), (0.1_f64.min(b), 10.0_f64.max(b)), position(c)],
+                        false,
+                    ),
+                };
+                genes.extend(values);
+                layout.bounds.extend::<Vec<_>>(bounds);
+                let range = start..genes.len();
+                if ordered {
+                    layout.ordered.push(range.clone());
+                }
+                let slot = if input {
+                    Slot::Input { variable, term }
+                } else {
+                    Slot::Output { variable, term }
+                };
+                layout.slots.push((slot, range));
+            }
+        }
+        if rule_weights {
+            for (rule, r) in template.rules().iter().enumerate() {
+                let start = genes.len();
+                genes.push(r.weight.clamp(0.0, 1.0));
+                layout.bounds.push((0.0, 1.0));
+                layout.slots.push((Slot::Weight(rule), start..start + 1));
+            }
+        }
+        (layout, genes)
+    }
+
+    /// Clamp genes to their bounds and restore the order of corner points
+    fn repair(&self, genes: &mut [f64]) {
+        for (gene, &(lo, hi)) in genes.iter_mut().zip(&self.bounds) {
+            *gene = if gene.is_finite() { gene.clamp(lo, hi) } else { (lo + hi) / 2.0 };
+        }
+        for range in &self.ordered {
+            genes[range.clone()].sort_by(f64::total_cmp);
+        }
+    }
+
+    fn random(&self, rng: &mut Rng) -> Vec<f64> {
+        let mut genes: Vec<f64> = self.bounds.iter().map(|&(lo, hi)| lo + rng.unit() * (hi - lo)).collect();
+        self.repair(&mut genes);
+        genes
+    }
+
+    /// `template` with the parameters in `genes`
+    fn decode(&self, template: &FuzzySystem, genes: &[f64]) -> FuzzySystem {
+        let mut system = template.clone();
+        for (slot, range) in &self.slots {
+            let g = &genes[range.clone()];
+            match *slot {
+                Slot::Input { variable, term } => {
+                    let mf = with_parameters(&template.inputs()[variable].terms[term].membership_function, g);
+                    system.set_input_term(variable, term, mf);
+                }
+                Slot::Output { variable, term } => {
+                    let mf = with_parameters(&template.outputs()[variable].terms[term].membership_function, g);
+                    system.set_output_term(variable, term, mf);
+                }
+                Slot::Weight(rule) => system.set_rule_weight(rule, g[0]),
+            }
+        }
+        system
+    }
+}
+
+/// A membership function of the same shape as `mf` with parameters `g`
+fn with_parameters(mf: &MembershipFunction, g: &[f64]) -> MembershipFunction {
+    match mf {
+        MembershipFunction::Triangular { .. } => MembershipFunction::Triangular { a: g[0], b: g[1], c: g[2] },
+        MembershipFunction::Trapezoidal { .. } => MembershipFunction::Trapezoidal {
+            a: g[0],
+            b: g[1],
+            c: g[2],
+            d: g[3],
+        },
+        MembershipFunction::Gaussian { .. } => MembershipFunction::Gaussian { mean: g[0], sigma: g[1] },
+        MembershipFunction::Sigmoid { .. } => MembershipFunction::Sigmoid { a: g[0], c: g[1] },
+        MembershipFunction::Bell { .. } => MembershipFunction::Bell { a: g[0], b: g[1], c: g[2] },
+    }
+}
+
+/// Mean per-sample loss of `system`, or infinity when it is not finite
+fn mean_loss<S, U, L>(system: &FuzzySystem, inputs: &[S], targets: &[U], loss: &L) -> f64
+where
+    S: AsRef<[f64]>,
+    U: AsRef<[f64]>,
+    L: Fn(&[f64], &[f64]) -> f64,
+{
+    let mut scratch = system.scratch();
+    let mut outputs = vec![0.0; system.outputs().len()];
+    let mut total = 0.0;
+    for (x, y) in inputs.iter().zip(targets) {
+        system.evaluate_with(x.as_ref(), &mut outputs, &mut scratch);
+        total += loss(&outputs, y.as_ref());
+    }
+    let mean = total / inputs.len() as f64;
+    if mean.is_nan() {
+        f64::INFINITY
+    } else {
+        mean
+    }
+}
+
+fn validate<S: AsRef<[f64]>>(
+    template: &FuzzySystem,
+    inputs: &[S],
+    targets: usize,
+    config: &TuningConfig,
+) -> Result<(), TuningError> {
+    match config.optimizer {
+        Optimizer::Genetic {
+            crossover_rate,
+            mutation_rate,
+            mutation_scale,
+            tournament_size,
+            elites,
+        } => {
+            if !(0.0..=1.0).contains(&crossover_rate) || !(0.0..=1.0).contains(&mutation_rate) {
+                return Err(TuningError::InvalidConfig("rates must be in [0, 1]"));
+            }
+            if mutation_scale.is_nan() || mutation_scale < 0.0 {
+                return Err(TuningError::InvalidConfig("mutation_scale must not be negative"));
+            }
+            if tournament_size == 0 {
+                return Err(TuningError::InvalidConfig("tournament_size must be at least 1"));
+            }
+            if config.population < 2 || elites >= config.population {
+                return Err(TuningError::InvalidConfig("population must be at least 2 and exceed elites"));
+            }
+        }
+        Optimizer::DifferentialEvolution {
+            differential_weight,
+            crossover_rate,
+        } => {
+            if !(differential_weight > 0.0 && differential_weight <= 2.0) {
+                return Err(TuningError::InvalidConfig("differential_weight must be in (0, 2]"));
+            }
+            if !(0.0..=1.0).contains(&crossover_rate) {
+                return Err(TuningError::InvalidConfig("rates must be in [0, 1]"));
+            }
+            if config.population < 4 {
+                return Err(TuningError::InvalidConfig("differential evolution needs a population of at least 4"));
+            }
+        }
+    }
+    if inputs.len() != targets {
+        return Err(TuningError::LengthMismatch {
+            samples: inputs.len(),
+            targets,
+        });
+    }
+    if inputs.is_empty() {
+        return Err(TuningError::NoSamples);
+    }
+    let expected = template.inputs().len();
+    for (sample, x) in inputs.iter().enumerate() {
+        let x = x.as_ref();
+        if x.len() != expected {
+            return Err(TuningError::WrongInputLength {
+                sample,
+                expected,
+                actual: x.len(),
+            });
+        }
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(TuningError::NonFinite(sample));
+        }
+    }
+    Ok(())
+}
+
+/// Index of the lowest loss
+fn fittest(losses: &[f64]) -> usize {
+    (0..losses.len()).min_by(|&a, &b| losses[a].total_cmp(&losses[b])).unwrap()
+}
+
+/// Tune `template` to minimize the mean of `loss(outputs, targets)` over the
+/// samples
+///
+/// `inputs` holds one value per input variable for each sample and
+/// `targets` whatever the loss compares the outputs with, such as one
+/// value per output for [`squared_error`]. NaN losses count as infinite.
+pub fn tune<S, U, L>(
+    template: &FuzzySystem,
+    inputs: &[S],
+    targets: &[U],
+    loss: L,
+    config: &TuningConfig,
+) -> Result<Tuning, TuningError>
+where
+    S: AsRef<[f64]>,
+    U: AsRef<[f64]>,
+    L: Fn(&[f64], &[f64]) -> f64,
+{
+    validate(template, inputs, targets.len(), config)?;
+    let (layout, genes) = Layout::new(template, config.rule_weights);
+    if genes.is_empty() {
+        return Err(TuningError::NoParameters);
+    }
+
+    let mut rng = Rng::new(config.seed);
+    let mut evaluations = 0;
+    let mut evaluate = |genes: &[f64]| {
+        evaluations += 1;
+        mean_loss(&layout.decode(template, genes), inputs, targets, &loss)
+    };
+
+    let mut population = vec![genes];
+    while population.len() < config.population {
+        population.push(layout.random(&mut rng));
+    }
+    let mut losses: Vec<f64> = population.iter().map(|g| evaluate(g)).collect();
+
+    let mut best = fittest(&losses);
+    let mut best_genes = population[best].clone();
+    let mut best_loss = losses[best];
+    let mut records = Vec::new();
+    let mut record = |generation: usize, losses: &[f64], best_genes: &[f64], best_loss: f64| {
+        let finite: Vec<f64> = losses.iter().copied().filter(|l| l.is_finite()).collect();
+        let mean_loss = if finite.is_empty() {
+            f64::INFINITY
+        } else {
+            finite.iter().sum::<f64>() / finite.len() as f64
+        };
+        records.push(GenerationRecord {
+            generation,
+            loss: best_loss,
+            mean_loss,
+            system: layout.decode(template, best_genes),
+        });
+    };
+    record(0, &losses, &best_genes, best_loss);
+
+    for generation in 1..=config.generations {
+        match config.optimizer {
+            Optimizer::Genetic {
+                crossover_rate,
+                mutation_rate,
+                mutation_scale,
+                tournament_size,
+                elites,
+            } => {
+                let mut ranked: Vec<usize> = (0..population.len()).collect();
+                ranked.sort_by(|&a, &b| losses[a].total_cmp(&losses[b]));
+                let mut next: Vec<Vec<f64>> = ranked[..elites].iter().map(|&i| population[i].clone()).collect();
+                let mut next_losses: Vec<f64> = ranked[..elites].iter().map(|&i| losses[i]).collect();
+
+                let select = |rng: &mut Rng| {
+                    (0..tournament_size)
+                        .map(|_| rng.below(population.len()))
+                        .min_by(|&a, &b| losses[a].total_cmp(&losses[b]))
+                        .unwrap()
+                };
+                while next.len() < config.population {
+                    let first = &population[select(&mut rng)];
+                    let second = &population[select(&mut rng)];
+                    let mut child: Vec<f64> = if rng.unit() < crossover_rate {
+                        // BLX-0.5: uniform over the parents' interval widened by half
+                        first
+                            .iter()
+                            .zip(second)
+                            .map(|(&a, &b)| {
+                                let (lo, hi) = (a.min(b), a.max(b));
+                                let d = hi - lo;
+                                lo - 0.5 * d + rng.unit() * 2.0 * d
+                            })
+                            .collect()
+                    } else {
+                        first.clone()
+                    };
+                    for (gene, &(lo, hi)) in child.iter_mut().zip(&layout.bounds) {
+                        if rng.unit() < mutation_rate {
+                            *gene += rng.normal() * mutation_scale * (hi - lo);
+                        }
+                    }
+                    layout.repair(&mut child);
+                    next_losses.push(evaluate(&child));
+                    next.push(child);
+                }
+                population = next;
+                losses = next_losses;
+            }
+            Optimizer::DifferentialEvolution {
+                differential_weight,
+                crossover_rate,
+            } => {
+                let size = population.len();
+                let width = population[0].len();
+                for i in 0..size {
+                    let mut pick = |exclude: &[usize]| loop {
+                        let r = rng.below(size);
+                        if !exclude.contains(&r) {
+                            return r;
+                        }
+                    };
+                    let r1 = pick(&[i]);
+                    let r2 = pick(&[i, r1]);
+                    let r3 = pick(&[i, r1, r2]);
+                    let forced = rng.below(width);
+                    let mut trial = population[i].clone();
+                    for (j, gene) in trial.iter_mut().enumerate() {
+                        if j == forced || rng.unit() < crossover_rate {
+                            *gene = population[r1][j] + differential_weight * (population[r2][j] - population[r3][j]);
+                        }
+                    }
+                    layout.repair(&mut trial);
+                    let trial_loss = evaluate(&trial);
+                    if trial_loss <= losses[i] {
+                        population[i] = trial;
+                        losses[i] = trial_loss;
+                    }
+                }
+            }
+        }
+
+        best = fittest(&losses);
+        if losses[best] < best_loss {
+            best_loss = losses[best];
+            best_genes = population[best].clone();
+        }
+        record(generation, &losses, &best_genes, best_loss);
+    }
+
+    let report = TuningReport {
+        optimizer: config.optimizer.name(),
+        parameters: best_genes.len(),
+        evaluations,
+        generations: records,
+    };
+    Ok(Tuning {
+        system: report.best().system.clone(),
+        report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::{Antecedent, Consequent, LinguisticVariable, Rule};
+
+    fn system() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        system.add_input(
+            LinguisticVariable::new("x", 0.0, 10.0)
+                .with_term("low", MembershipFunction::Trapezoidal { a: 0.0, b: 0.0, c: 2.0, d: 6.0 })
+                .with_term("high", MembershipFunction::Sigmoid { a: -1.5, c: 6.0 }),
+        );
+        system.add_output(
+            LinguisticVariable::new("y", 0.0, 1.0)
+                .with_term("low", MembershipFunction::Gaussian { mean: 0.0, sigma: 0.3 })
+                .with_term("high", MembershipFunction::Bell { a: 0.3, b: 2.0, c: 1.0 }),
+        );
+        system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+        system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 1)]).with_weight(0.5));
+        system
+    }
+
+    #[test]
+    fn test_encoding_round_trips() {
+        let template = system();
+        let (layout, genes) = Layout::new(&template, true);
+        assert_eq!(genes.len(), 4 + 2 + 2 + 3 + 2);
+        assert_eq!(layout.bounds.len(), genes.len());
+        assert!(genes.iter().zip(&layout.bounds).all(|(g, (lo, hi))| lo <= g && g <= hi));
+        assert_eq!(format!("{:?}", layout.decode(&template, &genes)), format!("{:?}", template));
+        // The sigmoid keeps its falling slope
+        let (lo, hi) = layout.bounds[4];
+        assert!((lo + 15.0).abs() < 1e-12 && (hi + 0.15).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_repair_restores_order_and_bounds() {
+        let (layout, _) = Layout::new(&system(), false);
+        let mut genes = vec![7.0, 1.0, 100.0, 3.0, -1.5, 6.0, 0.0, 0.3, 0.3, 2.0, 1.0];
+        layout.repair(&mut genes);
+        assert_eq!(&genes[..4], &[1.0, 3.0, 7.0, 15.0]);
+
+        let mut rng = Rng::new(1);
+        for _ in 0..50 {
+            let random = layout.random(&mut rng);
+            assert!(random[..4].windows(2).all(|w| w[0] <= w[1]));
+            assert!(random[4] < 0.0 && random[7] > 0.0 && random[8] > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let inputs = [[1.0]];
+        let targets = [[0.0]];
+        let de = TuningConfig {
+            optimizer: Optimizer::differential_evolution(),
+            population: 3,
+            ..TuningConfig::default()
+        };
+        assert!(matches!(
+            tune(&system(), &inputs, &targets, squared_error, &de),
+            Err(TuningError::InvalidConfig(_))
+        ));
+        let config = TuningConfig::default();
+        assert_eq!(
+            tune(&system(), &[[1.0, 2.0]], &targets, squared_error, &config).unwrap_err(),
+            TuningError::WrongInputLength { sample: 0, expected: 1, actual: 2 }
+        );
+        assert_eq!(
+            tune(&system(), &inputs, &[[0.0]; 2], squared_error, &config).unwrap_err(),
+            TuningError::LengthMismatch { samples: 1, targets: 2 }
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Wang–Mendel Rule Extraction
+//!
+//! Generates a rule base from numeric samples with the method of Wang and
+//! Mendel (1992), given the terms of every input and output:
+//! 1. Each sample proposes a rule in which every variable takes the term
+//!    where the sample's value has the highest membership
+//! 2. The rule's degree is the product of those memberships
+//! 3. Proposals with the same antecedents but different conclusions
+//!    conflict; the one with the highest degree is kept
+//!
+//! The variables and settings come from a template system (any rules it
+//! has are not copied), so the result evaluates like a hand-written system.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::definition::format_rule;
+use crate::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+
+/// Errors from [`extract_rules`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtractionError {
+    /// The template has no inputs or no outputs
+    MissingVariables,
+    /// A variable has no terms to choose from
+    NoTerms(String),
+    /// The number of output rows differs from the number of input rows
+    LengthMismatch { inputs: usize, outputs: usize },
+    /// A row has the wrong number of values
+    WrongRowLength { sample: usize, expected: usize, actual: usize },
+    /// A value is NaN or infinite
+    NonFinite(usize),
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractionError::MissingVariables => write!(f, "the template needs inputs and outputs"),
+            ExtractionError::NoTerms(name) => write!(f, "variable '{}' has no terms", name),
+            ExtractionError::LengthMismatch { inputs, outputs } => {
+                write!(f, "{} input rows but {} output rows", inputs, outputs)
+            }
+            ExtractionError::WrongRowLength { sample, expected, actual } => write!(
+                f,
+                "sample {} has {} values, expected {}",
+                sample, actual, expected
+            ),
+            ExtractionError::NonFinite(sample) => write!(f, "sample {} is not finite", sample),
+        }
+    }
+}
+
+impl std::error::Error for ExtractionError {}
+
+/// A generated rule and the samples behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedRule {
+    /// The rule as written in definition files
+    pub text: String,
+    /// Degree of the sample that proposed the kept rule
+    pub degree: f64,
+    /// Index of that sample
+    pub sample: usize,
+    /// Number of samples that proposed exactly this rule
+    pub support: usize,
+}
+
+/// A proposal discarded because a rule with the same antecedents had a
+/// higher degree
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedConflict {
+    /// Index of the kept rule in the generated system
+    pub rule: usize,
+    /// Sample whose proposal was discarded
+    pub sample: usize,
+    pub degree: f64,
+    /// The discarded rule as written in definition files
+    pub text: String,
+}
+
+/// Result of [`extract_rules`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionReport {
+    pub samples: usize,
+    /// Samples with no membership in any term of some variable
+    pub skipped: Vec<usize>,
+    /// One entry per rule of the generated system
+    pub rules: Vec<ExtractedRule>,
+    pub conflicts: Vec<ResolvedConflict>,
+}
+
+/// Maximum number of conflicts listed by the `Display` implementation
+const MAX_LISTED_CONFLICTS: usize = 10;
+
+impl fmt::Display for ExtractionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Wang-Mendel rule extraction")?;
+        writeln!(f, "  Samples: {} ({} skipped)", self.samples, self.skipped.len())?;
+        writeln!(f, "  Rules: {}", self.rules.len())?;
+        for (i, rule) in self.rules.iter().enumerate() {
+            writeln!(
+                f,
+                "    {}. {} (degree {:.3}, {} samples)",
+                i + 1,
+                rule.text,
+                rule.degree,
+                rule.support
+            )?;
+        }
+        writeln!(f, "  Conflicts resolved: {}", self.conflicts.len())?;
+        for conflict in self.conflicts.iter().take(MAX_LISTED_CONFLICTS) {
+            writeln!(
+                f,
+                "    rule {} kept over sample {}: {} (degree {:.3})",
+                conflict.rule + 1,
+                conflict.sample,
+                conflict.text,
+                conflict.degree
+            )?;
+        }
+        if self.conflicts.len() > MAX_LISTED_CONFLICTS {
+            writeln!(f, "    ... and {} more", self.conflicts.len() - MAX_LISTED_CONFLICTS)?;
+        }
+        Ok(())
+    }
+}
+
+/// A generated system and the report of how its rules were chosen
+#[derive(Debug, Clone)]
+pub struct Extraction {
+    pub system: FuzzySystem,
+    pub report: ExtractionReport,
+}
+
+/// One sample's proposed rule
+struct Proposal {
+    sample: usize,
+    degree: f64,
+    consequents: Vec<usize>,
+}
+
+/// Index and degree of the term with the highest membership, or `None`
+/// when every membership is zero; ties go to the first term
+fn best_term(variable: &LinguisticVariable, x: f64) -> Option<(usize, f64)> {
+    variable
+        .terms
+        .iter()
+        .enumerate()
+        .map(|(i, term)| (i, term.membership(x)))
+        .fold(None, |best: Option<(usize, f64)>, (i, mu)| match best {
+            Some((_, top)) if top >= mu => best,
+            _ if mu > 0.0 => Some((i, mu)),
+            _ => best,
+        })
+}
+
+fn check_rows<S: AsRef<[f64]>>(rows: &[S], expected: usize) -> Result<(), ExtractionError> {
+    for (sample, row) in rows.iter().enumerate() {
+        let row = row.as_ref();
+        if row.len() != expected {
+            return Err(ExtractionError::WrongRowLength {
+                sample,
+                expected,
+                actual: row.len(),
+            });
+        }
+        if row.iter().any(|v| !v.is_finite()) {
+            return Err(ExtractionError::NonFinite(sample));
+        }
+    }
+    Ok(())
+}
+
+/// Generate a rule base for `template` from samples (one row of input
+/// values and one row of output values per sample)
+pub fn extract_rules<S: AsRef<[f64]>, U: AsRef<[f64]>>(
+    template: &FuzzySystem,
+    inputs: &[S],
+    outputs: &[U],
+) -> Result<Extraction, ExtractionError> {
+    if template.inputs().is_empty() || template.outputs().is_empty() {
+        return Err(ExtractionError::MissingVariables);
+    }
+    if let Some(v) = template.inputs().iter().chain(template.outputs()).find(|v| v.terms.is_empty()) {
+        return Err(ExtractionError::NoTerms(v.name.clone()));
+    }
+    if inputs.len() != outputs.len() {
+        return Err(ExtractionError::LengthMismatch {
+            inputs: inputs.len(),
+            outputs: outputs.len(),
+        });
+    }
+    check_rows(inputs, template.inputs().len())?;
+    check_rows(outputs, template.outputs().len())?;
+
+    // Proposals grouped by antecedent terms, in term order
+    let mut groups: BTreeMap<Vec<usize>, Vec<Proposal>> = BTreeMap::new();
+    let mut skipped = Vec::new();
+    for (sample, (x, y)) in inputs.iter().zip(outputs).enumerate() {
+        let chosen = |variables: &[LinguisticVariable], values: &[f64]| {
+            variables
+                .iter()
+                .zip(values)
+                .map(|(v, &value)| best_term(v, value))
+                .collect::<Option<Vec<(usize, f64)>>>()
+        };
+        let (Some(antecedents), Some(consequents)) = (
+            chosen(template.inputs(), x.as_ref()),
+            chosen(template.outputs(), y.as_ref()),
+        ) else {
+            skipped.push(sample);
+            continue;
+        };
+        let degree = antecedents.iter().chain(&consequents).map(|&(_, mu)| mu).product();
+        groups
+            .entry(antecedents.iter().map(|&(term, _)| term).collect())
+            .or_default()
+            .push(Proposal {
+                sample,
+                degree,
+                consequents: consequents.iter().map(|&(term, _)| term).collect(),
+            });
+    }
+
+    let mut system = template.clone();
+    while !system.rules().is_empty() {
+        system.remove_rule(system.rules().len() - 1);
+    }
+    let to_rule = |antecedents: &[usize], consequents: &[usize]| {
+        Rule::new(
+            antecedents.iter().enumerate().map(|(i, &t)| Antecedent::new(i, t)).collect(),
+            consequents.iter().enumerate().map(|(i, &t)| Consequent::new(i, t)).collect(),
+        )
+    };
+
+    let mut rules = Vec::new();
+    let mut conflicts = Vec::new();
+    for (antecedents, proposals) in &groups {
+        // The first of equally strong proposals wins
+        let winner = proposals
+            .iter()
+            .fold(&proposals[0], |best, p| if p.degree > best.degree { p } else { best });
+        let rule = to_rule(antecedents, &winner.consequents);
+        let index = system.add_rule(rule);
+        rules.push(ExtractedRule {
+            text: format_rule(&system, &system.rules()[index]),
+            degree: winner.degree,
+            sample: winner.sample,
+            support: proposals.iter().filter(|p| p.consequents == winner.consequents).count(),
+        });
+        for p in proposals.iter().filter(|p| p.consequents != winner.consequents) {
+            conflicts.push(ResolvedConflict {
+                rule: index,
+                sample: p.sample,
+                degree: p.degree,
+                text: format_rule(&system, &to_rule(antecedents, &p.consequents)),
+            });
+        }
+    }
+
+    Ok(Extraction {
+        system,
+        report: ExtractionReport {
+            samples: inputs.len(),
+            skipped,
+            rules,
+            conflicts,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::membership::MembershipFunction;
+
+    fn low_high(name: &str) -> LinguisticVariable {
+        LinguisticVariable::new(name, 0.0, 10.0)
+            .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 10.0 })
+            .with_term("high", MembershipFunction::Triangular { a: 0.0, b: 10.0, c: 10.0 })
+    }
+
+    fn template() -> FuzzySystem {
+        let mut system = FuzzySystem::new();
+        system.add_input(low_high("x"));
+        system.add_output(low_high("y"));
+        system
+    }
+
+    #[test]
+    fn test_best_term() {
+        let x = low_high("x");
+        assert_eq!(best_term(&x, 2.0), Some((0, 0.8)));
+        assert_eq!(best_term(&x, 9.0), Some((1, 0.9)));
+        // Ties go to the first term
+        assert_eq!(best_term(&x, 5.0), Some((0, 0.5)));
+        assert_eq!(best_term(&x, 12.0), None);
+    }
+
+    #[test]
+    fn test_conflicts_keep_highest_degree() {
+        let inputs = [[1.0], [2.0], [3.0], [9.0]];
+        // Samples 0 and 1 say low -> low, sample 2 says low -> high
+        let outputs = [[1.0], [4.0], [9.5], [8.0]];
+        let extraction = extract_rules(&template(), &inputs, &outputs).unwrap();
+        let report = &extraction.report;
+
+        assert_eq!(extraction.system.rules().len(), 2);
+        assert_eq!(report.rules[0].text, "if x is low then y is low");
+        assert_eq!(report.rules[0].sample, 0);
+        assert!((report.rules[0].degree - 0.81).abs() < 1e-12);
+        assert_eq!(report.rules[0].support, 2);
+        assert_eq!(report.rules[1].text, "if x is high then y is high");
+
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].rule, 0);
+        assert_eq!(report.conflicts[0].sample, 2);
+        assert_eq!(report.conflicts[0].text, "if x is low then y is high");
+        assert!((report.conflicts[0].degree - 0.7 * 0.95).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_skipped_samples_and_template_rules() {
+        let mut system = template();
+        system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 0)]));
+        let extraction = extract_rules(&system, &[[1.0], [20.0]], &[[1.0], [1.0]]).unwrap();
+
+        assert_eq!(extraction.report.skipped, vec![1]);
+        assert_eq!(extraction.system.rules().len(), 1);
+        assert_eq!(extraction.report.rules[0].text, "if x is low then y is low");
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let system = template();
+        assert_eq!(
+            extract_rules(&system, &[[1.0]], &[[1.0], [2.0]]).unwrap_err(),
+            ExtractionError::LengthMismatch { inputs: 1, outputs: 2 }
+        );
+        assert_eq!(
+            extract_rules(&system, &[vec![1.0, 2.0]], &[[1.0]]).unwrap_err(),
+            ExtractionError::WrongRowLength { sample: 0, expected: 1, actual: 2 }
+        );
+        assert_eq!(
+            extract_rules(&system, &[[1.0]], &[[f64::NAN]]).unwrap_err(),
+            ExtractionError::NonFinite(0)
+        );
+        let empty: [[f64; 1]; 0] = [];
+        assert_eq!(
+            extract_rules(&FuzzySystem::new(), &empty, &empty).unwrap_err(),
+            ExtractionError::MissingVariables
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! JavaScript Bindings
+//!
+//! WebAssembly exports for running the engine in the browser or Node
+//! (`wasm` feature). The `wasm` workspace member builds them into a module:
+//! `cargo build --release -p fuzzy_logic_wasm --target wasm32-unknown-unknown`
+//! followed by `wasm-bindgen --out-name fuzzy_logic`.
+//!
+//! ```js
+//! import init, { FuzzySystem, MembershipFunction } from "./fuzzy_logic.js";
+//!
+//! await init();
+//! const warm = MembershipFunction.triangular(10, 25, 35);
+//! warm.evaluate(20);                    // 0.666...
+//!
+//! const tipping = new FuzzySystem(definitionText);
+//! tipping.evaluate([3, 8]);             // Float64Array [tip]
+//! tipping.trace([3, 8]).rules[0].strength;
+//! ```
+//!
+//! Invalid arguments throw a JavaScript `Error` with the same message the
+//! Rust API reports.
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::defuzzification::defuzzify as defuzzify_with;
+use crate::definition::{
+    defuzzification_from_name, format_rule, format_system, parse_membership_function,
+    parse_system, set_option, snorm_from_name, tnorm_from_name,
+};
+use crate::membership::MembershipFunction;
+use crate::operations::{fuzzy_complement, fuzzy_intersection, fuzzy_union};
+use crate::system::FuzzySystem;
+
+/// A membership function
+#[wasm_bindgen(js_name = MembershipFunction)]
+pub struct WasmMembershipFunction {
+    inner: MembershipFunction,
+}
+
+#[wasm_bindgen(js_class = MembershipFunction)]
+impl WasmMembershipFunction {
+    pub fn triangular(a: f64, b: f64, c: f64) -> WasmMembershipFunction {
+        MembershipFunction::Triangular { a, b, c }.into()
+    }
+
+    pub fn trapezoidal(a: f64, b: f64, c: f64, d: f64) -> WasmMembershipFunction {
+        MembershipFunction::Trapezoidal { a, b, c, d }.into()
+    }
+
+    pub fn gaussian(mean: f64, sigma: f64) -> WasmMembershipFunction {
+        MembershipFunction::Gaussian { mean, sigma }.into()
+    }
+
+    pub fn sigmoid(a: f64, c: f64) -> WasmMembershipFunction {
+        MembershipFunction::Sigmoid { a, c }.into()
+    }
+
+    pub fn bell(a: f64, b: f64, c: f64) -> WasmMembershipFunction {
+        MembershipFunction::Bell { a, b, c }.into()
+    }
+
+    /// Parse a term shape as written in definitions, e.g. `"triangular 0 5 10"`
+    pub fn parse(text: &str) -> Result<WasmMembershipFunction, JsError> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        parse_membership_function(&tokens)
+            .map(Into::into)
+            .map_err(|message| JsError::new(&message))
+    }
+
+    pub fn evaluate(&self, x: f64) -> f64 {
+        self.inner.evaluate(x)
+    }
+
+    /// Evaluate at every point of `xs`
+    #[wasm_bindgen(js_name = evaluateMany)]
+    pub fn evaluate_many(&self, xs: &[f64]) -> Vec<f64> {
+        xs.iter().map(|&x| self.inner.evaluate(x)).collect()
+    }
+
+    pub fn description(&self) -> String {
+        self.inner.description()
+    }
+}
+
+impl From<MembershipFunction> for WasmMembershipFunction {
+    fn from(inner: MembershipFunction) -> Self {
+        WasmMembershipFunction { inner }
+    }
+}
+
+/// Standard fuzzy OR: max(a, b)
+#[wasm_bindgen(js_name = fuzzyUnion)]
+pub fn union(a: f64, b: f64) -> f64 {
+    fuzzy_union(a, b)
+}
+
+/// Standard fuzzy AND: min(a, b)
+#[wasm_bindgen(js_name = fuzzyIntersection)]
+pub fn intersection(a: f64, b: f64) -> f64 {
+    fuzzy_intersection(a, b)
+}
+
+/// Standard fuzzy NOT: 1 - a
+#[wasm_bindgen(js_name = fuzzyComplement)]
+pub fn complement(a: f64) -> f64 {
+    fuzzy_complement(a)
+}
+
+/// Apply a t-norm by name (`min`, `prod`, `bounded`, `drastic`)
+#[wasm_bindgen]
+pub fn tnorm(name: &str, a: f64, b: f64) -> Result<f64, JsError> {
+    let norm = tnorm_from_name(name).map_err(|message| JsError::new(&message))?;
+    Ok(norm.apply(a, b))
+}
+
+/// Apply an s-norm by name (`max`, `probor`, `bounded`, `drastic`)
+#[wasm_bindgen]
+pub fn snorm(name: &str, a: f64, b: f64) -> Result<f64, JsError> {
+    let norm = snorm_from_name(name).map_err(|message| JsError::new(&message))?;
+    Ok(norm.apply(a, b))
+}
+
+/// Defuzzify a sampled fuzzy set by method name (`centroid`, `mom`, `som`,
+/// `lom`, `bisector`)
+#[wasm_bindgen]
+pub fn defuzzify(method: &str, xs: &[f64], membership: &[f64]) -> Result<f64, JsError> {
+    let method = defuzzification_from_name(method).map_err(|message| JsError::new(&message))?;
+    if xs.len() != membership.len() {
+        return Err(JsError::new("xs and membership must have the same length"));
+    }
+    Ok(defuzzify_with(xs, membership, method))
+}
+
+/// A rule-based system built from a definition
+#[wasm_bindgen(js_name = FuzzySystem)]
+pub struct WasmFuzzySystem {
+    inner: FuzzySystem,
+}
+
+#[wasm_bindgen(js_class = FuzzySystem)]
+impl WasmFuzzySystem {
+    /// Parse definition text (the format of the `systems/` files)
+    #[wasm_bindgen(constructor)]
+    pub fn new(definition: &str) -> Result<WasmFuzzySystem, JsError> {
+        let inner = parse_system(definition).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmFuzzySystem { inner })
+    }
+
+    #[wasm_bindgen(js_name = inputNames)]
+    pub fn input_names(&self) -> Vec<String> {
+        self.inner.inputs().iter().map(|v| v.name.clone()).collect()
+    }
+
+    #[wasm_bindgen(js_name = outputNames)]
+    pub fn output_names(&self) -> Vec<String> {
+        self.inner.outputs().iter().map(|v| v.name.clone()).collect()
+    }
+
+    /// Change a setting such as `("and", "prod")` or `("resolution", "201")`
+    #[wasm_bindgen(js_name = setOption)]
+    pub fn set_option(&mut self, setting: &str, value: &str) -> Result<(), JsError> {
+        set_option(&mut self.inner, setting, value).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Evaluate one value per input; returns one value per output
+    pub fn evaluate(&self, inputs: &[f64]) -> Result<Vec<f64>, JsError> {
+        self.check_inputs(inputs)?;
+        Ok(self.inner.evaluate(inputs))
+    }
+
+    /// Evaluate and explain: `{ outputs, memberships, rules }`, where
+    /// `memberships[variable][term]` is a degree and each rule is
+    /// `{ rule, strength }`
+    pub fn trace(&self, inputs: &[f64]) -> Result<JsValue, JsError> {
+        self.check_inputs(inputs)?;
+        let system = &self.inner;
+
+        let outputs = Object::new();
+        for (variable, value) in system.outputs().iter().zip(system.evaluate(inputs)) {
+            set(&outputs, &variable.name, value.into())?;
+        }
+
+        let memberships = Object::new();
+        for (variable, &x) in system.inputs().iter().zip(inputs) {
+            let terms = Object::new();
+            for (term, mu) in variable.terms.iter().zip(variable.fuzzify(x)) {
+                set(&terms, &term.name, mu.into())?;
+            }
+            set(&memberships, &variable.name, terms.into())?;
+        }
+
+        let rules = Array::new();
+        for (rule, strength) in system.rules().iter().zip(system.firing_strengths(inputs)) {
+            let entry = Object::new();
+            set(&entry, "rule", format_rule(system, rule).into())?;
+            set(&entry, "strength", strength.into())?;
+            rules.push(&entry);
+        }
+
+        let trace = Object::new();
+        set(&trace, "outputs", outputs.into())?;
+        set(&trace, "memberships", memberships.into())?;
+        set(&trace, "rules", rules.into())?;
+        Ok(trace.into())
+    }
+
+    /// The system in the definition format
+    pub fn definition(&self) -> String {
+        format_system(&self.inner)
+    }
+}
+
+impl WasmFuzzySystem {
+    fn check_inputs(&self, inputs: &[f64]) -> Result<(), JsError> {
+        let expected = self.inner.inputs().len();
+        if inputs.len() != expected {
+            return Err(JsError::new(&format!(
+                "expected {} inputs, got {}",
+                expected,
+                inputs.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn set(object: &Object, key: &str, value: JsValue) -> Result<(), JsError> {
+    Reflect::set(object, &key.into(), &value)
+        .map(|_| ())
+        .map_err(|_| JsError::new("cannot build trace object"))
+}
@@ -0,0 +1,131 @@
+//! ANFIS training tests
+//!
+//! Train on synthetic data and check the learned systems against held-out
+//! points, for each premise shape.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::anfis::{train, AnfisConfig, PremiseShape};
+use fuzzy_logic::membership::MembershipFunction;
+
+/// Every point of an `n × n` grid over `[0, 1]²`
+fn grid(n: usize) -> Vec<[f64; 2]> {
+    (0..n * n)
+        .map(|i| [(i % n) as f64 / (n - 1) as f64, (i / n) as f64 / (n - 1) as f64])
+        .collect()
+}
+
+fn surface(x: &[f64; 2]) -> f64 {
+    (3.0 * x[0]).sin() * (1.0 + x[1] * x[1])
+}
+
+#[test]
+fn test_learns_nonlinear_surface_with_each_shape() {
+    let inputs = grid(15);
+    let targets: Vec<f64> = inputs.iter().map(surface).collect();
+
+    for shape in [PremiseShape::Gaussian, PremiseShape::Bell, PremiseShape::Triangular] {
+        let config = AnfisConfig {
+            shape,
+            epochs: 60,
+            ..AnfisConfig::default()
+        };
+        let model = train(&inputs, &targets, &config).unwrap();
+        let report = &model.report;
+
+        assert_eq!(report.rules, 9);
+        assert_eq!(report.training_samples + report.validation_samples, inputs.len());
+        let first = report.epochs[0].validation_rmse.unwrap();
+        let best = report.best().validation_rmse.unwrap();
+        assert!(best < 0.5 * first, "{:?}: {} from {}", shape, best, first);
+        assert!(best < 0.01, "{:?}: validation RMSE {}", shape, best);
+
+        // The result is an ordinary system: check it off the training grid
+        for x in [[0.13, 0.71], [0.52, 0.05], [0.91, 0.38]] {
+            let y = model.system.evaluate(&x).unwrap();
+            assert!((y - surface(&x)).abs() < 0.05, "{:?} at {:?}: {}", shape, x, y);
+        }
+        let kind_matches = model.system.inputs().iter().flatten().all(|mf| {
+            matches!(
+                (shape, mf),
+                (PremiseShape::Gaussian, MembershipFunction::Gaussian { .. })
+                    | (PremiseShape::Bell, MembershipFunction::Bell { .. })
+                    | (PremiseShape::Triangular, MembershipFunction::Triangular { .. })
+            )
+        });
+        assert!(kind_matches);
+    }
+}
+
+#[test]
+fn test_premise_training_improves_on_least_squares_alone() {
+    let inputs = grid(15);
+    let targets: Vec<f64> = inputs.iter().map(surface).collect();
+    let config = AnfisConfig {
+        terms_per_input: 2,
+        epochs: 100,
+        validation_fraction: 0.0,
+        ..AnfisConfig::default()
+    };
+    let report = train(&inputs, &targets, &config).unwrap().report;
+
+    assert_eq!(report.validation_samples, 0);
+    assert!(report.epochs[0].validation_rmse.is_none());
+    // The first epoch is least squares over the initial terms
+    assert!(report.best().training_rmse < 0.5 * report.epochs[0].training_rmse);
+}
+
+#[test]
+fn test_alarm_classifier() {
+    // Labelled alarms: raised when temperature and pressure are both high
+    let inputs: Vec<[f64; 2]> = (0..400)
+        .map(|i| [20.0 + (i % 20) as f64 * 4.0, 1.0 + (i / 20) as f64 * 0.25])
+        .collect();
+    let alarm = |x: &[f64; 2]| x[0] > 60.0 && x[1] > 3.5;
+    let targets: Vec<f64> = inputs.iter().map(|x| if alarm(x) { 1.0 } else { 0.0 }).collect();
+
+    let config = AnfisConfig {
+        seed: 42,
+        ..AnfisConfig::default()
+    };
+    let model = train(&inputs, &targets, &config).unwrap();
+
+    let correct = inputs
+        .iter()
+        .filter(|x| (model.system.evaluate(&x[..]).unwrap() > 0.5) == alarm(x))
+        .count();
+    assert!(correct as f64 / inputs.len() as f64 > 0.95, "{} of {}", correct, inputs.len());
+}
+
+#[test]
+fn test_early_stopping_and_report() {
+    // Noisy targets: the validation error stops improving long before 500 epochs
+    let inputs = grid(12);
+    let targets: Vec<f64> = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, x)| surface(x) + if i % 2 == 0 { 0.3 } else { -0.3 })
+        .collect();
+    let config = AnfisConfig {
+        terms_per_input: 4,
+        epochs: 500,
+        patience: 5,
+        seed: 7,
+        ..AnfisConfig::default()
+    };
+    let model = train(&inputs, &targets, &config).unwrap();
+    let report = &model.report;
+
+    assert!(report.stopped_early);
+    assert_eq!(report.epochs.len(), report.best_epoch + config.patience);
+    let best = report.best().validation_rmse.unwrap();
+    assert!(report.epochs.iter().all(|r| r.validation_rmse.unwrap() >= best));
+
+    let text = report.to_string();
+    assert!(text.starts_with("ANFIS training: 16 rules, 115 training / 29 validation samples"));
+    assert!(text.contains("stopped early"));
+    assert_eq!(report.to_csv().lines().count(), report.epochs.len() + 1);
+
+    // Same seed, same split and result
+    let again = train(&inputs, &targets, &config).unwrap();
+    assert_eq!(&again.report, report);
+}
@@ -0,0 +1,281 @@
+//! Command-line tool tests
+//!
+//! These run the built binary against the definitions in `systems/`.
+#![cfg(feature = "std")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fuzzy_logic"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start fuzzy_logic");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("fuzzy_logic_cli_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn test_eval_arguments() {
+    let positional = run(&["eval", "systems/tipping.fuzzy", "3", "8"], "");
+    let named = run(&["eval", "systems/tipping.fuzzy", "food=8", "service=3"], "");
+
+    assert_eq!(positional.status.code(), Some(0));
+    assert_eq!(stdout(&positional), stdout(&named));
+    let tip: f64 = stdout(&positional).trim().strip_prefix("tip=").unwrap().parse().unwrap();
+    assert!(tip > 10.0 && tip < 20.0);
+}
+
+#[test]
+fn test_eval_csv_stdin() {
+    let output = run(&["eval", "systems/tipping.fuzzy"], "food,service\n8,3\n1,1\n\n9,9\n");
+    let text = stdout(&output);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "service,food,tip");
+    assert!(lines[1].starts_with("3,8,"));
+    let tips: Vec<f64> = lines[1..]
+        .iter()
+        .map(|l| l.rsplit(',').next().unwrap().parse().unwrap())
+        .collect();
+    assert!(tips[1] < tips[0] && tips[0] < tips[2]);
+}
+
+#[test]
+fn test_eval_stream_file_with_mapping() {
+    let path = temp_path("readings.jsonl");
+    std::fs::write(
+        &path,
+        "{\"t\": 35, \"h\": 80, \"sensor\": \"roof\"}\n{\"t\": 5, \"h\": 10, \"sensor\": \"cellar\"}\n",
+    )
+    .unwrap();
+    let output = run(
+        &["eval", "systems/temperature.fuzzy", "--input", path.to_str().unwrap(), "--map", "t=temp,h=humidity", "--passthrough"],
+        "",
+    );
+    std::fs::remove_file(&path).unwrap();
+    let text = stdout(&output);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"t\":35,\"h\":80,\"sensor\":\"roof\",\"fan\":"));
+    assert!(lines[1].contains("\"sensor\":\"cellar\""));
+}
+
+#[test]
+fn test_eval_stream_error_rows() {
+    let input = "service,food\n3,8\n3,abc\n";
+    let emitted = run(&["eval", "systems/tipping.fuzzy", "--on-error", "emit"], input);
+    assert_eq!(emitted.status.code(), Some(0));
+    assert!(stdout(&emitted).contains("3,abc,,food: 'abc' is not a number"));
+    assert!(stderr(&emitted).contains("1 of 2 records failed"));
+
+    let skipped = run(&["eval", "systems/tipping.fuzzy", "--on-error", "skip", "--trace"], input);
+    assert_eq!(skipped.status.code(), Some(0));
+    assert_eq!(stdout(&skipped).lines().count(), 2);
+    assert!(stdout(&skipped).lines().next().unwrap().ends_with("rule.9"));
+}
+
+#[test]
+fn test_eval_bad_data_exit_code() {
+    let output = run(&["eval", "systems/tipping.fuzzy"], "service,food\n3,abc\n");
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("line 2"));
+
+    let output = run(&["eval", "systems/tipping.fuzzy"], "service\n3\n");
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("no column 'food'"));
+}
+
+#[test]
+fn test_usage_errors() {
+    assert_eq!(run(&["frobnicate"], "").status.code(), Some(2));
+    assert_eq!(run(&["eval"], "").status.code(), Some(2));
+    assert_eq!(run(&["eval", "systems/tipping.fuzzy", "3"], "").status.code(), Some(2));
+    assert_eq!(run(&["eval", "systems/tipping.fuzzy", "mood=3", "food=1"], "").status.code(), Some(2));
+    assert_eq!(run(&["validate", "systems/tipping.fuzzy", "--bogus"], "").status.code(), Some(2));
+    assert_eq!(run(&["eval", "systems/tipping.fuzzy", "--format", "xml"], "").status.code(), Some(2));
+    assert_eq!(run(&["eval", "systems/tipping.fuzzy", "--trace", "3", "8"], "").status.code(), Some(2));
+    for command in ["describe", "surface", "plot"] {
+        assert_eq!(run(&[command, "systems/tipping.fuzzy", "extra"], "").status.code(), Some(2));
+    }
+
+    let help = run(&["help"], "");
+    assert_eq!(help.status.code(), Some(0));
+    assert!(stdout(&help).contains("Exit codes"));
+}
+
+#[test]
+fn test_extract_rules_from_samples() {
+    // Sample the tipping system, then regenerate its rules from the samples
+    let mut grid = String::from("service,food\n");
+    for service in 0..=10 {
+        for food in 0..=10 {
+            grid.push_str(&format!("{},{}\n", service, food));
+        }
+    }
+    let samples = stdout(&run(&["eval", "systems/tipping.fuzzy"], &grid));
+
+    let path = temp_path("extracted.fuzzy");
+    let output = run(&["extract", "systems/tipping.fuzzy", "--out", path.to_str().unwrap()], &samples);
+    let definition = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stderr(&output).starts_with("Wang-Mendel rule extraction\n  Samples: 121 (0 skipped)\n  Rules: 9\n"));
+    assert!(definition.contains("rule if service is poor and food is poor then tip is low"));
+
+    let original = run(&["eval", "systems/tipping.fuzzy", "2", "7"], "");
+    let extracted = run(&["eval", path.to_str().unwrap(), "2", "7"], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(stdout(&extracted), stdout(&original));
+
+    let missing = run(&["extract", "systems/tipping.fuzzy"], "service,food\n1,2\n");
+    assert_eq!(missing.status.code(), Some(4));
+    assert!(stderr(&missing).contains("no column 'tip'"));
+}
+
+#[test]
+fn test_simulate_scenario() {
+    let path = temp_path("room.csv");
+    let output = run(&["simulate", "systems/room.scenario", "--out", path.to_str().unwrap()], "");
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stderr(&output).starts_with("Closed-loop response\n  Rise time: "));
+
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "time,setpoint,output,control");
+    assert_eq!(rows.len(), 1 + 14401);
+    assert!(rows[1].starts_with("0,21,15,"));
+    let last: Vec<f64> = rows[14401].split(',').map(|v| v.parse().unwrap()).collect();
+    assert!((last[2] - 19.0).abs() < 0.01);
+
+    let scenario = temp_path("broken.scenario");
+    std::fs::write(&scenario, "plant room capacity 1 conductance 1 ambient 0\n").unwrap();
+    let broken = run(&["simulate", scenario.to_str().unwrap()], "");
+    std::fs::remove_file(&scenario).unwrap();
+    assert_eq!(broken.status.code(), Some(3));
+    assert!(stderr(&broken).contains("line 1: expected capacity VALUE"));
+
+    // Runs too long to simulate fail cleanly instead of allocating or hanging
+    let base = "plant first-order gain 1 time-constant 1\ncontroller fuzzy pi\npid kp 1 ki 1 kd 0 range 1\nsetpoint 1\n";
+    for (settings, message) in [
+        ("dead-time 1e300\n", "line 5: dead-time / step is more than 1000000 steps"),
+        ("step 1e-6\nduration 1e300\n", "line 6: duration / step is more than 1000000 steps"),
+    ] {
+        let scenario = temp_path("huge.scenario");
+        std::fs::write(&scenario, format!("{}{}", base, settings)).unwrap();
+        let huge = run(&["simulate", scenario.to_str().unwrap()], "");
+        std::fs::remove_file(&scenario).unwrap();
+        assert_eq!(huge.status.code(), Some(3));
+        assert!(stderr(&huge).contains(message), "{}", stderr(&huge));
+    }
+}
+
+#[test]
+fn test_validate() {
+    let output = run(&["validate", "systems/temperature.fuzzy", "--strict"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("rules 9"));
+
+    let missing = run(&["validate", "systems/missing.fuzzy"], "");
+    assert_eq!(missing.status.code(), Some(3));
+
+    let path = temp_path("broken.fuzzy");
+    std::fs::write(&path, "input x 0 10\n  term low triangular 0 0\n").unwrap();
+    let broken = run(&["validate", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(broken.status.code(), Some(3));
+    assert!(stderr(&broken).contains("line 2"));
+}
+
+#[test]
+fn test_validate_strict_fails_on_findings() {
+    let path = temp_path("gappy.fuzzy");
+    std::fs::write(
+        &path,
+        "input x 0 10\n  term low triangular 0 0 2\n  term high triangular 8 10 10\n\
+         output y 0 1\n  term small triangular 0 0 1\n\
+         rule if x is low then y is small\n",
+    )
+    .unwrap();
+    let lenient = run(&["validate", path.to_str().unwrap()], "");
+    let strict = run(&["validate", path.to_str().unwrap(), "--strict"], "");
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(lenient.status.code(), Some(0));
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(stdout(&strict).contains("x is high"));
+}
+
+#[test]
+fn test_describe() {
+    let output = run(&["describe", "systems/tipping.fuzzy"], "");
+    let text = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(text.contains("service [0, 10]"));
+    assert!(text.contains("defuzzification: centroid"));
+    assert!(text.contains("9. if service is excellent and food is excellent then tip is high"));
+}
+
+#[test]
+fn test_surface_and_plot() {
+    let csv = run(&["surface", "systems/tipping.fuzzy", "--steps", "5"], "");
+    assert_eq!(csv.status.code(), Some(0));
+    assert_eq!(stdout(&csv).lines().count(), 26);
+    assert!(stdout(&csv).starts_with("service,food,tip\n"));
+
+    let path = temp_path("surface.svg");
+    let svg = run(
+        &["surface", "systems/temperature.fuzzy", "--x", "humidity", "--format", "svg", "--out", path.to_str().unwrap()],
+        "",
+    );
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(svg.status.code(), Some(0));
+    assert!(content.starts_with("<svg"));
+
+    let plot = run(&["plot", "systems/tipping.fuzzy", "--variable", "food", "--width", "30"], "");
+    assert_eq!(plot.status.code(), Some(0));
+    assert!(stdout(&plot).contains("* poor"));
+    assert_eq!(run(&["plot", "systems/tipping.fuzzy", "--variable", "wine"], "").status.code(), Some(2));
+}
+
+#[test]
+fn test_repl_session() {
+    let output = run(
+        &["repl", "systems/tipping.fuzzy"],
+        "rules\nand prod\neval 3 8\nbogus\nquit\n",
+    );
+    let text = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(text.contains("9. if service is excellent and food is excellent then tip is high"));
+    assert!(text.contains("tip = "));
+    assert!(text.contains("error: unknown command 'bogus'"));
+    assert_eq!(run(&["repl", "systems/missing.fuzzy"], "").status.code(), Some(3));
+}
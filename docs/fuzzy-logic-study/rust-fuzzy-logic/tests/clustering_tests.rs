@@ -0,0 +1,159 @@
+//! Clustering tests
+//!
+//! Cluster synthetic data with known structure and check the centers,
+//! validity indices, projected terms and initialized rule bases.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::clustering::{fuzzy_c_means, gustafson_kessel, subtractive, FcmConfig, SubtractiveConfig};
+use fuzzy_logic::membership::MembershipFunction;
+
+const BLOB_CENTERS: [[f64; 2]; 3] = [[0.0, 0.0], [6.0, 1.0], [2.0, 7.0]];
+
+/// Three round blobs of 25 points each
+fn blobs() -> Vec<[f64; 2]> {
+    let mut points = Vec::new();
+    for center in BLOB_CENTERS {
+        for i in 0..25 {
+            let angle = i as f64 * 2.4;
+            let radius = 0.2 + 0.6 * (i % 5) as f64 / 4.0;
+            points.push([center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]);
+        }
+    }
+    points
+}
+
+fn nearest_center(clustering_centers: &[Vec<f64>], target: [f64; 2]) -> f64 {
+    clustering_centers
+        .iter()
+        .map(|c| ((c[0] - target[0]).powi(2) + (c[1] - target[1]).powi(2)).sqrt())
+        .fold(f64::INFINITY, f64::min)
+}
+
+#[test]
+fn test_fcm_recovers_blobs() {
+    let data = blobs();
+    let config = FcmConfig {
+        clusters: 3,
+        seed: 11,
+        ..FcmConfig::default()
+    };
+    let clustering = fuzzy_c_means(&data, &config).unwrap();
+
+    assert!(clustering.converged);
+    for target in BLOB_CENTERS {
+        assert!(nearest_center(&clustering.centers, target) < 0.1, "{:?}", clustering.centers);
+    }
+    let labels = clustering.labels();
+    for blob in labels.chunks(25) {
+        assert!(blob.iter().all(|&l| l == blob[0]));
+    }
+}
+
+#[test]
+fn test_validity_indices_choose_the_number_of_clusters() {
+    let data = blobs();
+    let scores: Vec<(usize, f64, f64)> = (2..=6)
+        .map(|clusters| {
+            let config = FcmConfig {
+                clusters,
+                seed: 5,
+                ..FcmConfig::default()
+            };
+            let clustering = fuzzy_c_means(&data, &config).unwrap();
+            (clusters, clustering.partition_coefficient(), clustering.xie_beni(&data))
+        })
+        .collect();
+
+    let best_pc = scores.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap().0;
+    let best_xb = scores.iter().min_by(|a, b| a.2.total_cmp(&b.2)).unwrap().0;
+    assert_eq!(best_pc, 3, "{:?}", scores);
+    assert_eq!(best_xb, 3, "{:?}", scores);
+}
+
+#[test]
+fn test_gustafson_kessel_separates_elongated_clusters() {
+    // Two long parallel lines, closer to each other than their length
+    let data: Vec<[f64; 2]> = (0..80)
+        .map(|i| {
+            let t = (i / 2) as f64 / 4.0;
+            [t, if i % 2 == 0 { 0.0 } else { 1.5 } + 0.05 * (t * 7.0).sin()]
+        })
+        .collect();
+    let config = FcmConfig {
+        clusters: 2,
+        seed: 2,
+        ..FcmConfig::default()
+    };
+
+    let gk = gustafson_kessel(&data, &config).unwrap();
+    let labels = gk.labels();
+    let lower = labels[0];
+    for (i, &label) in labels.iter().enumerate() {
+        assert_eq!(label == lower, i % 2 == 0, "sample {}", i);
+    }
+    // Plain C-means splits the lines across their length instead
+    let fcm = fuzzy_c_means(&data, &config).unwrap();
+    let fcm_labels = fcm.labels();
+    assert!((0..80).step_by(2).any(|i| fcm_labels[i] != fcm_labels[0]));
+}
+
+#[test]
+fn test_subtractive_chooses_cluster_count() {
+    let data = blobs();
+    let config = SubtractiveConfig {
+        radius: 0.3,
+        ..SubtractiveConfig::default()
+    };
+    let clustering = subtractive(&data, &config).unwrap();
+
+    assert_eq!(clustering.centers.len(), 3);
+    for target in BLOB_CENTERS {
+        assert!(nearest_center(&clustering.centers, target) < 1.0, "{:?}", clustering.centers);
+    }
+}
+
+#[test]
+fn test_projected_terms_and_sugeno_initialization() {
+    // Cluster the product space of input and output, then fit consequents
+    let samples: Vec<[f64; 2]> = (0..=100)
+        .map(|i| {
+            let x = i as f64 / 10.0;
+            [x, x.sin()]
+        })
+        .collect();
+    let config = FcmConfig {
+        clusters: 6,
+        seed: 1,
+        ..FcmConfig::default()
+    };
+    let clustering = fuzzy_c_means(&samples, &config).unwrap();
+
+    let terms = clustering.gaussian_terms();
+    assert_eq!(terms.len(), 2);
+    assert_eq!(terms[0].len(), 6);
+    for (j, term) in terms[0].iter().enumerate() {
+        assert_eq!(
+            *term,
+            MembershipFunction::Gaussian {
+                mean: clustering.centers[j][0],
+                sigma: clustering.spreads[j][0],
+            }
+        );
+    }
+
+    let inputs: Vec<[f64; 1]> = samples.iter().map(|s| [s[0]]).collect();
+    let targets: Vec<f64> = samples.iter().map(|s| s[1]).collect();
+    let system = clustering.sugeno_system(&inputs, &targets).unwrap();
+    assert_eq!(system.inputs().len(), 1);
+    assert_eq!(system.rules().len(), 6);
+
+    let rmse = (inputs
+        .iter()
+        .zip(&targets)
+        .map(|(x, y)| (system.evaluate(x).unwrap() - y).powi(2))
+        .sum::<f64>()
+        / inputs.len() as f64)
+        .sqrt();
+    // A straight line through the data is off by about 0.6
+    assert!(rmse < 0.1, "RMSE {}", rmse);
+}
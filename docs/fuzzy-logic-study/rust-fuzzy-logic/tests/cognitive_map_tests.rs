@@ -0,0 +1,120 @@
+//! Fuzzy cognitive map tests
+//!
+//! A plant-condition map: scenario analysis and learning its weights from
+//! recorded state sequences.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::cognitive_map::{
+    nonlinear_hebbian, prediction_error, CognitiveMapError, FuzzyCognitiveMap, HebbianConfig, Outcome, RunConfig,
+    Squashing,
+};
+
+const CONCEPTS: [&str; 4] = ["load", "temperature", "cooling", "alarms"];
+
+/// load → temperature → alarms, with cooling reacting to and damping the
+/// temperature
+fn plant_map(weights: [f64; 4]) -> FuzzyCognitiveMap {
+    let mut map = FuzzyCognitiveMap::new().with_squashing(Squashing::Sigmoid { steepness: 2.0 });
+    for name in CONCEPTS {
+        map.add_concept(name);
+    }
+    map.set_weight(0, 1, weights[0]);
+    map.set_weight(1, 2, weights[1]);
+    map.set_weight(2, 1, weights[2]);
+    map.set_weight(1, 3, weights[3]);
+    map
+}
+
+#[test]
+fn test_scenarios_compare_loads() {
+    let map = plant_map([0.8, 0.7, -0.6, 0.9]);
+    let load = map.concept_index("load").unwrap();
+    let alarms = map.concept_index("alarms").unwrap();
+    let config = RunConfig::default();
+
+    let light = map.what_if(&[0.5; 4], &[(load, 0.1)], &config);
+    let heavy = map.what_if(&[0.5; 4], &[(load, 1.0)], &config);
+    assert_eq!(light.outcome, Outcome::FixedPoint);
+    assert_eq!(heavy.outcome, Outcome::FixedPoint);
+    assert!(heavy.final_state()[alarms] > light.final_state()[alarms]);
+    // Without cooling, a heavy load raises more alarms
+    let cooling = map.concept_index("cooling").unwrap();
+    let failed = map.what_if(&[0.5; 4], &[(load, 1.0), (cooling, 0.0)], &config);
+    assert!(failed.final_state()[alarms] > heavy.final_state()[alarms]);
+    assert!(failed.to_string().contains("  alarms       "));
+}
+
+/// Recorded states in which temperature follows load, cooling works
+/// against temperature and alarms follow it
+fn recordings() -> Vec<Vec<Vec<f64>>> {
+    [[0.2, 0.6, 0.3, 0.8, 0.5, 0.1, 0.7], [0.9, 0.4, 0.6, 0.2, 0.5, 0.3, 0.8]]
+        .iter()
+        .map(|loads| {
+            loads
+                .iter()
+                .map(|&load| {
+                    let temperature = 0.1 + 0.8 * load;
+                    vec![load, temperature, 0.9 - 0.5 * temperature, 0.3 + 0.25 * temperature]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_hebbian_learning_follows_co_movement() {
+    let sequences = recordings();
+    let expert = plant_map([0.3, 0.3, -0.3, 0.3]);
+    let learning = nonlinear_hebbian(&expert, &sequences, &HebbianConfig::default()).unwrap();
+    assert!(learning.converged);
+    assert_eq!(learning.errors.len(), learning.epochs + 1);
+    assert_eq!(learning.errors[0], prediction_error(&expert, &sequences));
+
+    // Each weight settles at the ratio of its ends' changes: temperature
+    // moves 0.8 per unit of load and alarms 0.25 per unit of temperature
+    assert!((learning.map.weight(0, 1) - 0.8).abs() < 1e-4);
+    assert!((learning.map.weight(1, 3) - 0.25).abs() < 1e-4);
+    // Temperature moves -2 per unit of cooling, clamped to the weight range
+    assert_eq!(learning.map.weight(2, 1), -1.0);
+    // Cooling falls as temperature rises, which contradicts the expert's
+    // positive edge: it stops at zero instead of turning negative, and no
+    // new edges appear
+    assert_eq!(learning.map.weight(1, 2), 0.0);
+    assert_eq!(learning.map.edges().len(), 3);
+    assert!(learning.map.weight(0, 2) == 0.0 && learning.map.weight(3, 0) == 0.0);
+
+    // Decay pulls every weight towards zero
+    let decayed = nonlinear_hebbian(&expert, &sequences, &HebbianConfig { decay: 0.95, ..HebbianConfig::default() }).unwrap();
+    assert!(decayed.map.weight(0, 1) > 0.0 && decayed.map.weight(0, 1) < learning.map.weight(0, 1));
+    assert!(decayed.map.weight(1, 3) > 0.0 && decayed.map.weight(1, 3) < learning.map.weight(1, 3));
+}
+
+#[test]
+fn test_hebbian_learning_rejects_bad_data() {
+    let map = plant_map([0.5, 0.5, -0.5, 0.5]);
+    let config = HebbianConfig::default();
+    assert_eq!(
+        nonlinear_hebbian(&map, &[vec![vec![0.5; 4]]], &config).err(),
+        Some(CognitiveMapError::NoTransitions)
+    );
+    assert_eq!(
+        nonlinear_hebbian(&map, &[vec![vec![0.5; 4], vec![0.5; 3]]], &config).err(),
+        Some(CognitiveMapError::WrongStateLength {
+            sequence: 0,
+            step: 1,
+            expected: 4,
+            actual: 3
+        })
+    );
+    // Sigmoid activations cannot be negative
+    let negative = vec![vec![0.5; 4], vec![0.5, -0.2, 0.5, 0.5]];
+    assert_eq!(
+        nonlinear_hebbian(&map, &[negative], &config).err(),
+        Some(CognitiveMapError::OutOfRange { sequence: 0, step: 1 })
+    );
+    let bad = HebbianConfig { decay: 0.0, ..config };
+    assert!(matches!(
+        nonlinear_hebbian(&map, &[vec![vec![0.5; 4]; 2]], &bad),
+        Err(CognitiveMapError::InvalidConfig(_))
+    ));
+}
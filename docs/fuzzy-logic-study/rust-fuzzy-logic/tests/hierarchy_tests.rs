@@ -0,0 +1,151 @@
+//! Hierarchical system tests
+//!
+//! The alarm severity model: sensor health feeds zone risk, which feeds
+//! the site alarm priority.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::hierarchy::{Feed, Hierarchy};
+use fuzzy_logic::membership::MembershipFunction;
+use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+
+fn variable(name: &str, max: f64, terms: [&str; 2]) -> LinguisticVariable {
+    LinguisticVariable::new(name, 0.0, max)
+        .with_term(terms[0], MembershipFunction::Trapezoidal { a: 0.0, b: 0.0, c: 0.3 * max, d: 0.7 * max })
+        .with_term(terms[1], MembershipFunction::Trapezoidal { a: 0.3 * max, b: 0.7 * max, c: max, d: max })
+}
+
+/// Vibration (mm/s) and bearing temperature (°C) to health in [0, 1]
+fn sensor_health() -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    system.add_input(variable("vibration", 20.0, ["normal", "excessive"]));
+    system.add_input(variable("temperature", 120.0, ["normal", "hot"]));
+    system.add_output(variable("health", 1.0, ["poor", "good"]));
+    system.add_rule(Rule::new(
+        vec![Antecedent::new(0, 0), Antecedent::new(1, 0)],
+        vec![Consequent::new(0, 1)],
+    ));
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 0)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(1, 1)], vec![Consequent::new(0, 0)]));
+    system
+}
+
+/// Two pump healths to zone risk in [0, 1]; the zone is at risk when
+/// either pump is in poor health
+fn zone_risk() -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    system.add_input(variable("duty", 1.0, ["poor", "good"]));
+    system.add_input(variable("standby", 1.0, ["poor", "good"]));
+    system.add_output(variable("risk", 1.0, ["low", "high"]));
+    system.add_rule(Rule::new(
+        vec![Antecedent::new(0, 1), Antecedent::new(1, 1)],
+        vec![Consequent::new(0, 0)],
+    ));
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 1)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(1, 0)], vec![Consequent::new(0, 1)]));
+    system
+}
+
+/// Two zone risks to a site alarm priority in [0, 10]
+fn site_priority() -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    system.add_input(variable("north", 1.0, ["low", "high"]));
+    system.add_input(variable("south", 1.0, ["low", "high"]));
+    system.add_output(variable("priority", 10.0, ["routine", "urgent"]));
+    system.add_rule(Rule::new(
+        vec![Antecedent::new(0, 0), Antecedent::new(1, 0)],
+        vec![Consequent::new(0, 0)],
+    ));
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 1)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(1, 1)], vec![Consequent::new(0, 1)]));
+    system
+}
+
+/// Four pumps in two zones, with zone risk passed on as a crisp value or
+/// as a fuzzy set
+fn alarm_model(fuzzy: bool) -> Hierarchy {
+    let mut hierarchy = Hierarchy::new();
+    let mut pumps = Vec::new();
+    for pump in ["n1", "n2", "s1", "s2"] {
+        let vibration = hierarchy.add_input(format!("{}_vibration", pump));
+        let temperature = hierarchy.add_input(format!("{}_temperature", pump));
+        let node = hierarchy.add_node(format!("{}_health", pump), sensor_health()).unwrap();
+        hierarchy.connect(node, 0, Feed::Input(vibration)).unwrap();
+        hierarchy.connect(node, 1, Feed::Input(temperature)).unwrap();
+        pumps.push(node);
+    }
+    // The site node is added before the zones it depends on
+    let site = hierarchy.add_node("site", site_priority()).unwrap();
+    for (z, zone) in ["north", "south"].iter().enumerate() {
+        let node = hierarchy.add_node(*zone, zone_risk()).unwrap();
+        hierarchy.connect(node, 0, Feed::Crisp { node: pumps[2 * z], output: 0 }).unwrap();
+        hierarchy.connect(node, 1, Feed::Crisp { node: pumps[2 * z + 1], output: 0 }).unwrap();
+        let feed = if fuzzy {
+            Feed::Fuzzy { node, output: 0 }
+        } else {
+            Feed::Crisp { node, output: 0 }
+        };
+        hierarchy.connect(site, z, feed).unwrap();
+    }
+    hierarchy
+}
+
+/// Healthy readings except for one pump
+fn readings(vibration: f64, temperature: f64) -> Vec<f64> {
+    let mut values = vec![2.0, 40.0, 3.0, 45.0, 2.5, 42.0, 1.5, 38.0];
+    values[4] = vibration;
+    values[5] = temperature;
+    values
+}
+
+#[test]
+fn test_alarm_priority_follows_sensor_health() {
+    let hierarchy = alarm_model(false);
+    assert_eq!(hierarchy.inputs().len(), 8);
+    let order = hierarchy.evaluation_order().unwrap();
+    let position = |name: &str| order.iter().position(|&n| hierarchy.node_name(n) == name).unwrap();
+    assert!(position("s1_health") < position("south") && position("south") < position("site"));
+    assert_eq!(position("site"), order.len() - 1);
+
+    let calm = hierarchy.evaluate(&readings(2.0, 40.0)).unwrap();
+    let failing = hierarchy.evaluate(&readings(18.0, 110.0)).unwrap();
+    let calm_priority = calm.output("site", "priority").unwrap();
+    let failing_priority = failing.output("site", "priority").unwrap();
+    assert!(calm_priority < 4.0, "{}", calm_priority);
+    assert!(failing_priority > 6.0, "{}", failing_priority);
+    assert!(failing.output("south", "risk").unwrap() > failing.output("north", "risk").unwrap());
+
+    // The trace matches evaluating each system by hand
+    let health = sensor_health().evaluate(&[18.0, 110.0])[0];
+    assert!((failing.output("s1_health", "health").unwrap() - health).abs() < 1e-12);
+    let south = failing.node("south").unwrap();
+    assert_eq!(south.inputs[0], health);
+    assert_eq!(south.strengths, zone_risk().firing_strengths(&south.inputs));
+    let text = failing.to_string();
+    assert!(text.contains("Node south\n  duty = "), "{}", text);
+    assert!(text.contains("(crisp s1_health.health)"));
+}
+
+#[test]
+fn test_fuzzy_feeds_keep_upstream_uncertainty() {
+    let crisp = alarm_model(false);
+    let fuzzy = alarm_model(true);
+    // A borderline pump: a crisp feed collapses the zone's mixed risk into
+    // one number, while a fuzzy feed passes on how possible "high" is
+    let values = readings(8.0, 50.0);
+    let crisp = crisp.evaluate(&values).unwrap();
+    let fuzzy = fuzzy.evaluate(&values).unwrap();
+
+    let south = fuzzy.node("south").unwrap();
+    let site_crisp = crisp.node("site").unwrap();
+    let site_fuzzy = fuzzy.node("site").unwrap();
+    assert_eq!(site_fuzzy.inputs[1], south.outputs[0]);
+    // Each zone risk term stays at least as possible as its rules made it
+    let high = south.strengths[1].max(south.strengths[2]);
+    assert!(site_fuzzy.degrees[1][0] >= south.strengths[0] - 1e-9);
+    assert!(site_fuzzy.degrees[1][1] >= high - 1e-9);
+    // ...which leaves the site less certain that the south zone is safe
+    let spread = |degrees: &[f64]| (degrees[0] - degrees[1]).abs();
+    assert!(spread(&site_fuzzy.degrees[1]) < spread(&site_crisp.degrees[1]));
+    assert!(site_fuzzy.outputs[0] > site_crisp.outputs[0]);
+    assert!(fuzzy.to_string().contains("(fuzzy south.risk)"));
+}
@@ -2,6 +2,7 @@
 //!
 //! This file contains comprehensive tests that verify the library works
 //! correctly end-to-end.
+#![cfg(feature = "std")]
 
 use fuzzy_logic::membership::{FuzzySet, MembershipFunction};
 use fuzzy_logic::operations::*;
@@ -89,9 +90,9 @@ fn test_algebraic_operations() {
 #[test]
 fn test_bounded_operations() {
     assert_eq!(bounded_sum(0.7, 0.5), 1.0, "Bounded sum should cap at 1");
-    assert_eq!(bounded_sum(0.3, 0.4), 0.7, "Bounded sum < 1");
+    assert!((bounded_sum(0.3, 0.4) - 0.7).abs() < 1e-10, "Bounded sum < 1");
 
-    assert_eq!(bounded_difference(0.7, 0.5), 0.2, "Bounded difference");
+    assert!((bounded_difference(0.7, 0.5) - 0.2).abs() < 1e-10, "Bounded difference");
     assert_eq!(bounded_difference(0.3, 0.4), 0.0, "Bounded difference floors at 0");
 }
 
@@ -224,7 +225,7 @@ fn test_multiple_membership_functions() {
     for mf in mf_types {
         let val = mf.evaluate(5.0);
         assert!(
-            val >= 0.0 && val <= 1.0,
+            (0.0..=1.0).contains(&val),
             "Membership should be in [0, 1]"
         );
     }
@@ -0,0 +1,125 @@
+//! `no_std` core tests
+//!
+//! This test crate is itself `no_std`, so it only compiles against the
+//! parts of the library that are available without the standard library.
+//! Run it with the library built for embedded targets:
+//! `cargo test --no-default-features --features libm --test no_std_core`
+//! (add `alloc` to cover the rule-based systems).
+#![no_std]
+
+use fuzzy_logic::defuzzification::{bisector, centroid, defuzzify, mean_of_maximum, DefuzzificationMethod};
+use fuzzy_logic::fixed::Q16_16;
+use fuzzy_logic::membership::MembershipFunction;
+use fuzzy_logic::numeric::Real;
+use fuzzy_logic::operations::{fuzzy_complement, fuzzy_intersection, fuzzy_union, SNorm, TNorm};
+use fuzzy_logic::sugeno::{SugenoRule, SugenoSystem};
+
+static TEMPERATURE: [MembershipFunction; 3] = [
+    MembershipFunction::Trapezoidal { a: 0.0, b: 0.0, c: 10.0, d: 20.0 },
+    MembershipFunction::Triangular { a: 10.0, b: 20.0, c: 30.0 },
+    MembershipFunction::Trapezoidal { a: 20.0, b: 30.0, c: 40.0, d: 40.0 },
+];
+static HUMIDITY: [MembershipFunction; 2] = [
+    MembershipFunction::Sigmoid { a: -0.2, c: 50.0 },
+    MembershipFunction::Sigmoid { a: 0.2, c: 50.0 },
+];
+static INPUTS: [&[MembershipFunction]; 2] = [&TEMPERATURE, &HUMIDITY];
+static RULES: [SugenoRule; 4] = [
+    SugenoRule::constant(&[Some(0), None], 0.0),
+    SugenoRule::constant(&[Some(1), None], 40.0),
+    SugenoRule::linear(&[Some(2), Some(0)], &[2.0, 0.0], 10.0),
+    SugenoRule::constant(&[Some(2), Some(1)], 100.0),
+];
+static FAN: SugenoSystem = SugenoSystem::new(&INPUTS, &RULES).with_and_method(TNorm::AlgebraicProduct);
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn test_membership_functions() {
+    let gaussian = MembershipFunction::Gaussian { mean: 5.0, sigma: 2.0 };
+    assert_eq!(gaussian.evaluate(5.0), 1.0);
+    assert!(close(gaussian.evaluate(7.0), 0.606_530_659_712_633_4));
+    assert!(close(HUMIDITY[1].evaluate(50.0), 0.5));
+    assert!(close(TEMPERATURE[1].evaluate(15.0), 0.5));
+    assert_eq!(TEMPERATURE[0].evaluate(5.0), 1.0);
+}
+
+#[test]
+fn test_operations() {
+    assert_eq!(fuzzy_union(0.3, 0.6), 0.6);
+    assert_eq!(fuzzy_intersection(0.3, 0.6), 0.3);
+    assert!(close(fuzzy_complement(0.3), 0.7));
+    assert!(close(TNorm::AlgebraicProduct.apply(0.5, 0.4), 0.2));
+    assert!(close(SNorm::BoundedSum.apply(0.7, 0.6), 1.0));
+}
+
+#[test]
+fn test_defuzzification_on_arrays() {
+    let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+    let mu = [0.0, 0.5, 1.0, 0.5, 0.0];
+    assert!(close(centroid(&xs, &mu), 2.0));
+    assert!(close(mean_of_maximum(&xs, &mu), 2.0));
+    assert!(close(bisector(&xs, &mu), 2.0));
+    assert!(close(defuzzify(&xs, &mu, DefuzzificationMethod::Centroid), 2.0));
+}
+
+#[test]
+fn test_static_sugeno_controller() {
+    // Cold: only the first rule fires
+    assert_eq!(FAN.evaluate(&[0.0, 50.0]), Some(0.0));
+    // Halfway between cold and mild
+    assert!(close(FAN.evaluate(&[15.0, 50.0]).unwrap(), 20.0));
+    // Hot and very humid: the humid rule dominates
+    let speed = FAN.evaluate(&[35.0, 90.0]).unwrap();
+    assert!(speed > 95.0 && speed < 100.0);
+    assert_eq!(FAN.evaluate(&[-50.0, 50.0]), None);
+}
+
+#[test]
+fn test_fixed_point_sugeno_controller() {
+    let q = Q16_16::from_f64;
+    let temperature = [TEMPERATURE[0].cast(), TEMPERATURE[1].cast(), TEMPERATURE[2].cast()];
+    let humidity = [HUMIDITY[0].cast(), HUMIDITY[1].cast()];
+    let inputs: [&[MembershipFunction<Q16_16>]; 2] = [&temperature, &humidity];
+    let coefficients = [q(2.0), q(0.0)];
+    let rules = [
+        SugenoRule::constant(&[Some(0), None], q(0.0)),
+        SugenoRule::constant(&[Some(1), None], q(40.0)),
+        SugenoRule::linear(&[Some(2), Some(0)], &coefficients, q(10.0)),
+        SugenoRule::constant(&[Some(2), Some(1)], q(100.0)),
+    ];
+    let fan = SugenoSystem::new(&inputs, &rules).with_and_method(TNorm::AlgebraicProduct);
+
+    for (t, h) in [(0.0, 50.0), (15.0, 50.0), (27.5, 40.0), (35.0, 90.0)] {
+        let expected = FAN.evaluate(&[t, h]).unwrap();
+        let actual = fan.evaluate(&[q(t), q(h)]).unwrap().to_f64();
+        assert!((actual - expected).abs() < 1e-3);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_rule_based_system_with_alloc() {
+    extern crate alloc;
+    use alloc::vec;
+    use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+
+    let mut system = FuzzySystem::new();
+    system.add_input(
+        LinguisticVariable::new("temperature", 0.0, 40.0)
+            .with_term("cold", TEMPERATURE[0].clone())
+            .with_term("hot", TEMPERATURE[2].clone()),
+    );
+    system.add_output(
+        LinguisticVariable::new("fan", 0.0, 100.0)
+            .with_term("slow", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 50.0 })
+            .with_term("fast", MembershipFunction::Triangular { a: 50.0, b: 100.0, c: 100.0 }),
+    );
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 1)]));
+
+    assert!(system.evaluate(&[5.0])[0] < 25.0);
+    assert!(system.evaluate(&[38.0])[0] > 75.0);
+}
@@ -0,0 +1,116 @@
+//! Numeric type tests
+//!
+//! Run the tipping and temperature systems in `f32` and fixed point and
+//! bound their deviation from the `f64` results over a grid of inputs.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::definition::load_system;
+use fuzzy_logic::fixed::{Q16_16, Q1_31};
+use fuzzy_logic::membership::MembershipFunction;
+use fuzzy_logic::numeric::Real;
+use fuzzy_logic::system::{FuzzySystem, LinguisticVariable, SNorm, TNorm};
+
+fn load(name: &str) -> FuzzySystem {
+    load_system(format!("{}/systems/{}.fuzzy", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// Every combination of `steps + 1` evenly spaced values per input
+fn grid(system: &FuzzySystem, steps: usize) -> Vec<Vec<f64>> {
+    let mut points = vec![Vec::new()];
+    for variable in system.inputs() {
+        points = points
+            .into_iter()
+            .flat_map(|point| {
+                variable.universe(steps + 1).into_iter().map(move |x| {
+                    let mut point = point.clone();
+                    point.push(x);
+                    point
+                })
+            })
+            .collect();
+    }
+    points
+}
+
+/// Largest absolute difference between `system` run in `T` and in `f64`
+fn max_error<T: Real>(system: &FuzzySystem, steps: usize) -> f64 {
+    let converted = system.cast::<T>();
+    grid(system, steps)
+        .iter()
+        .map(|point| {
+            let inputs: Vec<T> = point.iter().map(|&x| T::from_f64(x)).collect();
+            let expected = system.evaluate(point);
+            let actual = converted.evaluate(&inputs);
+            expected
+                .iter()
+                .zip(&actual)
+                .map(|(e, a)| (e - a.to_f64()).abs())
+                .fold(0.0, f64::max)
+        })
+        .fold(0.0, f64::max)
+}
+
+fn scale(mf: &MembershipFunction, k: f64) -> MembershipFunction {
+    match *mf {
+        MembershipFunction::Triangular { a, b, c } => MembershipFunction::Triangular { a: a * k, b: b * k, c: c * k },
+        MembershipFunction::Trapezoidal { a, b, c, d } => {
+            MembershipFunction::Trapezoidal { a: a * k, b: b * k, c: c * k, d: d * k }
+        }
+        MembershipFunction::Gaussian { mean, sigma } => MembershipFunction::Gaussian { mean: mean * k, sigma: sigma * k },
+        MembershipFunction::Sigmoid { a, c } => MembershipFunction::Sigmoid { a: a / k, c: c * k },
+        MembershipFunction::Bell { a, b, c } => MembershipFunction::Bell { a: a * k, b, c: c * k },
+    }
+}
+
+/// The system with every universe `[0, max]` mapped onto `[0, 1]`, the
+/// range of Q1.31
+fn normalised(system: &FuzzySystem) -> FuzzySystem {
+    let rescale = |v: &LinguisticVariable| {
+        let k = 1.0 / v.max;
+        v.terms.iter().fold(LinguisticVariable::new(v.name.clone(), 0.0, 1.0), |variable, term| {
+            variable.with_term(term.name.clone(), scale(&term.membership_function, k))
+        })
+    };
+    let mut result = FuzzySystem::new();
+    for v in system.inputs() {
+        result.add_input(rescale(v));
+    }
+    for v in system.outputs() {
+        result.add_output(rescale(v));
+    }
+    for rule in system.rules() {
+        result.add_rule(rule.clone());
+    }
+    result
+}
+
+#[test]
+fn test_tipping_error_bounds() {
+    let tipping = load("tipping");
+    assert!(max_error::<f32>(&tipping, 20) < 1e-4);
+    // Tip in 0..30: within 0.001 percentage points
+    assert!(max_error::<Q16_16>(&tipping, 20) < 1e-3);
+
+    let unit = normalised(&tipping);
+    assert!(max_error::<Q1_31>(&unit, 20) < 1e-8);
+}
+
+#[test]
+fn test_temperature_error_bounds() {
+    let temperature = load("temperature");
+    assert!(max_error::<f32>(&temperature, 20) < 1e-4);
+    // Fan speed in 0..100
+    assert!(max_error::<Q16_16>(&temperature, 20) < 5e-3);
+
+    let unit = normalised(&temperature);
+    assert!(max_error::<Q1_31>(&unit, 20) < 1e-8);
+}
+
+#[test]
+fn test_fixed_point_with_product_operators() {
+    let mut tipping = load("tipping");
+    tipping.set_and_method(TNorm::AlgebraicProduct);
+    tipping.set_implication(TNorm::AlgebraicProduct);
+    tipping.set_aggregation(SNorm::AlgebraicSum);
+    assert!(max_error::<Q16_16>(&tipping, 20) < 1e-3);
+}
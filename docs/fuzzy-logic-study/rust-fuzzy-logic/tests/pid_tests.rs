@@ -0,0 +1,119 @@
+//! Fuzzy PID tests
+//!
+//! Close the loop around simple simulated plants and check tracking,
+//! saturation and anti-windup.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::pid::{
+    gain_schedule, pid_rule_base, AntiWindup, Controller, FuzzyPid, FuzzyPidGains, GainScheduledPid, PidMode,
+};
+
+const DT: f64 = 0.01;
+
+/// Drive a first-order lag `τ y' = K u - y + disturbance` from rest
+/// towards `setpoint`, returning the measurement and control action at
+/// every step
+fn first_order(
+    controller: &mut impl Controller,
+    setpoint: f64,
+    disturbance: f64,
+    seconds: f64,
+) -> Vec<(f64, f64)> {
+    let (gain, tau) = (2.0, 1.5);
+    let mut y = 0.0;
+    (0..(seconds / DT) as usize)
+        .map(|_| {
+            let u = controller.step(setpoint, y, DT);
+            y += (gain * u - y + disturbance) / tau * DT;
+            (y, u)
+        })
+        .collect()
+}
+
+/// Mass on a damped spring, `y'' = u - 0.4 y' - y`
+fn second_order(controller: &mut impl Controller, setpoint: f64, seconds: f64) -> Vec<(f64, f64)> {
+    let (mut y, mut v) = (0.0, 0.0);
+    (0..(seconds / DT) as usize)
+        .map(|_| {
+            let u = controller.step(setpoint, y, DT);
+            let a = u - 0.4 * v - y;
+            v += a * DT;
+            y += v * DT;
+            (y, u)
+        })
+        .collect()
+}
+
+fn overshoot(trace: &[(f64, f64)], setpoint: f64) -> f64 {
+    trace.iter().map(|(y, _)| y - setpoint).fold(0.0, f64::max)
+}
+
+#[test]
+fn test_integral_forms_remove_offset_and_pd_does_not() {
+    let disturbance = -1.0;
+    let setpoint = 1.0;
+    let mut results = Vec::new();
+    for mode in [PidMode::Pd, PidMode::Pi, PidMode::Pid] {
+        let gains = FuzzyPidGains::from_pid(mode, 2.0, 1.5, 0.1, 2.0);
+        let mut pid = FuzzyPid::new(pid_rule_base(), mode, gains);
+        let trace = first_order(&mut pid, setpoint, disturbance, 30.0);
+        results.push((mode, (setpoint - trace.last().unwrap().0).abs()));
+    }
+
+    assert!(results[0].1 > 0.05, "{:?}", results);
+    assert!(results[1].1 < 0.01, "{:?}", results);
+    assert!(results[2].1 < 0.01, "{:?}", results);
+}
+
+#[test]
+fn test_output_stays_within_limits() {
+    let gains = FuzzyPidGains::from_pid(PidMode::Pid, 20.0, 5.0, 0.5, 1.0);
+    let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pid, gains).with_output_limits(-1.0, 2.0);
+    let trace = second_order(&mut pid, 1.5, 40.0);
+
+    assert!(trace.iter().all(|&(_, u)| (-1.0..=2.0).contains(&u)));
+    assert!(trace.iter().any(|&(_, u)| u == 2.0));
+    assert!((trace.last().unwrap().0 - 1.5).abs() < 0.02);
+}
+
+#[test]
+fn test_anti_windup_limits_overshoot_after_saturation() {
+    // A large step keeps the actuator saturated for a long time
+    let run = |mode: PidMode, anti_windup: AntiWindup| {
+        let gains = FuzzyPidGains::from_pid(mode, 1.0, 2.0, 0.0, 5.0);
+        let mut pid = FuzzyPid::new(pid_rule_base(), mode, gains)
+            .with_output_limits(0.0, 3.0)
+            .with_anti_windup(anti_windup);
+        let trace = first_order(&mut pid, 5.0, 0.0, 40.0);
+        (overshoot(&trace, 5.0), trace.last().unwrap().0)
+    };
+
+    for mode in [PidMode::Pi, PidMode::Pid] {
+        let (wound, _) = run(mode, AntiWindup::None);
+        for anti_windup in [AntiWindup::Conditional, AntiWindup::BackCalculation(1.0)] {
+            let (limited, settled) = run(mode, anti_windup);
+            assert!(limited < 0.5 * wound, "{:?} {:?}: {} vs {}", mode, anti_windup, limited, wound);
+            assert!((settled - 5.0).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_gain_scheduling_tracks_setpoints() {
+    let schedule = gain_schedule((2.0, 6.0), (0.5, 2.0), (0.2, 1.0));
+    let mut pid = GainScheduledPid::new(schedule, 1.0, 0.5).with_output_limits(-10.0, 10.0);
+
+    for setpoint in [1.0, -0.5] {
+        pid.reset();
+        let trace = second_order(&mut pid, setpoint, 30.0);
+        assert!((trace.last().unwrap().0 - setpoint).abs() < 0.01);
+    }
+
+    // Far from the setpoint the schedule raises Kp and lowers Ki
+    pid.reset();
+    pid.step(1.0, 0.0, DT);
+    let far = pid.gains();
+    pid.step(1.0, 0.999, DT);
+    let near = pid.gains();
+    assert!(far.0 > near.0 && far.1 < near.1, "{:?} vs {:?}", far, near);
+}
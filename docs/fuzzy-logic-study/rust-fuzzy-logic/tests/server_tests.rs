@@ -0,0 +1,230 @@
+//! HTTP server tests
+//!
+//! These start `fuzzy_logic serve` on a free localhost port and talk to it
+//! over plain TCP.
+#![cfg(feature = "server")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A running server, stopped when dropped
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(args: &[&str]) -> Server {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_fuzzy_logic"))
+            .arg("serve")
+            .args(args)
+            .args(["--addr", "127.0.0.1:0", "--watch-interval", "50"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start fuzzy_logic serve");
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on http://")
+            .unwrap_or_else(|| panic!("unexpected banner {:?}", line))
+            .to_string();
+        Server { child, addr }
+    }
+
+    /// Send a request and return the status code and body
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fuzzy_logic_server_{}_{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn copy_system(name: &str, dir: &Path) -> PathBuf {
+    let target = dir.join(format!("{}.fuzzy", name));
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("systems").join(format!("{}.fuzzy", name)),
+        &target,
+    )
+    .unwrap();
+    target
+}
+
+/// Retry `check` until it passes or a few seconds elapse
+fn eventually(mut check: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if check() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
+#[test]
+fn test_health_and_listing() {
+    let server = Server::start(&["systems"]);
+
+    let (status, body) = server.request("GET", "/health", "");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"status\":\"ok\""));
+    assert!(body.contains("\"systems\":2"));
+
+    let (status, body) = server.request("GET", "/systems", "");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"name\":\"temperature\""));
+    assert!(body.contains("{\"name\":\"service\",\"min\":0,\"max\":10,\"terms\":[\"poor\",\"good\",\"excellent\"]}"));
+
+    let (status, body) = server.request("GET", "/systems/tipping", "");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"rules\":9"));
+}
+
+#[test]
+fn test_evaluate() {
+    let server = Server::start(&["systems/tipping.fuzzy"]);
+
+    let (status, body) = server.request(
+        "POST",
+        "/systems/tipping/evaluate",
+        r#"{"inputs": {"service": 3, "food": 8}}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body.starts_with("{\"system\":\"tipping\",\"outputs\":{\"tip\":"));
+    assert!(!body.contains("trace"));
+
+    let (status, body) = server.request(
+        "POST",
+        "/systems/tipping/evaluate",
+        r#"{"inputs": {"service": 3, "food": 8}, "trace": true}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body.contains("\"trace\":{\"memberships\":{\"service\":"));
+    assert!(body.contains("{\"rule\":\"if service is poor and food is poor then tip is low\",\"strength\":"));
+}
+
+#[test]
+fn test_errors() {
+    let server = Server::start(&["systems/tipping.fuzzy"]);
+
+    let (status, body) = server.request("POST", "/systems/tipping/evaluate", r#"{"inputs": {"service": 3}}"#);
+    assert_eq!(status, 400);
+    assert!(body.contains("missing input 'food'"));
+    let (status, _) = server.request("POST", "/systems/tipping/evaluate", r#"{"inputs": {"service": "x", "food": 1}}"#);
+    assert_eq!(status, 400);
+    let (status, _) = server.request("POST", "/systems/tipping/evaluate", "[1, 2]");
+    assert_eq!(status, 400);
+    let (status, _) = server.request("POST", "/systems/temperature/evaluate", "{}");
+    assert_eq!(status, 404);
+    let (status, _) = server.request("GET", "/systems/tipping/evaluate", "");
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn test_hot_reload() {
+    let dir = temp_dir("reload");
+    let path = copy_system("tipping", &dir);
+    let server = Server::start(&[dir.to_str().unwrap()]);
+    let evaluate = || {
+        server.request(
+            "POST",
+            "/systems/tipping/evaluate",
+            r#"{"inputs": {"service": 3, "food": 8}}"#,
+        )
+    };
+    let (_, before) = evaluate();
+
+    // Switching to bisector changes the output for the same inputs
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, format!("{}\ndefuzzification bisector\n", text)).unwrap();
+    assert!(eventually(|| evaluate().1 != before));
+
+    // A broken edit keeps the last good system serving
+    std::fs::write(&path, "input service 0 10\n  term poor triangular 0 0\n").unwrap();
+    assert!(eventually(|| server.request("GET", "/systems/tipping", "").1.contains("line 2")));
+    assert_eq!(evaluate().0, 200);
+
+    copy_system("temperature", &dir);
+    assert!(eventually(|| server.request("GET", "/systems/temperature", "").0 == 200));
+    std::fs::remove_file(dir.join("temperature.fuzzy")).unwrap();
+    assert!(eventually(|| server.request("GET", "/systems/temperature", "").0 == 404));
+
+    drop(server);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_busy_server_refuses_connections() {
+    let server = Server::start(&["systems/tipping.fuzzy"]);
+    // Idle connections occupy every worker and fill the queue
+    let idle: Vec<TcpStream> = (0..80).map(|_| TcpStream::connect(&server.addr).unwrap()).collect();
+
+    let mut stream = TcpStream::connect(&server.addr).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{}", response);
+    assert!(response.ends_with("{\"error\":\"server is busy\"}"));
+
+    // Closed connections free the workers again
+    drop(idle);
+    assert!(eventually(|| server.request("GET", "/health", "").0 == 200));
+}
+
+#[test]
+fn test_oversized_header_is_rejected() {
+    let server = Server::start(&["systems/tipping.fuzzy"]);
+    let mut stream = TcpStream::connect(&server.addr).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    write!(stream, "GET /health HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(64 << 10)).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"), "{}", response);
+
+    // The server keeps serving afterwards
+    assert_eq!(server.request("GET", "/health", "").0, 200);
+}
+
+#[test]
+fn test_invalid_definition_exit_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fuzzy_logic"))
+        .args(["serve", "systems/missing.fuzzy", "--addr", "127.0.0.1:0"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
@@ -0,0 +1,80 @@
+//! Closed-loop simulation tests
+//!
+//! Run the fuzzy controllers against the plant models directly and through
+//! scenario files.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::definition::format_system;
+use fuzzy_logic::pid::{gain_schedule, pid_rule_base, FuzzyPid, FuzzyPidGains, PidMode};
+use fuzzy_logic::simulation::{
+    simulate, DeadTime, Integrator, Scenario, ScenarioError, SimulationConfig, ThermalRoom,
+};
+
+fn room() -> ThermalRoom {
+    ThermalRoom {
+        capacity: 200_000.0,
+        conductance: 100.0,
+        ambient: 10.0,
+        heater_power: 3000.0,
+        initial: 10.0,
+    }
+}
+
+#[test]
+fn test_fuzzy_pi_heats_a_room_behind_dead_time() {
+    let config = SimulationConfig {
+        step: 1.0,
+        duration: 6000.0,
+        integrator: Integrator::RungeKutta4,
+    };
+    let run = |delay: f64| {
+        let plant = DeadTime {
+            plant: Box::new(room()),
+            delay,
+        };
+        let gains = FuzzyPidGains::from_pid(PidMode::Pi, 0.5, 0.002, 0.0, 5.0);
+        let mut pid = FuzzyPid::new(pid_rule_base(), PidMode::Pi, gains).with_output_limits(0.0, 1.0);
+        simulate(&plant, &mut pid, &|_| 20.0, &config).unwrap()
+    };
+
+    let prompt = run(0.0);
+    let delayed = run(60.0);
+    assert!(prompt.control.iter().chain(&delayed.control).all(|u| (0.0..=1.0).contains(u)));
+    // Holding 20 °C against 10 °C outside takes a third of the heater
+    assert!((prompt.control.last().unwrap() - 1.0 / 3.0).abs() < 1e-3);
+
+    let (prompt, delayed) = (prompt.metrics(), delayed.metrics());
+    assert!(prompt.steady_state_error.abs() < 0.01 && delayed.steady_state_error.abs() < 0.01);
+    assert!(delayed.overshoot > prompt.overshoot, "{} vs {}", delayed.overshoot, prompt.overshoot);
+    assert!(delayed.iae > prompt.iae);
+    assert!(prompt.settling_time.unwrap() < 3000.0);
+}
+
+#[test]
+fn test_scenario_with_definition_files() {
+    let dir = std::env::temp_dir().join(format!("fuzzy_logic_simulation_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("schedule.fuzzy"), format_system(&gain_schedule((2.0, 6.0), (0.5, 2.0), (0.2, 1.0))))
+        .unwrap();
+    std::fs::write(dir.join("rules.fuzzy"), format_system(&pid_rule_base())).unwrap();
+
+    let plant = "plant second-order gain 1 frequency 1 damping 0.2\nsetpoint 1\nstep 0.01\nduration 30\n";
+    let scheduled = format!("{}controller scheduled schedule.fuzzy\nscale error 1 change 0.5\nlimits -10 10\n", plant);
+    let fuzzy = format!("{}controller fuzzy pid rules.fuzzy\npid kp 4 ki 1 kd 0.5 range 1\n", plant);
+    for text in [scheduled, fuzzy] {
+        let mut scenario = Scenario::parse(&text, &dir).unwrap();
+        let metrics = scenario.run().unwrap().metrics();
+        assert!(metrics.steady_state_error.abs() < 0.01, "{}\n{}", text, metrics);
+        assert!(metrics.settling_time.is_some());
+    }
+
+    // The schedule has three outputs, so it cannot drive a fuzzy PID
+    let wrong = format!("{}controller fuzzy pi schedule.fuzzy\ngains error 1 change 1 integral 0 output 1\n", plant);
+    let error = Scenario::parse(&wrong, &dir).err().unwrap();
+    assert!(matches!(error, ScenarioError::Syntax { line: 5, .. }), "{}", error);
+    let missing = format!("{}controller scheduled absent.fuzzy\n", plant);
+    let error = Scenario::parse(&missing, &dir).err().unwrap();
+    assert!(matches!(error, ScenarioError::Definition { .. }), "{}", error);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,94 @@
+//! Fuzzy time-series tests
+//!
+//! Reproduce Chen's forecasts of the University of Alabama enrollments,
+//! fit Song and Chissom's relation to them and evaluate the model variants
+//! walk-forward.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::time_series::{walk_forward, ForecastConfig, FuzzyTimeSeries, Model, Partition, Weighting};
+
+/// Enrollments 1971-1992
+const ENROLLMENTS: [f64; 22] = [
+    13055.0, 13563.0, 13867.0, 14696.0, 15460.0, 15311.0, 15603.0, 15861.0, 16807.0, 16919.0, 16388.0, 15433.0,
+    15497.0, 15145.0, 15163.0, 15984.0, 16859.0, 18150.0, 18970.0, 19328.0, 19337.0, 18876.0,
+];
+
+#[test]
+fn test_chen_enrollment_forecasts() {
+    // Chen (1996): seven intervals of 1000 from 13000 to 20000
+    let partition = Partition::new(13000.0, 20000.0, 7);
+    let model = FuzzyTimeSeries::fit_with_partition(&ENROLLMENTS, partition, &ForecastConfig::default()).unwrap();
+    let groups: Vec<String> = model.groups().iter().map(|g| g.to_string()).collect();
+    // Groups list every occurrence; Chen's model uses the distinct ones
+    assert_eq!(groups[0], "A1 -> A1, A1, A2");
+
+    // Chen's published forecasts for 1972-1992
+    let expected = [
+        14000.0, 14000.0, 14000.0, 15500.0, 16000.0, 16000.0, 16000.0, 16000.0, 16833.0, 16833.0, 16833.0, 16000.0,
+        16000.0, 16000.0, 16000.0, 16000.0, 16833.0, 19000.0, 19000.0, 19000.0, 19000.0,
+    ];
+    let mut squared = 0.0;
+    for (t, &chen) in (1..ENROLLMENTS.len()).zip(&expected) {
+        let forecast = model.forecast_from(&ENROLLMENTS[..t]);
+        assert!((forecast - chen).abs() < 0.5, "{}: {} vs {}", 1971 + t, forecast, chen);
+        squared += (forecast - ENROLLMENTS[t]).powi(2);
+    }
+    // Chen reports a mean squared error of 407507 with rounded forecasts
+    let mse = squared / expected.len() as f64;
+    assert!((mse - 407_507.0).abs() < 100.0, "{}", mse);
+}
+
+#[test]
+fn test_song_chissom_enrollments() {
+    let config = ForecastConfig {
+        model: Model::SongChissom,
+        ..ForecastConfig::default()
+    };
+    let model = FuzzyTimeSeries::fit_with_partition(&ENROLLMENTS, Partition::new(13000.0, 20000.0, 7), &config).unwrap();
+    let relation = model.relation();
+    assert_eq!(relation.len(), 7);
+    // 1971-1972 moved A1 → A1, so R holds A1 × A1 at full degree
+    assert_eq!(relation[0][0], 1.0);
+    // Nothing left A1 for A5 or above, even through the 0.5 neighbours
+    assert!(relation[0][4..].iter().all(|&r| r == 0.0));
+
+    // High enrollments forecast high, low ones low
+    let low = model.forecast_from(&[13055.0]);
+    let high = model.forecast_from(&[19337.0]);
+    assert!(low < 16000.0 && high > 18000.0, "{} {}", low, high);
+
+    let evaluation = walk_forward(&ENROLLMENTS, 10, &config).unwrap();
+    assert!(evaluation.mape > 0.0 && evaluation.mape < 15.0, "{}", evaluation);
+}
+
+#[test]
+fn test_walk_forward_evaluation() {
+    let mut results = Vec::new();
+    for (order, weighting) in [
+        (1, Weighting::Equal),
+        (1, Weighting::Recurrence),
+        (1, Weighting::Chronological),
+        (2, Weighting::Equal),
+    ] {
+        let config = ForecastConfig {
+            order,
+            weighting,
+            ..ForecastConfig::default()
+        };
+        let evaluation = walk_forward(&ENROLLMENTS, 10, &config).unwrap();
+        assert_eq!(evaluation.forecasts.len(), 12);
+        assert_eq!(evaluation.forecasts[0].0, 10);
+        assert!(evaluation.mape > 0.0 && evaluation.mape < 15.0, "{:?}: {}", weighting, evaluation);
+        results.push(evaluation);
+    }
+
+    let evaluation = &results[0];
+    let text = evaluation.to_string();
+    assert!(text.starts_with("Walk-forward evaluation: 12 forecasts\n  MAPE: "));
+    assert!(evaluation.to_csv().starts_with("index,actual,forecast\n10,16388,"));
+    // RMSE weighs the errors at least as heavily as their mean
+    let mean_error = evaluation.forecasts.iter().map(|(_, a, f)| (a - f).abs()).sum::<f64>() / 12.0;
+    assert!(evaluation.rmse >= mean_error);
+
+    assert!(walk_forward(&ENROLLMENTS, 22, &ForecastConfig::default()).is_err());
+}
@@ -0,0 +1,132 @@
+//! Evolutionary tuning tests
+//!
+//! Tune mistuned systems with both optimizers, under a smooth loss and a
+//! non-differentiable one.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::membership::MembershipFunction;
+use fuzzy_logic::system::{Antecedent, Consequent, FuzzySystem, LinguisticVariable, Rule};
+use fuzzy_logic::tuning::{squared_error, tune, Optimizer, TuningConfig};
+
+/// Temperature to fan speed, with the input terms peaking at `peaks`
+fn fan_system(peaks: [f64; 3]) -> FuzzySystem {
+    let mut system = FuzzySystem::new();
+    system.add_input(
+        LinguisticVariable::new("temperature", 0.0, 40.0)
+            .with_term("cold", MembershipFunction::Trapezoidal { a: -10.0, b: 0.0, c: peaks[0], d: peaks[1] })
+            .with_term("warm", MembershipFunction::Triangular { a: peaks[0], b: peaks[1], c: peaks[2] })
+            .with_term("hot", MembershipFunction::Gaussian { mean: peaks[2], sigma: 5.0 }),
+    );
+    system.add_output(
+        LinguisticVariable::new("fan_speed", 0.0, 100.0)
+            .with_term("low", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 50.0 })
+            .with_term("medium", MembershipFunction::Triangular { a: 20.0, b: 50.0, c: 80.0 })
+            .with_term("high", MembershipFunction::Triangular { a: 50.0, b: 100.0, c: 100.0 }),
+    );
+    for term in 0..3 {
+        system.add_rule(Rule::new(vec![Antecedent::new(0, term)], vec![Consequent::new(0, term)]));
+    }
+    system
+}
+
+fn is_ordered(system: &FuzzySystem) -> bool {
+    system.inputs().iter().chain(system.outputs()).flat_map(|v| &v.terms).all(|set| {
+        match set.membership_function {
+            MembershipFunction::Triangular { a, b, c } => a <= b && b <= c,
+            MembershipFunction::Trapezoidal { a, b, c, d } => a <= b && b <= c && c <= d,
+            MembershipFunction::Gaussian { sigma, .. } => sigma > 0.0,
+            _ => true,
+        }
+    })
+}
+
+#[test]
+fn test_both_optimizers_recover_a_reference_system() {
+    let reference = fan_system([10.0, 22.0, 34.0]);
+    let template = fan_system([4.0, 12.0, 20.0]);
+    let inputs: Vec<[f64; 1]> = (0..=40).map(|t| [t as f64]).collect();
+    let targets: Vec<Vec<f64>> = inputs.iter().map(|x| reference.evaluate(x)).collect();
+
+    for optimizer in [Optimizer::genetic(), Optimizer::differential_evolution()] {
+        let config = TuningConfig {
+            optimizer: optimizer.clone(),
+            population: 20,
+            generations: 30,
+            seed: 9,
+            ..TuningConfig::default()
+        };
+        let tuning = tune(&template, &inputs, &targets, squared_error, &config).unwrap();
+        let report = &tuning.report;
+
+        assert_eq!(report.parameters, 4 + 3 + 2 + 9);
+        assert_eq!(report.generations.len(), 31);
+        // Elites are carried over without being evaluated again
+        let elites = if matches!(optimizer, Optimizer::Genetic { .. }) { 1 } else { 0 };
+        assert_eq!(report.evaluations, 20 + 30 * (20 - elites));
+        let initial = report.generations[0].loss;
+        let best = report.best().loss;
+        assert!(best < 0.1 * initial, "{}: {} from {}", report.optimizer, best, initial);
+        assert!(report.generations.windows(2).all(|w| w[1].loss <= w[0].loss));
+        assert!(report.generations.iter().all(|r| is_ordered(&r.system)));
+
+        let rmse = squared_error(&tuning.system.evaluate(&[28.0]), &reference.evaluate(&[28.0])).sqrt();
+        assert!(rmse < 5.0, "{}: {}", report.optimizer, rmse);
+
+        // Same seed, same run
+        let again = tune(&template, &inputs, &targets, squared_error, &config).unwrap();
+        assert_eq!(again.report.to_csv(), report.to_csv());
+    }
+}
+
+#[test]
+fn test_rule_weights_reduce_false_alarm_cost() {
+    // Each sensor raises the alarm alone, but an alarm is only warranted
+    // when both temperature and pressure are high
+    let mut system = FuzzySystem::new();
+    for (name, max) in [("temperature", 100.0), ("pressure", 10.0)] {
+        system.add_input(
+            LinguisticVariable::new(name, 0.0, max)
+                .with_term("normal", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: max })
+                .with_term("high", MembershipFunction::Triangular { a: 0.0, b: max, c: max }),
+        );
+    }
+    system.add_output(
+        LinguisticVariable::new("alarm", 0.0, 1.0)
+            .with_term("off", MembershipFunction::Triangular { a: 0.0, b: 0.0, c: 1.0 })
+            .with_term("on", MembershipFunction::Triangular { a: 0.0, b: 1.0, c: 1.0 }),
+    );
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 1)], vec![Consequent::new(0, 1)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(1, 1)], vec![Consequent::new(0, 1)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(0, 0)], vec![Consequent::new(0, 0)]));
+    system.add_rule(Rule::new(vec![Antecedent::new(1, 0)], vec![Consequent::new(0, 0)]));
+
+    let inputs: Vec<[f64; 2]> = (0..121).map(|i| [(i % 11) as f64 * 10.0, (i / 11) as f64]).collect();
+    let labels: Vec<[f64; 1]> = inputs
+        .iter()
+        .map(|x| [if x[0] >= 70.0 && x[1] >= 7.0 { 1.0 } else { 0.0 }])
+        .collect();
+    // A false alarm costs ten times a missed one; the threshold makes the
+    // loss piecewise constant
+    let cost = |outputs: &[f64], label: &[f64]| match (outputs[0] > 0.5, label[0] > 0.5) {
+        (true, false) => 10.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+
+    let config = TuningConfig {
+        optimizer: Optimizer::differential_evolution(),
+        population: 24,
+        generations: 40,
+        rule_weights: true,
+        seed: 3,
+    };
+    let tuning = tune(&system, &inputs, &labels, cost, &config).unwrap();
+    let report = &tuning.report;
+
+    assert_eq!(report.parameters, 4 * 3 + 2 * 3 + 4);
+    let initial = report.generations[0].loss;
+    assert!(report.best().loss < 0.5 * initial, "{} from {}", report.best().loss, initial);
+    assert!(tuning.system.rules().iter().all(|r| (0.0..=1.0).contains(&r.weight)));
+    let text = report.to_string();
+    assert!(text.starts_with("Tuning by differential evolution: 22 parameters"));
+}
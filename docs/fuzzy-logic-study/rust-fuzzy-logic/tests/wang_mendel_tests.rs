@@ -0,0 +1,68 @@
+//! Wang–Mendel extraction tests
+//!
+//! Regenerate the rule base of a known system from samples of its own
+//! response and compare the two.
+#![cfg(feature = "std")]
+
+use fuzzy_logic::definition::{format_rule, load_system};
+use fuzzy_logic::wang_mendel::extract_rules;
+use fuzzy_logic::FuzzySystem;
+
+fn load(name: &str) -> FuzzySystem {
+    load_system(format!("{}/systems/{}.fuzzy", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// Inputs on an `n × n` grid and the system's outputs there
+fn samples(system: &FuzzySystem, n: usize) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let (x, y) = (&system.inputs()[0], &system.inputs()[1]);
+    let inputs: Vec<Vec<f64>> = x
+        .universe(n)
+        .into_iter()
+        .flat_map(|a| y.universe(n).into_iter().map(move |b| vec![a, b]))
+        .collect();
+    let outputs = inputs.iter().map(|point| system.evaluate(point)).collect();
+    (inputs, outputs)
+}
+
+#[test]
+fn test_recovers_tipping_rule_base() {
+    let original = load("tipping");
+    let (inputs, outputs) = samples(&original, 21);
+    let extraction = extract_rules(&original, &inputs, &outputs).unwrap();
+    let system = &extraction.system;
+    let report = &extraction.report;
+
+    // Every antecedent combination is seen, so the rule base is complete
+    assert_eq!(system.rules().len(), 9);
+    assert!(report.skipped.is_empty());
+    assert_eq!(report.rules.iter().map(|r| r.support).sum::<usize>() + report.conflicts.len(), 441);
+    assert_eq!(system.and_method(), original.and_method());
+
+    // The hand-written rules are found again
+    let texts = |s: &FuzzySystem| -> Vec<String> { s.rules().iter().map(|r| format_rule(s, r)).collect() };
+    assert_eq!(texts(system), texts(&original));
+
+    let (check_inputs, _) = samples(&original, 13);
+    let mean_error = check_inputs
+        .iter()
+        .map(|x| (system.evaluate(x)[0] - original.evaluate(x)[0]).abs())
+        .sum::<f64>()
+        / check_inputs.len() as f64;
+    assert!(mean_error < 2.0, "mean error {}", mean_error);
+}
+
+#[test]
+fn test_report_lists_conflicts() {
+    let original = load("tipping");
+    let (inputs, outputs) = samples(&original, 21);
+    let report = extract_rules(&original, &inputs, &outputs).unwrap().report;
+
+    let text = report.to_string();
+    assert!(text.starts_with("Wang-Mendel rule extraction\n  Samples: 441 (0 skipped)\n"));
+    assert!(text.contains(&format!("  Conflicts resolved: {}\n", report.conflicts.len())));
+    assert!(report.conflicts.len() > 10);
+    assert!(text.ends_with(&format!("    ... and {} more\n", report.conflicts.len() - 10)));
+    for conflict in &report.conflicts {
+        assert!(conflict.degree <= report.rules[conflict.rule].degree);
+    }
+}
@@ -0,0 +1,79 @@
+//! WebAssembly binding tests
+//!
+//! Run headless under Node with
+//! `cargo test --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm_tests`
+//! (needs `wasm-bindgen-test-runner` on the PATH).
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use fuzzy_logic::wasm::{
+    complement, defuzzify, intersection, snorm, tnorm, union, WasmFuzzySystem,
+    WasmMembershipFunction,
+};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TIPPING: &str = include_str!("../systems/tipping.fuzzy");
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &key.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_membership_functions() {
+    let warm = WasmMembershipFunction::triangular(10.0, 25.0, 35.0);
+    assert!((warm.evaluate(20.0) - 2.0 / 3.0).abs() < 1e-12);
+    assert_eq!(warm.evaluate_many(&[10.0, 25.0, 40.0]), vec![0.0, 1.0, 0.0]);
+
+    let parsed = WasmMembershipFunction::parse("trapezoidal 0 2 4 6").unwrap();
+    assert_eq!(parsed.evaluate(3.0), 1.0);
+    assert!(WasmMembershipFunction::parse("triangular 0 1").is_err());
+    assert_eq!(WasmMembershipFunction::gaussian(5.0, 1.0).evaluate(5.0), 1.0);
+}
+
+#[wasm_bindgen_test]
+fn test_operations() {
+    assert_eq!(union(0.7, 0.5), 0.7);
+    assert_eq!(intersection(0.7, 0.5), 0.5);
+    assert!((complement(0.7) - 0.3).abs() < 1e-12);
+    assert!((tnorm("prod", 0.5, 0.5).unwrap() - 0.25).abs() < 1e-12);
+    assert!((snorm("probor", 0.5, 0.5).unwrap() - 0.75).abs() < 1e-12);
+    assert!(tnorm("median", 0.5, 0.5).is_err());
+
+    let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+    let mu = [0.0, 0.5, 1.0, 0.5, 0.0];
+    assert!((defuzzify("centroid", &xs, &mu).unwrap() - 2.0).abs() < 1e-12);
+    assert!(defuzzify("centroid", &xs, &mu[..3]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_system_evaluate_and_trace() {
+    let mut system = WasmFuzzySystem::new(TIPPING).unwrap();
+    assert_eq!(system.input_names(), vec!["service", "food"]);
+    assert_eq!(system.output_names(), vec!["tip"]);
+
+    let native: fuzzy_logic::FuzzySystem = TIPPING.parse().unwrap();
+    let tip = system.evaluate(&[3.0, 8.0]).unwrap();
+    assert_eq!(tip, native.evaluate(&[3.0, 8.0]));
+    assert!(system.evaluate(&[3.0]).is_err());
+
+    let trace = system.trace(&[3.0, 8.0]).unwrap();
+    assert_eq!(get(&get(&trace, "outputs"), "tip").as_f64(), Some(tip[0]));
+    let poor = get(&get(&get(&trace, "memberships"), "service"), "poor");
+    assert!((poor.as_f64().unwrap() - 0.4).abs() < 1e-12);
+    let rules = Array::from(&get(&trace, "rules"));
+    assert_eq!(rules.length(), 9);
+    assert_eq!(
+        get(&rules.get(0), "rule").as_string().unwrap(),
+        "if service is poor and food is poor then tip is low"
+    );
+
+    system.set_option("defuzzification", "bisector").unwrap();
+    assert!(system.definition().contains("defuzzification bisector"));
+    assert!(system.set_option("defuzzification", "median").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_definition() {
+    assert!(WasmFuzzySystem::new("input x 0 10\n  term low triangular 0 0\n").is_err());
+}
@@ -0,0 +1,7 @@
+//! WebAssembly Module
+//!
+//! Builds the bindings of [`fuzzy_logic::wasm`] into a `cdylib` for
+//! `wasm-bindgen`. The main crate stays an `rlib` so that `no_std`
+//! dependents can link it.
+
+pub use fuzzy_logic::wasm::*;